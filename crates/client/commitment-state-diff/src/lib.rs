@@ -4,8 +4,9 @@ use std::sync::Arc;
 use std::task::Poll;
 
 use futures::channel::mpsc;
-use futures::Stream;
+use futures::{SinkExt, Stream, StreamExt};
 use indexmap::{IndexMap, IndexSet};
+use mc_db::{ArchiveKey, StateArchiveDb};
 use mp_hashers::HasherT;
 use mp_storage::{SN_COMPILED_CLASS_HASH_PREFIX, SN_CONTRACT_CLASS_HASH_PREFIX, SN_NONCE_PREFIX, SN_STORAGE_PREFIX};
 use pallet_starknet_runtime_api::StarknetRuntimeApi;
@@ -13,7 +14,7 @@ use sc_client_api::client::BlockchainEvents;
 use sc_client_api::{StorageEventStream, StorageNotification};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
-use sp_runtime::traits::{Block as BlockT, Header};
+use sp_runtime::traits::{Block as BlockT, Header, UniqueSaturatedInto};
 use starknet_api::api_core::{ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey};
 use starknet_api::block::BlockHash;
 use starknet_api::hash::{StarkFelt, StarkHash};
@@ -245,3 +246,81 @@ where
         previous_state_root: backend.temporary_global_state_root_getter(),
     })
 }
+
+#[derive(Debug, Error)]
+enum ArchiveStateDiffError {
+    #[error("failed to interact with substrate header backend")]
+    SubstrateHeaderBackend(#[from] sp_blockchain::Error),
+    #[error("failed to write to the state archive: {0}")]
+    StateArchive(#[from] mc_db::DbError),
+}
+
+/// Archives the state diffs received on `rx`, then forwards them to `tx` if any.
+///
+/// Meant to sit between the `CommitmentStateDiffWorker` and the DA worker, so that both can
+/// consume the same stream of state diffs.
+pub async fn archive_state_diffs<B: BlockT, C>(
+    client: Arc<C>,
+    backend: Arc<mc_db::Backend<B>>,
+    mut rx: mpsc::Receiver<BlockDAData>,
+    mut tx: Option<mpsc::Sender<BlockDAData>>,
+) where
+    C: ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B>,
+    C: HeaderBackend<B>,
+{
+    while let Some(block_da_data) = rx.next().await {
+        if let Err(e) = archive_state_diff(client.as_ref(), backend.state_archive(), &block_da_data) {
+            log::error!("Failed to archive the state diff of block {}: {e}", block_da_data.block_number);
+        }
+
+        if let Some(tx) = tx.as_mut() {
+            if let Err(e) = tx.send(block_da_data).await {
+                log::error!("State archive channel receiver has been dropped: {e}");
+                return;
+            }
+        }
+    }
+}
+
+fn archive_state_diff<B: BlockT, C>(
+    client: &C,
+    state_archive: &StateArchiveDb,
+    block_da_data: &BlockDAData,
+) -> Result<(), ArchiveStateDiffError>
+where
+    C: ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B>,
+    C: HeaderBackend<B>,
+{
+    // The value of a key before its first archived change is read from the state of the parent block
+    let parent_hash = match block_da_data.block_number.checked_sub(1) {
+        Some(parent_number) => client.hash(parent_number.unique_saturated_into())?,
+        None => None,
+    };
+    let runtime_api = client.runtime_api();
+
+    state_archive.store_state_diff(block_da_data.block_number, &block_da_data.state_diff, |key| {
+        let Some(parent_hash) = parent_hash else {
+            // Nothing exists before genesis
+            return (block_da_data.block_number == 0).then(StarkFelt::default);
+        };
+
+        match *key {
+            // `get_storage_at` fails if the contract is not deployed, its storage is then empty
+            ArchiveKey::Storage(contract_address, storage_key) => runtime_api
+                .get_storage_at(parent_hash, contract_address, storage_key)
+                .ok()
+                .map(|value| value.unwrap_or_default()),
+            ArchiveKey::Nonce(contract_address) => {
+                runtime_api.nonce(parent_hash, contract_address).ok().map(|nonce| nonce.0)
+            }
+            ArchiveKey::ClassHash(contract_address) => runtime_api
+                .contract_class_hash_by_address(parent_hash, contract_address)
+                .ok()
+                .map(|class_hash| class_hash.0),
+        }
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+use blockifier::execution::contract_class::ContractClass;
+use lru::LruCache;
+use parity_scale_codec::{Decode, Encode};
+use sp_database::Database;
+use starknet_api::api_core::{ClassHash, CompiledClassHash};
+
+use crate::{DbError, DbHash};
+
+/// Number of compiled classes kept deserialized in memory.
+const CACHE_SIZE: usize = 128;
+
+/// The compiled classes kept in memory, along with the compiled class hashes of the Sierra
+/// classes compiled by the node but not declared yet.
+struct CompiledClassesCache {
+    compiled_classes: LruCache<CompiledClassHash, ContractClass>,
+    compiled_class_hashes: LruCache<ClassHash, CompiledClassHash>,
+}
+
+/// Allow interaction with the compiled classes db
+///
+/// The classes compiled from Sierra are stored once by compiled class hash, however many Sierra
+/// classes compile to them. It is up to the caller to check that a Sierra class compiles to a
+/// compiled class hash, against the `CompiledClassHashes` of the chain for the declared classes.
+/// Only the compiled classes of declared classes are persisted, the most recently used ones
+/// being served from memory.
+pub struct CompiledClassesDb {
+    db: Arc<dyn Database<DbHash>>,
+    cache: Mutex<CompiledClassesCache>,
+}
+
+impl CompiledClassesDb {
+    pub(crate) fn new(db: Arc<dyn Database<DbHash>>) -> Self {
+        let cache_size = NonZeroUsize::new(CACHE_SIZE).expect("the cache size is not zero");
+        let cache = CompiledClassesCache {
+            compiled_classes: LruCache::new(cache_size),
+            compiled_class_hashes: LruCache::new(cache_size),
+        };
+        Self { db, cache: Mutex::new(cache) }
+    }
+
+    /// Store the class of `compiled_class_hash`, the compiled class hash of a declared class.
+    pub fn store_compiled_class(
+        &self,
+        compiled_class_hash: CompiledClassHash,
+        compiled_class: ContractClass,
+    ) -> Result<(), DbError> {
+        let mut transaction = sp_database::Transaction::new();
+
+        transaction.set(
+            crate::columns::COMPILED_CONTRACT_CLASSES,
+            &compiled_class_hash.encode(),
+            &compiled_class.encode(),
+        );
+
+        self.db.commit(transaction)?;
+        self.cache.lock().expect("poisoned lock").compiled_classes.put(compiled_class_hash, compiled_class);
+
+        Ok(())
+    }
+
+    /// Keep in memory the class compiled from the Sierra class `class_hash`, which is not
+    /// declared yet, e.g. by a transaction to estimate or simulate.
+    pub fn cache_compiled_class(
+        &self,
+        class_hash: ClassHash,
+        compiled_class_hash: CompiledClassHash,
+        compiled_class: ContractClass,
+    ) {
+        let mut cache = self.cache.lock().expect("poisoned lock");
+        cache.compiled_classes.put(compiled_class_hash, compiled_class);
+        cache.compiled_class_hashes.put(class_hash, compiled_class_hash);
+    }
+
+    /// Return the compiled class hash of a Sierra class not declared yet, if the node compiled
+    /// it recently.
+    pub fn cached_compiled_class_hash(&self, class_hash: ClassHash) -> Option<CompiledClassHash> {
+        self.cache.lock().expect("poisoned lock").compiled_class_hashes.get(&class_hash).copied()
+    }
+
+    /// Return the class of `compiled_class_hash`, if any.
+    pub fn get_compiled_class(&self, compiled_class_hash: CompiledClassHash) -> Result<Option<ContractClass>, DbError> {
+        let mut cache = self.cache.lock().expect("poisoned lock");
+        if let Some(compiled_class) = cache.compiled_classes.get(&compiled_class_hash) {
+            return Ok(Some(compiled_class.clone()));
+        }
+
+        let Some(raw) = self.db.get(crate::columns::COMPILED_CONTRACT_CLASSES, &compiled_class_hash.encode()) else {
+            return Ok(None);
+        };
+        let compiled_class = ContractClass::decode(&mut &raw[..])?;
+        cache.compiled_classes.put(compiled_class_hash, compiled_class.clone());
+
+        Ok(Some(compiled_class))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blockifier::execution::contract_class::ContractClassV1;
+    use starknet_api::hash::StarkFelt;
+
+    use super::*;
+
+    fn compiled_class_hash(value: u64) -> CompiledClassHash {
+        CompiledClassHash(StarkFelt::from(value))
+    }
+
+    fn compiled_class() -> ContractClass {
+        ContractClass::V1(ContractClassV1::default())
+    }
+
+    #[test]
+    fn stored_classes_are_read_back_from_the_database() {
+        let db: Arc<dyn Database<DbHash>> = Arc::new(sp_database::MemDb::default());
+        CompiledClassesDb::new(db.clone()).store_compiled_class(compiled_class_hash(1), compiled_class()).unwrap();
+
+        // A new instance starts with an empty cache
+        let compiled_classes = CompiledClassesDb::new(db);
+        assert_eq!(compiled_classes.get_compiled_class(compiled_class_hash(1)).unwrap(), Some(compiled_class()));
+        assert_eq!(compiled_classes.get_compiled_class(compiled_class_hash(2)).unwrap(), None);
+    }
+
+    #[test]
+    fn classes_compiled_from_undeclared_classes_are_only_kept_in_memory() {
+        let db: Arc<dyn Database<DbHash>> = Arc::new(sp_database::MemDb::default());
+        let class_hash = ClassHash(StarkFelt::from(1u64));
+
+        let cached = CompiledClassesDb::new(db.clone());
+        cached.cache_compiled_class(class_hash, compiled_class_hash(2), compiled_class());
+        assert_eq!(cached.cached_compiled_class_hash(class_hash), Some(compiled_class_hash(2)));
+        assert_eq!(cached.get_compiled_class(compiled_class_hash(2)).unwrap(), Some(compiled_class()));
+
+        let compiled_classes = CompiledClassesDb::new(db);
+        assert_eq!(compiled_classes.cached_compiled_class_hash(class_hash), None);
+        assert_eq!(compiled_classes.get_compiled_class(compiled_class_hash(2)).unwrap(), None);
+    }
+
+    #[test]
+    fn least_recently_used_classes_are_evicted_from_memory() {
+        let db: Arc<dyn Database<DbHash>> = Arc::new(sp_database::MemDb::default());
+        let compiled_classes = CompiledClassesDb::new(db);
+
+        for value in 0..=CACHE_SIZE as u64 {
+            let class_hash = ClassHash(StarkFelt::from(value));
+            compiled_classes.cache_compiled_class(class_hash, compiled_class_hash(value), compiled_class());
+        }
+
+        assert_eq!(compiled_classes.get_compiled_class(compiled_class_hash(0)).unwrap(), None);
+        assert_eq!(compiled_classes.cached_compiled_class_hash(ClassHash(StarkFelt::from(0u64))), None);
+        assert_eq!(
+            compiled_classes.get_compiled_class(compiled_class_hash(CACHE_SIZE as u64)).unwrap(),
+            Some(compiled_class())
+        );
+    }
+}
@@ -8,4 +8,6 @@ pub enum DbError {
     Uuid(#[from] uuid::Error),
     #[error("A value was queryied that was not initialized at column: `{0}` key: `{1}`")]
     ValueNotInitialized(u32, String),
+    #[error("State diffs must be archived in order: expected block `{expected}`, got `{got}`")]
+    NonContiguousStateArchive { expected: u64, got: u64 },
 }
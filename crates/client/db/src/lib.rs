@@ -14,6 +14,7 @@
 mod error;
 pub use error::DbError;
 
+mod compiled_classes_db;
 mod mapping_db;
 pub use mapping_db::MappingCommitment;
 use sierra_classes_db::SierraClassesDb;
@@ -23,13 +24,16 @@ mod db_opening_utils;
 mod messaging_db;
 mod sierra_classes_db;
 pub use messaging_db::LastSyncedEventBlock;
+mod state_archive_db;
+pub use state_archive_db::{ArchiveKey, ArchivedValue, StateArchiveDb};
 mod l1_handler_tx_fee;
 mod meta_db;
-
+mod transaction_outputs_db;
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use compiled_classes_db::CompiledClassesDb;
 use da_db::DaDb;
 use l1_handler_tx_fee::L1HandlerTxFeeDb;
 use mapping_db::MappingDb;
@@ -38,6 +42,7 @@ use meta_db::MetaDb;
 use sc_client_db::DatabaseSource;
 use sp_database::Database;
 use sp_runtime::traits::Block as BlockT;
+pub use transaction_outputs_db::{TransactionOutput, TransactionOutputsDb};
 
 const DB_HASH_LEN: usize = 32;
 /// Hash type that this backend uses for the database.
@@ -53,7 +58,7 @@ pub(crate) mod columns {
     // ===== /!\ ===================================================================================
     // MUST BE INCREMENTED WHEN A NEW COLUMN IN ADDED
     // ===== /!\ ===================================================================================
-    pub const NUM_COLUMNS: u32 = 9;
+    pub const NUM_COLUMNS: u32 = 12;
 
     pub const META: u32 = 0;
     pub const BLOCK_MAPPING: u32 = 1;
@@ -75,12 +80,24 @@ pub(crate) mod columns {
 
     /// This column stores the fee paid on l1 for L1Handler transactions
     pub const L1_HANDLER_PAID_FEE: u32 = 8;
+
+    /// This column contains the history of every contract storage, nonce and class hash, built
+    /// from the blocks state diffs
+    pub const STATE_ARCHIVE: u32 = 9;
+
+    /// This column maps transaction hashes to the events, messages to L1 and revert reason
+    /// produced by their execution
+    pub const TRANSACTION_OUTPUTS: u32 = 10;
+
+    /// This column contains the classes compiled from the Sierra classes, by compiled class hash
+    pub const COMPILED_CONTRACT_CLASSES: u32 = 11;
 }
 
 pub mod static_keys {
     pub const CURRENT_SYNCING_TIPS: &[u8] = b"CURRENT_SYNCING_TIPS";
     pub const LAST_PROVED_BLOCK: &[u8] = b"LAST_PROVED_BLOCK";
     pub const LAST_SYNCED_L1_EVENT_BLOCK: &[u8] = b"LAST_SYNCED_L1_EVENT_BLOCK";
+    pub const STATE_ARCHIVE_RANGE: &[u8] = b"STATE_ARCHIVE_RANGE";
 }
 
 /// The Madara client database backend
@@ -94,7 +111,10 @@ pub struct Backend<B: BlockT> {
     da: Arc<DaDb>,
     messaging: Arc<MessagingDb>,
     sierra_classes: Arc<SierraClassesDb>,
+    compiled_classes: Arc<CompiledClassesDb>,
     l1_handler_paid_fee: Arc<L1HandlerTxFeeDb>,
+    state_archive: Arc<StateArchiveDb>,
+    transaction_outputs: Arc<TransactionOutputsDb>,
 }
 
 /// Returns the Starknet database directory.
@@ -137,7 +157,10 @@ impl<B: BlockT> Backend<B> {
             da: Arc::new(DaDb { db: db.clone() }),
             messaging: Arc::new(MessagingDb { db: db.clone() }),
             sierra_classes: Arc::new(SierraClassesDb { db: db.clone() }),
+            compiled_classes: Arc::new(CompiledClassesDb::new(db.clone())),
             l1_handler_paid_fee: Arc::new(L1HandlerTxFeeDb { db: db.clone() }),
+            state_archive: Arc::new(StateArchiveDb { db: db.clone() }),
+            transaction_outputs: Arc::new(TransactionOutputsDb { db: db.clone() }),
         })
     }
 
@@ -166,11 +189,26 @@ impl<B: BlockT> Backend<B> {
         &self.sierra_classes
     }
 
+    /// Return the compiled classes database manager
+    pub fn compiled_classes(&self) -> &Arc<CompiledClassesDb> {
+        &self.compiled_classes
+    }
+
     /// Return l1 handler tx paid fee database manager
     pub fn l1_handler_paid_fee(&self) -> &Arc<L1HandlerTxFeeDb> {
         &self.l1_handler_paid_fee
     }
 
+    /// Return the state archive database manager
+    pub fn state_archive(&self) -> &Arc<StateArchiveDb> {
+        &self.state_archive
+    }
+
+    /// Return the transaction outputs database manager
+    pub fn transaction_outputs(&self) -> &Arc<TransactionOutputsDb> {
+        &self.transaction_outputs
+    }
+
     /// In the future, we will compute the block global state root asynchronously in the client,
     /// using the Starknet-Bonzai-trie.
     /// That what replaces it for now :)
@@ -113,6 +113,73 @@ impl<B: BlockT> MappingDb<B> {
         Ok(())
     }
 
+    /// Map one more Starknet block hash to a Substrate block, e.g. the hash of a block imported
+    /// from another sequencer on that sequencer
+    ///
+    /// The Substrate block is not registered as seen, the Starknet block it contains is still to be
+    /// mapped by the mapping sync.
+    pub fn write_block_hash_alias(&self, block_hash: B::Hash, starknet_block_hash: StarkHash) -> Result<(), DbError> {
+        let _lock = self.write_lock.lock();
+
+        let mut substrate_hashes = self.block_hash(starknet_block_hash)?.unwrap_or_default();
+        if !substrate_hashes.contains(&block_hash) {
+            substrate_hashes.push(block_hash);
+        }
+
+        let mut transaction = sp_database::Transaction::new();
+
+        transaction.set(crate::columns::BLOCK_MAPPING, &starknet_block_hash.encode(), &substrate_hashes.encode());
+
+        self.db.commit(transaction)?;
+
+        Ok(())
+    }
+
+    /// Unregister a Substrate block that has been reverted, along with the Starknet block and
+    /// transactions it was mapped to
+    ///
+    /// `commitment` is `None` for a Substrate block which does not contain a Starknet one.
+    pub fn revert_hashes(&self, block_hash: B::Hash, commitment: Option<MappingCommitment<B>>) -> Result<(), DbError> {
+        let _lock = self.write_lock.lock();
+
+        let mut transaction = sp_database::Transaction::new();
+
+        transaction.remove(crate::columns::SYNCED_MAPPING, &block_hash.encode());
+
+        if let Some(commitment) = commitment {
+            let substrate_hashes = self
+                .block_hash(commitment.starknet_block_hash)?
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|hash| *hash != commitment.block_hash)
+                .collect::<Vec<_>>();
+            if substrate_hashes.is_empty() {
+                transaction.remove(crate::columns::BLOCK_MAPPING, &commitment.starknet_block_hash.encode());
+            } else {
+                transaction.set(
+                    crate::columns::BLOCK_MAPPING,
+                    &commitment.starknet_block_hash.encode(),
+                    &substrate_hashes.encode(),
+                );
+            }
+
+            for transaction_hash in commitment.starknet_transaction_hashes.iter() {
+                transaction.remove(crate::columns::TRANSACTION_MAPPING, &transaction_hash.encode());
+            }
+
+            if self.cache_more_things {
+                transaction.remove(
+                    crate::columns::STARKNET_TRANSACTION_HASHES_CACHE,
+                    &commitment.starknet_block_hash.encode(),
+                );
+            }
+        }
+
+        self.db.commit(transaction)?;
+
+        Ok(())
+    }
+
     /// Retrieves the substrate block hash
     /// associated with the given transaction hash, if any.
     ///
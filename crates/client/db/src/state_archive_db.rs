@@ -0,0 +1,371 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+// Substrate
+use parity_scale_codec::{Decode, Encode};
+use sp_database::Database;
+// Starknet
+use starknet_api::api_core::ContractAddress;
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::{StorageKey, ThinStateDiff};
+
+use crate::{DbError, DbHash};
+
+const STORAGE_TAG: u8 = 0;
+const NONCE_TAG: u8 = 1;
+const CLASS_HASH_TAG: u8 = 2;
+const BLOCK_CHANGES_TAG: u8 = 3;
+
+/// A piece of contract state tracked by the archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKey {
+    Storage(ContractAddress, StorageKey),
+    Nonce(ContractAddress),
+    ClassHash(ContractAddress),
+}
+
+impl ArchiveKey {
+    fn db_key(&self) -> Vec<u8> {
+        match self {
+            ArchiveKey::Storage(contract_address, key) => {
+                [&[STORAGE_TAG][..], &contract_address.encode(), &key.encode()].concat()
+            }
+            ArchiveKey::Nonce(contract_address) => [&[NONCE_TAG][..], &contract_address.encode()].concat(),
+            ArchiveKey::ClassHash(contract_address) => [&[CLASS_HASH_TAG][..], &contract_address.encode()].concat(),
+        }
+    }
+}
+
+/// The result of an archive lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchivedValue {
+    /// The value held by the key at the requested block
+    Value(StarkFelt),
+    /// The key has not been modified between the requested block and the given archive tip, so its
+    /// value can be read from the state of the tip
+    UnchangedUntil(u64),
+}
+
+/// The changes of a key since the archive started
+///
+/// The changes themselves are stored one per entry, under [change_db_key], so that archiving a
+/// change does not rewrite the previous ones.
+#[derive(Debug, Encode, Decode)]
+struct KeyHistory {
+    /// The value before the first archived change
+    initial: StarkFelt,
+    /// The number of archived changes
+    len: u64,
+}
+
+/// The key of the `index`th change of the key stored at `db_key`
+///
+/// The changes of a key are archived in order, so they are sorted by block number.
+fn change_db_key(db_key: &[u8], index: u64) -> Vec<u8> {
+    [db_key, &index.to_be_bytes()[..]].concat()
+}
+
+/// The key of the list of the keys changed at `block_number`, used to revert the block
+fn block_changes_db_key(block_number: u64) -> Vec<u8> {
+    [&[BLOCK_CHANGES_TAG][..], &block_number.to_be_bytes()[..]].concat()
+}
+
+/// Archive of the state diffs of a contiguous range of blocks
+///
+/// Allows reading storage, nonces and class hashes at blocks whose substrate state has been
+/// pruned.
+pub struct StateArchiveDb {
+    pub(crate) db: Arc<dyn Database<DbHash>>,
+}
+
+impl StateArchiveDb {
+    /// Return the first and last blocks whose state diffs have been archived
+    pub fn archived_range(&self) -> Result<Option<(u64, u64)>, DbError> {
+        match self.db.get(crate::columns::STATE_ARCHIVE, crate::static_keys::STATE_ARCHIVE_RANGE) {
+            Some(raw) => Ok(Some(<(u64, u64)>::decode(&mut &raw[..])?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Archive the state diff of `block_number`
+    ///
+    /// Blocks must be archived in order. `initial_value` is queried for the value a key held
+    /// before `block_number` the first time that key is modified.
+    pub fn store_state_diff<F>(
+        &self,
+        block_number: u64,
+        diff: &ThinStateDiff,
+        mut initial_value: F,
+    ) -> Result<(), DbError>
+    where
+        F: FnMut(&ArchiveKey) -> Option<StarkFelt>,
+    {
+        let first_block = match self.archived_range()? {
+            None => block_number,
+            Some((first, tip)) if block_number == tip + 1 => first,
+            Some((_, tip)) if block_number <= tip => {
+                log::warn!("State diff of block {block_number} is already archived");
+                return Ok(());
+            }
+            Some((_, tip)) => return Err(DbError::NonContiguousStateArchive { expected: tip + 1, got: block_number }),
+        };
+
+        let changes = diff
+            .storage_diffs
+            .iter()
+            .flat_map(|(contract_address, storage)| {
+                storage.iter().map(|(key, value)| (ArchiveKey::Storage(*contract_address, *key), *value))
+            })
+            .chain(diff.nonces.iter().map(|(contract_address, nonce)| (ArchiveKey::Nonce(*contract_address), nonce.0)))
+            .chain(
+                diff.deployed_contracts
+                    .iter()
+                    .chain(diff.replaced_classes.iter())
+                    .map(|(contract_address, class_hash)| (ArchiveKey::ClassHash(*contract_address), class_hash.0)),
+            );
+
+        // A contract deployed and then replaced in the same block keeps its last class hash
+        let changes: BTreeMap<Vec<u8>, (ArchiveKey, StarkFelt)> =
+            changes.map(|(key, value)| (key.db_key(), (key, value))).collect();
+
+        let mut transaction = sp_database::Transaction::new();
+        let mut changed_db_keys = Vec::with_capacity(changes.len());
+
+        for (db_key, (key, value)) in changes {
+            let mut history = match self.db.get(crate::columns::STATE_ARCHIVE, &db_key) {
+                Some(raw) => KeyHistory::decode(&mut &raw[..])?,
+                None => KeyHistory {
+                    initial: initial_value(&key).ok_or_else(|| {
+                        DbError::ValueNotInitialized(crate::columns::STATE_ARCHIVE, format!("{key:?}"))
+                    })?,
+                    len: 0,
+                },
+            };
+
+            transaction.set(
+                crate::columns::STATE_ARCHIVE,
+                &change_db_key(&db_key, history.len),
+                &(block_number, value).encode(),
+            );
+            history.len += 1;
+            transaction.set(crate::columns::STATE_ARCHIVE, &db_key, &history.encode());
+            changed_db_keys.push(db_key);
+        }
+
+        transaction.set(crate::columns::STATE_ARCHIVE, &block_changes_db_key(block_number), &changed_db_keys.encode());
+
+        transaction.set(
+            crate::columns::STATE_ARCHIVE,
+            crate::static_keys::STATE_ARCHIVE_RANGE,
+            &(first_block, block_number).encode(),
+        );
+
+        self.db.commit(transaction)?;
+
+        Ok(())
+    }
+
+    /// Drop the archived state diffs of the blocks after `block_number`
+    ///
+    /// Meant for the blocks reverted from the chain, which are then archived again once re-built.
+    pub fn revert_to(&self, block_number: u64) -> Result<(), DbError> {
+        let Some((first_block, mut tip)) = self.archived_range()? else {
+            return Ok(());
+        };
+
+        while tip > block_number {
+            let block_changes_db_key = block_changes_db_key(tip);
+            let changed_db_keys = match self.db.get(crate::columns::STATE_ARCHIVE, &block_changes_db_key) {
+                Some(raw) => Vec::<Vec<u8>>::decode(&mut &raw[..])?,
+                None => {
+                    return Err(DbError::ValueNotInitialized(
+                        crate::columns::STATE_ARCHIVE,
+                        format!("changes of block {tip}"),
+                    ));
+                }
+            };
+
+            let mut transaction = sp_database::Transaction::new();
+
+            for db_key in changed_db_keys {
+                let raw = self.db.get(crate::columns::STATE_ARCHIVE, &db_key).ok_or_else(|| {
+                    DbError::ValueNotInitialized(crate::columns::STATE_ARCHIVE, format!("{db_key:?}"))
+                })?;
+                let mut history = KeyHistory::decode(&mut &raw[..])?;
+                history.len -= 1;
+                transaction.remove(crate::columns::STATE_ARCHIVE, &change_db_key(&db_key, history.len));
+                if history.len == 0 {
+                    // The initial value is queried again by the next change of the key
+                    transaction.remove(crate::columns::STATE_ARCHIVE, &db_key);
+                } else {
+                    transaction.set(crate::columns::STATE_ARCHIVE, &db_key, &history.encode());
+                }
+            }
+
+            transaction.remove(crate::columns::STATE_ARCHIVE, &block_changes_db_key);
+            if tip == first_block {
+                transaction.remove(crate::columns::STATE_ARCHIVE, crate::static_keys::STATE_ARCHIVE_RANGE);
+            } else {
+                transaction.set(
+                    crate::columns::STATE_ARCHIVE,
+                    crate::static_keys::STATE_ARCHIVE_RANGE,
+                    &(first_block, tip - 1).encode(),
+                );
+            }
+
+            self.db.commit(transaction)?;
+
+            if tip == first_block {
+                break;
+            }
+            tip -= 1;
+        }
+
+        Ok(())
+    }
+
+    /// Return the value of `key` at `block_number`, or `None` if that block is not covered by the
+    /// archive
+    pub fn value_at(&self, key: &ArchiveKey, block_number: u64) -> Result<Option<ArchivedValue>, DbError> {
+        // The initial values of the first archived block are the state of its parent
+        let tip = match self.archived_range()? {
+            Some((first, tip)) if block_number + 1 >= first && block_number <= tip => tip,
+            _ => return Ok(None),
+        };
+
+        let db_key = key.db_key();
+        let history = match self.db.get(crate::columns::STATE_ARCHIVE, &db_key) {
+            Some(raw) => KeyHistory::decode(&mut &raw[..])?,
+            None => return Ok(Some(ArchivedValue::UnchangedUntil(tip))),
+        };
+
+        // Seek the last change made at or before `block_number`, by binary search over the changes
+        let (mut low, mut high) = (0, history.len);
+        let mut value = history.initial;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let (change_block, change_value) = self.change(&db_key, mid)?;
+            if change_block <= block_number {
+                value = change_value;
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        Ok(Some(ArchivedValue::Value(value)))
+    }
+
+    fn change(&self, db_key: &[u8], index: u64) -> Result<(u64, StarkFelt), DbError> {
+        let change_db_key = change_db_key(db_key, index);
+        let raw = self.db.get(crate::columns::STATE_ARCHIVE, &change_db_key).ok_or_else(|| {
+            DbError::ValueNotInitialized(crate::columns::STATE_ARCHIVE, format!("change {index} of {db_key:?}"))
+        })?;
+        Ok(<(u64, StarkFelt)>::decode(&mut &raw[..])?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_api::api_core::{Nonce, PatriciaKey};
+
+    use super::*;
+
+    fn archive() -> StateArchiveDb {
+        StateArchiveDb { db: Arc::new(sp_database::MemDb::default()) }
+    }
+
+    fn address(value: u64) -> ContractAddress {
+        ContractAddress(PatriciaKey(StarkFelt::from(value)))
+    }
+
+    fn nonce_diff(contract_address: ContractAddress, nonce: u64) -> ThinStateDiff {
+        let mut diff = ThinStateDiff::default();
+        diff.nonces.insert(contract_address, Nonce(StarkFelt::from(nonce)));
+        diff
+    }
+
+    #[test]
+    fn value_at_returns_latest_change_before_block() {
+        let archive = archive();
+        let account = address(1);
+        let key = ArchiveKey::Nonce(account);
+
+        archive.store_state_diff(5, &nonce_diff(account, 1), |_| Some(StarkFelt::default())).unwrap();
+        archive.store_state_diff(6, &ThinStateDiff::default(), |_| unreachable!()).unwrap();
+        archive.store_state_diff(7, &nonce_diff(account, 2), |_| unreachable!()).unwrap();
+
+        assert_eq!(archive.archived_range().unwrap(), Some((5, 7)));
+        assert_eq!(archive.value_at(&key, 3).unwrap(), None);
+        assert_eq!(archive.value_at(&key, 4).unwrap(), Some(ArchivedValue::Value(StarkFelt::from(0u64))));
+        assert_eq!(archive.value_at(&key, 6).unwrap(), Some(ArchivedValue::Value(StarkFelt::from(1u64))));
+        assert_eq!(archive.value_at(&key, 7).unwrap(), Some(ArchivedValue::Value(StarkFelt::from(2u64))));
+        assert_eq!(archive.value_at(&key, 8).unwrap(), None);
+        assert_eq!(
+            archive.value_at(&ArchiveKey::Nonce(address(2)), 6).unwrap(),
+            Some(ArchivedValue::UnchangedUntil(7))
+        );
+    }
+
+    #[test]
+    fn value_at_seeks_among_many_changes() {
+        let archive = archive();
+        let account = address(1);
+        let key = ArchiveKey::Nonce(account);
+
+        archive.store_state_diff(0, &nonce_diff(account, 0), |_| Some(StarkFelt::default())).unwrap();
+        for block_number in 1..100 {
+            // The nonce changes every other block
+            let diff = if block_number % 2 == 0 { nonce_diff(account, block_number) } else { ThinStateDiff::default() };
+            archive.store_state_diff(block_number, &diff, |_| unreachable!()).unwrap();
+        }
+
+        for block_number in 0..100 {
+            assert_eq!(
+                archive.value_at(&key, block_number).unwrap(),
+                Some(ArchivedValue::Value(StarkFelt::from(block_number - block_number % 2)))
+            );
+        }
+    }
+
+    #[test]
+    fn revert_to_drops_the_changes_of_the_reverted_blocks() {
+        let archive = archive();
+        let account = address(1);
+        let key = ArchiveKey::Nonce(account);
+
+        archive.store_state_diff(5, &nonce_diff(account, 1), |_| Some(StarkFelt::default())).unwrap();
+        archive.store_state_diff(6, &nonce_diff(account, 2), |_| unreachable!()).unwrap();
+        archive.store_state_diff(7, &nonce_diff(address(2), 1), |_| Some(StarkFelt::default())).unwrap();
+
+        archive.revert_to(5).unwrap();
+
+        assert_eq!(archive.archived_range().unwrap(), Some((5, 5)));
+        assert_eq!(archive.value_at(&key, 5).unwrap(), Some(ArchivedValue::Value(StarkFelt::from(1u64))));
+        assert_eq!(
+            archive.value_at(&ArchiveKey::Nonce(address(2)), 5).unwrap(),
+            Some(ArchivedValue::UnchangedUntil(5))
+        );
+
+        // The reverted blocks can be archived again
+        archive.store_state_diff(6, &nonce_diff(account, 3), |_| unreachable!()).unwrap();
+        assert_eq!(archive.value_at(&key, 6).unwrap(), Some(ArchivedValue::Value(StarkFelt::from(3u64))));
+
+        archive.revert_to(4).unwrap();
+        assert_eq!(archive.archived_range().unwrap(), None);
+        archive.store_state_diff(5, &nonce_diff(account, 4), |_| Some(StarkFelt::from(7u64))).unwrap();
+        assert_eq!(archive.value_at(&key, 4).unwrap(), Some(ArchivedValue::Value(StarkFelt::from(7u64))));
+    }
+
+    #[test]
+    fn store_state_diff_rejects_gaps() {
+        let archive = archive();
+
+        archive.store_state_diff(1, &ThinStateDiff::default(), |_| unreachable!()).unwrap();
+
+        assert!(matches!(
+            archive.store_state_diff(3, &ThinStateDiff::default(), |_| unreachable!()),
+            Err(DbError::NonContiguousStateArchive { expected: 2, got: 3 })
+        ));
+    }
+}
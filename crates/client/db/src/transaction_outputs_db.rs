@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use parity_scale_codec::{Decode, Encode};
+use sp_database::Database;
+use starknet_api::transaction::{Event, MessageToL1, TransactionHash};
+
+use crate::{DbError, DbHash};
+
+/// What the execution of a transaction produced, as served by the RPC
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode)]
+pub struct TransactionOutput {
+    pub events: Vec<Event>,
+    pub messages: Vec<MessageToL1>,
+    /// The revert reason, if the transaction was reverted
+    pub revert_error: Option<String>,
+}
+
+/// Allow interaction with the transaction outputs db
+///
+/// Keeps the events, messages to L1 and revert reasons of the transactions once they have been
+/// pruned from the runtime state.
+pub struct TransactionOutputsDb {
+    pub(crate) db: Arc<dyn Database<DbHash>>,
+}
+
+impl TransactionOutputsDb {
+    /// Store the outputs of the transactions of a block
+    pub fn store_outputs(&self, outputs: Vec<(TransactionHash, TransactionOutput)>) -> Result<(), DbError> {
+        let mut transaction = sp_database::Transaction::new();
+
+        for (tx_hash, output) in outputs {
+            transaction.set(crate::columns::TRANSACTION_OUTPUTS, &tx_hash.encode(), &output.encode());
+        }
+
+        self.db.commit(transaction)?;
+
+        Ok(())
+    }
+
+    /// Remove the outputs of the transactions of a reverted block
+    pub fn remove_outputs(&self, tx_hashes: &[TransactionHash]) -> Result<(), DbError> {
+        let mut transaction = sp_database::Transaction::new();
+
+        for tx_hash in tx_hashes {
+            transaction.remove(crate::columns::TRANSACTION_OUTPUTS, &tx_hash.encode());
+        }
+
+        self.db.commit(transaction)?;
+
+        Ok(())
+    }
+
+    /// Return the outputs of a transaction, or `None` if they have not been stored yet
+    pub fn get_output(&self, tx_hash: &TransactionHash) -> Result<Option<TransactionOutput>, DbError> {
+        match self.db.get(crate::columns::TRANSACTION_OUTPUTS, &tx_hash.encode()) {
+            Some(raw) => Ok(Some(TransactionOutput::decode(&mut &raw[..])?)),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,415 @@
+//! Conversions of the Starknet RPC types to the feeder gateway ones.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+use starknet_core::types::{
+    self as rpc, BlockStatus as RpcBlockStatus, DeclareTransaction as RpcDeclareTransaction, ExecutionResult,
+    FieldElement, InvokeTransaction as RpcInvokeTransaction, MaybePendingBlockWithTxs, MaybePendingStateUpdate,
+    MaybePendingTransactionReceipt, PendingTransactionReceipt, TransactionFinalityStatus as RpcFinalityStatus,
+};
+
+use crate::types::*;
+
+/// Fields shared by the receipts of every transaction type.
+struct ReceiptFields {
+    transaction_hash: FieldElement,
+    actual_fee: FieldElement,
+    messages_sent: Vec<rpc::MsgToL1>,
+    events: Vec<rpc::Event>,
+    execution_result: ExecutionResult,
+    execution_resources: rpc::ExecutionResources,
+    /// Hash, number and finality of the block of the transaction, unless pending.
+    block: Option<(FieldElement, u64, RpcFinalityStatus)>,
+}
+
+macro_rules! receipt_fields {
+    ($receipt:ident) => {
+        receipt_fields!($receipt, Some(($receipt.block_hash, $receipt.block_number, $receipt.finality_status)))
+    };
+    ($receipt:ident, $block:expr) => {
+        ReceiptFields {
+            block: $block,
+            transaction_hash: $receipt.transaction_hash,
+            actual_fee: $receipt.actual_fee,
+            messages_sent: $receipt.messages_sent,
+            events: $receipt.events,
+            execution_result: $receipt.execution_result,
+            execution_resources: $receipt.execution_resources,
+        }
+    };
+}
+
+fn receipt_fields(receipt: MaybePendingTransactionReceipt) -> ReceiptFields {
+    use rpc::TransactionReceipt as R;
+    use PendingTransactionReceipt as P;
+
+    match receipt {
+        MaybePendingTransactionReceipt::Receipt(receipt) => match receipt {
+            R::Invoke(r) => receipt_fields!(r),
+            R::L1Handler(r) => receipt_fields!(r),
+            R::Declare(r) => receipt_fields!(r),
+            R::Deploy(r) => receipt_fields!(r),
+            R::DeployAccount(r) => receipt_fields!(r),
+        },
+        MaybePendingTransactionReceipt::PendingReceipt(receipt) => match receipt {
+            P::Invoke(r) => receipt_fields!(r, None),
+            P::L1Handler(r) => receipt_fields!(r, None),
+            P::Declare(r) => receipt_fields!(r, None),
+            P::DeployAccount(r) => receipt_fields!(r, None),
+        },
+    }
+}
+
+/// Address of the contract deployed by the transaction of `receipt`, if any.
+pub(crate) fn receipt_contract_address(receipt: &MaybePendingTransactionReceipt) -> Option<FieldElement> {
+    use rpc::TransactionReceipt as R;
+
+    match receipt {
+        MaybePendingTransactionReceipt::Receipt(R::Deploy(r)) => Some(r.contract_address),
+        MaybePendingTransactionReceipt::Receipt(R::DeployAccount(r)) => Some(r.contract_address),
+        MaybePendingTransactionReceipt::PendingReceipt(PendingTransactionReceipt::DeployAccount(r)) => {
+            Some(r.contract_address)
+        }
+        _ => None,
+    }
+}
+
+/// Hash of the block of the transaction of `receipt`, unless pending.
+pub(crate) fn receipt_block_hash(receipt: &MaybePendingTransactionReceipt) -> Option<FieldElement> {
+    use rpc::TransactionReceipt as R;
+
+    match receipt {
+        MaybePendingTransactionReceipt::Receipt(R::Invoke(r)) => Some(r.block_hash),
+        MaybePendingTransactionReceipt::Receipt(R::L1Handler(r)) => Some(r.block_hash),
+        MaybePendingTransactionReceipt::Receipt(R::Declare(r)) => Some(r.block_hash),
+        MaybePendingTransactionReceipt::Receipt(R::Deploy(r)) => Some(r.block_hash),
+        MaybePendingTransactionReceipt::Receipt(R::DeployAccount(r)) => Some(r.block_hash),
+        MaybePendingTransactionReceipt::PendingReceipt(_) => None,
+    }
+}
+
+/// Hash of the transaction of `receipt`.
+pub(crate) fn receipt_transaction_hash(receipt: &MaybePendingTransactionReceipt) -> FieldElement {
+    use rpc::TransactionReceipt as R;
+    use PendingTransactionReceipt as P;
+
+    match receipt {
+        MaybePendingTransactionReceipt::Receipt(R::Invoke(r)) => r.transaction_hash,
+        MaybePendingTransactionReceipt::Receipt(R::L1Handler(r)) => r.transaction_hash,
+        MaybePendingTransactionReceipt::Receipt(R::Declare(r)) => r.transaction_hash,
+        MaybePendingTransactionReceipt::Receipt(R::Deploy(r)) => r.transaction_hash,
+        MaybePendingTransactionReceipt::Receipt(R::DeployAccount(r)) => r.transaction_hash,
+        MaybePendingTransactionReceipt::PendingReceipt(P::Invoke(r)) => r.transaction_hash,
+        MaybePendingTransactionReceipt::PendingReceipt(P::L1Handler(r)) => r.transaction_hash,
+        MaybePendingTransactionReceipt::PendingReceipt(P::Declare(r)) => r.transaction_hash,
+        MaybePendingTransactionReceipt::PendingReceipt(P::DeployAccount(r)) => r.transaction_hash,
+    }
+}
+
+pub(crate) fn receipt(receipt: MaybePendingTransactionReceipt, transaction_index: u64) -> TransactionReceipt {
+    let fields = receipt_fields(receipt);
+
+    let (execution_status, revert_error) = match fields.execution_result {
+        ExecutionResult::Succeeded => (TransactionExecutionStatus::Succeeded, None),
+        ExecutionResult::Reverted { reason } => (TransactionExecutionStatus::Reverted, Some(reason)),
+    };
+    // Pending transactions are accepted on L2 already
+    let finality_status = match fields.block {
+        Some((_, _, RpcFinalityStatus::AcceptedOnL1)) => TransactionFinalityStatus::AcceptedOnL1,
+        _ => TransactionFinalityStatus::AcceptedOnL2,
+    };
+    let status = match (execution_status, finality_status) {
+        (TransactionExecutionStatus::Reverted, _) => TransactionStatus::Reverted,
+        (_, TransactionFinalityStatus::AcceptedOnL1) => TransactionStatus::AcceptedOnL1,
+        _ => TransactionStatus::AcceptedOnL2,
+    };
+
+    TransactionReceipt {
+        status: Some(status),
+        finality_status: Some(finality_status),
+        execution_status: Some(execution_status),
+        block_hash: fields.block.map(|(block_hash, _, _)| block_hash),
+        block_number: fields.block.map(|(_, block_number, _)| block_number),
+        transaction_index: Some(transaction_index),
+        transaction_hash: fields.transaction_hash,
+        l2_to_l1_messages: fields
+            .messages_sent
+            .into_iter()
+            .map(|message| L2ToL1Message {
+                from_address: message.from_address,
+                to_address: message.to_address,
+                payload: message.payload,
+            })
+            .collect(),
+        events: fields
+            .events
+            .into_iter()
+            .map(|event| Event { from_address: event.from_address, keys: event.keys, data: event.data })
+            .collect(),
+        execution_resources: Some(execution_resources(fields.execution_resources)),
+        actual_fee: Some(fields.actual_fee),
+        revert_error,
+    }
+}
+
+fn execution_resources(resources: rpc::ExecutionResources) -> ExecutionResources {
+    let builtin_instance_counter = [
+        ("range_check_builtin", resources.range_check_builtin_applications),
+        ("pedersen_builtin", resources.pedersen_builtin_applications),
+        ("poseidon_builtin", resources.poseidon_builtin_applications),
+        ("ec_op_builtin", resources.ec_op_builtin_applications),
+        ("ecdsa_builtin", resources.ecdsa_builtin_applications),
+        ("bitwise_builtin", resources.bitwise_builtin_applications),
+        ("keccak_builtin", resources.keccak_builtin_applications),
+    ]
+    .into_iter()
+    .filter(|(_, applications)| *applications > 0)
+    .map(|(name, applications)| (name.to_string(), applications))
+    .collect::<BTreeMap<_, _>>();
+
+    ExecutionResources {
+        n_steps: resources.steps,
+        builtin_instance_counter,
+        n_memory_holes: resources.memory_holes.unwrap_or_default(),
+    }
+}
+
+/// Converts a transaction, deploying its contract at `contract_address` for the deploy ones.
+pub fn transaction(tx: rpc::Transaction, contract_address: Option<FieldElement>) -> Transaction {
+    match tx {
+        rpc::Transaction::Invoke(RpcInvokeTransaction::V0(tx)) => Transaction::Invoke(InvokeTransaction {
+            transaction_hash: tx.transaction_hash,
+            version: FieldElement::ZERO,
+            max_fee: tx.max_fee,
+            signature: tx.signature,
+            nonce: None,
+            sender_address: None,
+            contract_address: Some(tx.contract_address),
+            entry_point_selector: Some(tx.entry_point_selector),
+            calldata: tx.calldata,
+        }),
+        rpc::Transaction::Invoke(RpcInvokeTransaction::V1(tx)) => Transaction::Invoke(InvokeTransaction {
+            transaction_hash: tx.transaction_hash,
+            version: FieldElement::ONE,
+            max_fee: tx.max_fee,
+            signature: tx.signature,
+            nonce: Some(tx.nonce),
+            sender_address: Some(tx.sender_address),
+            contract_address: None,
+            entry_point_selector: None,
+            calldata: tx.calldata,
+        }),
+        rpc::Transaction::Declare(RpcDeclareTransaction::V0(tx)) => Transaction::Declare(DeclareTransaction {
+            transaction_hash: tx.transaction_hash,
+            version: FieldElement::ZERO,
+            max_fee: tx.max_fee,
+            signature: tx.signature,
+            nonce: FieldElement::ZERO,
+            class_hash: tx.class_hash,
+            compiled_class_hash: None,
+            sender_address: tx.sender_address,
+        }),
+        rpc::Transaction::Declare(RpcDeclareTransaction::V1(tx)) => Transaction::Declare(DeclareTransaction {
+            transaction_hash: tx.transaction_hash,
+            version: FieldElement::ONE,
+            max_fee: tx.max_fee,
+            signature: tx.signature,
+            nonce: tx.nonce,
+            class_hash: tx.class_hash,
+            compiled_class_hash: None,
+            sender_address: tx.sender_address,
+        }),
+        rpc::Transaction::Declare(RpcDeclareTransaction::V2(tx)) => Transaction::Declare(DeclareTransaction {
+            transaction_hash: tx.transaction_hash,
+            version: FieldElement::TWO,
+            max_fee: tx.max_fee,
+            signature: tx.signature,
+            nonce: tx.nonce,
+            class_hash: tx.class_hash,
+            compiled_class_hash: Some(tx.compiled_class_hash),
+            sender_address: tx.sender_address,
+        }),
+        rpc::Transaction::Deploy(tx) => Transaction::Deploy(DeployTransaction {
+            transaction_hash: tx.transaction_hash,
+            version: FieldElement::from(tx.version),
+            contract_address,
+            contract_address_salt: tx.contract_address_salt,
+            class_hash: tx.class_hash,
+            constructor_calldata: tx.constructor_calldata,
+        }),
+        rpc::Transaction::DeployAccount(tx) => Transaction::DeployAccount(DeployAccountTransaction {
+            transaction_hash: tx.transaction_hash,
+            version: FieldElement::ONE,
+            max_fee: tx.max_fee,
+            signature: tx.signature,
+            nonce: tx.nonce,
+            contract_address,
+            contract_address_salt: tx.contract_address_salt,
+            class_hash: tx.class_hash,
+            constructor_calldata: tx.constructor_calldata,
+        }),
+        rpc::Transaction::L1Handler(tx) => Transaction::L1Handler(L1HandlerTransaction {
+            transaction_hash: tx.transaction_hash,
+            version: FieldElement::from(tx.version),
+            contract_address: tx.contract_address,
+            entry_point_selector: tx.entry_point_selector,
+            nonce: FieldElement::from(tx.nonce),
+            calldata: tx.calldata,
+        }),
+    }
+}
+
+/// Converts a block, given the receipts of its transactions in order.
+pub fn block(block: MaybePendingBlockWithTxs, receipts: Vec<MaybePendingTransactionReceipt>) -> Block {
+    let (mut gateway_block, transactions) = match block {
+        MaybePendingBlockWithTxs::Block(block) => (
+            Block {
+                block_hash: Some(block.block_hash),
+                parent_block_hash: block.parent_hash,
+                block_number: Some(block.block_number),
+                state_root: Some(block.new_root),
+                status: block_status(block.status),
+                timestamp: block.timestamp,
+                sequencer_address: block.sequencer_address,
+                gas_price: block.l1_gas_price.price_in_wei.into(),
+                strk_l1_gas_price: block.l1_gas_price.price_in_strk.map(FieldElement::from),
+                starknet_version: block.starknet_version,
+                transactions: Vec::new(),
+                transaction_receipts: Vec::new(),
+            },
+            block.transactions,
+        ),
+        MaybePendingBlockWithTxs::PendingBlock(block) => (
+            Block {
+                block_hash: None,
+                parent_block_hash: block.parent_hash,
+                block_number: None,
+                state_root: None,
+                status: BlockStatus::Pending,
+                timestamp: block.timestamp,
+                sequencer_address: block.sequencer_address,
+                gas_price: block.l1_gas_price.price_in_wei.into(),
+                strk_l1_gas_price: block.l1_gas_price.price_in_strk.map(FieldElement::from),
+                starknet_version: block.starknet_version,
+                transactions: Vec::new(),
+                transaction_receipts: Vec::new(),
+            },
+            block.transactions,
+        ),
+    };
+
+    gateway_block.transactions = transactions
+        .into_iter()
+        .zip(&receipts)
+        .map(|(tx, receipt)| transaction(tx, receipt_contract_address(receipt)))
+        .collect();
+    gateway_block.transaction_receipts = receipts
+        .into_iter()
+        .enumerate()
+        .map(|(index, receipt)| {
+            // The block tells the status and position of its transactions
+            TransactionReceipt {
+                status: None,
+                finality_status: None,
+                block_hash: None,
+                block_number: None,
+                ..self::receipt(receipt, index as u64)
+            }
+        })
+        .collect();
+
+    gateway_block
+}
+
+fn block_status(status: RpcBlockStatus) -> BlockStatus {
+    match status {
+        RpcBlockStatus::Pending => BlockStatus::Pending,
+        RpcBlockStatus::AcceptedOnL2 => BlockStatus::AcceptedOnL2,
+        RpcBlockStatus::AcceptedOnL1 => BlockStatus::AcceptedOnL1,
+        RpcBlockStatus::Rejected => BlockStatus::Rejected,
+    }
+}
+
+pub fn state_update(state_update: MaybePendingStateUpdate) -> StateUpdate {
+    let (block_hash, new_root, old_root, state_diff) = match state_update {
+        MaybePendingStateUpdate::Update(update) => {
+            (Some(update.block_hash), Some(update.new_root), update.old_root, update.state_diff)
+        }
+        MaybePendingStateUpdate::PendingUpdate(update) => (None, None, update.old_root, update.state_diff),
+    };
+
+    let state_diff = StateDiff {
+        storage_diffs: state_diff
+            .storage_diffs
+            .into_iter()
+            .map(|diff| {
+                let entries = diff
+                    .storage_entries
+                    .into_iter()
+                    .map(|entry| StorageEntry { key: entry.key, value: entry.value })
+                    .collect();
+                (diff.address, entries)
+            })
+            .collect(),
+        nonces: state_diff.nonces.into_iter().map(|update| (update.contract_address, update.nonce)).collect(),
+        deployed_contracts: state_diff
+            .deployed_contracts
+            .into_iter()
+            .map(|item| DeployedContract { address: item.address, class_hash: item.class_hash })
+            .collect(),
+        old_declared_contracts: state_diff.deprecated_declared_classes,
+        declared_classes: state_diff
+            .declared_classes
+            .into_iter()
+            .map(|item| DeclaredClass { class_hash: item.class_hash, compiled_class_hash: item.compiled_class_hash })
+            .collect(),
+        replaced_classes: state_diff
+            .replaced_classes
+            .into_iter()
+            .map(|item| DeployedContract { address: item.contract_address, class_hash: item.class_hash })
+            .collect(),
+    };
+
+    StateUpdate { block_hash, new_root, old_root, state_diff }
+}
+
+/// Converts a class, uncompressing the program of the Cairo 0 ones.
+pub(crate) fn contract_class(class: rpc::ContractClass) -> Result<ContractClass, GatewayError> {
+    let class = match class {
+        rpc::ContractClass::Sierra(class) => ContractClass::Sierra(class),
+        rpc::ContractClass::Legacy(class) => {
+            let mut program = Vec::new();
+            GzDecoder::new(&class.program[..]).read_to_end(&mut program).map_err(|e| {
+                GatewayError::new(ErrorCode::InternalError, format!("Failed to decompress the program: {e}"))
+            })?;
+            let program = serde_json::from_slice(&program)
+                .map_err(|e| GatewayError::new(ErrorCode::InternalError, format!("Invalid program: {e}")))?;
+            let abi = class
+                .abi
+                .map(serde_json::to_value)
+                .transpose()
+                .map_err(|e| GatewayError::new(ErrorCode::InternalError, format!("Invalid ABI: {e}")))?;
+
+            let entry_points = |entry_points: Vec<rpc::LegacyContractEntryPoint>| {
+                entry_points
+                    .into_iter()
+                    .map(|entry_point| LegacyEntryPoint {
+                        selector: entry_point.selector,
+                        offset: FieldElement::from(entry_point.offset),
+                    })
+                    .collect()
+            };
+            let entry_points_by_type = LegacyEntryPointsByType {
+                constructor: entry_points(class.entry_points_by_type.constructor),
+                external: entry_points(class.entry_points_by_type.external),
+                l1_handler: entry_points(class.entry_points_by_type.l1_handler),
+            };
+
+            ContractClass::Legacy(LegacyContractClass { program, entry_points_by_type, abi })
+        }
+    };
+
+    Ok(class)
+}
@@ -0,0 +1,14 @@
+//! Starknet feeder gateway API.
+//!
+//! Serves the `/feeder_gateway/*` endpoints much of the Starknet tooling reads, full nodes
+//! syncing a chain among them, with the JSON shapes of the gateway. The data comes from the
+//! Starknet RPC of the node.
+
+#[cfg(test)]
+mod tests;
+
+pub mod conversions;
+mod server;
+pub mod types;
+
+pub use server::run_feeder_gateway;
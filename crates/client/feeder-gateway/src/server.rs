@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use jsonrpsee::types::error::CallError;
+use mc_rpc_core::{StarknetBlockReceipts, StarknetReadRpcApiServer};
+use serde::Serialize;
+use starknet_core::types::{BlockId, BlockTag, FieldElement, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs};
+
+use crate::conversions;
+use crate::types::{BlockSignature, ErrorCode, GatewayError, TransactionReceipt};
+
+/// Codes of the Starknet RPC errors answered by the gateway endpoints.
+const RPC_CONTRACT_NOT_FOUND: i32 = 20;
+const RPC_BLOCK_NOT_FOUND: i32 = 24;
+const RPC_CLASS_HASH_NOT_FOUND: i32 = 28;
+const RPC_TXN_HASH_NOT_FOUND: i32 = 29;
+
+type GatewayResult<T> = Result<T, GatewayError>;
+
+/// Query parameters of a request.
+type Params = HashMap<String, String>;
+
+impl From<jsonrpsee::core::Error> for GatewayError {
+    fn from(error: jsonrpsee::core::Error) -> Self {
+        let code = match &error {
+            jsonrpsee::core::Error::Call(CallError::Custom(error)) => match error.code() {
+                RPC_BLOCK_NOT_FOUND => ErrorCode::BlockNotFound,
+                RPC_CLASS_HASH_NOT_FOUND => ErrorCode::UndeclaredClass,
+                RPC_CONTRACT_NOT_FOUND => ErrorCode::UninitializedContract,
+                _ => ErrorCode::InternalError,
+            },
+            _ => ErrorCode::InternalError,
+        };
+        let message = match &error {
+            jsonrpsee::core::Error::Call(CallError::Custom(error)) => error.message().to_string(),
+            error => error.to_string(),
+        };
+        GatewayError::new(code, message)
+    }
+}
+
+/// Serve the feeder gateway endpoints on `addr`, answered by the Starknet RPC `rpc`.
+pub async fn run_feeder_gateway<R>(addr: SocketAddr, rpc: Arc<R>)
+where
+    R: StarknetReadRpcApiServer + StarknetBlockReceipts,
+{
+    let make_service = make_service_fn(move |_| {
+        let rpc = rpc.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let rpc = rpc.clone();
+                async move { Ok::<_, Infallible>(handle(rpc.as_ref(), request).await) }
+            }))
+        }
+    });
+
+    let server = match Server::try_bind(&addr) {
+        Ok(builder) => builder.serve(make_service),
+        Err(e) => {
+            log::error!("Failed to start the feeder gateway on {addr}: {e}");
+            return;
+        }
+    };
+    log::info!("Feeder gateway listening on {addr}");
+
+    if let Err(e) = server.await {
+        log::error!("Feeder gateway stopped: {e}");
+    }
+}
+
+async fn handle<R: StarknetReadRpcApiServer + StarknetBlockReceipts>(
+    rpc: &R,
+    request: Request<Body>,
+) -> Response<Body> {
+    if request.method() != Method::GET {
+        return empty_response(StatusCode::METHOD_NOT_ALLOWED);
+    }
+    let params: Params = request
+        .uri()
+        .query()
+        .map(|query| url::form_urlencoded::parse(query.as_bytes()).into_owned().collect())
+        .unwrap_or_default();
+
+    let response = match request.uri().path().trim_end_matches('/') {
+        "/feeder_gateway/get_block" => get_block(rpc, &params),
+        "/feeder_gateway/get_state_update" => get_state_update(rpc, &params),
+        "/feeder_gateway/get_class_by_hash" => get_class_by_hash(rpc, &params),
+        "/feeder_gateway/get_transaction_receipt" => get_transaction_receipt(rpc, &params).await,
+        "/feeder_gateway/get_signature" => get_signature(rpc, &params),
+        _ => return empty_response(StatusCode::NOT_FOUND),
+    };
+
+    match response {
+        Ok(body) => json_response(StatusCode::OK, body),
+        Err(error) => {
+            let status = match error.code {
+                ErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+                _ => StatusCode::BAD_REQUEST,
+            };
+            json_response(status, serde_json::to_vec(&error).unwrap_or_default())
+        }
+    }
+}
+
+fn get_block<R: StarknetReadRpcApiServer + StarknetBlockReceipts>(rpc: &R, params: &Params) -> GatewayResult<Vec<u8>> {
+    let block = rpc.get_block_with_txs(block_id(params)?)?;
+    let (receipts, transactions) = match &block {
+        // By hash, for `latest` not to move between the reads
+        MaybePendingBlockWithTxs::Block(block) => {
+            (rpc.get_block_receipts(BlockId::Hash(block.block_hash))?, &block.transactions)
+        }
+        MaybePendingBlockWithTxs::PendingBlock(block) => {
+            (rpc.get_block_receipts(BlockId::Tag(BlockTag::Pending))?, &block.transactions)
+        }
+    };
+    // The pending transactions can change between the reads
+    if !receipts
+        .iter()
+        .map(conversions::receipt_transaction_hash)
+        .eq(transactions.iter().map(|transaction| *transaction.transaction_hash()))
+    {
+        return Err(GatewayError::new(ErrorCode::InternalError, "The block changed while it was read"));
+    }
+
+    json(&conversions::block(block, receipts))
+}
+
+fn get_state_update<R: StarknetReadRpcApiServer>(rpc: &R, params: &Params) -> GatewayResult<Vec<u8>> {
+    let state_update = rpc.get_state_update(block_id(params)?)?;
+    json(&conversions::state_update(state_update))
+}
+
+fn get_class_by_hash<R: StarknetReadRpcApiServer>(rpc: &R, params: &Params) -> GatewayResult<Vec<u8>> {
+    let class_hash = felt_param(params, "classHash")?;
+    let class = rpc.get_class(block_id(params)?, class_hash)?;
+    json(&conversions::contract_class(class)?)
+}
+
+async fn get_transaction_receipt<R: StarknetReadRpcApiServer>(rpc: &R, params: &Params) -> GatewayResult<Vec<u8>> {
+    let transaction_hash = felt_param(params, "transactionHash")?;
+    let receipt = match rpc.get_transaction_receipt(transaction_hash).await {
+        Ok(receipt) => receipt,
+        Err(jsonrpsee::core::Error::Call(CallError::Custom(error))) if error.code() == RPC_TXN_HASH_NOT_FOUND => {
+            return json(&TransactionReceipt::not_received(transaction_hash));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    // The index of the transaction is not part of the RPC receipt
+    let block_id = match conversions::receipt_block_hash(&receipt) {
+        Some(block_hash) => BlockId::Hash(block_hash),
+        None => BlockId::Tag(BlockTag::Pending),
+    };
+    let transaction_hashes = match rpc.get_block_with_tx_hashes(block_id)? {
+        MaybePendingBlockWithTxHashes::Block(block) => block.transactions,
+        MaybePendingBlockWithTxHashes::PendingBlock(block) => block.transactions,
+    };
+    let transaction_index = transaction_hashes
+        .iter()
+        .position(|hash| *hash == transaction_hash)
+        .ok_or_else(|| GatewayError::new(ErrorCode::InternalError, "The transaction is missing from its block"))?;
+
+    json(&conversions::receipt(receipt, transaction_index as u64))
+}
+
+/// Madara blocks are not signed, their signature is empty.
+fn get_signature<R: StarknetReadRpcApiServer>(rpc: &R, params: &Params) -> GatewayResult<Vec<u8>> {
+    let block_hash = match rpc.get_block_with_tx_hashes(block_id(params)?)? {
+        MaybePendingBlockWithTxHashes::Block(block) => block.block_hash,
+        MaybePendingBlockWithTxHashes::PendingBlock(_) => {
+            return Err(GatewayError::new(ErrorCode::MalformedRequest, "The pending block has no signature"));
+        }
+    };
+    json(&BlockSignature { block_hash, signature: Vec::new() })
+}
+
+/// Reads the block of a request, either its `blockHash` or its `blockNumber`, which can also be
+/// `latest` or `pending`. Defaults to the latest block.
+pub(crate) fn block_id(params: &Params) -> GatewayResult<BlockId> {
+    if params.contains_key("blockHash") {
+        return felt_param(params, "blockHash").map(BlockId::Hash);
+    }
+    let block_id =
+        match params.get("blockNumber").map(String::as_str) {
+            None | Some("latest") => BlockId::Tag(BlockTag::Latest),
+            Some("pending") => BlockId::Tag(BlockTag::Pending),
+            Some(number) => BlockId::Number(number.parse().map_err(|_| {
+                GatewayError::new(ErrorCode::MalformedRequest, format!("Invalid block number '{number}'"))
+            })?),
+        };
+    Ok(block_id)
+}
+
+pub(crate) fn felt_param(params: &Params, name: &str) -> GatewayResult<FieldElement> {
+    let value = params
+        .get(name)
+        .ok_or_else(|| GatewayError::new(ErrorCode::MalformedRequest, format!("Missing parameter '{name}'")))?;
+    FieldElement::from_hex_be(value)
+        .map_err(|_| GatewayError::new(ErrorCode::MalformedRequest, format!("Invalid {name} '{value}'")))
+}
+
+fn json<T: Serialize>(value: &T) -> GatewayResult<Vec<u8>> {
+    serde_json::to_vec(value).map_err(|e| GatewayError::new(ErrorCode::InternalError, e.to_string()))
+}
+
+fn json_response(status: StatusCode, body: Vec<u8>) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+fn empty_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
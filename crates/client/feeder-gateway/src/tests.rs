@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::io::Write;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::json;
+use starknet_core::types::{
+    BlockId, BlockTag, CompressedLegacyContractClass, ContractStorageDiffItem, DeployedContractItem,
+    ExecutionResources, ExecutionResult, FieldElement, InvokeTransaction, InvokeTransactionReceipt,
+    InvokeTransactionV1, LegacyContractEntryPoint, LegacyEntryPointsByType, MaybePendingStateUpdate,
+    MaybePendingTransactionReceipt, NonceUpdate, StateDiff, StateUpdate, StorageEntry, Transaction,
+    TransactionFinalityStatus, TransactionReceipt,
+};
+
+use crate::conversions;
+use crate::server::block_id;
+use crate::types::{self, ErrorCode, GatewayError};
+
+fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+}
+
+fn felt(value: u64) -> FieldElement {
+    FieldElement::from(value)
+}
+
+#[test]
+fn block_id_is_read_from_the_query() {
+    assert_eq!(block_id(&params(&[])).unwrap(), BlockId::Tag(BlockTag::Latest));
+    assert_eq!(block_id(&params(&[("blockNumber", "pending")])).unwrap(), BlockId::Tag(BlockTag::Pending));
+    assert_eq!(block_id(&params(&[("blockNumber", "12")])).unwrap(), BlockId::Number(12));
+    assert_eq!(block_id(&params(&[("blockHash", "0x2a")])).unwrap(), BlockId::Hash(felt(42)));
+
+    let error = block_id(&params(&[("blockNumber", "twelve")])).unwrap_err();
+    assert_eq!(error.code, ErrorCode::MalformedRequest);
+}
+
+#[test]
+fn errors_have_the_gateway_codes() {
+    let error = GatewayError::new(ErrorCode::BlockNotFound, "Block not found");
+
+    assert_eq!(
+        serde_json::to_value(error).unwrap(),
+        json!({ "code": "StarknetErrorCode.BLOCK_NOT_FOUND", "message": "Block not found" })
+    );
+}
+
+#[test]
+fn invoke_transactions_are_invoke_functions() {
+    let tx = Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1 {
+        transaction_hash: felt(1),
+        max_fee: felt(2),
+        signature: vec![felt(3)],
+        nonce: felt(4),
+        sender_address: felt(5),
+        calldata: vec![felt(6), felt(7)],
+    }));
+
+    assert_eq!(
+        serde_json::to_value(conversions::transaction(tx, None)).unwrap(),
+        json!({
+            "type": "INVOKE_FUNCTION",
+            "transaction_hash": "0x1",
+            "version": "0x1",
+            "max_fee": "0x2",
+            "signature": ["0x3"],
+            "nonce": "0x4",
+            "sender_address": "0x5",
+            "calldata": ["0x6", "0x7"],
+        })
+    );
+}
+
+#[test]
+fn receipts_tell_the_revert_error() {
+    let receipt = MaybePendingTransactionReceipt::Receipt(TransactionReceipt::Invoke(InvokeTransactionReceipt {
+        transaction_hash: felt(1),
+        actual_fee: felt(100),
+        finality_status: TransactionFinalityStatus::AcceptedOnL2,
+        block_hash: felt(2),
+        block_number: 3,
+        messages_sent: Vec::new(),
+        events: Vec::new(),
+        execution_result: ExecutionResult::Reverted { reason: "Out of gas".to_string() },
+        execution_resources: ExecutionResources {
+            steps: 10,
+            memory_holes: Some(1),
+            range_check_builtin_applications: 2,
+            pedersen_builtin_applications: 0,
+            poseidon_builtin_applications: 0,
+            ec_op_builtin_applications: 0,
+            ecdsa_builtin_applications: 0,
+            bitwise_builtin_applications: 0,
+            keccak_builtin_applications: 0,
+        },
+    }));
+
+    assert_eq!(
+        serde_json::to_value(conversions::receipt(receipt, 4)).unwrap(),
+        json!({
+            "status": "REVERTED",
+            "finality_status": "ACCEPTED_ON_L2",
+            "execution_status": "REVERTED",
+            "block_hash": "0x2",
+            "block_number": 3,
+            "transaction_index": 4,
+            "transaction_hash": "0x1",
+            "l2_to_l1_messages": [],
+            "events": [],
+            "execution_resources": {
+                "n_steps": 10,
+                "builtin_instance_counter": { "range_check_builtin": 2 },
+                "n_memory_holes": 1,
+            },
+            "actual_fee": "0x64",
+            "revert_error": "Out of gas",
+        })
+    );
+}
+
+#[test]
+fn unknown_transactions_are_not_received() {
+    assert_eq!(
+        serde_json::to_value(types::TransactionReceipt::not_received(felt(1))).unwrap(),
+        json!({
+            "status": "NOT_RECEIVED",
+            "finality_status": "NOT_RECEIVED",
+            "transaction_hash": "0x1",
+            "l2_to_l1_messages": [],
+            "events": [],
+        })
+    );
+}
+
+#[test]
+fn state_diffs_are_keyed_by_contract() {
+    let state_update = MaybePendingStateUpdate::Update(StateUpdate {
+        block_hash: felt(1),
+        new_root: felt(2),
+        old_root: felt(3),
+        state_diff: StateDiff {
+            storage_diffs: vec![ContractStorageDiffItem {
+                address: felt(10),
+                storage_entries: vec![StorageEntry { key: felt(11), value: felt(12) }],
+            }],
+            deprecated_declared_classes: vec![felt(20)],
+            declared_classes: Vec::new(),
+            deployed_contracts: vec![DeployedContractItem { address: felt(30), class_hash: felt(20) }],
+            replaced_classes: Vec::new(),
+            nonces: vec![NonceUpdate { contract_address: felt(10), nonce: felt(1) }],
+        },
+    });
+
+    assert_eq!(
+        serde_json::to_value(conversions::state_update(state_update)).unwrap(),
+        json!({
+            "block_hash": "0x1",
+            "new_root": "0x2",
+            "old_root": "0x3",
+            "state_diff": {
+                "storage_diffs": { "0xa": [{ "key": "0xb", "value": "0xc" }] },
+                "nonces": { "0xa": "0x1" },
+                "deployed_contracts": [{ "address": "0x1e", "class_hash": "0x14" }],
+                "old_declared_contracts": ["0x14"],
+                "declared_classes": [],
+                "replaced_classes": [],
+            },
+        })
+    );
+}
+
+#[test]
+fn legacy_programs_are_uncompressed() {
+    let program = json!({ "builtins": ["pedersen"], "data": ["0x1"] });
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&serde_json::to_vec(&program).unwrap()).unwrap();
+
+    let class = starknet_core::types::ContractClass::Legacy(CompressedLegacyContractClass {
+        program: encoder.finish().unwrap(),
+        entry_points_by_type: LegacyEntryPointsByType {
+            constructor: Vec::new(),
+            external: vec![LegacyContractEntryPoint { offset: 58, selector: felt(1) }],
+            l1_handler: Vec::new(),
+        },
+        abi: None,
+    });
+
+    assert_eq!(
+        serde_json::to_value(conversions::contract_class(class).unwrap()).unwrap(),
+        json!({
+            "program": program,
+            "entry_points_by_type": {
+                "CONSTRUCTOR": [],
+                "EXTERNAL": [{ "selector": "0x1", "offset": "0x3a" }],
+                "L1_HANDLER": [],
+            },
+        })
+    );
+}
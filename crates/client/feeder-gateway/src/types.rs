@@ -0,0 +1,439 @@
+//! JSON shapes of the feeder gateway responses.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet_core::serde::unsigned_field_element::UfeHex;
+use starknet_core::types::{FieldElement, FlattenedSierraClass};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BlockStatus {
+    Pending,
+    AcceptedOnL2,
+    AcceptedOnL1,
+    Rejected,
+}
+
+/// Response of `get_block`. The pending block has no hash, number nor state root.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Block {
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<FieldElement>,
+    #[serde_as(as = "UfeHex")]
+    pub parent_block_hash: FieldElement,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_root: Option<FieldElement>,
+    pub status: BlockStatus,
+    pub timestamp: u64,
+    #[serde_as(as = "UfeHex")]
+    pub sequencer_address: FieldElement,
+    /// L1 gas price in wei.
+    #[serde_as(as = "UfeHex")]
+    pub gas_price: FieldElement,
+    /// L1 gas price in fri.
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strk_l1_gas_price: Option<FieldElement>,
+    pub starknet_version: String,
+    pub transactions: Vec<Transaction>,
+    pub transaction_receipts: Vec<TransactionReceipt>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Transaction {
+    #[serde(rename = "INVOKE_FUNCTION")]
+    Invoke(InvokeTransaction),
+    Declare(DeclareTransaction),
+    Deploy(DeployTransaction),
+    DeployAccount(DeployAccountTransaction),
+    L1Handler(L1HandlerTransaction),
+}
+
+impl Transaction {
+    pub fn transaction_hash(&self) -> FieldElement {
+        match self {
+            Transaction::Invoke(tx) => tx.transaction_hash,
+            Transaction::Declare(tx) => tx.transaction_hash,
+            Transaction::Deploy(tx) => tx.transaction_hash,
+            Transaction::DeployAccount(tx) => tx.transaction_hash,
+            Transaction::L1Handler(tx) => tx.transaction_hash,
+        }
+    }
+
+    /// The signature of the transaction, empty for the transactions without one.
+    pub fn signature(&self) -> &[FieldElement] {
+        match self {
+            Transaction::Invoke(tx) => &tx.signature,
+            Transaction::Declare(tx) => &tx.signature,
+            Transaction::DeployAccount(tx) => &tx.signature,
+            Transaction::Deploy(_) | Transaction::L1Handler(_) => &[],
+        }
+    }
+}
+
+/// An invoke transaction. Version 0 calls `entry_point_selector` of `contract_address`, version 1
+/// calls the `__execute__` entry point of `sender_address`.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvokeTransaction {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub version: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub max_fee: FieldElement,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub signature: Vec<FieldElement>,
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<FieldElement>,
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender_address: Option<FieldElement>,
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<FieldElement>,
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_point_selector: Option<FieldElement>,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub calldata: Vec<FieldElement>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeclareTransaction {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub version: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub max_fee: FieldElement,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub signature: Vec<FieldElement>,
+    #[serde_as(as = "UfeHex")]
+    pub nonce: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: FieldElement,
+    /// Set from version 2, declaring Cairo 1 classes.
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiled_class_hash: Option<FieldElement>,
+    #[serde_as(as = "UfeHex")]
+    pub sender_address: FieldElement,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeployTransaction {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub version: FieldElement,
+    /// Taken from the receipt of the transaction.
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<FieldElement>,
+    #[serde_as(as = "UfeHex")]
+    pub contract_address_salt: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: FieldElement,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub constructor_calldata: Vec<FieldElement>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeployAccountTransaction {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub version: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub max_fee: FieldElement,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub signature: Vec<FieldElement>,
+    #[serde_as(as = "UfeHex")]
+    pub nonce: FieldElement,
+    /// Taken from the receipt of the transaction.
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<FieldElement>,
+    #[serde_as(as = "UfeHex")]
+    pub contract_address_salt: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: FieldElement,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub constructor_calldata: Vec<FieldElement>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct L1HandlerTransaction {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub version: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub contract_address: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub entry_point_selector: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub nonce: FieldElement,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub calldata: Vec<FieldElement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionStatus {
+    NotReceived,
+    Received,
+    Pending,
+    Rejected,
+    Reverted,
+    AcceptedOnL2,
+    AcceptedOnL1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionFinalityStatus {
+    NotReceived,
+    Received,
+    AcceptedOnL2,
+    AcceptedOnL1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionExecutionStatus {
+    Succeeded,
+    Reverted,
+    Rejected,
+}
+
+/// Response of `get_transaction_receipt`, also listed by the blocks without their status and
+/// block fields.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionReceipt {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<TransactionStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finality_status: Option<TransactionFinalityStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_status: Option<TransactionExecutionStatus>,
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<FieldElement>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_index: Option<u64>,
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: FieldElement,
+    #[serde(default)]
+    pub l2_to_l1_messages: Vec<L2ToL1Message>,
+    #[serde(default)]
+    pub events: Vec<Event>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_resources: Option<ExecutionResources>,
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual_fee: Option<FieldElement>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_error: Option<String>,
+}
+
+impl TransactionReceipt {
+    /// Receipt of a transaction the node does not know of.
+    pub fn not_received(transaction_hash: FieldElement) -> Self {
+        Self {
+            status: Some(TransactionStatus::NotReceived),
+            finality_status: Some(TransactionFinalityStatus::NotReceived),
+            execution_status: None,
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            transaction_hash,
+            l2_to_l1_messages: Vec::new(),
+            events: Vec::new(),
+            execution_resources: None,
+            actual_fee: None,
+            revert_error: None,
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct L2ToL1Message {
+    #[serde_as(as = "UfeHex")]
+    pub from_address: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub to_address: FieldElement,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub payload: Vec<FieldElement>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    #[serde_as(as = "UfeHex")]
+    pub from_address: FieldElement,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub keys: Vec<FieldElement>,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub data: Vec<FieldElement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionResources {
+    pub n_steps: u64,
+    /// Applications of the builtins used, by name, e.g. `pedersen_builtin`.
+    pub builtin_instance_counter: BTreeMap<String, u64>,
+    pub n_memory_holes: u64,
+}
+
+/// Response of `get_state_update`. The state update of the pending block has no block hash nor
+/// new root.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateUpdate {
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<FieldElement>,
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_root: Option<FieldElement>,
+    #[serde_as(as = "UfeHex")]
+    pub old_root: FieldElement,
+    pub state_diff: StateDiff,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDiff {
+    /// Storage updates, by contract address.
+    #[serde_as(as = "HashMap<UfeHex, _>")]
+    pub storage_diffs: HashMap<FieldElement, Vec<StorageEntry>>,
+    /// Nonces, by contract address.
+    #[serde_as(as = "HashMap<UfeHex, UfeHex>")]
+    pub nonces: HashMap<FieldElement, FieldElement>,
+    pub deployed_contracts: Vec<DeployedContract>,
+    /// Declared Cairo 0 classes.
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub old_declared_contracts: Vec<FieldElement>,
+    pub declared_classes: Vec<DeclaredClass>,
+    pub replaced_classes: Vec<DeployedContract>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageEntry {
+    #[serde_as(as = "UfeHex")]
+    pub key: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub value: FieldElement,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeployedContract {
+    #[serde_as(as = "UfeHex")]
+    pub address: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: FieldElement,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeclaredClass {
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub compiled_class_hash: FieldElement,
+}
+
+/// Response of `get_class_by_hash`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ContractClass {
+    Sierra(FlattenedSierraClass),
+    Legacy(LegacyContractClass),
+}
+
+/// A Cairo 0 class, with its program uncompressed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LegacyContractClass {
+    pub program: serde_json::Value,
+    pub entry_points_by_type: LegacyEntryPointsByType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abi: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LegacyEntryPointsByType {
+    #[serde(rename = "CONSTRUCTOR")]
+    pub constructor: Vec<LegacyEntryPoint>,
+    #[serde(rename = "EXTERNAL")]
+    pub external: Vec<LegacyEntryPoint>,
+    #[serde(rename = "L1_HANDLER")]
+    pub l1_handler: Vec<LegacyEntryPoint>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LegacyEntryPoint {
+    #[serde_as(as = "UfeHex")]
+    pub selector: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub offset: FieldElement,
+}
+
+/// Response of `get_signature`.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockSignature {
+    #[serde_as(as = "UfeHex")]
+    pub block_hash: FieldElement,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub signature: Vec<FieldElement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorCode {
+    #[serde(rename = "StarknetErrorCode.BLOCK_NOT_FOUND")]
+    BlockNotFound,
+    #[serde(rename = "StarknetErrorCode.UNDECLARED_CLASS")]
+    UndeclaredClass,
+    #[serde(rename = "StarknetErrorCode.UNINITIALIZED_CONTRACT")]
+    UninitializedContract,
+    #[serde(rename = "StarkErrorCode.MALFORMED_REQUEST")]
+    MalformedRequest,
+    #[serde(rename = "StarknetErrorCode.INTERNAL_ERROR")]
+    InternalError,
+}
+
+/// Body of the error responses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[error("{code:?}: {message}")]
+pub struct GatewayError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl GatewayError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
@@ -0,0 +1,237 @@
+//! Fork mode: a local chain built on top of the state of a remote Starknet chain.
+//!
+//! The runtime answers the state reads it misses locally through the `mp-fork` host functions,
+//! served here from the Starknet JSON-RPC endpoint of the forked chain at the fork block. The
+//! remote values are cached in memory, and in the local state by the blocks reading them.
+//!
+//! The host functions are called from the synchronous runtime calls, possibly made from within
+//! an async task, so the requests are run by a worker thread of their own.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::{Arc, Mutex};
+
+use blockifier::execution::contract_class::ContractClass;
+use futures::future::BoxFuture;
+use mp_felt::Felt252Wrapper;
+use mp_fork::{ForkedStateExt, ForkedStateReader};
+use mp_transactions::from_broadcasted_transactions::{compile_sierra_contract_class, to_blockifier_contract_class};
+use sc_client_api::execution_extensions::ExtensionsFactory;
+use sp_externalities::Extensions;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+use starknet_api::api_core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+use starknet_core::types::{BlockId, ContractClass as CoreContractClass, FieldElement, StarknetError};
+use starknet_providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use starknet_providers::{MaybeUnknownErrorCode, Provider, ProviderError, StarknetErrorWithMessage};
+use url::Url;
+
+/// Requests waiting for the worker before the runtime calls block on sending theirs.
+const MAX_PENDING_REQUESTS: usize = 64;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to start the fork worker: {0}")]
+    Worker(#[from] std::io::Error),
+    #[error("Failed to fetch the latest block of the forked chain: {0}")]
+    ForkBlock(String),
+}
+
+/// Fork mode configuration.
+#[derive(Debug, Clone)]
+pub struct ForkConfig {
+    /// Starknet JSON-RPC endpoint of the forked chain.
+    pub url: Url,
+    /// Block the state is forked at, the latest one when `None`.
+    pub block_number: Option<u64>,
+}
+
+#[derive(Default)]
+struct Cache {
+    storage: HashMap<(ContractAddress, StorageKey), StarkFelt>,
+    nonces: HashMap<ContractAddress, Nonce>,
+    class_hashes: HashMap<ContractAddress, ClassHash>,
+    /// The classes and compiled class hashes, `None` when missing in the forked chain
+    contract_classes: HashMap<ClassHash, Option<(ContractClass, Option<CompiledClassHash>)>>,
+}
+
+/// The state of the forked chain at the fork block.
+#[derive(Clone)]
+pub struct ForkedState {
+    provider: Arc<JsonRpcClient<HttpTransport>>,
+    requests: SyncSender<BoxFuture<'static, ()>>,
+    block_number: u64,
+    cache: Arc<Mutex<Cache>>,
+}
+
+impl ForkedState {
+    /// Starts the worker fetching the state of the forked chain, resolving the fork block.
+    pub fn new(config: ForkConfig) -> Result<Self, Error> {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        let (requests, receiver) = mpsc::sync_channel::<BoxFuture<'static, ()>>(MAX_PENDING_REQUESTS);
+        // Stops once every handle to the forked state is dropped
+        std::thread::Builder::new().name("fork-worker".to_string()).spawn(move || {
+            while let Ok(request) = receiver.recv() {
+                runtime.block_on(request);
+            }
+        })?;
+
+        let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(config.url)));
+        let mut forked_state = Self { provider, requests, block_number: 0, cache: Default::default() };
+        forked_state.block_number = match config.block_number {
+            Some(block_number) => block_number,
+            None => {
+                let provider = forked_state.provider.clone();
+                forked_state
+                    .fetch(async move { provider.block_number().await })
+                    .map_err(Error::ForkBlock)?
+                    .map_err(|e| Error::ForkBlock(e.to_string()))?
+            }
+        };
+
+        Ok(forked_state)
+    }
+
+    /// The block the state is forked at.
+    pub fn block_number(&self) -> u64 {
+        self.block_number
+    }
+
+    /// Runs the request on the worker, blocking until it completes. Fails if the worker stopped.
+    fn fetch<T, F>(&self, request: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let request = Box::pin(async move {
+            let _ = sender.send(request.await);
+        });
+        self.requests.send(request).map_err(|_| "the fork worker stopped".to_string())?;
+        receiver.recv().map_err(|_| "the fork worker stopped".to_string())
+    }
+
+    /// Fetches a value of the forked chain, with the `default` value when the contract or class
+    /// does not exist there.
+    fn fetch_or_default<T, F>(&self, request: F, default: T) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T, ProviderError<<JsonRpcClient<HttpTransport> as Provider>::Error>>>
+            + Send
+            + 'static,
+    {
+        match self.fetch(request)? {
+            Ok(value) => Ok(value),
+            Err(ProviderError::StarknetError(StarknetErrorWithMessage {
+                code: MaybeUnknownErrorCode::Known(StarknetError::ContractNotFound | StarknetError::ClassHashNotFound),
+                ..
+            })) => Ok(default),
+            Err(e) => Err(format!("Failed to read the state of the forked chain: {e}")),
+        }
+    }
+
+    fn block_id(&self) -> BlockId {
+        BlockId::Number(self.block_number)
+    }
+
+    /// The class declared in the forked chain, along with its compiled class hash if it is a Sierra
+    /// class.
+    fn class(&self, class_hash: ClassHash) -> Result<Option<(ContractClass, Option<CompiledClassHash>)>, String> {
+        if let Some(class) = self.cache.lock().expect("Poisoned lock").contract_classes.get(&class_hash) {
+            return Ok(class.clone());
+        }
+
+        let (provider, block_id) = (self.provider.clone(), self.block_id());
+        let hash: FieldElement = Felt252Wrapper::from(class_hash).into();
+        let class =
+            match self.fetch_or_default(async move { provider.get_class(block_id, hash).await.map(Some) }, None)? {
+                Some(CoreContractClass::Sierra(contract_class)) => {
+                    let (contract_class, compiled_class_hash) = compile_sierra_contract_class(contract_class)
+                        .map_err(|e| format!("Failed to compile the class {hash:#x} of the forked chain: {e}"))?;
+                    Some((contract_class, Some(Felt252Wrapper(compiled_class_hash).into())))
+                }
+                Some(contract_class) => {
+                    let contract_class = to_blockifier_contract_class(contract_class)
+                        .map_err(|e| format!("Failed to convert the class {hash:#x} of the forked chain: {e}"))?;
+                    Some((contract_class, None))
+                }
+                None => None,
+            };
+
+        self.cache.lock().expect("Poisoned lock").contract_classes.insert(class_hash, class.clone());
+        Ok(class)
+    }
+}
+
+impl ForkedStateReader for ForkedState {
+    fn storage_at(&self, contract_address: ContractAddress, key: StorageKey) -> Result<StarkFelt, String> {
+        if let Some(value) = self.cache.lock().expect("Poisoned lock").storage.get(&(contract_address, key)) {
+            return Ok(*value);
+        }
+
+        let (provider, block_id) = (self.provider.clone(), self.block_id());
+        let address: FieldElement = Felt252Wrapper::from(contract_address).into();
+        let storage_key: FieldElement = Felt252Wrapper::from(key).into();
+        let value = self.fetch_or_default(
+            async move { provider.get_storage_at(address, storage_key, block_id).await },
+            FieldElement::ZERO,
+        )?;
+
+        let value = Felt252Wrapper(value).into();
+        self.cache.lock().expect("Poisoned lock").storage.insert((contract_address, key), value);
+        Ok(value)
+    }
+
+    fn nonce_at(&self, contract_address: ContractAddress) -> Result<Nonce, String> {
+        if let Some(nonce) = self.cache.lock().expect("Poisoned lock").nonces.get(&contract_address) {
+            return Ok(*nonce);
+        }
+
+        let (provider, block_id) = (self.provider.clone(), self.block_id());
+        let address: FieldElement = Felt252Wrapper::from(contract_address).into();
+        let nonce =
+            self.fetch_or_default(async move { provider.get_nonce(block_id, address).await }, FieldElement::ZERO)?;
+
+        let nonce = Felt252Wrapper(nonce).into();
+        self.cache.lock().expect("Poisoned lock").nonces.insert(contract_address, nonce);
+        Ok(nonce)
+    }
+
+    fn class_hash_at(&self, contract_address: ContractAddress) -> Result<ClassHash, String> {
+        if let Some(class_hash) = self.cache.lock().expect("Poisoned lock").class_hashes.get(&contract_address) {
+            return Ok(*class_hash);
+        }
+
+        let (provider, block_id) = (self.provider.clone(), self.block_id());
+        let address: FieldElement = Felt252Wrapper::from(contract_address).into();
+        let class_hash = self
+            .fetch_or_default(async move { provider.get_class_hash_at(block_id, address).await }, FieldElement::ZERO)?;
+
+        let class_hash = Felt252Wrapper(class_hash).into();
+        self.cache.lock().expect("Poisoned lock").class_hashes.insert(contract_address, class_hash);
+        Ok(class_hash)
+    }
+
+    fn contract_class(&self, class_hash: ClassHash) -> Result<Option<ContractClass>, String> {
+        Ok(self.class(class_hash)?.map(|(contract_class, _)| contract_class))
+    }
+
+    /// Compiled locally from the Sierra class, the RPC not serving the compiled class hashes.
+    fn compiled_class_hash(&self, class_hash: ClassHash) -> Result<Option<CompiledClassHash>, String> {
+        Ok(self.class(class_hash)?.and_then(|(_, compiled_class_hash)| compiled_class_hash))
+    }
+}
+
+/// Registers the forked state on every runtime call.
+impl<B: BlockT> ExtensionsFactory<B> for ForkedState {
+    fn extensions_for(&self, _block_hash: B::Hash, _block_number: NumberFor<B>) -> Extensions {
+        let mut extensions = Extensions::new();
+        extensions.register(ForkedStateExt::new(Arc::new(self.clone())));
+        extensions
+    }
+}
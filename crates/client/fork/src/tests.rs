@@ -0,0 +1,144 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use mp_fork::ForkedStateReader;
+use serde_json::{json, Value};
+use starknet_api::api_core::{ClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use super::{ForkConfig, ForkedState};
+
+/// Recorded responses of a Starknet RPC, by method.
+fn recorded_response(method: &str) -> Value {
+    match method {
+        "starknet_blockNumber" => json!({ "result": 100 }),
+        "starknet_getStorageAt" => json!({ "result": "0x7" }),
+        "starknet_getNonce" => json!({ "error": { "code": 20, "message": "Contract not found" } }),
+        "starknet_getClassHashAt" => json!({ "result": "0x42" }),
+        "starknet_getClass" => json!({ "error": { "code": 28, "message": "Class hash not found" } }),
+        _ => json!({ "error": { "code": -32601, "message": "Method not found" } }),
+    }
+}
+
+/// A Starknet RPC failing every request but the block number one.
+fn failing_response(method: &str) -> Value {
+    match method {
+        "starknet_blockNumber" => json!({ "result": 100 }),
+        _ => json!({ "error": { "code": -32603, "message": "Internal error" } }),
+    }
+}
+
+/// Serves the recorded responses over HTTP, returning the url of the server and the count of
+/// requests it answered.
+fn stand_in_server() -> (url::Url, Arc<AtomicUsize>) {
+    serve(recorded_response)
+}
+
+fn serve(responses: fn(&str) -> Value) -> (url::Url, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap()).parse().unwrap();
+    let requests = Arc::new(AtomicUsize::new(0));
+
+    let served = requests.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+            served.fetch_add(1, Ordering::SeqCst);
+
+            let mut response = responses(request["method"].as_str().unwrap());
+            response["jsonrpc"] = json!("2.0");
+            response["id"] = request["id"].clone();
+            let response = response.to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: \
+                 close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+        }
+    });
+
+    (url, requests)
+}
+
+fn contract_address() -> ContractAddress {
+    ContractAddress(PatriciaKey(StarkFelt::from(0xf0f0u64)))
+}
+
+#[test]
+fn fork_block_defaults_to_the_latest_block() {
+    let (url, _) = stand_in_server();
+
+    let forked_state = ForkedState::new(ForkConfig { url, block_number: None }).unwrap();
+
+    assert_eq!(forked_state.block_number(), 100);
+}
+
+#[test]
+fn state_is_read_from_the_forked_chain() {
+    let (url, _) = stand_in_server();
+    let forked_state = ForkedState::new(ForkConfig { url, block_number: Some(10) }).unwrap();
+
+    assert_eq!(
+        forked_state.storage_at(contract_address(), StorageKey(PatriciaKey(StarkFelt::from(1u64)))),
+        Ok(StarkFelt::from(7u64))
+    );
+    assert_eq!(forked_state.class_hash_at(contract_address()), Ok(ClassHash(StarkFelt::from(0x42u64))));
+    // Contracts and classes missing in the forked chain have the default values
+    assert_eq!(forked_state.nonce_at(contract_address()), Ok(Nonce::default()));
+    assert_eq!(forked_state.contract_class(ClassHash(StarkFelt::from(0x42u64))), Ok(None));
+    assert_eq!(forked_state.compiled_class_hash(ClassHash(StarkFelt::from(0x42u64))), Ok(None));
+}
+
+#[test]
+fn failing_reads_are_errors() {
+    let (url, requests) = serve(failing_response);
+    let forked_state = ForkedState::new(ForkConfig { url, block_number: Some(10) }).unwrap();
+    let key = StorageKey(PatriciaKey(StarkFelt::from(1u64)));
+
+    assert!(forked_state.storage_at(contract_address(), key).is_err());
+    assert!(forked_state.nonce_at(contract_address()).is_err());
+    assert!(forked_state.class_hash_at(contract_address()).is_err());
+    assert!(forked_state.contract_class(ClassHash(StarkFelt::from(0x42u64))).is_err());
+
+    // The failures are not cached
+    assert!(forked_state.storage_at(contract_address(), key).is_err());
+    assert_eq!(requests.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+fn forked_state_is_cached() {
+    let (url, requests) = stand_in_server();
+    let forked_state = ForkedState::new(ForkConfig { url, block_number: Some(10) }).unwrap();
+    let key = StorageKey(PatriciaKey(StarkFelt::from(1u64)));
+
+    forked_state.storage_at(contract_address(), key);
+    forked_state.storage_at(contract_address(), key);
+    forked_state.nonce_at(contract_address());
+    forked_state.nonce_at(contract_address());
+
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
@@ -1,3 +1,4 @@
+use mc_db::TransactionOutput;
 use mc_rpc_core::utils::get_block_by_block_hash;
 use mp_digest_log::{find_starknet_block, FindLogError};
 use mp_hashers::HasherT;
@@ -9,6 +10,7 @@ use sc_client_api::backend::{Backend, StorageProvider};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::{Backend as _, HeaderBackend};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Zero};
+use starknet_api::transaction::TransactionHash;
 
 use crate::block_metrics::BlockMetrics;
 
@@ -79,6 +81,26 @@ where
                                 .set(starknet_block.header().l1_gas_price.price_in_strk.unwrap_or(0).into_f64());
                         }
 
+                        // The transaction outputs may be pruned from the runtime state later on, so we keep
+                        // a copy of them in db. The block is only mapped once all of them are stored, a
+                        // failure to read one of them failing the mapping of the block, which is retried.
+                        let outputs = mapping_commitment
+                            .starknet_transaction_hashes
+                            .iter()
+                            .map(|tx_hash| {
+                                let tx_hash = TransactionHash(*tx_hash);
+                                transaction_output(client, substrate_block_hash, tx_hash)
+                                    .map(|output| (tx_hash, output))
+                                    .map_err(|e| {
+                                        anyhow::anyhow!(
+                                            "Failed to read the output of transaction {tx_hash} in block \
+                                             {substrate_block_hash}: {e}"
+                                        )
+                                    })
+                            })
+                            .collect::<anyhow::Result<Vec<_>>>()?;
+                        backend.transaction_outputs().store_outputs(outputs).map_err(|e| anyhow::anyhow!(e))?;
+
                         backend.mapping().write_hashes(mapping_commitment).map_err(|e| anyhow::anyhow!(e))
                     }
                 }
@@ -92,6 +114,26 @@ where
     }
 }
 
+fn transaction_output<B: BlockT, C>(
+    client: &C,
+    substrate_block_hash: B::Hash,
+    tx_hash: TransactionHash,
+) -> anyhow::Result<TransactionOutput>
+where
+    C: ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B>,
+{
+    let runtime_api = client.runtime_api();
+
+    Ok(TransactionOutput {
+        events: runtime_api.get_events_for_tx_by_hash(substrate_block_hash, tx_hash)?,
+        messages: runtime_api.get_tx_messages_to_l1(substrate_block_hash, tx_hash)?,
+        revert_error: runtime_api
+            .get_tx_execution_outcome(substrate_block_hash, tx_hash)?
+            .map(|revert_error| String::from_utf8_lossy(&revert_error).into_owned()),
+    })
+}
+
 fn sync_genesis_block<B: BlockT, C, H>(
     _client: &C,
     backend: &mc_db::Backend<B>,
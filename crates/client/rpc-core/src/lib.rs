@@ -13,6 +13,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 pub mod utils;
+pub mod versions;
 
 use mp_transactions::TransactionStatus;
 use pallet_starknet::genesis_loader::PredeployedAccount;
@@ -22,8 +23,8 @@ use starknet_core::types::{
     BroadcastedInvokeTransaction, BroadcastedTransaction, ContractClass, DeclareTransactionResult,
     DeployAccountTransactionResult, EventFilterWithPage, EventsPage, FeeEstimate, FieldElement, FunctionCall,
     InvokeTransactionResult, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs, MaybePendingStateUpdate,
-    MaybePendingTransactionReceipt, MsgFromL1, SimulatedTransaction, SimulationFlag, SyncStatusType, Transaction,
-    TransactionTrace, TransactionTraceWithHash,
+    MaybePendingTransactionReceipt, MsgFromL1, SimulatedTransaction, SimulationFlag, StorageEntry, SyncStatusType,
+    Transaction, TransactionTrace, TransactionTraceWithHash,
 };
 
 #[serde_as]
@@ -36,11 +37,182 @@ pub struct PredeployedAccountWithBalance {
     pub balance: FieldElement,
 }
 
+/// Values substituted to the on-chain state of a contract by the `*WithOverrides` methods.
+///
+/// Omitted fields are read from the chain.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractStateOverride {
+    #[serde_as(as = "UfeHex")]
+    pub contract_address: FieldElement,
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default)]
+    pub nonce: Option<FieldElement>,
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default)]
+    pub class_hash: Option<FieldElement>,
+    /// Balance of the contract in the fee token.
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default)]
+    pub balance: Option<FieldElement>,
+    #[serde(default)]
+    pub storage: Vec<StorageEntry>,
+}
+
+impl From<ContractStateOverride> for mp_simulations::ContractStateOverride {
+    fn from(value: ContractStateOverride) -> Self {
+        Self {
+            contract_address: value.contract_address.into(),
+            nonce: value.nonce.map(Into::into),
+            class_hash: value.class_hash.map(Into::into),
+            balance: value.balance.map(Into::into),
+            storage: value.storage.into_iter().map(|entry| (entry.key.into(), entry.value.into())).collect(),
+        }
+    }
+}
+
+/// A block of the sequence simulated by `madara_simulateBlocks`.
+///
+/// Omitted context fields are inherited from the previous block, the block number being
+/// incremented.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockToSimulate {
+    #[serde(default)]
+    pub block_number: Option<u64>,
+    #[serde(default)]
+    pub block_timestamp: Option<u64>,
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default)]
+    pub sequencer_address: Option<FieldElement>,
+    /// Gas price, in wei.
+    #[serde(default)]
+    pub gas_price: Option<u128>,
+    pub transactions: Vec<BroadcastedTransaction>,
+}
+
+/// Paymaster sponsoring the invoke transaction given to the `*Sponsored*` methods.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Paymaster {
+    #[serde_as(as = "UfeHex")]
+    pub address: FieldElement,
+    /// Signature of the paymaster over the hash of the sponsored transaction.
+    #[serde_as(as = "Vec<UfeHex>")]
+    #[serde(default)]
+    pub signature: Vec<FieldElement>,
+}
+
 /// Madara rpc interface for additional features.
 #[rpc(server, namespace = "madara")]
 pub trait MadaraRpcApi: StarknetReadRpcApi {
     #[method(name = "predeployedAccounts")]
     fn predeployed_accounts(&self) -> RpcResult<Vec<PredeployedAccountWithBalance>>;
+
+    /// Same as `starknet_call`, with the state of the block modified by the given overrides
+    #[method(name = "callWithOverrides")]
+    fn call_with_overrides(
+        &self,
+        request: FunctionCall,
+        block_id: BlockId,
+        state_overrides: Vec<ContractStateOverride>,
+    ) -> RpcResult<Vec<String>>;
+
+    /// Same as `starknet_estimateFee`, with the state of the block modified by the given overrides
+    #[method(name = "estimateFeeWithOverrides")]
+    fn estimate_fee_with_overrides(
+        &self,
+        request: Vec<BroadcastedTransaction>,
+        block_id: BlockId,
+        state_overrides: Vec<ContractStateOverride>,
+    ) -> RpcResult<Vec<FeeEstimate>>;
+
+    /// Same as `starknet_simulateTransactions`, with the state of the block modified by the given
+    /// overrides
+    #[method(name = "simulateTransactionsWithOverrides")]
+    fn simulate_transactions_with_overrides(
+        &self,
+        block_id: BlockId,
+        transactions: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+        state_overrides: Vec<ContractStateOverride>,
+    ) -> RpcResult<Vec<SimulatedTransaction>>;
+
+    /// Simulates a sequence of blocks on top of the given block, each one being executed on the
+    /// state left by the previous one. Returns the simulated transactions, block by block
+    #[method(name = "simulateBlocks")]
+    fn simulate_blocks(
+        &self,
+        block_id: BlockId,
+        blocks: Vec<BlockToSimulate>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<Vec<SimulatedTransaction>>>;
+
+    /// Same as `starknet_addInvokeTransaction`, the transaction being sponsored by the paymaster.
+    /// Its signature is the one of the sender over the sponsored transaction hash, which commits to
+    /// the paymaster address
+    #[method(name = "addSponsoredInvokeTransaction")]
+    async fn add_sponsored_invoke_transaction(
+        &self,
+        invoke_transaction: BroadcastedInvokeTransaction,
+        paymaster: Paymaster,
+    ) -> RpcResult<InvokeTransactionResult>;
+
+    /// Same as `starknet_estimateFee`, for an invoke transaction sponsored by the paymaster
+    #[method(name = "estimateSponsoredInvokeFee")]
+    fn estimate_sponsored_invoke_fee(
+        &self,
+        invoke_transaction: BroadcastedInvokeTransaction,
+        paymaster: Paymaster,
+        block_id: BlockId,
+    ) -> RpcResult<FeeEstimate>;
+
+    /// Same as `starknet_simulateTransactions`, for an invoke transaction sponsored by the
+    /// paymaster
+    #[method(name = "simulateSponsoredInvokeTransaction")]
+    fn simulate_sponsored_invoke_transaction(
+        &self,
+        block_id: BlockId,
+        invoke_transaction: BroadcastedInvokeTransaction,
+        paymaster: Paymaster,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<SimulatedTransaction>;
+}
+
+/// Madara devnet rpc interface, editing the state and the clock of a development chain.
+///
+/// Every method is unsafe. The state edits are applied by sealing a new block.
+#[rpc(server, namespace = "madara_dev")]
+pub trait MadaraDevRpcApi {
+    /// Adds `amount` to the fee token balance of `address`
+    #[method(name = "mint")]
+    async fn mint(&self, address: FieldElement, amount: FieldElement) -> RpcResult<()>;
+
+    /// Moves the timestamp of the next blocks `seconds` forward. Returns the timestamp of the next
+    /// block
+    #[method(name = "increaseTime")]
+    fn increase_time(&self, seconds: u64) -> RpcResult<u64>;
+
+    /// Sets the timestamp, in seconds, of the next block
+    #[method(name = "setNextBlockTimestamp")]
+    fn set_next_block_timestamp(&self, timestamp: u64) -> RpcResult<()>;
+
+    #[method(name = "setStorageAt")]
+    async fn set_storage_at(&self, address: FieldElement, key: FieldElement, value: FieldElement) -> RpcResult<()>;
+
+    #[method(name = "setNonce")]
+    async fn set_nonce(&self, address: FieldElement, nonce: FieldElement) -> RpcResult<()>;
+
+    #[method(name = "setClassHashAt")]
+    async fn set_class_hash_at(&self, address: FieldElement, class_hash: FieldElement) -> RpcResult<()>;
+
+    /// Records the current best block. Returns the id of the snapshot
+    #[method(name = "snapshot")]
+    fn snapshot(&self) -> RpcResult<u64>;
+
+    /// Reverts the chain to the best block of the snapshot, dropping the snapshots taken after it
+    #[method(name = "revert")]
+    fn revert(&self, snapshot_id: u64) -> RpcResult<()>;
 }
 
 /// Starknet write rpc interface.
@@ -188,3 +360,13 @@ pub trait StarknetTraceRpcApi {
     /// Returns the execution trace of a transaction
     async fn trace_transaction(&self, transaction_hash: FieldElement) -> RpcResult<TransactionTrace>;
 }
+
+/// Starknet reads outside of the RPC spec, for the other APIs served by the node such as the
+/// feeder gateway.
+pub trait StarknetBlockReceipts {
+    /// Returns the receipts of the transactions of a block, in their order in the block.
+    ///
+    /// The block is re-executed once for all of them, where `starknet_getTransactionReceipt`
+    /// re-executes the transactions of the block up to the requested one.
+    fn get_block_receipts(&self, block_id: BlockId) -> RpcResult<Vec<MaybePendingTransactionReceipt>>;
+}
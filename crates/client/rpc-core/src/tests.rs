@@ -1,6 +1,10 @@
-use starknet_core::types::BlockTag;
+use std::sync::Arc;
+
+use starknet_core::types::{BlockTag, EntryPointsByType, FlattenedSierraClass, SierraEntryPoint};
 
 use super::*;
+use crate::utils::{flattened_sierra_to_sierra_contract_class, sierra_contract_class_to_flattened_sierra};
+use crate::versions::RpcVersion;
 
 #[test]
 fn block_id_serialization() {
@@ -38,3 +42,51 @@ fn block_id_deserialization() {
         BlockId::Number(42)
     );
 }
+
+#[test]
+fn contract_state_override_deserialization() {
+    let state_override: ContractStateOverride = serde_json::from_value(serde_json::json!({
+        "contract_address": "0x42",
+        "balance": "0x100",
+        "storage": [{ "key": "0x1", "value": "0x2" }]
+    }))
+    .unwrap();
+
+    assert_eq!(state_override.contract_address, FieldElement::from_hex_be("0x42").unwrap());
+    assert_eq!(state_override.nonce, None);
+    assert_eq!(state_override.class_hash, None);
+    assert_eq!(state_override.balance, Some(FieldElement::from_hex_be("0x100").unwrap()));
+    assert_eq!(state_override.storage, vec![StorageEntry { key: FieldElement::ONE, value: FieldElement::TWO }]);
+}
+
+#[test]
+fn versioned_paths() {
+    assert_eq!(RpcVersion::from_path("/rpc/v0_5"), Some(RpcVersion::V0_5));
+    assert_eq!(RpcVersion::from_path("/rpc/v0_5/"), Some(RpcVersion::V0_5));
+    assert_eq!(RpcVersion::from_path("/rpc/v0_6"), Some(RpcVersion::V0_6));
+    assert_eq!(RpcVersion::from_path("/rpc/v0_4"), None);
+    assert_eq!(RpcVersion::from_path("/"), None);
+}
+
+#[test]
+fn versioned_method_names() {
+    assert_eq!(RpcVersion::V0_5.method_name("starknet_blockNumber"), "v0_5/starknet_blockNumber");
+    assert_eq!(RpcVersion::V0_6.method_name("starknet_blockNumber"), "v0_6/starknet_blockNumber");
+}
+
+#[test]
+fn sierra_classes_are_flattened_back() {
+    let flattened = FlattenedSierraClass {
+        sierra_program: vec![FieldElement::ONE, FieldElement::TWO],
+        contract_class_version: "0.1.0".to_string(),
+        entry_points_by_type: EntryPointsByType {
+            constructor: Vec::new(),
+            external: vec![SierraEntryPoint { selector: FieldElement::from(42u64), function_idx: 1 }],
+            l1_handler: Vec::new(),
+        },
+        abi: "[]".to_string(),
+    };
+
+    let sierra = flattened_sierra_to_sierra_contract_class(Arc::new(flattened.clone()));
+    assert_eq!(sierra_contract_class_to_flattened_sierra(&sierra), flattened);
+}
@@ -248,6 +248,40 @@ pub fn flattened_sierra_to_sierra_contract_class(
     }
 }
 
+/// Converts a [starknet_api::state::ContractClass] back to a [FlattenedSierraClass]
+///
+/// The contract class version is not part of the stored class, the one of the compiler is assumed.
+pub fn sierra_contract_class_to_flattened_sierra(
+    contract_class: &starknet_api::state::ContractClass,
+) -> FlattenedSierraClass {
+    let entry_points = |entry_point_type: starknet_api::state::EntryPointType| {
+        contract_class
+            .entry_point_by_type
+            .get(&entry_point_type)
+            .map(|entry_points| {
+                entry_points
+                    .iter()
+                    .map(|entry_point| SierraEntryPoint {
+                        selector: Felt252Wrapper::from(entry_point.selector.0).into(),
+                        function_idx: entry_point.function_idx.0,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    FlattenedSierraClass {
+        sierra_program: contract_class.sierra_program.iter().map(|felt| Felt252Wrapper::from(*felt).into()).collect(),
+        contract_class_version: "0.1.0".to_string(),
+        entry_points_by_type: EntryPointsByType {
+            constructor: entry_points(starknet_api::state::EntryPointType::Constructor),
+            external: entry_points(starknet_api::state::EntryPointType::External),
+            l1_handler: entry_points(starknet_api::state::EntryPointType::L1Handler),
+        },
+        abi: contract_class.abi.clone(),
+    }
+}
+
 /// Converts a [FieldElement] to a [BigUint]
 fn field_element_to_big_uint(value: &FieldElement) -> BigUint {
     BigInt::from_bytes_be(Sign::Plus, &value.to_bytes_be()).to_biguint().unwrap()
@@ -0,0 +1,50 @@
+//! Starknet RPC spec versions served side by side
+//!
+//! Every supported version is served on a version specific path, e.g. `/rpc/v0_5`, the `starknet_*`
+//! methods received on that path being routed to the methods of that version.
+
+/// A Starknet RPC spec version supported by the node
+///
+/// The methods are implemented once against the v0.5 spec, the other versions converting their
+/// params and responses from and to the v0.5 shapes. The V3 transactions of v0.6 are not
+/// supported yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcVersion {
+    V0_5,
+    V0_6,
+}
+
+impl RpcVersion {
+    /// All the supported versions
+    pub const ALL: [RpcVersion; 2] = [RpcVersion::V0_5, RpcVersion::V0_6];
+
+    /// The version returned by `starknet_specVersion`
+    pub fn spec_version(&self) -> &'static str {
+        match self {
+            RpcVersion::V0_5 => "0.5.1",
+            RpcVersion::V0_6 => "0.6.0",
+        }
+    }
+
+    /// The path this version is served on
+    pub fn path(&self) -> &'static str {
+        match self {
+            RpcVersion::V0_5 => "/rpc/v0_5",
+            RpcVersion::V0_6 => "/rpc/v0_6",
+        }
+    }
+
+    /// Return the version served on `path`, if any
+    pub fn from_path(path: &str) -> Option<Self> {
+        let path = path.trim_end_matches('/');
+        Self::ALL.into_iter().find(|version| version.path() == path)
+    }
+
+    /// The name `method` is registered under for this version, e.g. `v0_6/starknet_blockNumber`
+    ///
+    /// All the versions are registered in a single module, the calls received on the path of a
+    /// version being renamed accordingly.
+    pub fn method_name(&self, method: &str) -> String {
+        format!("{}/{method}", self.path().trim_start_matches("/rpc/"))
+    }
+}
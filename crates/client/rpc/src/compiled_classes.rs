@@ -0,0 +1,147 @@
+//! Classes compiled from Sierra, kept in the compiled classes database, or only in memory for
+//! the classes not declared yet, so that the methods converting or re-executing declare
+//! transactions do not compile them again.
+
+use blockifier::execution::contract_class::{ContractClass, ContractClassV1};
+use log::error;
+use mc_storage::StorageOverride;
+use mp_felt::Felt252Wrapper;
+use mp_transactions::from_broadcasted_transactions::{
+    declare_v2_into_user_transaction, get_casm_cotract_class_hash, BroadcastedTransactionConversionError,
+};
+use mp_transactions::UserTransaction;
+use sp_runtime::traits::Block as BlockT;
+use starknet_api::api_core::{ClassHash, CompiledClassHash};
+use starknet_core::types::{BroadcastedDeclareTransaction, BroadcastedTransaction};
+
+use crate::errors::StarknetRpcApiError;
+
+/// Converts a broadcasted transaction, reusing the compiled class of a declared Sierra class
+/// compiled before.
+pub(crate) fn user_transaction<B: BlockT>(
+    backend: &mc_db::Backend<B>,
+    storage_override: &dyn StorageOverride<B>,
+    block_hash: B::Hash,
+    transaction: BroadcastedTransaction,
+) -> Result<UserTransaction, BroadcastedTransactionConversionError> {
+    match transaction {
+        BroadcastedTransaction::Declare(transaction) => {
+            user_declare_transaction(backend, storage_override, block_hash, transaction)
+        }
+        transaction => transaction.try_into(),
+    }
+}
+
+/// Converts a broadcasted declare transaction, reusing the compiled class of its Sierra class
+/// compiled before.
+///
+/// The compiled class is only reused if the Sierra class compiles to the compiled class hash of
+/// the transaction: the one the chain holds for it at `block_hash`, or the one it was compiled
+/// to by the node for a class not declared yet. A class compiled by the conversion is stored for
+/// the next ones, in memory only if the chain does not hold it yet.
+pub(crate) fn user_declare_transaction<B: BlockT>(
+    backend: &mc_db::Backend<B>,
+    storage_override: &dyn StorageOverride<B>,
+    block_hash: B::Hash,
+    transaction: BroadcastedDeclareTransaction,
+) -> Result<UserTransaction, BroadcastedTransactionConversionError> {
+    let transaction = match transaction {
+        BroadcastedDeclareTransaction::V2(transaction) => transaction,
+        transaction => return transaction.try_into(),
+    };
+
+    let compiled_classes = backend.compiled_classes();
+    let mut declared = false;
+    let mut compiled = None;
+    let transaction = declare_v2_into_user_transaction(transaction, |class_hash, compiled_class_hash| {
+        let class_hash = ClassHash(Felt252Wrapper(class_hash).into());
+        let compiled_class_hash = CompiledClassHash(Felt252Wrapper(compiled_class_hash).into());
+        declared =
+            storage_override.compiled_class_hash_by_class_hash(block_hash, class_hash) == Some(compiled_class_hash);
+
+        let compiled_class =
+            if declared || compiled_classes.cached_compiled_class_hash(class_hash) == Some(compiled_class_hash) {
+                compiled_classes.get_compiled_class(compiled_class_hash).unwrap_or_else(|e| {
+                    error!("Failed to read the compiled class of {class_hash}: {e}");
+                    None
+                })
+            } else {
+                None
+            };
+        if compiled_class.is_none() {
+            compiled = Some((class_hash, compiled_class_hash));
+        }
+        compiled_class
+    })?;
+
+    // The conversion checked that the class compiles to its compiled class hash
+    if let (Some((class_hash, compiled_class_hash)), UserTransaction::Declare(_, compiled_class)) =
+        (compiled, &transaction)
+    {
+        if !declared {
+            compiled_classes.cache_compiled_class(class_hash, compiled_class_hash, compiled_class.clone());
+        } else if let Err(e) = compiled_classes.store_compiled_class(compiled_class_hash, compiled_class.clone()) {
+            error!("Failed to store the compiled class of {class_hash}: {e}");
+        }
+    }
+
+    Ok(transaction)
+}
+
+/// Returns the compiled class of a Sierra class declared at `block_hash`, of the compiled class
+/// hash the chain holds for it. The class is compiled from the Sierra classes database the first
+/// time.
+///
+/// A class the chain does not hold yet, declared by a pending transaction, is compiled without
+/// being stored.
+pub(crate) fn declared_compiled_class<B: BlockT>(
+    backend: &mc_db::Backend<B>,
+    storage_override: &dyn StorageOverride<B>,
+    block_hash: B::Hash,
+    class_hash: ClassHash,
+) -> Result<ContractClass, StarknetRpcApiError> {
+    let compiled_class_hash = storage_override.compiled_class_hash_by_class_hash(block_hash, class_hash);
+
+    let compiled_classes = backend.compiled_classes();
+    if let Some(compiled_class_hash) = compiled_class_hash {
+        match compiled_classes.get_compiled_class(compiled_class_hash) {
+            Ok(Some(compiled_class)) => return Ok(compiled_class),
+            Ok(None) => {}
+            Err(e) => error!("Failed to read the compiled class of {class_hash}: {e}"),
+        }
+    }
+
+    let contract_class = backend
+        .sierra_classes()
+        .get_sierra_class(class_hash)
+        .map_err(|e| {
+            error!("Failed to fetch sierra class with hash {class_hash}: {e}");
+            StarknetRpcApiError::InternalServerError
+        })?
+        .ok_or_else(|| {
+            error!("The sierra class with hash {class_hash} is not present in db backend");
+            StarknetRpcApiError::InternalServerError
+        })?;
+    let contract_class = mp_transactions::utils::sierra_to_casm_contract_class(contract_class).map_err(|e| {
+        error!("Failed to convert the SierraContractClass to CasmContractClass: {e}");
+        StarknetRpcApiError::InternalServerError
+    })?;
+    if let Some(compiled_class_hash) = compiled_class_hash {
+        if get_casm_cotract_class_hash(&contract_class) != Felt252Wrapper::from(compiled_class_hash.0).0 {
+            error!("The sierra class with hash {class_hash} does not compile to {}", compiled_class_hash.0);
+            return Err(StarknetRpcApiError::InternalServerError);
+        }
+    }
+    let compiled_class = ContractClass::V1(ContractClassV1::try_from(contract_class).map_err(|e| {
+        error!("Failed to convert the compiler CasmContractClass to blockifier CasmContractClass: {e}");
+        StarknetRpcApiError::InternalServerError
+    })?);
+
+    if let Some(compiled_class_hash) = compiled_class_hash {
+        if let Err(e) = compiled_classes.store_compiled_class(compiled_class_hash, compiled_class.clone()) {
+            error!("Failed to store the compiled class of {class_hash}: {e}");
+        }
+    }
+
+    Ok(compiled_class)
+}
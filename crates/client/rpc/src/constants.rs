@@ -1,4 +1,11 @@
+use std::time::Duration;
+
 /// Maximum number of filter keys that can be passed to the `get_events` RPC.
 pub const MAX_EVENTS_KEYS: usize = 100;
 /// Maximum number of events that can be fetched in a single chunk for the `get_events` RPC.
 pub const MAX_EVENTS_CHUNK_SIZE: usize = 1000;
+/// Maximum number of submitted transactions remembered by the node, for both the received and the
+/// rejected ones, in order to answer `starknet_getTransactionStatus` before block inclusion.
+pub const MAX_SUBMITTED_TRANSACTIONS_TRACKED: usize = 10_000;
+/// Time after which a submitted transaction is forgotten by the node.
+pub const SUBMITTED_TRANSACTIONS_MAX_AGE: Duration = Duration::from_secs(60 * 60);
@@ -0,0 +1,441 @@
+//! Madara devnet RPC, editing the state and the clock of a development chain.
+//!
+//! The state edits and mints are sent to the runtime as local-only unsigned extrinsics, applied by
+//! sealing a new block. The clock is shared with the timestamp inherent provider of the manual and
+//! instant sealing, so that the time moves by the block time at every block unless told otherwise.
+
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use futures::channel::{mpsc, oneshot};
+use futures::SinkExt;
+use jsonrpsee::core::{async_trait, RpcResult};
+use jsonrpsee::types::error::CallError;
+use log::error;
+use mc_db::MappingCommitment;
+use mc_rpc_core::utils::get_block_by_block_hash;
+use mc_rpc_core::MadaraDevRpcApiServer;
+use mp_felt::Felt252Wrapper;
+use mp_hashers::HasherT;
+use mp_simulations::ContractStateOverride;
+use mp_transactions::compute_hash::ComputeTransactionHash;
+use pallet_starknet_runtime_api::{ConvertTransactionRuntimeApi, StarknetRuntimeApi};
+use sc_client_api::backend::Backend;
+use sc_consensus_manual_seal::rpc::EngineCommand;
+use sc_rpc_api::DenyUnsafe;
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool, TransactionSource};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_arithmetic::traits::UniqueSaturatedInto;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
+use starknet_api::transaction::TransactionHash;
+use starknet_core::types::FieldElement;
+
+use crate::errors::StarknetRpcApiError;
+
+/// Timestamps, in milliseconds, of the blocks of a development chain.
+#[derive(Clone)]
+pub struct DevClock {
+    block_time: u64,
+    state: Arc<Mutex<DevClockState>>,
+}
+
+#[derive(Default)]
+struct DevClockState {
+    last_timestamp: u64,
+    next_timestamp: Option<u64>,
+}
+
+impl DevClock {
+    /// A clock starting at 0 and moving by `block_time` ms at every block.
+    pub fn new(block_time: u64) -> Self {
+        Self { block_time, state: Default::default() }
+    }
+
+    /// The timestamp of the next block.
+    pub fn next_timestamp(&self) -> u64 {
+        let state = self.state.lock().expect("Poisoned lock");
+        state.next_timestamp.unwrap_or(state.last_timestamp + self.block_time)
+    }
+
+    /// Moves the timestamp of the next blocks `duration` ms forward, returning the timestamp of
+    /// the next block.
+    pub fn increase_time(&self, duration: u64) -> u64 {
+        let mut state = self.state.lock().expect("Poisoned lock");
+        let next_timestamp = state.next_timestamp.unwrap_or(state.last_timestamp + self.block_time) + duration;
+        state.next_timestamp = Some(next_timestamp);
+        next_timestamp
+    }
+
+    /// Sets the timestamp of the next block, which has to be at least one block time after the
+    /// last one.
+    pub fn set_next_timestamp(&self, timestamp: u64) -> Result<(), String> {
+        let mut state = self.state.lock().expect("Poisoned lock");
+        let min_timestamp = state.last_timestamp + self.block_time;
+        if timestamp < min_timestamp {
+            return Err(format!("The next block timestamp cannot be earlier than {min_timestamp}ms"));
+        }
+        state.next_timestamp = Some(timestamp);
+        Ok(())
+    }
+
+    /// Moves the clock to the timestamp of the next block, returning it.
+    ///
+    /// To be called once per authored block.
+    pub fn advance(&self) -> u64 {
+        let mut state = self.state.lock().expect("Poisoned lock");
+        let timestamp = state.next_timestamp.take().unwrap_or(state.last_timestamp + self.block_time);
+        state.last_timestamp = timestamp;
+        timestamp
+    }
+}
+
+/// A Madara devnet RPC server
+pub struct MadaraDev<B: BlockT, BE, C, P, H> {
+    client: Arc<C>,
+    backend: Arc<BE>,
+    madara_backend: Arc<mc_db::Backend<B>>,
+    pool: Arc<P>,
+    /// Manual seal command sink, `None` when the blocks are sealed as soon as a transaction is
+    /// imported.
+    command_sink: Option<mpsc::Sender<EngineCommand<B::Hash>>>,
+    clock: DevClock,
+    /// The best block numbers of the snapshots, indexed by id.
+    snapshots: Mutex<Vec<NumberFor<B>>>,
+    deny_unsafe: DenyUnsafe,
+    _marker: PhantomData<H>,
+}
+
+impl<B: BlockT, BE, C, P, H> MadaraDev<B, BE, C, P, H> {
+    pub fn new(
+        client: Arc<C>,
+        backend: Arc<BE>,
+        madara_backend: Arc<mc_db::Backend<B>>,
+        pool: Arc<P>,
+        command_sink: Option<mpsc::Sender<EngineCommand<B::Hash>>>,
+        clock: DevClock,
+        deny_unsafe: DenyUnsafe,
+    ) -> Self {
+        Self {
+            client,
+            backend,
+            madara_backend,
+            pool,
+            command_sink,
+            clock,
+            snapshots: Default::default(),
+            deny_unsafe,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<B, BE, C, P, H> MadaraDev<B, BE, C, P, H>
+where
+    B: BlockT,
+    C: HeaderBackend<B> + ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B> + ConvertTransactionRuntimeApi<B>,
+    P: TransactionPool<Block = B>,
+    H: HasherT,
+{
+    /// Fails with `UnimplementedMethod` if the runtime of the given block cannot convert the dev
+    /// extrinsics, introduced by the version 2 of the `ConvertTransactionRuntimeApi`.
+    fn ensure_dev_extrinsics_supported(&self, block_hash: B::Hash) -> Result<(), StarknetRpcApiError> {
+        let supported = self
+            .client
+            .runtime_api()
+            .has_api_with::<dyn ConvertTransactionRuntimeApi<B>, _>(block_hash, |version| version >= 2)
+            .map_err(|e| {
+                error!("Failed to fetch the runtime API version: {e}");
+                StarknetRpcApiError::InternalServerError
+            })?;
+
+        if supported { Ok(()) } else { Err(StarknetRpcApiError::UnimplementedMethod) }
+    }
+
+    /// Submits the state edit to the pool and seals it in a new block.
+    async fn apply_state_edit(&self, state_edit: ContractStateOverride) -> RpcResult<()> {
+        let best_block_hash = self.client.info().best_hash;
+        self.ensure_dev_extrinsics_supported(best_block_hash)?;
+        let extrinsic =
+            self.client.runtime_api().convert_state_edits(best_block_hash, vec![state_edit]).map_err(|e| {
+                error!("Failed to convert the state edits to an extrinsic: {e}");
+                StarknetRpcApiError::InternalServerError
+            })?;
+
+        self.submit_and_seal(best_block_hash, extrinsic).await
+    }
+
+    /// Submits the dev extrinsic to the pool and seals it in a new block.
+    async fn submit_and_seal(&self, best_block_hash: B::Hash, extrinsic: B::Extrinsic) -> RpcResult<()> {
+        self.pool.submit_one(best_block_hash, TransactionSource::Local, extrinsic).await.map_err(|e| {
+            error!("Failed to submit the dev extrinsic: {e}");
+            StarknetRpcApiError::InternalServerError
+        })?;
+
+        // Instant sealing already seals a block on import
+        let Some(command_sink) = &self.command_sink else {
+            return Ok(());
+        };
+        let (sender, receiver) = oneshot::channel();
+        command_sink
+            .clone()
+            .send(EngineCommand::SealNewBlock {
+                create_empty: true,
+                finalize: true,
+                parent_hash: None,
+                sender: Some(sender),
+            })
+            .await
+            .map_err(|e| {
+                error!("Failed to send the seal command: {e}");
+                StarknetRpcApiError::InternalServerError
+            })?;
+
+        match receiver.await {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(e)) => {
+                error!("Failed to seal the dev extrinsic: {e}");
+                Err(StarknetRpcApiError::InternalServerError.into())
+            }
+            Err(e) => {
+                error!("Failed to seal the dev extrinsic: {e}");
+                Err(StarknetRpcApiError::InternalServerError.into())
+            }
+        }
+    }
+
+    /// The Madara db mapping of a block, `None` if it does not contain a Starknet block.
+    fn mapping_commitment(&self, block_hash: B::Hash) -> RpcResult<Option<MappingCommitment<B>>> {
+        let Ok(starknet_block) = get_block_by_block_hash(self.client.as_ref(), block_hash) else {
+            return Ok(None);
+        };
+        let chain_id = self.client.runtime_api().chain_id(block_hash).map_err(|e| {
+            error!("Failed to fetch the chain id: {e}");
+            StarknetRpcApiError::InternalServerError
+        })?;
+
+        Ok(Some(MappingCommitment {
+            block_hash,
+            starknet_block_hash: starknet_block.header().hash::<H>().into(),
+            starknet_transaction_hashes: starknet_block
+                .transactions()
+                .iter()
+                .map(|tx| tx.compute_hash::<H>(chain_id, false).into())
+                .collect(),
+        }))
+    }
+
+    /// Removes the reverted blocks from the Madara db, so that they are mapped again once
+    /// re-built.
+    fn revert_madara_db(
+        &self,
+        reverted_blocks: Vec<(B::Hash, Option<MappingCommitment<B>>)>,
+        snapshot_number: u64,
+    ) -> Result<(), mc_db::DbError> {
+        let reverted_hashes = reverted_blocks.iter().map(|(block_hash, _)| *block_hash).collect::<Vec<_>>();
+
+        for (block_hash, commitment) in reverted_blocks {
+            if let Some(commitment) = &commitment {
+                let tx_hashes =
+                    commitment.starknet_transaction_hashes.iter().copied().map(TransactionHash).collect::<Vec<_>>();
+                self.madara_backend.transaction_outputs().remove_outputs(&tx_hashes)?;
+            }
+            self.madara_backend.mapping().revert_hashes(block_hash, commitment)?;
+        }
+
+        // The mapping sync would otherwise look for the headers of the reverted blocks
+        let mut syncing_tips = self.madara_backend.meta().current_syncing_tips()?;
+        syncing_tips.retain(|tip| !reverted_hashes.contains(tip));
+        self.madara_backend.meta().write_current_syncing_tips(syncing_tips)?;
+
+        self.madara_backend.state_archive().revert_to(snapshot_number)
+    }
+}
+
+#[async_trait]
+impl<B, BE, C, P, H> MadaraDevRpcApiServer for MadaraDev<B, BE, C, P, H>
+where
+    B: BlockT,
+    BE: Backend<B> + 'static,
+    C: HeaderBackend<B> + ProvideRuntimeApi<B> + 'static,
+    C::Api: StarknetRuntimeApi<B> + ConvertTransactionRuntimeApi<B>,
+    P: TransactionPool<Block = B> + 'static,
+    H: HasherT + Send + Sync + 'static,
+{
+    async fn mint(&self, address: FieldElement, amount: FieldElement) -> RpcResult<()> {
+        self.deny_unsafe.check_if_safe()?;
+
+        let best_block_hash = self.client.info().best_hash;
+        self.ensure_dev_extrinsics_supported(best_block_hash)?;
+        let extrinsic = self
+            .client
+            .runtime_api()
+            .convert_mint(best_block_hash, Felt252Wrapper(address).into(), Felt252Wrapper(amount).into())
+            .map_err(|e| {
+                error!("Failed to convert the mint to an extrinsic: {e}");
+                StarknetRpcApiError::InternalServerError
+            })?;
+
+        self.submit_and_seal(best_block_hash, extrinsic).await
+    }
+
+    fn increase_time(&self, seconds: u64) -> RpcResult<u64> {
+        self.deny_unsafe.check_if_safe()?;
+
+        Ok(self.clock.increase_time(seconds.saturating_mul(1000)) / 1000)
+    }
+
+    fn set_next_block_timestamp(&self, timestamp: u64) -> RpcResult<()> {
+        self.deny_unsafe.check_if_safe()?;
+
+        self.clock
+            .set_next_timestamp(timestamp.saturating_mul(1000))
+            .map_err(|e| CallError::InvalidParams(anyhow::anyhow!(e)).into())
+    }
+
+    async fn set_storage_at(&self, address: FieldElement, key: FieldElement, value: FieldElement) -> RpcResult<()> {
+        self.deny_unsafe.check_if_safe()?;
+
+        self.apply_state_edit(ContractStateOverride {
+            contract_address: address.into(),
+            storage: vec![(key.into(), value.into())],
+            ..Default::default()
+        })
+        .await
+    }
+
+    async fn set_nonce(&self, address: FieldElement, nonce: FieldElement) -> RpcResult<()> {
+        self.deny_unsafe.check_if_safe()?;
+
+        self.apply_state_edit(ContractStateOverride {
+            contract_address: address.into(),
+            nonce: Some(nonce.into()),
+            ..Default::default()
+        })
+        .await
+    }
+
+    async fn set_class_hash_at(&self, address: FieldElement, class_hash: FieldElement) -> RpcResult<()> {
+        self.deny_unsafe.check_if_safe()?;
+
+        self.apply_state_edit(ContractStateOverride {
+            contract_address: address.into(),
+            class_hash: Some(class_hash.into()),
+            ..Default::default()
+        })
+        .await
+    }
+
+    fn snapshot(&self) -> RpcResult<u64> {
+        self.deny_unsafe.check_if_safe()?;
+
+        let mut snapshots = self.snapshots.lock().expect("Poisoned lock");
+        snapshots.push(self.client.info().best_number);
+        Ok(snapshots.len() as u64 - 1)
+    }
+
+    fn revert(&self, snapshot_id: u64) -> RpcResult<()> {
+        self.deny_unsafe.check_if_safe()?;
+
+        let mut snapshots = self.snapshots.lock().expect("Poisoned lock");
+        let Some(&snapshot_number) = snapshots.get(snapshot_id as usize) else {
+            return Err(CallError::InvalidParams(anyhow::anyhow!("Unknown snapshot {snapshot_id}")).into());
+        };
+
+        let info = self.client.info();
+        let blocks = info.best_number - snapshot_number.min(info.best_number);
+
+        // The Madara db mapping of the blocks has to be read before they are removed
+        let mut reverted_blocks = Vec::new();
+        let mut block_hash = info.best_hash;
+        let n_blocks: u64 = blocks.unique_saturated_into();
+        for _ in 0..n_blocks {
+            let header = self.client.header(block_hash).ok().flatten().ok_or_else(|| {
+                error!("Failed to retrieve the header of block {block_hash}");
+                StarknetRpcApiError::InternalServerError
+            })?;
+            reverted_blocks.push((block_hash, self.mapping_commitment(block_hash)?));
+            block_hash = *header.parent_hash();
+        }
+
+        // The transactions of the pool have been validated against the reverted state
+        let pool_hashes = self
+            .pool
+            .ready()
+            .map(|tx| tx.hash().clone())
+            .chain(self.pool.futures().iter().map(|tx| tx.hash().clone()))
+            .collect::<Vec<_>>();
+        self.pool.remove_invalid(&pool_hashes);
+
+        let (reverted, _) = self.backend.revert(blocks, true).map_err(|e| {
+            error!("Failed to revert {blocks} blocks: {e}");
+            StarknetRpcApiError::InternalServerError
+        })?;
+        if reverted != blocks {
+            let reverted: u64 = reverted.unique_saturated_into();
+            error!("Only {reverted} blocks could be reverted to snapshot {snapshot_id}");
+            return Err(StarknetRpcApiError::InternalServerError.into());
+        }
+
+        self.revert_madara_db(reverted_blocks, snapshot_number.unique_saturated_into()).map_err(|e| {
+            error!("Failed to revert the Madara db to snapshot {snapshot_id}: {e}");
+            StarknetRpcApiError::InternalServerError
+        })?;
+
+        snapshots.truncate(snapshot_id as usize);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clock_moves_by_the_block_time() {
+        let clock = DevClock::new(6000);
+
+        assert_eq!(clock.next_timestamp(), 6000);
+        assert_eq!(clock.advance(), 6000);
+        assert_eq!(clock.advance(), 12000);
+        assert_eq!(clock.next_timestamp(), 18000);
+    }
+
+    #[test]
+    fn increased_time_adds_up_until_the_next_block() {
+        let clock = DevClock::new(6000);
+        clock.advance();
+
+        assert_eq!(clock.increase_time(1000), 13000);
+        assert_eq!(clock.increase_time(1000), 14000);
+        assert_eq!(clock.advance(), 14000);
+        // The clock moves by the block time again from the increased timestamp
+        assert_eq!(clock.advance(), 20000);
+    }
+
+    #[test]
+    fn next_timestamp_is_at_least_one_block_time_after_the_last_one() {
+        let clock = DevClock::new(6000);
+        clock.advance();
+
+        assert!(clock.set_next_timestamp(11999).is_err());
+        assert_eq!(clock.next_timestamp(), 12000);
+
+        clock.set_next_timestamp(30000).unwrap();
+        assert_eq!(clock.next_timestamp(), 30000);
+        assert_eq!(clock.advance(), 30000);
+        assert_eq!(clock.next_timestamp(), 36000);
+    }
+
+    #[test]
+    fn clones_share_the_clock() {
+        let clock = DevClock::new(6000);
+        let inherent_clock = clock.clone();
+
+        clock.increase_time(4000);
+
+        assert_eq!(inherent_clock.advance(), 10000);
+        assert_eq!(clock.next_timestamp(), 16000);
+    }
+}
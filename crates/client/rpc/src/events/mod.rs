@@ -52,8 +52,6 @@ where
                 StarknetRpcApiError::BlockNotFound
             })?;
 
-        let runtime_api = self.client.runtime_api();
-
         let chain_id = self.get_chain_id(substrate_block_hash).map_err(|_| {
             error!("Failed to retrieve chain id");
             StarknetRpcApiError::InternalServerError
@@ -74,11 +72,7 @@ where
 
         let mut emitted_events: Vec<EmittedEvent> = vec![];
         for tx_hash in txn_hashes {
-            let raw_events =
-                runtime_api.get_events_for_tx_by_hash(substrate_block_hash, TransactionHash(tx_hash)).map_err(|e| {
-                    error!("Failed to retrieve starknet events for transaction: error: {e}");
-                    StarknetRpcApiError::InternalServerError
-                })?;
+            let raw_events = self.get_events_for_tx_by_hash(substrate_block_hash, TransactionHash(tx_hash))?;
             for event in raw_events {
                 emitted_events.push(EmittedEvent {
                     from_address: Felt252Wrapper::from(event.from_address).0,
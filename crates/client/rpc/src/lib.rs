@@ -2,14 +2,18 @@
 //!
 //! It uses the madara client and backend in order to answer queries.
 
+mod compiled_classes;
 mod constants;
+pub mod dev;
 mod errors;
 mod events;
 mod madara_backend_client;
 mod runtime_api;
 pub mod starknetrpcwrapper;
+mod submitted_transactions;
 mod trace_api;
 mod types;
+pub mod versioned;
 
 use std::collections::HashMap;
 use std::marker::PhantomData;
@@ -20,19 +24,23 @@ use errors::StarknetRpcApiError;
 use jsonrpsee::core::{async_trait, RpcResult};
 use jsonrpsee::types::error::CallError;
 use log::error;
+use mc_db::{ArchiveKey, ArchivedValue, TransactionOutput};
 use mc_genesis_data_provider::GenesisProvider;
 pub use mc_rpc_core::utils::*;
 pub use mc_rpc_core::{
-    Felt, MadaraRpcApiServer, PredeployedAccountWithBalance, StarknetReadRpcApiServer, StarknetTraceRpcApiServer,
+    BlockToSimulate, ContractStateOverride, Felt, MadaraDevRpcApiServer, MadaraRpcApiServer, Paymaster,
+    PredeployedAccountWithBalance, StarknetBlockReceipts, StarknetReadRpcApiServer, StarknetTraceRpcApiServer,
     StarknetWriteRpcApiServer,
 };
 use mc_storage::OverrideHandle;
 use mp_block::BlockTransactions;
 use mp_felt::Felt252Wrapper;
 use mp_hashers::HasherT;
+use mp_simulations::StateOverrides;
 use mp_transactions::compute_hash::ComputeTransactionHash;
+use mp_transactions::from_broadcasted_transactions::sponsored_invoke_into_user_transaction;
 use mp_transactions::to_starknet_core_transaction::to_starknet_core_tx;
-use mp_transactions::{TransactionStatus, UserTransaction};
+use mp_transactions::{TransactionStatus, TxType, UserTransaction};
 use pallet_starknet_runtime_api::{ConvertTransactionRuntimeApi, StarknetRuntimeApi};
 use sc_client_api::backend::{Backend, StorageProvider};
 use sc_client_api::BlockBackend;
@@ -47,8 +55,9 @@ use sp_core::H256;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 use sp_runtime::transaction_validity::InvalidTransaction;
 use sp_runtime::DispatchError;
+use starknet_api::api_core::{ClassHash, Nonce};
 use starknet_api::block::BlockHash;
-use starknet_api::hash::StarkHash;
+use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::transaction::{Calldata, TransactionHash};
 use starknet_core::types::{
     BlockHashAndNumber, BlockId, BlockStatus, BlockTag, BlockWithTxHashes, BlockWithTxs, BroadcastedDeclareTransaction,
@@ -59,12 +68,17 @@ use starknet_core::types::{
     L1HandlerTransactionReceipt, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs, MaybePendingStateUpdate,
     MaybePendingTransactionReceipt, MsgFromL1, PendingBlockWithTxHashes, PendingBlockWithTxs,
     PendingDeclareTransactionReceipt, PendingDeployAccountTransactionReceipt, PendingInvokeTransactionReceipt,
-    PendingL1HandlerTransactionReceipt, PendingStateUpdate, PendingTransactionReceipt, StateDiff, StateUpdate,
-    SyncStatus, SyncStatusType, Transaction, TransactionExecutionStatus, TransactionFinalityStatus, TransactionReceipt,
+    PendingL1HandlerTransactionReceipt, PendingStateUpdate, PendingTransactionReceipt, SimulatedTransaction,
+    SimulationFlag, StateDiff, StateUpdate, SyncStatus, SyncStatusType, Transaction, TransactionExecutionStatus,
+    TransactionFinalityStatus, TransactionReceipt,
 };
 use starknet_core::utils::get_selector_from_name;
 
-use crate::constants::{MAX_EVENTS_CHUNK_SIZE, MAX_EVENTS_KEYS};
+use crate::compiled_classes::{user_declare_transaction, user_transaction};
+use crate::constants::{
+    MAX_EVENTS_CHUNK_SIZE, MAX_EVENTS_KEYS, MAX_SUBMITTED_TRANSACTIONS_TRACKED, SUBMITTED_TRANSACTIONS_MAX_AGE,
+};
+use crate::submitted_transactions::{SubmissionOutcome, SubmittedTransactions};
 use crate::trace_api::map_transaction_to_user_transaction;
 use crate::types::RpcEventFilter;
 
@@ -79,6 +93,7 @@ pub struct Starknet<A: ChainApi, B: BlockT, BE, G, C, P, H> {
     sync_service: Arc<SyncingService<B>>,
     starting_block: <<B>::Header as HeaderT>::Number,
     genesis_provider: Arc<G>,
+    submitted_transactions: Arc<SubmittedTransactions>,
     _marker: PhantomData<(B, BE, H)>,
 }
 
@@ -114,6 +129,10 @@ impl<A: ChainApi, B: BlockT, BE, G, C, P, H> Starknet<A, B, BE, G, C, P, H> {
             sync_service,
             starting_block,
             genesis_provider,
+            submitted_transactions: Arc::new(SubmittedTransactions::new(
+                MAX_SUBMITTED_TRANSACTIONS_TRACKED,
+                SUBMITTED_TRANSACTIONS_MAX_AGE,
+            )),
             _marker: PhantomData,
         }
     }
@@ -261,6 +280,7 @@ where
 /// Taken from https://github.com/paritytech/substrate/blob/master/client/rpc/src/author/mod.rs#L78
 const TX_SOURCE: TransactionSource = TransactionSource::External;
 
+#[async_trait]
 impl<A, B, BE, G, C, P, H> MadaraRpcApiServer for Starknet<A, B, BE, G, C, P, H>
 where
     A: ChainApi<Block = B> + 'static,
@@ -300,6 +320,176 @@ where
             })
             .collect::<Vec<_>>())
     }
+
+    fn call_with_overrides(
+        &self,
+        request: FunctionCall,
+        block_id: BlockId,
+        state_overrides: Vec<ContractStateOverride>,
+    ) -> RpcResult<Vec<String>> {
+        let substrate_block_hash = self.substrate_block_hash_from_starknet_block(block_id).map_err(|e| {
+            error!("'{e}'");
+            StarknetRpcApiError::BlockNotFound
+        })?;
+
+        let calldata = Calldata(Arc::new(request.calldata.iter().map(|x| Felt252Wrapper::from(*x).into()).collect()));
+        let state_overrides: StateOverrides = state_overrides.into_iter().map(Into::into).collect();
+
+        let result = self.do_call_with_overrides(
+            substrate_block_hash,
+            Felt252Wrapper(request.contract_address).into(),
+            Felt252Wrapper(request.entry_point_selector).into(),
+            calldata,
+            state_overrides,
+        )?;
+
+        let result = self.convert_error(substrate_block_hash, result)?;
+
+        Ok(result.iter().map(|x| format!("{:#x}", x.0)).collect())
+    }
+
+    fn estimate_fee_with_overrides(
+        &self,
+        request: Vec<BroadcastedTransaction>,
+        block_id: BlockId,
+        state_overrides: Vec<ContractStateOverride>,
+    ) -> RpcResult<Vec<FeeEstimate>> {
+        let substrate_block_hash = self.substrate_block_hash_from_starknet_block(block_id).map_err(|e| {
+            error!("'{e}'");
+            StarknetRpcApiError::BlockNotFound
+        })?;
+
+        let storage_override = self.overrides.for_block_hash(self.client.as_ref(), substrate_block_hash);
+        let transactions = request
+            .into_iter()
+            .map(|tx| user_transaction(&self.backend, storage_override.as_ref(), substrate_block_hash, tx))
+            .collect::<Result<Vec<UserTransaction>, _>>()
+            .map_err(|e| {
+                error!("Failed to convert BroadcastedTransaction to UserTransaction: {e}");
+                StarknetRpcApiError::InternalServerError
+            })?;
+        let state_overrides: StateOverrides = state_overrides.into_iter().map(Into::into).collect();
+
+        let fee_estimates = self.do_estimate_fee_with_overrides(substrate_block_hash, transactions, state_overrides)?;
+
+        let estimates = fee_estimates
+            .into_iter()
+            // FIXME: https://github.com/keep-starknet-strange/madara/issues/329
+            .map(|x| FeeEstimate { gas_price: 10, gas_consumed: x.1, overall_fee: x.0 })
+            .collect();
+
+        Ok(estimates)
+    }
+
+    fn simulate_transactions_with_overrides(
+        &self,
+        block_id: BlockId,
+        transactions: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+        state_overrides: Vec<ContractStateOverride>,
+    ) -> RpcResult<Vec<SimulatedTransaction>> {
+        let substrate_block_hash = self.substrate_block_hash_from_starknet_block(block_id).map_err(|e| {
+            error!("'{e}'");
+            StarknetRpcApiError::BlockNotFound
+        })?;
+        let state_overrides: StateOverrides = state_overrides.into_iter().map(Into::into).collect();
+
+        self.do_simulate_transactions_with_overrides(
+            substrate_block_hash,
+            transactions,
+            simulation_flags,
+            state_overrides,
+        )
+    }
+
+    fn simulate_blocks(
+        &self,
+        block_id: BlockId,
+        blocks: Vec<BlockToSimulate>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<Vec<SimulatedTransaction>>> {
+        let substrate_block_hash = self.substrate_block_hash_from_starknet_block(block_id).map_err(|e| {
+            error!("'{e}'");
+            StarknetRpcApiError::BlockNotFound
+        })?;
+
+        self.do_simulate_blocks(substrate_block_hash, blocks, simulation_flags)
+    }
+
+    async fn add_sponsored_invoke_transaction(
+        &self,
+        invoke_transaction: BroadcastedInvokeTransaction,
+        paymaster: Paymaster,
+    ) -> RpcResult<InvokeTransactionResult> {
+        let best_block_hash = self.get_best_block_hash();
+
+        let transaction = sponsored_user_transaction(invoke_transaction, paymaster)?;
+
+        let chain_id = Felt252Wrapper(self.chain_id()?.0);
+        let tx_hash = transaction.compute_hash::<H>(chain_id, false);
+
+        let extrinsic = self.convert_tx_to_extrinsic(best_block_hash, transaction)?;
+
+        self.submit_extrinsic(best_block_hash, extrinsic, tx_hash).await?;
+
+        Ok(InvokeTransactionResult { transaction_hash: tx_hash.into() })
+    }
+
+    fn estimate_sponsored_invoke_fee(
+        &self,
+        invoke_transaction: BroadcastedInvokeTransaction,
+        paymaster: Paymaster,
+        block_id: BlockId,
+    ) -> RpcResult<FeeEstimate> {
+        let substrate_block_hash = self.substrate_block_hash_from_starknet_block(block_id).map_err(|e| {
+            error!("'{e}'");
+            StarknetRpcApiError::BlockNotFound
+        })?;
+
+        let transaction = sponsored_user_transaction(invoke_transaction, paymaster)?;
+
+        let (overall_fee, gas_consumed) = self
+            .estimate_fee(substrate_block_hash, vec![transaction])?
+            .pop()
+            .ok_or(StarknetRpcApiError::InternalServerError)?;
+
+        // FIXME: https://github.com/keep-starknet-strange/madara/issues/329
+        Ok(FeeEstimate { gas_price: 10, gas_consumed, overall_fee })
+    }
+
+    fn simulate_sponsored_invoke_transaction(
+        &self,
+        block_id: BlockId,
+        invoke_transaction: BroadcastedInvokeTransaction,
+        paymaster: Paymaster,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<SimulatedTransaction> {
+        let substrate_block_hash = self.substrate_block_hash_from_starknet_block(block_id).map_err(|e| {
+            error!("'{e}'");
+            StarknetRpcApiError::BlockNotFound
+        })?;
+
+        let transaction = sponsored_user_transaction(invoke_transaction, paymaster)?;
+
+        self.do_simulate_user_transactions(
+            substrate_block_hash,
+            vec![TxType::Invoke],
+            vec![transaction],
+            simulation_flags,
+        )?
+        .pop()
+        .ok_or_else(|| StarknetRpcApiError::InternalServerError.into())
+    }
+}
+
+fn sponsored_user_transaction(
+    invoke_transaction: BroadcastedInvokeTransaction,
+    paymaster: Paymaster,
+) -> Result<UserTransaction, StarknetRpcApiError> {
+    sponsored_invoke_into_user_transaction(invoke_transaction, paymaster.address, paymaster.signature).map_err(|e| {
+        error!("Failed to convert BroadcastedInvokeTransaction to a sponsored UserTransaction: {e}");
+        StarknetRpcApiError::InternalServerError
+    })
 }
 
 #[async_trait]
@@ -336,10 +526,13 @@ where
             None
         };
 
-        let transaction: UserTransaction = declare_transaction.try_into().map_err(|e| {
-            error!("Failed to convert BroadcastedDeclareTransaction to UserTransaction, error: {e}");
-            StarknetRpcApiError::InternalServerError
-        })?;
+        let storage_override = self.overrides.for_block_hash(self.client.as_ref(), best_block_hash);
+        let transaction =
+            user_declare_transaction(&self.backend, storage_override.as_ref(), best_block_hash, declare_transaction)
+                .map_err(|e| {
+                    error!("Failed to convert BroadcastedDeclareTransaction to UserTransaction, error: {e}");
+                    StarknetRpcApiError::InternalServerError
+                })?;
         let class_hash = match transaction {
             UserTransaction::Declare(ref tx, _) => tx.class_hash(),
             _ => Err(StarknetRpcApiError::InternalServerError)?,
@@ -356,13 +549,14 @@ where
             return Err(StarknetRpcApiError::ClassAlreadyDeclared.into());
         }
 
-        let extrinsic = self.convert_tx_to_extrinsic(best_block_hash, transaction.clone())?;
+        let chain_id = Felt252Wrapper(self.chain_id()?.0);
+        let tx_hash = transaction.compute_hash::<H>(chain_id, false);
 
-        submit_extrinsic(self.pool.clone(), best_block_hash, extrinsic).await?;
+        let extrinsic = self.convert_tx_to_extrinsic(best_block_hash, transaction.clone())?;
 
-        let chain_id = Felt252Wrapper(self.chain_id()?.0);
+        self.submit_extrinsic(best_block_hash, extrinsic, tx_hash).await?;
 
-        let tx_hash = transaction.compute_hash::<H>(chain_id, false).into();
+        let tx_hash: FieldElement = tx_hash.into();
 
         if let Some(sierra_contract_class) = opt_sierra_contract_class {
             if let Some(e) = self
@@ -398,13 +592,14 @@ where
             StarknetRpcApiError::InternalServerError
         })?;
 
-        let extrinsic = self.convert_tx_to_extrinsic(best_block_hash, transaction.clone())?;
+        let chain_id = Felt252Wrapper(self.chain_id()?.0);
+        let tx_hash = transaction.compute_hash::<H>(chain_id, false);
 
-        submit_extrinsic(self.pool.clone(), best_block_hash, extrinsic).await?;
+        let extrinsic = self.convert_tx_to_extrinsic(best_block_hash, transaction)?;
 
-        let chain_id = Felt252Wrapper(self.chain_id()?.0);
+        self.submit_extrinsic(best_block_hash, extrinsic, tx_hash).await?;
 
-        Ok(InvokeTransactionResult { transaction_hash: transaction.compute_hash::<H>(chain_id, false).into() })
+        Ok(InvokeTransactionResult { transaction_hash: tx_hash.into() })
     }
 
     /// Add an Deploy Account Transaction
@@ -428,18 +623,19 @@ where
             StarknetRpcApiError::InternalServerError
         })?;
 
-        let extrinsic = self.convert_tx_to_extrinsic(best_block_hash, transaction.clone())?;
-
-        submit_extrinsic(self.pool.clone(), best_block_hash, extrinsic).await?;
-
         let chain_id = Felt252Wrapper(self.chain_id()?.0);
+        let tx_hash = transaction.compute_hash::<H>(chain_id, false);
         let account_address = match &transaction {
             UserTransaction::DeployAccount(tx) => tx.account_address(),
             _ => Err(StarknetRpcApiError::InternalServerError)?,
         };
 
+        let extrinsic = self.convert_tx_to_extrinsic(best_block_hash, transaction)?;
+
+        self.submit_extrinsic(best_block_hash, extrinsic, tx_hash).await?;
+
         Ok(DeployAccountTransactionResult {
-            transaction_hash: transaction.compute_hash::<H>(chain_id, false).into(),
+            transaction_hash: tx_hash.into(),
             contract_address: account_address.into(),
         })
     }
@@ -556,16 +752,21 @@ where
     ///     confirmed, pending, or rejected.
     ///   - `execution_status`: The execution status of the transaction, providing details on the
     ///     execution outcome if the transaction has been processed.
+    ///   - `failure_reason`: The reason why the transaction was rejected, if it was.
     fn get_transaction_status(&self, transaction_hash: FieldElement) -> RpcResult<TransactionStatus> {
-        let substrate_block_hash = self
+        let substrate_block_hash = match self
             .backend
             .mapping()
             .block_hash_from_transaction_hash(Felt252Wrapper(transaction_hash).into())
             .map_err(|e| {
                 error!("Failed to get transaction's substrate block hash from mapping_db: {e}");
                 StarknetRpcApiError::TxnHashNotFound
-            })?
-            .ok_or(StarknetRpcApiError::TxnHashNotFound)?;
+            })? {
+            Some(substrate_block_hash) => substrate_block_hash,
+            // The transaction has not been included in a block (yet)
+            None => return Ok(self.get_submitted_transaction_status(transaction_hash)?),
+        };
+        self.submitted_transactions.forget(&Felt252Wrapper(transaction_hash).into());
 
         let starknet_block = get_block_by_block_hash(self.client.as_ref(), substrate_block_hash)?;
 
@@ -596,7 +797,7 @@ where
             }
         };
 
-        Ok(TransactionStatus { finality_status: TransactionFinalityStatus::AcceptedOnL2, execution_status })
+        Ok(TransactionStatus::executed(TransactionFinalityStatus::AcceptedOnL2, execution_status))
     }
 
     /// Get the value of the storage at the given address and key.
@@ -641,6 +842,7 @@ where
             .overrides
             .for_block_hash(self.client.as_ref(), substrate_block_hash)
             .get_storage_by_storage_key(substrate_block_hash, contract_address, key)
+            .or_else(|| self.get_archived_value(block_id, ArchiveKey::Storage(contract_address, key)))
             .ok_or_else(|| {
                 error!("Failed to retrieve storage at '{contract_address:?}' and '{key:?}'");
                 StarknetRpcApiError::ContractNotFound
@@ -754,6 +956,7 @@ where
             .overrides
             .for_block_hash(self.client.as_ref(), substrate_block_hash)
             .contract_class_hash_by_address(substrate_block_hash, contract_address)
+            .or_else(|| self.get_archived_value(block_id, ArchiveKey::ClassHash(contract_address)).map(ClassHash))
             .ok_or_else(|| {
                 error!("Failed to retrieve contract class hash at '{contract_address:?}'");
                 StarknetRpcApiError::ContractNotFound
@@ -929,7 +1132,7 @@ where
             block_hash: block_hash.into(),
             parent_hash: Felt252Wrapper::from(parent_blockhash).into(),
             block_number: starknet_block.header().block_number,
-            new_root: Felt252Wrapper::from(self.backend.temporary_global_state_root_getter()).into(),
+            new_root: Felt252Wrapper::from(starknet_block.header().global_state_root).into(),
             timestamp: starknet_block.header().block_timestamp,
             sequencer_address: Felt252Wrapper::from(starknet_block.header().sequencer_address).into(),
             l1_gas_price: starknet_block.header().l1_gas_price.into(),
@@ -967,6 +1170,7 @@ where
             .overrides
             .for_block_hash(self.client.as_ref(), substrate_block_hash)
             .nonce(substrate_block_hash, contract_address)
+            .or_else(|| self.get_archived_value(block_id, ArchiveKey::Nonce(contract_address)).map(Nonce))
             .ok_or_else(|| {
                 error!("Failed to get nonce at '{contract_address:?}'");
                 StarknetRpcApiError::ContractNotFound
@@ -1018,14 +1222,20 @@ where
         let best_block_hash = self.get_best_block_hash();
         let chain_id = Felt252Wrapper(self.chain_id()?.0);
 
-        let transactions =
-            request.into_iter().map(|tx| tx.try_into()).collect::<Result<Vec<UserTransaction>, _>>().map_err(|e| {
+        let storage_override = self.overrides.for_block_hash(self.client.as_ref(), best_block_hash);
+        let transactions = request
+            .into_iter()
+            .map(|tx| user_transaction(&self.backend, storage_override.as_ref(), best_block_hash, tx))
+            .collect::<Result<Vec<UserTransaction>, _>>()
+            .map_err(|e| {
                 error!("Failed to convert BroadcastedTransaction to UserTransaction: {e}");
                 StarknetRpcApiError::InternalServerError
             })?;
 
         let fee_estimates = self.estimate_fee(best_block_hash, transactions)?;
 
+        // The v0.5 estimates carry no fee unit, every fee is paid in wei until the V3 transactions
+        // are supported
         let estimates = fee_estimates
             .into_iter()
 			// FIXME: https://github.com/keep-starknet-strange/madara/issues/329
@@ -1185,7 +1395,7 @@ where
             block_hash: block_hash.into(),
             parent_hash: Felt252Wrapper::from(starknet_block.header().parent_block_hash).into(),
             block_number: starknet_block.header().block_number,
-            new_root: Felt252Wrapper::from(self.backend.temporary_global_state_root_getter()).into(),
+            new_root: Felt252Wrapper::from(starknet_block.header().global_state_root).into(),
             timestamp: starknet_block.header().block_timestamp,
             sequencer_address: Felt252Wrapper::from(starknet_block.header().sequencer_address).into(),
             transactions,
@@ -1227,7 +1437,7 @@ where
 
             let latest_block = self.get_best_block_hash();
             let latest_block = get_block_by_block_hash(self.client.as_ref(), latest_block).unwrap_or_default();
-            let old_root = self.backend.temporary_global_state_root_getter().into();
+            let old_root = Felt252Wrapper::from(latest_block.header().global_state_root).into();
             let pending_state_update = PendingStateUpdate { old_root, state_diff };
 
             return Ok(MaybePendingStateUpdate::PendingUpdate(pending_state_update));
@@ -1250,7 +1460,7 @@ where
 
             let parent_block = get_block_by_block_hash(self.client.as_ref(), substrate_parent_block_hash)?;
 
-            Felt252Wrapper::from(self.backend.temporary_global_state_root_getter()).into()
+            Felt252Wrapper::from(parent_block.header().global_state_root).into()
         } else {
             FieldElement::default()
         };
@@ -1264,7 +1474,7 @@ where
 
         let state_update = StateUpdate {
             block_hash: starknet_block.header().hash::<H>().into(),
-            new_root: Felt252Wrapper::from(self.backend.temporary_global_state_root_getter()).into(),
+            new_root: Felt252Wrapper::from(starknet_block.header().global_state_root).into(),
             old_root,
             state_diff,
         };
@@ -1449,7 +1659,94 @@ where
     }
 }
 
+impl<A, B, BE, G, C, P, H> StarknetBlockReceipts for Starknet<A, B, BE, G, C, P, H>
+where
+    A: ChainApi<Block = B> + 'static,
+    B: BlockT,
+    P: TransactionPool<Block = B> + 'static,
+    BE: Backend<B> + 'static,
+    C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BE> + 'static,
+    C: ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B> + ConvertTransactionRuntimeApi<B>,
+    G: GenesisProvider + Send + Sync + 'static,
+    H: HasherT + Send + Sync + 'static,
+{
+    fn get_block_receipts(&self, block_id: BlockId) -> RpcResult<Vec<MaybePendingTransactionReceipt>> {
+        let chain_id = Felt252Wrapper(self.chain_id()?.0);
+
+        if is_pending_block(block_id) {
+            return Ok(self.prepare_pending_block_receipts(chain_id)?);
+        }
+
+        let substrate_block_hash = self.substrate_block_hash_from_starknet_block(block_id).map_err(|e| {
+            error!("Block not found: '{e}'");
+            StarknetRpcApiError::BlockNotFound
+        })?;
+
+        Ok(self.prepare_block_receipts(chain_id, substrate_block_hash, None)?)
+    }
+}
+
 /// RPC Helper methods
+/// Transaction outputs helpers
+///
+/// The outputs are read from the Madara db, where they are copied when the block is imported,
+/// and from the runtime state for the blocks that have not been synced yet.
+impl<A, B, BE, G, C, P, H> Starknet<A, B, BE, G, C, P, H>
+where
+    A: ChainApi<Block = B> + 'static,
+    B: BlockT,
+    BE: Backend<B>,
+    C: HeaderBackend<B> + 'static,
+    C: ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B> + ConvertTransactionRuntimeApi<B>,
+    H: HasherT + Send + Sync + 'static,
+{
+    fn get_stored_transaction_output(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> Result<Option<TransactionOutput>, StarknetRpcApiError> {
+        self.backend.transaction_outputs().get_output(&tx_hash).map_err(|e| {
+            error!("Failed to read the outputs of transaction {tx_hash} from db: {e}");
+            StarknetRpcApiError::InternalServerError
+        })
+    }
+
+    fn get_events_for_tx_by_hash(
+        &self,
+        substrate_block_hash: B::Hash,
+        tx_hash: TransactionHash,
+    ) -> Result<Vec<starknet_api::transaction::Event>, StarknetRpcApiError> {
+        match self.get_stored_transaction_output(tx_hash)? {
+            Some(output) => Ok(output.events),
+            None => self.do_get_events_for_tx_by_hash(substrate_block_hash, tx_hash),
+        }
+    }
+
+    fn get_tx_execution_outcome(
+        &self,
+        substrate_block_hash: B::Hash,
+        transaction_hash: FieldElement,
+    ) -> Result<Option<Vec<u8>>, StarknetRpcApiError> {
+        let tx_hash = Felt252Wrapper(transaction_hash).into();
+        match self.get_stored_transaction_output(tx_hash)? {
+            Some(output) => Ok(output.revert_error.map(String::into_bytes)),
+            None => self.do_get_tx_execution_outcome(substrate_block_hash, tx_hash),
+        }
+    }
+
+    fn get_messages_for_tx_by_hash(
+        &self,
+        substrate_block_hash: B::Hash,
+        transaction_hash: FieldElement,
+    ) -> Result<Vec<starknet_api::transaction::MessageToL1>, StarknetRpcApiError> {
+        match self.get_stored_transaction_output(Felt252Wrapper(transaction_hash).into())? {
+            Some(output) => Ok(output.messages),
+            None => self.get_tx_messages_to_l1(substrate_block_hash, transaction_hash),
+        }
+    }
+}
+
 impl<A, B, BE, G, C, P, H> Starknet<A, B, BE, G, C, P, H>
 where
     A: ChainApi<Block = B> + 'static,
@@ -1529,6 +1826,22 @@ where
         transaction_hash: FieldElement,
         substrate_block_hash: B::Hash,
     ) -> Result<MaybePendingTransactionReceipt, StarknetRpcApiError> {
+        self.prepare_block_receipts(chain_id, substrate_block_hash, Some(transaction_hash))?
+            .pop()
+            .ok_or(StarknetRpcApiError::InternalServerError)
+    }
+
+    /// Builds the receipts of the transactions of a block, re-executing the block once for all of
+    /// them.
+    ///
+    /// Only the receipt of `target_transaction_hash` is built when it is set, re-executing the
+    /// transactions of the block up to it.
+    fn prepare_block_receipts(
+        &self,
+        chain_id: Felt252Wrapper,
+        substrate_block_hash: B::Hash,
+        target_transaction_hash: Option<FieldElement>,
+    ) -> Result<Vec<MaybePendingTransactionReceipt>, StarknetRpcApiError> {
         let starknet_block: mp_block::Block = get_block_by_block_hash(self.client.as_ref(), substrate_block_hash)
             .map_err(|_e| StarknetRpcApiError::BlockNotFound)?;
         let block_header = starknet_block.header();
@@ -1548,57 +1861,24 @@ where
 
         let transactions = self.filter_extrinsics(substrate_block_hash, block_extrinsics)?;
         let txn_hashes = self.get_cached_transaction_hashes(starknet_block.header().hash::<H>().into());
-        let mut transaction = None;
-        for (index, tx) in transactions.iter().enumerate() {
-            let tx_hash = self.try_txn_hash_from_cache(index, &txn_hashes, &transactions, chain_id)?;
-            if tx_hash == transaction_hash.into() {
-                transaction = Some(tx);
-                break;
-            }
+        let mut transaction_hashes = Vec::with_capacity(transactions.len());
+        for index in 0..transactions.len() {
+            transaction_hashes.push(self.try_txn_hash_from_cache(index, &txn_hashes, &transactions, chain_id)?.0);
         }
-        if transaction.is_none() {
-            error!(
-                "Failed to find transaction hash in block. Substrate block hash: {substrate_block_hash}, transaction \
-                 hash: {transaction_hash}"
-            );
-            return Err(StarknetRpcApiError::InternalServerError);
-        }
-        let transaction = transaction.unwrap();
-
-        let events = self.get_events_for_tx_by_hash(substrate_block_hash, Felt252Wrapper(transaction_hash).into())?;
-
-        let execution_result = {
-            let revert_error = self.get_tx_execution_outcome(substrate_block_hash, transaction_hash)?;
-
-            // This is safe because the message is a Vec<u8> build from a String
-            revert_error_to_execution_result(
-                revert_error.map(|message| unsafe { String::from_utf8_unchecked(message) }),
-            )
-        };
-
-        let events_converted: Vec<starknet_core::types::Event> =
-            events.clone().into_iter().map(starknet_api_to_starknet_core_event).collect();
-
-        let actual_fee = if fee_disabled {
-            FieldElement::ZERO
-        } else {
-            // Event {
-            //     from_address: fee_token_address,
-            //     keys: [selector("Transfer")],
-            //     data: [
-            //         send_from_address,       // account_contract_address
-            //         send_to_address,         // to (sequencer address)
-            //         expected_fee_value_low,  // transfer amount (fee)
-            //         expected_fee_value_high,
-            //     ]},
-            // fee transfer must be the last event, except enabled disable-transaction-fee feature
-            events_converted.last().unwrap().data[2]
+        let receipts_range = match target_transaction_hash {
+            Some(transaction_hash) => {
+                let index = transaction_hashes.iter().position(|hash| *hash == transaction_hash).ok_or_else(|| {
+                    error!(
+                        "Failed to find transaction hash in block. Substrate block hash: {substrate_block_hash}, \
+                         transaction hash: {transaction_hash}"
+                    );
+                    StarknetRpcApiError::InternalServerError
+                })?;
+                index..index + 1
+            }
+            None => 0..transactions.len(),
         };
 
-        let messages = self.get_tx_messages_to_l1(substrate_block_hash, transaction_hash)?;
-
-        let messages_sent = messages.into_iter().map(starknet_api_to_starknet_core_message_to_l1).collect();
-
         // TODO
         // Is any better way to get execution resources of processed tx?
         let parent_substrate_block_hash = self
@@ -1607,29 +1887,80 @@ where
                 error!("Parent Block not found: {e}");
                 StarknetRpcApiError::BlockNotFound
             })?;
-        let execution_info = self.get_transaction_execution_info(
-            parent_substrate_block_hash,
-            starknet_block.transactions(),
-            chain_id,
-            transaction_hash,
-        )?;
+        let (transactions_before, transactions_to_trace) = match target_transaction_hash {
+            Some(transaction_hash) => map_transaction_to_user_transaction(
+                self,
+                substrate_block_hash,
+                starknet_block.transactions(),
+                chain_id,
+                Some(transaction_hash.into()),
+            )?,
+            None => {
+                let (block_transactions, _) = map_transaction_to_user_transaction(
+                    self,
+                    substrate_block_hash,
+                    starknet_block.transactions(),
+                    chain_id,
+                    None,
+                )?;
+                (Vec::new(), block_transactions)
+            }
+        };
+        let execution_infos = self
+            .re_execute_transactions(parent_substrate_block_hash, transactions_before, transactions_to_trace)
+            .map_err(|e| {
+                log::error!("Failed to re-execute transactions: {e}");
+                StarknetRpcApiError::InternalServerError
+            })?;
+        if execution_infos.len() != receipts_range.len() {
+            log::error!("Failed to get the execution info of the transactions of block {substrate_block_hash}");
+            return Err(StarknetRpcApiError::InternalServerError);
+        }
 
-        let execution_resources = actual_resources_to_execution_resources(execution_info.actual_resources);
+        let mut receipts = Vec::with_capacity(receipts_range.len());
+        for ((transaction, transaction_hash), (execution_info, _)) in
+            transactions[receipts_range.clone()].iter().zip(&transaction_hashes[receipts_range]).zip(execution_infos)
+        {
+            let transaction_hash = *transaction_hash;
+            let events =
+                self.get_events_for_tx_by_hash(substrate_block_hash, Felt252Wrapper(transaction_hash).into())?;
+
+            let execution_result = {
+                let revert_error = self.get_tx_execution_outcome(substrate_block_hash, transaction_hash)?;
+
+                // This is safe because the message is a Vec<u8> build from a String
+                revert_error_to_execution_result(
+                    revert_error.map(|message| unsafe { String::from_utf8_unchecked(message) }),
+                )
+            };
 
-        let receipt = match transaction {
-            mp_transactions::Transaction::Declare(_, _) => TransactionReceipt::Declare(DeclareTransactionReceipt {
-                transaction_hash,
-                actual_fee,
-                finality_status: TransactionFinalityStatus::AcceptedOnL2,
-                block_hash,
-                block_number,
-                messages_sent,
-                events: events_converted,
-                execution_result,
-                execution_resources,
-            }),
-            mp_transactions::Transaction::DeployAccount(tx) => {
-                TransactionReceipt::DeployAccount(DeployAccountTransactionReceipt {
+            let events_converted: Vec<starknet_core::types::Event> =
+                events.clone().into_iter().map(starknet_api_to_starknet_core_event).collect();
+
+            let actual_fee = if fee_disabled {
+                FieldElement::ZERO
+            } else {
+                // Event {
+                //     from_address: fee_token_address,
+                //     keys: [selector("Transfer")],
+                //     data: [
+                //         send_from_address,       // account or paymaster contract address
+                //         send_to_address,         // to (sequencer address)
+                //         expected_fee_value_low,  // transfer amount (fee)
+                //         expected_fee_value_high,
+                //     ]},
+                // fee transfer must be the last event, except enabled disable-transaction-fee feature
+                events_converted.last().unwrap().data[2]
+            };
+
+            let messages = self.get_messages_for_tx_by_hash(substrate_block_hash, transaction_hash)?;
+
+            let messages_sent = messages.into_iter().map(starknet_api_to_starknet_core_message_to_l1).collect();
+
+            let execution_resources = actual_resources_to_execution_resources(execution_info.actual_resources);
+
+            let receipt = match transaction {
+                mp_transactions::Transaction::Declare(_, _) => TransactionReceipt::Declare(DeclareTransactionReceipt {
                     transaction_hash,
                     actual_fee,
                     finality_status: TransactionFinalityStatus::AcceptedOnL2,
@@ -1637,25 +1968,24 @@ where
                     block_number,
                     messages_sent,
                     events: events_converted,
-                    contract_address: tx.get_account_address(),
                     execution_result,
                     execution_resources,
-                })
-            }
-            mp_transactions::Transaction::Invoke(_) => TransactionReceipt::Invoke(InvokeTransactionReceipt {
-                transaction_hash,
-                actual_fee,
-                finality_status: TransactionFinalityStatus::AcceptedOnL2,
-                block_hash,
-                block_number,
-                messages_sent,
-                events: events_converted,
-                execution_result,
-                execution_resources,
-            }),
-            mp_transactions::Transaction::L1Handler(ref tx) => {
-                TransactionReceipt::L1Handler(L1HandlerTransactionReceipt {
-                    message_hash: Hash256::from_felt(&tx.compute_hash::<H>(chain_id, false).0),
+                }),
+                mp_transactions::Transaction::DeployAccount(tx) => {
+                    TransactionReceipt::DeployAccount(DeployAccountTransactionReceipt {
+                        transaction_hash,
+                        actual_fee,
+                        finality_status: TransactionFinalityStatus::AcceptedOnL2,
+                        block_hash,
+                        block_number,
+                        messages_sent,
+                        events: events_converted,
+                        contract_address: tx.get_account_address(),
+                        execution_result,
+                        execution_resources,
+                    })
+                }
+                mp_transactions::Transaction::Invoke(_) => TransactionReceipt::Invoke(InvokeTransactionReceipt {
                     transaction_hash,
                     actual_fee,
                     finality_status: TransactionFinalityStatus::AcceptedOnL2,
@@ -1665,28 +1995,27 @@ where
                     events: events_converted,
                     execution_result,
                     execution_resources,
-                })
-            }
-        };
-
-        Ok(MaybePendingTransactionReceipt::Receipt(receipt))
-    }
+                }),
+                mp_transactions::Transaction::L1Handler(ref tx) => {
+                    TransactionReceipt::L1Handler(L1HandlerTransactionReceipt {
+                        message_hash: Hash256::from_felt(&tx.compute_hash::<H>(chain_id, false).0),
+                        transaction_hash,
+                        actual_fee,
+                        finality_status: TransactionFinalityStatus::AcceptedOnL2,
+                        block_hash,
+                        block_number,
+                        messages_sent,
+                        events: events_converted,
+                        execution_result,
+                        execution_resources,
+                    })
+                }
+            };
 
-    fn get_events_for_tx_by_hash(
-        &self,
-        substrate_block_hash: B::Hash,
-        tx_hash: TransactionHash,
-    ) -> Result<Vec<starknet_api::transaction::Event>, StarknetRpcApiError> {
-        let events = self.do_get_events_for_tx_by_hash(substrate_block_hash, tx_hash)?;
-        Ok(events)
-    }
+            receipts.push(MaybePendingTransactionReceipt::Receipt(receipt));
+        }
 
-    fn get_tx_execution_outcome(
-        &self,
-        substrate_block_hash: B::Hash,
-        transaction_hash: FieldElement,
-    ) -> Result<Option<Vec<u8>>, StarknetRpcApiError> {
-        self.do_get_tx_execution_outcome(substrate_block_hash, Felt252Wrapper(transaction_hash).into())
+        Ok(receipts)
     }
 
     fn find_pending_tx(
@@ -1712,6 +2041,55 @@ where
             .find_pending_tx(chain_id, transaction_hash, &pending_txs)?
             .ok_or(StarknetRpcApiError::TxnHashNotFound)?;
 
+        let execution_info = self.get_transaction_execution_info(
+            parent_substrate_block_hash,
+            parent_substrate_block_hash,
+            &pending_txs,
+            chain_id,
+            transaction_hash,
+        )?;
+
+        Ok(self.pending_transaction_receipt(chain_id, &pending_tx, transaction_hash, execution_info))
+    }
+
+    /// Builds the receipts of the pending transactions, re-executing them once for all of them.
+    fn prepare_pending_block_receipts(
+        &self,
+        chain_id: Felt252Wrapper,
+    ) -> Result<Vec<MaybePendingTransactionReceipt>, StarknetRpcApiError> {
+        let parent_substrate_block_hash = self.get_best_block_hash();
+        let pending_txs = self.get_pending_txs(parent_substrate_block_hash)?;
+
+        let (pending_transactions, _) =
+            map_transaction_to_user_transaction(self, parent_substrate_block_hash, &pending_txs, chain_id, None)?;
+        let execution_infos = self
+            .re_execute_transactions(parent_substrate_block_hash, Vec::new(), pending_transactions)
+            .map_err(|e| {
+                log::error!("Failed to re-execute transactions: {e}");
+                StarknetRpcApiError::InternalServerError
+            })?;
+        if execution_infos.len() != pending_txs.len() {
+            log::error!("Failed to get the execution info of the pending transactions");
+            return Err(StarknetRpcApiError::InternalServerError);
+        }
+
+        Ok(pending_txs
+            .iter()
+            .zip(execution_infos)
+            .map(|(pending_tx, (execution_info, _))| {
+                let transaction_hash = pending_tx.compute_hash::<H>(chain_id.0.into(), false).0;
+                self.pending_transaction_receipt(chain_id, pending_tx, transaction_hash, execution_info)
+            })
+            .collect())
+    }
+
+    fn pending_transaction_receipt(
+        &self,
+        chain_id: Felt252Wrapper,
+        pending_tx: &mp_transactions::Transaction,
+        transaction_hash: FieldElement,
+        execution_info: TransactionExecutionInfo,
+    ) -> MaybePendingTransactionReceipt {
         // TODO: Massa labs is working on pending blocks within Substrate. That will allow fetching
         // events and messages directly from the runtime the same way we do for finalized blocks.
         // So for now we return empty events and messages. Another option is to expose the event and message
@@ -1721,8 +2099,6 @@ where
         let messages_sent = Vec::new();
         let events = Vec::new();
 
-        let execution_info =
-            self.get_transaction_execution_info(parent_substrate_block_hash, &pending_txs, chain_id, transaction_hash)?;
         let actual_fee = execution_info.actual_fee.0.into();
         let execution_result = revert_error_to_execution_result(execution_info.revert_error);
         let execution_resources = actual_resources_to_execution_resources(execution_info.actual_resources);
@@ -1739,7 +2115,7 @@ where
                 };
                 PendingTransactionReceipt::Declare(receipt)
             }
-            mp_transactions::Transaction::DeployAccount(ref tx) => {
+            mp_transactions::Transaction::DeployAccount(tx) => {
                 let contract_address = tx.get_account_address();
                 let receipt = PendingDeployAccountTransactionReceipt {
                     transaction_hash,
@@ -1763,7 +2139,7 @@ where
                 };
                 PendingTransactionReceipt::Invoke(receipt)
             }
-            mp_transactions::Transaction::L1Handler(ref tx) => {
+            mp_transactions::Transaction::L1Handler(tx) => {
                 let receipt = PendingL1HandlerTransactionReceipt {
                     message_hash: Hash256::from_felt(&tx.compute_hash::<H>(chain_id, false).0),
                     transaction_hash,
@@ -1777,11 +2153,12 @@ where
             }
         };
 
-        Ok(MaybePendingTransactionReceipt::PendingReceipt(receipt))
+        MaybePendingTransactionReceipt::PendingReceipt(receipt)
     }
 
     fn get_transaction_execution_info(
         &self,
+        substrate_block_hash: B::Hash,
         parent_substrate_block_hash: B::Hash,
         previous_transactions: &BlockTransactions,
         chain_id: Felt252Wrapper,
@@ -1790,8 +2167,13 @@ where
     where
         B: BlockT,
     {
-        let (transactions_before, transaction_to_trace) =
-            map_transaction_to_user_transaction(self, previous_transactions, chain_id, Some(transaction_hash.into()))?;
+        let (transactions_before, transaction_to_trace) = map_transaction_to_user_transaction(
+            self,
+            substrate_block_hash,
+            previous_transactions,
+            chain_id,
+            Some(transaction_hash.into()),
+        )?;
 
         if transaction_to_trace.is_empty() {
             return Err(StarknetRpcApiError::TxnHashNotFound);
@@ -1817,6 +2199,119 @@ where
         Ok(execution_info.0.clone())
     }
 
+    /// Submits the extrinsic to the pool and records the outcome of the submission, so that the
+    /// status of the transaction can be queried before it gets included in a block.
+    async fn submit_extrinsic(
+        &self,
+        best_block_hash: B::Hash,
+        extrinsic: B::Extrinsic,
+        transaction_hash: Felt252Wrapper,
+    ) -> Result<(), StarknetRpcApiError> {
+        match self.pool.submit_one(best_block_hash, TX_SOURCE, extrinsic).await {
+            Ok(_) => {
+                self.submitted_transactions.mark_received(transaction_hash.into());
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to submit extrinsic: {:?}", e);
+                let pool_error = e.into_pool_error();
+                self.submitted_transactions.record_pool_error(transaction_hash.into(), &pool_error);
+                match pool_error {
+                    Ok(PoolError::InvalidTransaction(InvalidTransaction::BadProof)) => {
+                        Err(StarknetRpcApiError::ValidationFailure)
+                    }
+                    _ => Err(StarknetRpcApiError::InternalServerError),
+                }
+            }
+        }
+    }
+
+    /// Returns the status of a transaction which has not been included in a block.
+    ///
+    /// Only the transactions submitted through this node are known: they are either still in the
+    /// pool, or have been rejected by it.
+    fn get_submitted_transaction_status(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<TransactionStatus, StarknetRpcApiError> {
+        match self.submitted_transactions.outcome(&Felt252Wrapper(transaction_hash).into()) {
+            Some(SubmissionOutcome::Rejected(reason)) => Ok(TransactionStatus::rejected(reason)),
+            Some(SubmissionOutcome::Received) => {
+                let best_block_hash = self.get_best_block_hash();
+                let chain_id = self.get_chain_id(best_block_hash)?;
+                // Transactions with a future nonce are waiting in the pool as well
+                let pool_transactions: Vec<B::Extrinsic> = self
+                    .pool
+                    .ready()
+                    .map(|tx| tx.data().clone())
+                    .chain(self.pool.futures().into_iter().map(|tx| tx.data().clone()))
+                    .collect();
+                let pool_transactions = self.filter_extrinsics(best_block_hash, pool_transactions)?;
+
+                match self.find_pending_tx(chain_id, transaction_hash, &pool_transactions)? {
+                    Some(_) => Ok(TransactionStatus::received()),
+                    // Either dropped from the pool, or included in a block not yet synced in the mapping db
+                    None => {
+                        self.submitted_transactions.forget(&Felt252Wrapper(transaction_hash).into());
+                        Err(StarknetRpcApiError::TxnHashNotFound)
+                    }
+                }
+            }
+            None => Err(StarknetRpcApiError::TxnHashNotFound),
+        }
+    }
+
+    /// Reads the value of `key` at `block_id` from the state archive.
+    ///
+    /// Used when the substrate state of the block is not available anymore. Returns `None` if the
+    /// block is not covered by the archive or if the contract was not deployed at that block.
+    fn get_archived_value(&self, block_id: BlockId, key: ArchiveKey) -> Option<StarkFelt> {
+        // Tags designate the latest blocks, whose state is never pruned
+        if let BlockId::Tag(_) = block_id {
+            return None;
+        }
+        let block_number = self.substrate_block_number_from_starknet_block(block_id).ok()?;
+
+        let contract_address = match key {
+            ArchiveKey::Storage(contract_address, _)
+            | ArchiveKey::Nonce(contract_address)
+            | ArchiveKey::ClassHash(contract_address) => contract_address,
+        };
+        let class_hash = self.read_state_archive(block_number, ArchiveKey::ClassHash(contract_address))?;
+        if class_hash == StarkFelt::default() {
+            return None;
+        }
+
+        self.read_state_archive(block_number, key)
+    }
+
+    fn read_state_archive(&self, block_number: u64, key: ArchiveKey) -> Option<StarkFelt> {
+        let archived_value = self.backend.state_archive().value_at(&key, block_number).unwrap_or_else(|e| {
+            error!("Failed to read from the state archive: {e}");
+            None
+        })?;
+
+        match archived_value {
+            ArchivedValue::Value(value) => Some(value),
+            // The key has not changed since `block_number`, so the state of the archive tip holds its value
+            ArchivedValue::UnchangedUntil(tip) => {
+                let tip_hash = self.client.hash(UniqueSaturatedInto::unique_saturated_into(tip)).ok().flatten()?;
+                let overrides = self.overrides.for_block_hash(self.client.as_ref(), tip_hash);
+                match key {
+                    ArchiveKey::Storage(contract_address, storage_key) => {
+                        overrides.get_storage_by_storage_key(tip_hash, contract_address, storage_key)
+                    }
+                    ArchiveKey::Nonce(contract_address) => {
+                        overrides.nonce(tip_hash, contract_address).map(|nonce| nonce.0)
+                    }
+                    ArchiveKey::ClassHash(contract_address) => overrides
+                        .contract_class_hash_by_address(tip_hash, contract_address)
+                        .map(|class_hash| class_hash.0),
+                }
+            }
+        }
+    }
+
     fn convert_error<T>(
         &self,
         best_block_hash: <B as BlockT>::Hash,
@@ -1832,25 +2327,6 @@ where
     }
 }
 
-async fn submit_extrinsic<P, B>(
-    pool: Arc<P>,
-    best_block_hash: <B as BlockT>::Hash,
-    extrinsic: <B as BlockT>::Extrinsic,
-) -> Result<<P as TransactionPool>::Hash, StarknetRpcApiError>
-where
-    P: TransactionPool<Block = B> + 'static,
-    B: BlockT,
-    <B as BlockT>::Extrinsic: Send + Sync + 'static,
-{
-    pool.submit_one(best_block_hash, TX_SOURCE, extrinsic).await.map_err(|e| {
-        error!("Failed to submit extrinsic: {:?}", e);
-        match e.into_pool_error() {
-            Ok(PoolError::InvalidTransaction(InvalidTransaction::BadProof)) => StarknetRpcApiError::ValidationFailure,
-            _ => StarknetRpcApiError::InternalServerError,
-        }
-    })
-}
-
 /// The current timestamp in seconds.
 fn calculate_pending_block_timestamp() -> u64 {
     let timestamp_in_millisecond = sp_timestamp::InherentDataProvider::from_system_time().as_millis();
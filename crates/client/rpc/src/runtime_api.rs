@@ -7,14 +7,14 @@ pub use mc_rpc_core::{
 };
 use mp_felt::Felt252Wrapper;
 use mp_hashers::HasherT;
-use mp_simulations::SimulationFlags;
+use mp_simulations::{SimulationFlags, StateOverrides};
 use mp_transactions::{HandleL1MessageTransaction, Transaction, UserTransaction};
 use pallet_starknet_runtime_api::{
     ConvertTransactionRuntimeApi, StarknetRuntimeApi, StarknetTransactionExecutionError,
 };
 use sc_client_api::backend::Backend;
 use sc_transaction_pool::ChainApi;
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi, RuntimeApiInfo};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::traits::Block as BlockT;
 use sp_runtime::DispatchError;
@@ -49,6 +49,40 @@ where
         })
     }
 
+    /// Fails with `UnimplementedMethod` if the runtime of the given block serves a version of `Api`
+    /// older than `version`, the one introducing the method about to be called.
+    pub fn ensure_runtime_api_version<Api: RuntimeApiInfo + ?Sized>(
+        &self,
+        block_hash: B::Hash,
+        version: u32,
+    ) -> RpcApiResult<()> {
+        let supported =
+            self.client.runtime_api().has_api_with::<Api, _>(block_hash, |v| v >= version).map_err(|e| {
+                error!("Failed to fetch the runtime API version. Substrate block hash: {block_hash}, error: {e}");
+                StarknetRpcApiError::InternalServerError
+            })?;
+
+        if supported { Ok(()) } else { Err(StarknetRpcApiError::UnimplementedMethod) }
+    }
+
+    pub fn do_call_with_overrides(
+        &self,
+        block_hash: B::Hash,
+        contract_address: ContractAddress,
+        entry_point_selector: EntryPointSelector,
+        calldata: Calldata,
+        state_overrides: StateOverrides,
+    ) -> RpcApiResult<Result<Vec<Felt252Wrapper>, sp_runtime::DispatchError>> {
+        self.ensure_runtime_api_version::<dyn StarknetRuntimeApi<B>>(block_hash, 2)?;
+        self.client
+            .runtime_api()
+            .call_with_overrides(block_hash, contract_address, entry_point_selector, calldata, state_overrides)
+            .map_err(|e| {
+                error!("Request parameters error: {e}");
+                StarknetRpcApiError::InternalServerError
+            })
+    }
+
     pub fn do_estimate_message_fee(
         &self,
         block_hash: B::Hash,
@@ -134,6 +168,27 @@ where
                 StarknetRpcApiError::ContractError
             })
     }
+
+    pub fn do_estimate_fee_with_overrides(
+        &self,
+        block_hash: B::Hash,
+        transactions: Vec<UserTransaction>,
+        state_overrides: StateOverrides,
+    ) -> RpcApiResult<Vec<(u64, u64)>> {
+        self.ensure_runtime_api_version::<dyn StarknetRuntimeApi<B>>(block_hash, 2)?;
+        self.client
+            .runtime_api()
+            .estimate_fee_with_overrides(block_hash, transactions, state_overrides)
+            .map_err(|e| {
+                error!("Request parameters error: {e}");
+                StarknetRpcApiError::InternalServerError
+            })?
+            .map_err(|e| {
+                error!("Failed to call function: {:#?}", e);
+                StarknetRpcApiError::ContractError
+            })
+    }
+
     pub fn get_best_block_hash(&self) -> B::Hash {
         self.client.info().best_hash
     }
@@ -3,8 +3,8 @@ use std::sync::Arc;
 use jsonrpsee::core::{async_trait, RpcResult};
 use mc_genesis_data_provider::GenesisProvider;
 pub use mc_rpc_core::{
-    Felt, MadaraRpcApiServer, PredeployedAccountWithBalance, StarknetReadRpcApiServer, StarknetTraceRpcApiServer,
-    StarknetWriteRpcApiServer,
+    BlockToSimulate, ContractStateOverride, Felt, MadaraRpcApiServer, Paymaster, PredeployedAccountWithBalance,
+    StarknetReadRpcApiServer, StarknetTraceRpcApiServer, StarknetWriteRpcApiServer,
 };
 use mp_hashers::HasherT;
 use mp_transactions::TransactionStatus;
@@ -36,6 +36,7 @@ impl<A: ChainApi, B: BlockT, BE, G, C, P, H> Clone for StarknetRpcWrapper<A, B,
     }
 }
 
+#[async_trait]
 impl<A, B, BE, G, C, P, H> MadaraRpcApiServer for StarknetRpcWrapper<A, B, BE, G, C, P, H>
 where
     A: ChainApi<Block = B> + 'static,
@@ -51,6 +52,70 @@ where
     fn predeployed_accounts(&self) -> RpcResult<Vec<PredeployedAccountWithBalance>> {
         self.0.predeployed_accounts()
     }
+
+    fn call_with_overrides(
+        &self,
+        request: FunctionCall,
+        block_id: BlockId,
+        state_overrides: Vec<ContractStateOverride>,
+    ) -> RpcResult<Vec<String>> {
+        self.0.call_with_overrides(request, block_id, state_overrides)
+    }
+
+    fn estimate_fee_with_overrides(
+        &self,
+        request: Vec<BroadcastedTransaction>,
+        block_id: BlockId,
+        state_overrides: Vec<ContractStateOverride>,
+    ) -> RpcResult<Vec<FeeEstimate>> {
+        self.0.estimate_fee_with_overrides(request, block_id, state_overrides)
+    }
+
+    fn simulate_transactions_with_overrides(
+        &self,
+        block_id: BlockId,
+        transactions: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+        state_overrides: Vec<ContractStateOverride>,
+    ) -> RpcResult<Vec<SimulatedTransaction>> {
+        self.0.simulate_transactions_with_overrides(block_id, transactions, simulation_flags, state_overrides)
+    }
+
+    fn simulate_blocks(
+        &self,
+        block_id: BlockId,
+        blocks: Vec<BlockToSimulate>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<Vec<SimulatedTransaction>>> {
+        self.0.simulate_blocks(block_id, blocks, simulation_flags)
+    }
+
+    async fn add_sponsored_invoke_transaction(
+        &self,
+        invoke_transaction: BroadcastedInvokeTransaction,
+        paymaster: Paymaster,
+    ) -> RpcResult<InvokeTransactionResult> {
+        self.0.add_sponsored_invoke_transaction(invoke_transaction, paymaster).await
+    }
+
+    fn estimate_sponsored_invoke_fee(
+        &self,
+        invoke_transaction: BroadcastedInvokeTransaction,
+        paymaster: Paymaster,
+        block_id: BlockId,
+    ) -> RpcResult<FeeEstimate> {
+        self.0.estimate_sponsored_invoke_fee(invoke_transaction, paymaster, block_id)
+    }
+
+    fn simulate_sponsored_invoke_transaction(
+        &self,
+        block_id: BlockId,
+        invoke_transaction: BroadcastedInvokeTransaction,
+        paymaster: Paymaster,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<SimulatedTransaction> {
+        self.0.simulate_sponsored_invoke_transaction(block_id, invoke_transaction, paymaster, simulation_flags)
+    }
 }
 
 #[async_trait]
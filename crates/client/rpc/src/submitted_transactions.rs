@@ -0,0 +1,222 @@
+//! Bookkeeping of the transactions submitted through the write API of this node.
+//!
+//! Once a transaction is included in a block, its status is resolved through the mapping db.
+//! Before that, the only trace of it lives in the transaction pool, and a transaction refused by
+//! the pool leaves no trace at all. This store keeps track of both cases so that
+//! `starknet_getTransactionStatus` can answer `RECEIVED` and `REJECTED`.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use sc_transaction_pool_api::error::Error as PoolError;
+use sp_runtime::transaction_validity::InvalidTransaction;
+use starknet_api::hash::StarkHash;
+
+/// Outcome of the submission of a transaction to the pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmissionOutcome {
+    /// The transaction was accepted by the pool.
+    Received,
+    /// The transaction was refused by the pool, along with the reason why.
+    Rejected(String),
+}
+
+/// A fixed capacity map keeping the most recently inserted entries, for a limited time.
+struct BoundedMap<V> {
+    capacity: usize,
+    max_age: Duration,
+    /// The keys along with the time they were inserted at, oldest first.
+    order: VecDeque<(Instant, StarkHash)>,
+    entries: HashMap<StarkHash, V>,
+}
+
+impl<V> BoundedMap<V> {
+    fn new(capacity: usize, max_age: Duration) -> Self {
+        Self { capacity, max_age, order: VecDeque::with_capacity(capacity), entries: HashMap::with_capacity(capacity) }
+    }
+
+    fn insert(&mut self, key: StarkHash, value: V, now: Instant) {
+        self.prune(now);
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.insert(key, value).is_some() {
+            return;
+        }
+        self.order.push_back((now, key));
+        if self.order.len() > self.capacity {
+            if let Some((_, oldest)) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    fn get(&mut self, key: &StarkHash, now: Instant) -> Option<&V> {
+        self.prune(now);
+        self.entries.get(key)
+    }
+
+    fn remove(&mut self, key: &StarkHash) {
+        if self.entries.remove(key).is_some() {
+            self.order.retain(|(_, k)| k != key);
+        }
+    }
+
+    /// Evicts the entries older than `max_age`.
+    fn prune(&mut self, now: Instant) {
+        while let Some((inserted_at, key)) = self.order.front() {
+            if now.saturating_duration_since(*inserted_at) <= self.max_age {
+                break;
+            }
+            self.entries.remove(key);
+            self.order.pop_front();
+        }
+    }
+}
+
+/// Store of the transactions recently submitted to this node.
+///
+/// Both the received and the rejected sets are bounded, the oldest entries being evicted first.
+/// The entries are also evicted once older than the configured maximum age, and the received
+/// ones as soon as the transaction is known to be included in a block or dropped from the pool.
+pub struct SubmittedTransactions {
+    received: Mutex<BoundedMap<()>>,
+    rejected: Mutex<BoundedMap<String>>,
+}
+
+impl SubmittedTransactions {
+    pub fn new(capacity: usize, max_age: Duration) -> Self {
+        Self {
+            received: Mutex::new(BoundedMap::new(capacity, max_age)),
+            rejected: Mutex::new(BoundedMap::new(capacity, max_age)),
+        }
+    }
+
+    /// Records that the transaction has been accepted by the pool.
+    pub fn mark_received(&self, tx_hash: StarkHash) {
+        self.rejected.lock().expect("poisoned lock").remove(&tx_hash);
+        self.received.lock().expect("poisoned lock").insert(tx_hash, (), Instant::now());
+    }
+
+    /// Records that the transaction has been refused by the pool.
+    pub fn mark_rejected(&self, tx_hash: StarkHash, reason: String) {
+        self.received.lock().expect("poisoned lock").remove(&tx_hash);
+        self.rejected.lock().expect("poisoned lock").insert(tx_hash, reason, Instant::now());
+    }
+
+    /// Records the error returned by the pool on the submission of a transaction.
+    ///
+    /// Only the transactions the pool deems invalid are rejected. A duplicate submission, or a
+    /// temporary refusal, leaves the status of a previous submission untouched.
+    pub fn record_pool_error<E: std::fmt::Display>(&self, tx_hash: StarkHash, error: &Result<PoolError, E>) {
+        if let Some(reason) = rejection_reason(error) {
+            self.mark_rejected(tx_hash, reason);
+        }
+    }
+
+    /// Returns the outcome of the submission of the given transaction, if it is still known.
+    pub fn outcome(&self, tx_hash: &StarkHash) -> Option<SubmissionOutcome> {
+        let now = Instant::now();
+        if let Some(reason) = self.rejected.lock().expect("poisoned lock").get(tx_hash, now) {
+            return Some(SubmissionOutcome::Rejected(reason.clone()));
+        }
+        self.received.lock().expect("poisoned lock").get(tx_hash, now).map(|_| SubmissionOutcome::Received)
+    }
+
+    /// Forgets about a transaction, e.g. once it has been included in a block.
+    pub fn forget(&self, tx_hash: &StarkHash) {
+        self.received.lock().expect("poisoned lock").remove(tx_hash);
+        self.rejected.lock().expect("poisoned lock").remove(tx_hash);
+    }
+}
+
+/// A human readable explanation of why the pool refused a transaction, if the transaction itself
+/// is at fault.
+fn rejection_reason<E: std::fmt::Display>(error: &Result<PoolError, E>) -> Option<String> {
+    match error {
+        Ok(PoolError::InvalidTransaction(InvalidTransaction::BadProof)) => {
+            Some("Account validation failed".to_string())
+        }
+        Ok(PoolError::InvalidTransaction(InvalidTransaction::Stale)) => Some("Invalid transaction nonce".to_string()),
+        Ok(PoolError::InvalidTransaction(InvalidTransaction::Payment)) => Some("Insufficient fee paid".to_string()),
+        Ok(e @ (PoolError::InvalidTransaction(_) | PoolError::UnknownTransaction(_))) => Some(e.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_runtime::transaction_validity::UnknownTransaction;
+    use starknet_api::hash::StarkFelt;
+
+    use super::*;
+
+    fn hash(n: u64) -> StarkHash {
+        StarkFelt::from(n)
+    }
+
+    #[test]
+    fn received_then_rejected() {
+        let store = SubmittedTransactions::new(4, Duration::from_secs(60));
+        store.mark_received(hash(1));
+        assert_eq!(store.outcome(&hash(1)), Some(SubmissionOutcome::Received));
+
+        store.mark_rejected(hash(1), "Account validation failed".to_string());
+        assert_eq!(store.outcome(&hash(1)), Some(SubmissionOutcome::Rejected("Account validation failed".to_string())));
+
+        store.forget(&hash(1));
+        assert_eq!(store.outcome(&hash(1)), None);
+    }
+
+    #[test]
+    fn resubmission_keeps_the_transaction_received() {
+        let store = SubmittedTransactions::new(4, Duration::from_secs(60));
+        store.mark_received(hash(1));
+
+        store.record_pool_error::<String>(hash(1), &Ok(PoolError::AlreadyImported(Box::new(()))));
+        assert_eq!(store.outcome(&hash(1)), Some(SubmissionOutcome::Received));
+        store.record_pool_error::<String>(hash(1), &Ok(PoolError::TemporarilyBanned));
+        assert_eq!(store.outcome(&hash(1)), Some(SubmissionOutcome::Received));
+        store.record_pool_error(hash(1), &Err("the runtime is unavailable".to_string()));
+        assert_eq!(store.outcome(&hash(1)), Some(SubmissionOutcome::Received));
+
+        store.record_pool_error::<String>(hash(1), &Ok(PoolError::InvalidTransaction(InvalidTransaction::Stale)));
+        assert_eq!(store.outcome(&hash(1)), Some(SubmissionOutcome::Rejected("Invalid transaction nonce".to_string())));
+    }
+
+    #[test]
+    fn unknown_transactions_are_rejected() {
+        let store = SubmittedTransactions::new(4, Duration::from_secs(60));
+        store
+            .record_pool_error::<String>(hash(1), &Ok(PoolError::UnknownTransaction(UnknownTransaction::CannotLookup)));
+
+        assert!(matches!(store.outcome(&hash(1)), Some(SubmissionOutcome::Rejected(_))));
+    }
+
+    #[test]
+    fn oldest_entries_are_evicted() {
+        let store = SubmittedTransactions::new(2, Duration::from_secs(60));
+        store.mark_rejected(hash(1), "a".to_string());
+        store.mark_rejected(hash(2), "b".to_string());
+        store.mark_rejected(hash(3), "c".to_string());
+
+        assert_eq!(store.outcome(&hash(1)), None);
+        assert_eq!(store.outcome(&hash(2)), Some(SubmissionOutcome::Rejected("b".to_string())));
+        assert_eq!(store.outcome(&hash(3)), Some(SubmissionOutcome::Rejected("c".to_string())));
+    }
+
+    #[test]
+    fn expired_entries_are_evicted() {
+        let mut map = BoundedMap::new(4, Duration::from_secs(60));
+        let start = Instant::now();
+        map.insert(hash(1), (), start);
+        map.insert(hash(2), (), start + Duration::from_secs(30));
+
+        assert!(map.get(&hash(1), start + Duration::from_secs(60)).is_some());
+        assert!(map.get(&hash(1), start + Duration::from_secs(61)).is_none());
+        assert!(map.get(&hash(2), start + Duration::from_secs(61)).is_some());
+        assert!(map.get(&hash(2), start + Duration::from_secs(91)).is_none());
+        assert!(map.order.is_empty());
+    }
+}
@@ -1,4 +1,3 @@
-use blockifier::execution::contract_class::{ContractClass, ContractClassV1};
 use blockifier::execution::entry_point::CallInfo;
 use blockifier::state::cached_state::CommitmentStateDiff;
 use blockifier::transaction::errors::TransactionExecutionError;
@@ -7,11 +6,14 @@ use jsonrpsee::core::{async_trait, RpcResult};
 use log::error;
 use mc_genesis_data_provider::GenesisProvider;
 use mc_rpc_core::utils::{blockifier_to_rpc_state_diff_types, get_block_by_block_hash};
-use mc_rpc_core::{StarknetReadRpcApiServer, StarknetTraceRpcApiServer};
+use mc_rpc_core::{BlockToSimulate, StarknetReadRpcApiServer, StarknetTraceRpcApiServer};
+use mc_storage::StorageOverride;
 use mp_block::BlockTransactions;
 use mp_felt::Felt252Wrapper;
 use mp_hashers::HasherT;
-use mp_simulations::{SimulationFlags, TransactionSimulationResult};
+use mp_simulations::{
+    BlockContextOverrides, SimulatedBlock, SimulationFlags, StateOverrides, TransactionSimulationResult,
+};
 use mp_transactions::compute_hash::ComputeTransactionHash;
 use mp_transactions::{DeclareTransaction, Transaction, TxType, UserOrL1HandlerTransaction, UserTransaction};
 use pallet_starknet_runtime_api::{ConvertTransactionRuntimeApi, StarknetRuntimeApi};
@@ -30,6 +32,7 @@ use starknet_core::types::{
 use starknet_ff::FieldElement;
 use thiserror::Error;
 
+use crate::compiled_classes::{declared_compiled_class, user_declare_transaction};
 use crate::errors::StarknetRpcApiError;
 use crate::Starknet;
 
@@ -58,20 +61,13 @@ where
         let chain_id = Felt252Wrapper(self.chain_id()?.0);
         let best_block_hash = self.client.info().best_hash;
 
-        let tx_type_and_tx_iterator = transactions.into_iter().map(|tx| match tx {
-            BroadcastedTransaction::Invoke(invoke_tx) => invoke_tx.try_into().map(|tx| (TxType::Invoke, tx)),
-            BroadcastedTransaction::Declare(declare_tx) => declare_tx.try_into().map(|tx| (TxType::Declare, tx)),
-            BroadcastedTransaction::DeployAccount(deploy_account_tx) => {
-                deploy_account_tx.try_into().map(|tx| (TxType::DeployAccount, tx))
-            }
-        });
-        let (tx_types, user_transactions) =
-            itertools::process_results(tx_type_and_tx_iterator, |iter| iter.unzip::<_, _, Vec<_>, Vec<_>>()).map_err(
-                |e| {
-                    error!("Failed to convert BroadcastedTransaction to UserTransaction: {e}");
-                    StarknetRpcApiError::InternalServerError
-                },
-            )?;
+        let storage_override = self.overrides.for_block_hash(self.client.as_ref(), substrate_block_hash);
+        let (tx_types, user_transactions) = broadcasted_to_user_transactions(
+            &self.backend,
+            storage_override.as_ref(),
+            substrate_block_hash,
+            transactions,
+        )?;
 
         let simulation_flags = SimulationFlags::from(simulation_flags);
 
@@ -106,8 +102,13 @@ where
         })?;
         let chain_id = Felt252Wrapper(self.chain_id()?.0);
 
-        let (block_transactions, _) =
-            map_transaction_to_user_transaction(self, starknet_block.transactions(), chain_id, None)?;
+        let (block_transactions, _) = map_transaction_to_user_transaction(
+            self,
+            substrate_block_hash,
+            starknet_block.transactions(),
+            chain_id,
+            None,
+        )?;
 
         let previous_block_substrate_hash = get_previous_block_substrate_hash(self, substrate_block_hash)?;
 
@@ -138,6 +139,7 @@ where
 
         let (txs_to_execute_before, tx_to_trace) = map_transaction_to_user_transaction(
             self,
+            substrate_block_hash,
             starknet_block.transactions(),
             chain_id,
             Some(transaction_hash_to_trace),
@@ -208,6 +210,139 @@ where
             })?)
     }
 
+    /// Simulates the transactions against the state of the given block, modified by the overrides.
+    pub fn do_simulate_transactions_with_overrides(
+        &self,
+        substrate_block_hash: B::Hash,
+        transactions: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+        state_overrides: StateOverrides,
+    ) -> RpcResult<Vec<SimulatedTransaction>> {
+        let storage_override = self.overrides.for_block_hash(self.client.as_ref(), substrate_block_hash);
+        let (tx_types, user_transactions) = broadcasted_to_user_transactions(
+            &self.backend,
+            storage_override.as_ref(),
+            substrate_block_hash,
+            transactions,
+        )?;
+
+        self.do_simulate_user_transactions_with_overrides(
+            substrate_block_hash,
+            tx_types,
+            user_transactions,
+            simulation_flags,
+            state_overrides,
+        )
+    }
+
+    /// Simulates the transactions against the state of the given block.
+    pub fn do_simulate_user_transactions(
+        &self,
+        substrate_block_hash: B::Hash,
+        tx_types: Vec<TxType>,
+        user_transactions: Vec<UserTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<SimulatedTransaction>> {
+        self.do_simulate_user_transactions_with_overrides(
+            substrate_block_hash,
+            tx_types,
+            user_transactions,
+            simulation_flags,
+            Default::default(),
+        )
+    }
+
+    fn do_simulate_user_transactions_with_overrides(
+        &self,
+        substrate_block_hash: B::Hash,
+        tx_types: Vec<TxType>,
+        user_transactions: Vec<UserTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+        state_overrides: StateOverrides,
+    ) -> RpcResult<Vec<SimulatedTransaction>> {
+        let simulation_flags = SimulationFlags::from(simulation_flags);
+
+        self.ensure_runtime_api_version::<dyn StarknetRuntimeApi<B>>(substrate_block_hash, 2)?;
+        let res = self
+            .client
+            .runtime_api()
+            .simulate_transactions_with_overrides(
+                substrate_block_hash,
+                user_transactions,
+                simulation_flags,
+                state_overrides,
+            )
+            .map_err(|e| {
+                error!("Request parameters error: {e}");
+                StarknetRpcApiError::InternalServerError
+            })?
+            .map_err(|e| {
+                error!("Failed to call function: {:#?}", e);
+                StarknetRpcApiError::ContractError
+            })?;
+
+        let simulated_transactions =
+            tx_execution_infos_to_simulated_transactions(tx_types, res).map_err(StarknetRpcApiError::from)?;
+
+        Ok(simulated_transactions)
+    }
+
+    /// Simulates a sequence of blocks on top of the given block.
+    pub fn do_simulate_blocks(
+        &self,
+        substrate_block_hash: B::Hash,
+        blocks: Vec<BlockToSimulate>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<Vec<SimulatedTransaction>>> {
+        self.ensure_runtime_api_version::<dyn StarknetRuntimeApi<B>>(substrate_block_hash, 2)?;
+
+        let mut blocks_tx_types = Vec::with_capacity(blocks.len());
+        let mut simulated_blocks = Vec::with_capacity(blocks.len());
+        let storage_override = self.overrides.for_block_hash(self.client.as_ref(), substrate_block_hash);
+        for block in blocks {
+            let (tx_types, user_transactions) = broadcasted_to_user_transactions(
+                &self.backend,
+                storage_override.as_ref(),
+                substrate_block_hash,
+                block.transactions,
+            )?;
+            blocks_tx_types.push(tx_types);
+            simulated_blocks.push(SimulatedBlock {
+                block_context_overrides: BlockContextOverrides {
+                    block_number: block.block_number,
+                    block_timestamp: block.block_timestamp,
+                    sequencer_address: block.sequencer_address.map(Into::into),
+                    gas_price: block.gas_price,
+                },
+                transactions: user_transactions,
+            });
+        }
+
+        let simulation_flags = SimulationFlags::from(simulation_flags);
+
+        let res = self
+            .client
+            .runtime_api()
+            .simulate_blocks(substrate_block_hash, simulated_blocks, simulation_flags)
+            .map_err(|e| {
+                error!("Request parameters error: {e}");
+                StarknetRpcApiError::InternalServerError
+            })?
+            .map_err(|e| {
+                error!("Failed to call function: {:#?}", e);
+                StarknetRpcApiError::ContractError
+            })?;
+
+        let mut simulated_transactions = Vec::with_capacity(res.len());
+        for (tx_types, block_res) in blocks_tx_types.into_iter().zip(res) {
+            simulated_transactions.push(
+                tx_execution_infos_to_simulated_transactions(tx_types, block_res).map_err(StarknetRpcApiError::from)?,
+            );
+        }
+
+        Ok(simulated_transactions)
+    }
+
     fn execution_info_to_transaction_trace(
         execution_infos: Vec<(TransactionExecutionInfo, CommitmentStateDiff)>,
         block_transactions: Vec<UserOrL1HandlerTransaction>,
@@ -401,6 +536,28 @@ fn tx_execution_infos_to_tx_trace(
     Ok(tx_trace)
 }
 
+fn broadcasted_to_user_transactions<B: BlockT>(
+    backend: &mc_db::Backend<B>,
+    storage_override: &dyn StorageOverride<B>,
+    block_hash: B::Hash,
+    transactions: Vec<BroadcastedTransaction>,
+) -> Result<(Vec<TxType>, Vec<UserTransaction>), StarknetRpcApiError> {
+    let tx_type_and_tx_iterator = transactions.into_iter().map(|tx| match tx {
+        BroadcastedTransaction::Invoke(invoke_tx) => invoke_tx.try_into().map(|tx| (TxType::Invoke, tx)),
+        BroadcastedTransaction::Declare(declare_tx) => {
+            user_declare_transaction(backend, storage_override, block_hash, declare_tx).map(|tx| (TxType::Declare, tx))
+        }
+        BroadcastedTransaction::DeployAccount(deploy_account_tx) => {
+            deploy_account_tx.try_into().map(|tx| (TxType::DeployAccount, tx))
+        }
+    });
+
+    itertools::process_results(tx_type_and_tx_iterator, |iter| iter.unzip::<_, _, Vec<_>, Vec<_>>()).map_err(|e| {
+        error!("Failed to convert BroadcastedTransaction to UserTransaction: {e}");
+        StarknetRpcApiError::InternalServerError
+    })
+}
+
 fn tx_execution_infos_to_simulated_transactions(
     tx_types: Vec<TxType>,
     transaction_execution_results: Vec<(CommitmentStateDiff, TransactionSimulationResult)>,
@@ -433,8 +590,11 @@ fn tx_execution_infos_to_simulated_transactions(
     Ok(results)
 }
 
+/// Converts the transactions of a block, stopping after the one to trace if any. The classes the
+/// block declares are looked up at `substrate_block_hash`.
 pub fn map_transaction_to_user_transaction<A, B, BE, G, C, P, H>(
     starknet: &Starknet<A, B, BE, G, C, P, H>,
+    substrate_block_hash: B::Hash,
     transactions: &BlockTransactions,
     chain_id: Felt252Wrapper,
     target_transaction_hash: Option<Felt252Wrapper>,
@@ -453,11 +613,11 @@ where
         let current_tx_hash = tx.compute_hash::<H>(chain_id, false);
 
         if Some(current_tx_hash) == target_transaction_hash {
-            let converted_tx = convert_transaction(tx, starknet, chain_id)?;
+            let converted_tx = convert_transaction(tx, starknet, substrate_block_hash, chain_id)?;
             transaction_to_trace.push(converted_tx);
             break;
         } else {
-            let converted_tx = convert_transaction(tx, starknet, chain_id)?;
+            let converted_tx = convert_transaction(tx, starknet, substrate_block_hash, chain_id)?;
             user_transactions.push(converted_tx);
         }
     }
@@ -468,6 +628,7 @@ where
 fn convert_transaction<A, B, BE, G, C, P, H>(
     tx: &Transaction,
     starknet: &Starknet<A, B, BE, G, C, P, H>,
+    substrate_block_hash: B::Hash,
     chain_id: Felt252Wrapper,
 ) -> Result<UserOrL1HandlerTransaction, StarknetRpcApiError>
 where
@@ -491,27 +652,14 @@ where
                     UserTransaction::Declare(declare_tx.clone(), contract_class.clone()),
                 )),
                 DeclareTransaction::V2(_tx) => {
-                    let contract_class = starknet
-                        .backend
-                        .sierra_classes()
-                        .get_sierra_class(class_hash)
-                        .map_err(|e| {
-                            error!("Failed to fetch sierra class with hash {class_hash}: {e}");
-                            StarknetRpcApiError::InternalServerError
-                        })?
-                        .ok_or_else(|| {
-                            error!("The sierra class with hash {class_hash} is not present in db backend");
-                            StarknetRpcApiError::InternalServerError
-                        })?;
-                    let contract_class = mp_transactions::utils::sierra_to_casm_contract_class(contract_class)
-                        .map_err(|e| {
-                            error!("Failed to convert the SierraContractClass to CasmContractClass: {e}");
-                            StarknetRpcApiError::InternalServerError
-                        })?;
-                    let contract_class = ContractClass::V1(ContractClassV1::try_from(contract_class).map_err(|e| {
-                        error!("Failed to convert the compiler CasmContractClass to blockifier CasmContractClass: {e}");
-                        StarknetRpcApiError::InternalServerError
-                    })?);
+                    let storage_override =
+                        starknet.overrides.for_block_hash(starknet.client.as_ref(), substrate_block_hash);
+                    let contract_class = declared_compiled_class(
+                        &starknet.backend,
+                        storage_override.as_ref(),
+                        substrate_block_hash,
+                        class_hash,
+                    )?;
 
                     Ok(UserOrL1HandlerTransaction::User(UserTransaction::Declare(declare_tx.clone(), contract_class)))
                 }
@@ -0,0 +1,107 @@
+//! Serve several Starknet RPC spec versions side by side
+//!
+//! The Starknet methods are implemented once, against the types of `starknet-core`. Every spec
+//! version converts the params of its calls to the canonical methods, forwards the calls to them,
+//! then converts the responses to the shapes of that version.
+
+mod v0_6;
+
+use jsonrpsee::core::traits::ToRpcParams;
+use jsonrpsee::core::Error as JsonRpseeError;
+use jsonrpsee::{Methods, RpcModule};
+pub use mc_rpc_core::versions::RpcVersion;
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+const SPEC_VERSION_METHOD: &str = "starknet_specVersion";
+const STARKNET_NAMESPACE: &str = "starknet_";
+
+/// Params forwarded to the canonical method
+struct RawParams(Option<Box<RawValue>>);
+
+impl ToRpcParams for RawParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, serde_json::Error> {
+        Ok(self.0)
+    }
+}
+
+/// Build the module serving the Starknet methods of `canonical` in the shapes of every version
+///
+/// The methods of a version are registered under the names given by [`RpcVersion::method_name`],
+/// the calls received on the path of that version being renamed accordingly by the server.
+pub fn versioned_module(canonical: &Methods) -> Result<RpcModule<Methods>, JsonRpseeError> {
+    let mut module = RpcModule::new(canonical.clone());
+
+    for version in RpcVersion::ALL {
+        for method in canonical.method_names().filter(|method| method.starts_with(STARKNET_NAMESPACE)) {
+            // Method names must be `'static`, the module is only built once at startup
+            let versioned_name: &'static str = Box::leak(version.method_name(method).into_boxed_str());
+
+            if method == SPEC_VERSION_METHOD {
+                module.register_method(versioned_name, move |_, _| Ok(version.spec_version()))?;
+                continue;
+            }
+
+            module.register_async_method(versioned_name, move |params, canonical| async move {
+                let params = match params.as_str() {
+                    Some(params) => {
+                        let params = adapt_params(version, method, serde_json::from_str(params)?);
+                        Some(RawValue::from_string(params.to_string())?)
+                    }
+                    None => None,
+                };
+                let response: Value = canonical.call(method, RawParams(params)).await?;
+
+                Ok(adapt_response(version, method, response))
+            })?;
+        }
+    }
+
+    Ok(module)
+}
+
+/// Convert the params of a call of `version` to the params of the canonical method
+fn adapt_params(version: RpcVersion, method: &str, params: Value) -> Value {
+    match version {
+        // The canonical methods follow the v0.5 spec
+        RpcVersion::V0_5 => params,
+        RpcVersion::V0_6 => v0_6::adapt_params(method, params),
+    }
+}
+
+/// Convert the response of a canonical method to the shape expected by `version`
+fn adapt_response(version: RpcVersion, method: &str, response: Value) -> Value {
+    match version {
+        RpcVersion::V0_5 => response,
+        RpcVersion::V0_6 => v0_6::adapt_response(method, response),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpsee::types::EmptyServerParams;
+
+    use super::*;
+
+    fn canonical() -> Methods {
+        let mut module = RpcModule::new(());
+        module.register_method("starknet_specVersion", |_, _| Ok("0.4.0")).unwrap();
+        module.register_method("starknet_blockNumber", |_, _| Ok(42)).unwrap();
+        module.register_method("madara_predeployedAccounts", |_, _| Ok(Vec::<u64>::new())).unwrap();
+        module.into()
+    }
+
+    #[tokio::test]
+    async fn versioned_module_serves_the_starknet_methods_of_every_version() {
+        let module = versioned_module(&canonical()).unwrap();
+
+        let spec_version: String = module.call("v0_5/starknet_specVersion", EmptyServerParams::new()).await.unwrap();
+        assert_eq!(spec_version, "0.5.1");
+        let spec_version: String = module.call("v0_6/starknet_specVersion", EmptyServerParams::new()).await.unwrap();
+        assert_eq!(spec_version, "0.6.0");
+        let block_number: u64 = module.call("v0_6/starknet_blockNumber", EmptyServerParams::new()).await.unwrap();
+        assert_eq!(block_number, 42);
+        assert!(module.method("starknet_blockNumber").is_none());
+        assert!(module.method("v0_5/madara_predeployedAccounts").is_none());
+    }
+}
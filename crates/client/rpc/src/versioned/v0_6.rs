@@ -0,0 +1,116 @@
+//! Conversions between the v0.6 spec and the canonical v0.5 methods
+
+use serde_json::{json, Value};
+
+/// Fees are paid in the fee token, which is priced in wei
+const FEE_UNIT: &str = "WEI";
+
+/// Convert the params of a v0.6 call to the params of the canonical method
+pub(super) fn adapt_params(method: &str, mut params: Value) -> Value {
+    if method == "starknet_estimateFee" {
+        // The simulation flags were added between the request and the block id, the fees are
+        // estimated as in v0.5 whatever the flags
+        match &mut params {
+            Value::Array(params) if params.len() == 3 => {
+                params.remove(1);
+            }
+            Value::Object(params) => {
+                params.remove("simulation_flags");
+            }
+            _ => {}
+        }
+    }
+
+    params
+}
+
+/// Convert the response of a canonical method to the v0.6 shape
+pub(super) fn adapt_response(method: &str, mut response: Value) -> Value {
+    match method {
+        "starknet_estimateFee" => for_each(&mut response, add_fee_unit),
+        "starknet_estimateMessageFee" => add_fee_unit(&mut response),
+        "starknet_simulateTransactions" => for_each(&mut response, |simulated_transaction| {
+            if let Some(fee_estimation) = simulated_transaction.get_mut("fee_estimation") {
+                add_fee_unit(fee_estimation)
+            }
+        }),
+        "starknet_getTransactionReceipt" => {
+            if let Some(actual_fee) = response.get_mut("actual_fee") {
+                let amount = actual_fee.take();
+                *actual_fee = json!({ "amount": amount, "unit": FEE_UNIT });
+            }
+        }
+        "starknet_getBlockWithTxHashes" | "starknet_getBlockWithTxs" => {
+            if let Some(Value::Object(l1_gas_price)) = response.get_mut("l1_gas_price") {
+                l1_gas_price.entry("price_in_fri").or_insert_with(|| json!("0x0"));
+            }
+        }
+        _ => {}
+    }
+
+    response
+}
+
+fn for_each(response: &mut Value, f: impl FnMut(&mut Value)) {
+    if let Value::Array(items) = response {
+        items.iter_mut().for_each(f);
+    }
+}
+
+fn add_fee_unit(fee_estimate: &mut Value) {
+    if let Value::Object(fee_estimate) = fee_estimate {
+        fee_estimate.insert("unit".to_string(), json!(FEE_UNIT));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn estimate_fee_simulation_flags_are_dropped() {
+        let request = json!([{ "type": "INVOKE" }]);
+
+        assert_eq!(
+            adapt_params("starknet_estimateFee", json!([request, ["SKIP_VALIDATE"], "latest"])),
+            json!([request, "latest"])
+        );
+        assert_eq!(
+            adapt_params(
+                "starknet_estimateFee",
+                json!({ "request": request, "simulation_flags": [], "block_id": "latest" })
+            ),
+            json!({ "request": request, "block_id": "latest" })
+        );
+    }
+
+    #[test]
+    fn fee_estimates_are_priced_in_wei() {
+        let response = json!([{ "gas_consumed": "0x1", "gas_price": "0x2", "overall_fee": "0x2" }]);
+
+        assert_eq!(
+            adapt_response("starknet_estimateFee", response),
+            json!([{ "gas_consumed": "0x1", "gas_price": "0x2", "overall_fee": "0x2", "unit": "WEI" }])
+        );
+    }
+
+    #[test]
+    fn receipt_actual_fee_becomes_a_fee_payment() {
+        let response = json!({ "transaction_hash": "0x1", "actual_fee": "0x2a" });
+
+        assert_eq!(
+            adapt_response("starknet_getTransactionReceipt", response),
+            json!({ "transaction_hash": "0x1", "actual_fee": { "amount": "0x2a", "unit": "WEI" } })
+        );
+    }
+
+    #[test]
+    fn other_methods_are_untouched() {
+        let response = json!({ "block_number": 1 });
+
+        assert_eq!(adapt_params("starknet_getStateUpdate", response.clone()), response);
+        assert_eq!(adapt_response("starknet_getStateUpdate", response.clone()), response);
+    }
+}
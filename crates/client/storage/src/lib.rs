@@ -22,6 +22,9 @@ use sp_runtime::traits::Block as BlockT;
 use sp_storage::StorageKey;
 
 /// Create and return a handle of the starknet schema overrides
+///
+/// Each `StarknetStorageSchemaVersion` has its own override, so that the blocks written before and
+/// after a storage migration can both be read. Unknown schemas fall back to the runtime API.
 pub fn overrides_handle<B, C, BE>(client: Arc<C>) -> Arc<OverrideHandle<B>>
 where
     B: BlockT,
@@ -7,10 +7,10 @@ use frame_support::{Identity, StorageHasher};
 use mp_storage::StarknetStorageSchemaVersion;
 use pallet_starknet_runtime_api::StarknetRuntimeApi;
 use sc_client_api::{Backend, HeaderBackend, StorageProvider};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_io::hashing::twox_128;
 use sp_runtime::traits::Block as BlockT;
-use starknet_api::api_core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::api_core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{Event as StarknetEvent, TransactionHash};
@@ -74,6 +74,12 @@ pub trait StorageOverride<B: BlockT>: Send + Sync {
         block_hash: B::Hash,
         contract_class_hash: ClassHash,
     ) -> Option<ContractClass>;
+    /// Return the compiled class hash of a Sierra class for the provided block.
+    fn compiled_class_hash_by_class_hash(
+        &self,
+        block_hash: B::Hash,
+        class_hash: ClassHash,
+    ) -> Option<CompiledClassHash>;
     /// Returns the nonce for a provided contract address and block hash.
     fn nonce(&self, block_hash: B::Hash, address: ContractAddress) -> Option<Nonce>;
 
@@ -175,6 +181,28 @@ where
         self.client.runtime_api().contract_class_by_class_hash(block_hash, contract_class_hash).ok()?
     }
 
+    /// Return the compiled class hash of a Sierra class for the provided block.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash` - The block hash
+    /// * `class_hash` - The hash of the Sierra class
+    ///
+    /// # Returns
+    /// * `Some(compiled_class_hash)` - The compiled class hash of the class, declared at the
+    ///   provided block, the runtime API serving it since its version 2
+    fn compiled_class_hash_by_class_hash(
+        &self,
+        block_hash: <B as BlockT>::Hash,
+        class_hash: ClassHash,
+    ) -> Option<CompiledClassHash> {
+        let api = self.client.runtime_api();
+        if !api.has_api_with::<dyn StarknetRuntimeApi<B>, _>(block_hash, |version| version >= 2).ok()? {
+            return None;
+        }
+        api.compiled_class_hash_by_class_hash(block_hash, class_hash).ok()?
+    }
+
     /// Return the nonce for a provided contract address and block hash.
     ///
     /// # Arguments
@@ -3,8 +3,8 @@ use std::sync::Arc;
 
 use blockifier::execution::contract_class::ContractClass;
 use mp_storage::{
-    PALLET_STARKNET, STARKNET_CONTRACT_CLASS, STARKNET_CONTRACT_CLASS_HASH, STARKNET_NONCE, STARKNET_STORAGE,
-    STARKNET_TX_EVENTS,
+    PALLET_STARKNET, STARKNET_COMPILED_CLASS_HASH, STARKNET_CONTRACT_CLASS, STARKNET_CONTRACT_CLASS_HASH,
+    STARKNET_NONCE, STARKNET_STORAGE, STARKNET_TX_EVENTS,
 };
 use parity_scale_codec::{Decode, Encode};
 // Substrate
@@ -12,7 +12,7 @@ use sc_client_api::backend::{Backend, StorageProvider};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::traits::Block as BlockT;
 use sp_storage::StorageKey;
-use starknet_api::api_core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::api_core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey as StarknetStorageKey;
 use starknet_api::transaction::{Event as StarknetEvent, TransactionHash};
@@ -118,6 +118,18 @@ where
         )
     }
 
+    fn compiled_class_hash_by_class_hash(
+        &self,
+        block_hash: <B as BlockT>::Hash,
+        class_hash: ClassHash,
+    ) -> Option<CompiledClassHash> {
+        let storage_compiled_class_hash_prefix = storage_prefix_build(PALLET_STARKNET, STARKNET_COMPILED_CLASS_HASH);
+        self.query_storage::<CompiledClassHash>(
+            block_hash,
+            &StorageKey(storage_key_build(storage_compiled_class_hash_prefix, &self.encode_storage_key(&class_hash))),
+        )
+    }
+
     fn nonce(&self, block_hash: <B as BlockT>::Hash, address: ContractAddress) -> Option<Nonce> {
         self.contract_class_hash_by_address(block_hash, address)?;
 
@@ -0,0 +1,257 @@
+//! Portable archive of Starknet blocks.
+//!
+//! An archive holds a range of blocks. Each of them is stored with its header, transactions and
+//! receipts, events included, in the shapes of the feeder gateway, along with its state diff and
+//! the classes it declares. The archive is written as JSON lines: a first line describes the
+//! archive, then each line holds a block and a checksum of its content. The binary form is the
+//! gzip compression of the JSON lines.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Lines, Read, Write};
+use std::sync::Mutex;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::{Compression, Crc};
+use futures::future::BoxFuture;
+use mc_feeder_gateway::types::{Block, StateUpdate};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use starknet_api::state::ThinStateDiff;
+use starknet_core::types::{self as rpc, FieldElement};
+
+use crate::source::{BlockSource, SourceBlock};
+use crate::{convert, Error};
+
+/// Version of the archive format, bumped on breaking changes.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// The gzip magic number, starting the archives in the binary format.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum ArchiveFormat {
+    /// JSON lines, a block per line.
+    JsonLines,
+    /// Gzip-compressed JSON lines.
+    Binary,
+}
+
+/// First line of an archive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveHeader {
+    pub version: u32,
+    pub chain_id: FieldElement,
+    /// Number of the first block of the archive.
+    pub first_block: u64,
+    /// Number of the last block of the archive, included.
+    pub last_block: u64,
+}
+
+/// A block of an archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedBlock {
+    /// The header, transactions and receipts of the block.
+    pub block: Block,
+    pub state_diff: ThinStateDiff,
+    /// The classes declared by the block.
+    pub classes: Vec<ArchivedClass>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedClass {
+    pub class_hash: FieldElement,
+    pub class: rpc::ContractClass,
+}
+
+/// A line of the archive holding a block, checksummed as written.
+#[derive(Serialize, Deserialize)]
+struct Entry<'a> {
+    /// CRC-32 of `block`.
+    checksum: u32,
+    #[serde(borrow)]
+    block: &'a RawValue,
+}
+
+fn checksum(content: &str) -> u32 {
+    let mut crc = Crc::new();
+    crc.update(content.as_bytes());
+    crc.sum()
+}
+
+enum Output<W: Write> {
+    JsonLines(W),
+    Binary(GzEncoder<W>),
+}
+
+/// Writes an archive, block after block.
+pub struct ArchiveWriter<W: Write> {
+    output: Output<W>,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    /// Starts an archive, writing its header.
+    pub fn new(output: W, format: ArchiveFormat, header: &ArchiveHeader) -> Result<Self, Error> {
+        let output = match format {
+            ArchiveFormat::JsonLines => Output::JsonLines(output),
+            ArchiveFormat::Binary => Output::Binary(GzEncoder::new(output, Compression::default())),
+        };
+        let mut writer = Self { output };
+        let header = serde_json::to_string(header).map_err(|e| Error::InvalidArchive(e.to_string()))?;
+        writer.write_line(&header)?;
+
+        Ok(writer)
+    }
+
+    /// Appends a block, which must follow the previous one.
+    pub fn write_block(&mut self, block: &ArchivedBlock) -> Result<(), Error> {
+        let content = serde_json::to_string(block).map_err(|e| Error::InvalidArchive(e.to_string()))?;
+        let content = RawValue::from_string(content).map_err(|e| Error::InvalidArchive(e.to_string()))?;
+        let entry = Entry { checksum: checksum(content.get()), block: &content };
+        let line = serde_json::to_string(&entry).map_err(|e| Error::InvalidArchive(e.to_string()))?;
+
+        self.write_line(&line)
+    }
+
+    /// Flushes the archive, returning the underlying output.
+    pub fn finish(self) -> Result<W, Error> {
+        let mut output = match self.output {
+            Output::JsonLines(output) => output,
+            Output::Binary(encoder) => encoder.finish()?,
+        };
+        output.flush()?;
+
+        Ok(output)
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<(), Error> {
+        let output: &mut dyn Write = match &mut self.output {
+            Output::JsonLines(output) => output,
+            Output::Binary(encoder) => encoder,
+        };
+        output.write_all(line.as_bytes())?;
+        output.write_all(b"\n")?;
+
+        Ok(())
+    }
+}
+
+/// Reads an archive in any of the formats, checking the checksums and the range of its blocks.
+pub struct ArchiveReader {
+    lines: Lines<Box<dyn BufRead + Send>>,
+    header: ArchiveHeader,
+    /// Number of the next block to read.
+    next_block: u64,
+}
+
+impl ArchiveReader {
+    /// Opens an archive, reading its header.
+    pub fn new<R: Read + Send + 'static>(input: R) -> Result<Self, Error> {
+        let mut input = BufReader::new(input);
+        let input: Box<dyn BufRead + Send> = if input.fill_buf()?.starts_with(&GZIP_MAGIC) {
+            Box::new(BufReader::new(GzDecoder::new(input)))
+        } else {
+            Box::new(input)
+        };
+        let mut lines = input.lines();
+
+        let header = lines.next().ok_or_else(|| Error::InvalidArchive("the archive is empty".to_string()))??;
+        let header: ArchiveHeader =
+            serde_json::from_str(&header).map_err(|e| Error::InvalidArchive(format!("invalid archive header: {e}")))?;
+        if header.version != ARCHIVE_VERSION {
+            return Err(Error::InvalidArchive(format!(
+                "unsupported version {}, expected {ARCHIVE_VERSION}",
+                header.version
+            )));
+        }
+        if header.first_block > header.last_block {
+            return Err(Error::InvalidArchive(format!(
+                "empty block range {}..={}",
+                header.first_block, header.last_block
+            )));
+        }
+
+        Ok(Self { lines, next_block: header.first_block, header })
+    }
+
+    pub fn header(&self) -> &ArchiveHeader {
+        &self.header
+    }
+
+    /// Reads the next block, `None` once the last block of the range has been read.
+    pub fn next_block(&mut self) -> Result<Option<ArchivedBlock>, Error> {
+        let block_number = self.next_block;
+        if block_number > self.header.last_block {
+            return Ok(None);
+        }
+        let invalid = |message: String| Error::InvalidArchive(format!("block {block_number}: {message}"));
+
+        let line = self.lines.next().ok_or_else(|| invalid("the archive is truncated".to_string()))??;
+        let entry: Entry<'_> = serde_json::from_str(&line).map_err(|e| invalid(e.to_string()))?;
+        if checksum(entry.block.get()) != entry.checksum {
+            return Err(invalid("checksum mismatch".to_string()));
+        }
+        let block: ArchivedBlock = serde_json::from_str(entry.block.get()).map_err(|e| invalid(e.to_string()))?;
+        if block.block.block_number != Some(block_number) {
+            return Err(invalid(format!("found block {:?} instead", block.block.block_number)));
+        }
+
+        self.next_block += 1;
+        Ok(Some(block))
+    }
+}
+
+/// Serves the blocks of an archive to the importer, which asks for them in order.
+pub(crate) struct ArchiveSource {
+    reader: Mutex<ArchiveReader>,
+    /// The classes declared by the last block read.
+    classes: Mutex<HashMap<FieldElement, rpc::ContractClass>>,
+}
+
+impl ArchiveSource {
+    pub(crate) fn new(reader: ArchiveReader) -> Self {
+        Self { reader: Mutex::new(reader), classes: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl BlockSource for ArchiveSource {
+    fn block(&self, block_number: u64) -> BoxFuture<'_, Result<Option<SourceBlock>, Error>> {
+        Box::pin(async move {
+            let mut reader = self.reader.lock().expect("poisoned lock");
+            if block_number < reader.next_block {
+                return Err(Error::InvalidArchive(format!(
+                    "block {block_number} is missing, the archive starts at block {}",
+                    reader.header.first_block
+                )));
+            }
+            // The blocks the local chain already has are skipped
+            let archived = loop {
+                match reader.next_block()? {
+                    Some(archived) if archived.block.block_number == Some(block_number) => break archived,
+                    Some(_) => continue,
+                    None => return Ok(None),
+                }
+            };
+
+            let mut classes = self.classes.lock().expect("poisoned lock");
+            *classes = archived.classes.into_iter().map(|class| (class.class_hash, class.class)).collect();
+
+            let state_update = StateUpdate {
+                block_hash: archived.block.block_hash,
+                new_root: archived.block.state_root,
+                old_root: FieldElement::ZERO,
+                state_diff: convert::thin_state_diff(&archived.state_diff),
+            };
+            Ok(Some(SourceBlock { block: archived.block, state_update }))
+        })
+    }
+
+    fn class(&self, class_hash: FieldElement, block_number: u64) -> BoxFuture<'_, Result<rpc::ContractClass, Error>> {
+        Box::pin(async move {
+            self.classes.lock().expect("poisoned lock").get(&class_hash).cloned().ok_or_else(|| {
+                Error::InvalidArchive(format!("block {block_number}: missing declared class {class_hash:#x}"))
+            })
+        })
+    }
+}
@@ -0,0 +1,309 @@
+//! Conversions of the feeder gateway types to the Madara ones.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use blockifier::execution::contract_class::ContractClass;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use mc_feeder_gateway::types::{
+    self as gateway, DeclaredClass, DeployedContract, StateDiff, StorageEntry, Transaction, TransactionReceipt,
+};
+use mp_felt::Felt252Wrapper;
+use mp_state::BlockStateDiff;
+use mp_transactions::{
+    DeclareTransaction, DeclareTransactionV0, DeclareTransactionV1, DeclareTransactionV2, DeployAccountTransaction,
+    HandleL1MessageTransaction, ImportedTransaction, InvokeTransaction, InvokeTransactionV0, InvokeTransactionV1,
+    Transaction as MpTransaction, UserOrL1HandlerTransaction, UserTransaction,
+};
+use starknet_api::api_core::{ClassHash, ContractAddress, EthAddress};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::ThinStateDiff;
+use starknet_api::transaction::{Event, EventContent, EventData, Fee, L2ToL1Payload, MessageToL1};
+use starknet_core::types::{self as rpc, FieldElement};
+
+use crate::Error;
+
+fn felts(values: &[FieldElement]) -> Vec<Felt252Wrapper> {
+    values.iter().copied().map(Felt252Wrapper).collect()
+}
+
+/// Converts a transaction of the source, to be re-executed. `classes` holds the classes declared
+/// by the block.
+pub(crate) fn transaction(
+    tx: &Transaction,
+    receipt: &TransactionReceipt,
+    classes: &HashMap<FieldElement, ContractClass>,
+) -> Result<UserOrL1HandlerTransaction, Error> {
+    let transaction_hash = tx.transaction_hash();
+    let invalid = |message: &str| Error::InvalidTransaction(transaction_hash, message.to_string());
+    let max_fee =
+        |max_fee: FieldElement| u128::try_from(Felt252Wrapper(max_fee)).map_err(|_| invalid("max fee too big"));
+
+    let transaction = match tx {
+        Transaction::Invoke(tx) if tx.version == FieldElement::ZERO => InvokeTransaction::V0(InvokeTransactionV0 {
+            max_fee: max_fee(tx.max_fee)?,
+            signature: felts(&tx.signature),
+            contract_address: tx.contract_address.ok_or_else(|| invalid("missing contract address"))?.into(),
+            entry_point_selector: tx
+                .entry_point_selector
+                .ok_or_else(|| invalid("missing entry point selector"))?
+                .into(),
+            calldata: felts(&tx.calldata),
+        })
+        .into(),
+        Transaction::Invoke(tx) if tx.version == FieldElement::ONE => InvokeTransaction::V1(InvokeTransactionV1 {
+            max_fee: max_fee(tx.max_fee)?,
+            signature: felts(&tx.signature),
+            nonce: tx.nonce.ok_or_else(|| invalid("missing nonce"))?.into(),
+            sender_address: tx.sender_address.ok_or_else(|| invalid("missing sender address"))?.into(),
+            calldata: felts(&tx.calldata),
+            offset_version: false,
+        })
+        .into(),
+        Transaction::Declare(tx) => {
+            let class = classes.get(&tx.class_hash).cloned().ok_or_else(|| invalid("missing declared class"))?;
+            let (max_fee, signature, nonce, class_hash, sender_address) = (
+                max_fee(tx.max_fee)?,
+                felts(&tx.signature),
+                tx.nonce.into(),
+                tx.class_hash.into(),
+                tx.sender_address.into(),
+            );
+            let declare = if tx.version == FieldElement::ZERO {
+                DeclareTransaction::V0(DeclareTransactionV0 { max_fee, signature, nonce, class_hash, sender_address })
+            } else if tx.version == FieldElement::ONE {
+                DeclareTransaction::V1(DeclareTransactionV1 {
+                    max_fee,
+                    signature,
+                    nonce,
+                    class_hash,
+                    sender_address,
+                    offset_version: false,
+                })
+            } else if tx.version == FieldElement::TWO {
+                DeclareTransaction::V2(DeclareTransactionV2 {
+                    max_fee,
+                    signature,
+                    nonce,
+                    class_hash,
+                    sender_address,
+                    compiled_class_hash: tx
+                        .compiled_class_hash
+                        .ok_or_else(|| invalid("missing compiled class hash"))?
+                        .into(),
+                    offset_version: false,
+                })
+            } else {
+                return Err(invalid("unsupported version"));
+            };
+            UserTransaction::Declare(declare, class)
+        }
+        Transaction::DeployAccount(tx) => UserTransaction::DeployAccount(DeployAccountTransaction {
+            max_fee: max_fee(tx.max_fee)?,
+            signature: felts(&tx.signature),
+            nonce: tx.nonce.into(),
+            contract_address_salt: tx.contract_address_salt.into(),
+            constructor_calldata: felts(&tx.constructor_calldata),
+            class_hash: tx.class_hash.into(),
+            offset_version: false,
+        }),
+        Transaction::L1Handler(tx) => {
+            let fee = receipt.actual_fee.ok_or_else(|| invalid("missing paid fee"))?;
+            let transaction = HandleL1MessageTransaction {
+                nonce: u64::try_from(Felt252Wrapper(tx.nonce)).map_err(|_| invalid("nonce too big"))?,
+                contract_address: tx.contract_address.into(),
+                entry_point_selector: tx.entry_point_selector.into(),
+                calldata: felts(&tx.calldata),
+            };
+            return Ok(UserOrL1HandlerTransaction::L1Handler(transaction, Fee(max_fee(fee)?)));
+        }
+        Transaction::Invoke(_) => return Err(invalid("unsupported version")),
+        Transaction::Deploy(_) => return Err(Error::UnsupportedTransaction(transaction_hash)),
+    };
+
+    Ok(UserOrL1HandlerTransaction::User(transaction))
+}
+
+/// Converts a transaction of the source, along with the outputs of its execution there, to be
+/// imported without executing it.
+pub(crate) fn imported_transaction(
+    transaction: UserOrL1HandlerTransaction,
+    transaction_hash: FieldElement,
+    receipt: &TransactionReceipt,
+) -> Result<ImportedTransaction, Error> {
+    let transaction = match transaction {
+        UserOrL1HandlerTransaction::User(UserTransaction::Declare(tx, class)) => MpTransaction::Declare(tx, class),
+        UserOrL1HandlerTransaction::User(UserTransaction::DeployAccount(tx)) => MpTransaction::DeployAccount(tx),
+        UserOrL1HandlerTransaction::User(UserTransaction::Invoke(tx)) => MpTransaction::Invoke(tx),
+        UserOrL1HandlerTransaction::L1Handler(tx, _) => MpTransaction::L1Handler(tx),
+    };
+    let events = receipt
+        .events
+        .iter()
+        .map(|event| Event {
+            from_address: Felt252Wrapper(event.from_address).into(),
+            content: EventContent {
+                keys: event.keys.iter().map(|key| Felt252Wrapper(*key).into()).collect(),
+                data: EventData(event.data.iter().map(|value| Felt252Wrapper(*value).into()).collect()),
+            },
+        })
+        .collect();
+    let messages = receipt
+        .l2_to_l1_messages
+        .iter()
+        .map(|message| {
+            let to_address =
+                EthAddress::try_from(StarkFelt::from(Felt252Wrapper(message.to_address))).map_err(|_| {
+                    Error::InvalidTransaction(transaction_hash, format!("invalid L1 address {:#x}", message.to_address))
+                })?;
+            Ok(MessageToL1 {
+                from_address: Felt252Wrapper(message.from_address).into(),
+                to_address,
+                payload: L2ToL1Payload(message.payload.iter().map(|value| Felt252Wrapper(*value).into()).collect()),
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+
+    Ok(ImportedTransaction {
+        transaction_hash: Felt252Wrapper(transaction_hash).into(),
+        transaction,
+        events,
+        messages,
+        revert_error: receipt.revert_error.clone(),
+    })
+}
+
+/// Converts the state diff of a block. `classes` holds the classes declared by the block.
+pub(crate) fn state_diff(
+    state_diff: &StateDiff,
+    classes: &HashMap<FieldElement, ContractClass>,
+) -> Result<BlockStateDiff, Error> {
+    let class = |class_hash: &FieldElement| {
+        classes.get(class_hash).cloned().ok_or_else(|| Error::Source(format!("Missing class {class_hash:#x}")))
+    };
+
+    let mut declared_classes = Vec::new();
+    for class_hash in &state_diff.old_declared_contracts {
+        declared_classes.push((Felt252Wrapper(*class_hash).into(), class(class_hash)?, None));
+    }
+    for declared in &state_diff.declared_classes {
+        declared_classes.push((
+            Felt252Wrapper(declared.class_hash).into(),
+            class(&declared.class_hash)?,
+            Some(Felt252Wrapper(declared.compiled_class_hash).into()),
+        ));
+    }
+
+    Ok(BlockStateDiff {
+        storage: state_diff
+            .storage_diffs
+            .iter()
+            .flat_map(|(address, entries)| {
+                entries.iter().map(|entry| {
+                    (
+                        Felt252Wrapper(*address).into(),
+                        Felt252Wrapper(entry.key).into(),
+                        Felt252Wrapper(entry.value).into(),
+                    )
+                })
+            })
+            .collect(),
+        nonces: state_diff
+            .nonces
+            .iter()
+            .map(|(address, nonce)| (Felt252Wrapper(*address).into(), Felt252Wrapper(*nonce).into()))
+            .collect(),
+        class_hashes: state_diff
+            .deployed_contracts
+            .iter()
+            .chain(&state_diff.replaced_classes)
+            .map(|contract| (Felt252Wrapper(contract.address).into(), Felt252Wrapper(contract.class_hash).into()))
+            .collect(),
+        declared_classes,
+    })
+}
+
+fn felt(value: StarkFelt) -> FieldElement {
+    Felt252Wrapper::from(value).0
+}
+
+/// Converts a state diff to the shape of the gateway.
+pub(crate) fn thin_state_diff(state_diff: &ThinStateDiff) -> StateDiff {
+    let deployed_contract = |(address, class_hash): (&ContractAddress, &ClassHash)| DeployedContract {
+        address: felt(*address.0.key()),
+        class_hash: felt(class_hash.0),
+    };
+
+    StateDiff {
+        storage_diffs: state_diff
+            .storage_diffs
+            .iter()
+            .map(|(address, entries)| {
+                let entries = entries
+                    .iter()
+                    .map(|(key, value)| StorageEntry { key: felt(*key.0.key()), value: felt(*value) })
+                    .collect();
+                (felt(*address.0.key()), entries)
+            })
+            .collect(),
+        nonces: state_diff.nonces.iter().map(|(address, nonce)| (felt(*address.0.key()), felt(nonce.0))).collect(),
+        deployed_contracts: state_diff.deployed_contracts.iter().map(deployed_contract).collect(),
+        old_declared_contracts: state_diff
+            .deprecated_declared_classes
+            .iter()
+            .map(|class_hash| felt(class_hash.0))
+            .collect(),
+        declared_classes: state_diff
+            .declared_classes
+            .iter()
+            .map(|(class_hash, compiled_class_hash)| DeclaredClass {
+                class_hash: felt(class_hash.0),
+                compiled_class_hash: felt(compiled_class_hash.0),
+            })
+            .collect(),
+        replaced_classes: state_diff.replaced_classes.iter().map(deployed_contract).collect(),
+    }
+}
+
+/// Converts a class answered by the gateway to its RPC shape, compressing back the program of
+/// the Cairo 0 ones.
+pub(crate) fn contract_class(class: gateway::ContractClass) -> Result<rpc::ContractClass, Error> {
+    let class = match class {
+        gateway::ContractClass::Sierra(class) => rpc::ContractClass::Sierra(class),
+        gateway::ContractClass::Legacy(class) => {
+            let program =
+                serde_json::to_vec(&class.program).map_err(|e| Error::Source(format!("Invalid program: {e}")))?;
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&program).map_err(|e| Error::Source(format!("Failed to compress the program: {e}")))?;
+            let program =
+                encoder.finish().map_err(|e| Error::Source(format!("Failed to compress the program: {e}")))?;
+            let abi = class
+                .abi
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| Error::Source(format!("Invalid ABI: {e}")))?;
+
+            let entry_points = |entry_points: Vec<gateway::LegacyEntryPoint>| {
+                entry_points
+                    .into_iter()
+                    .map(|entry_point| {
+                        let offset = u64::try_from(Felt252Wrapper(entry_point.offset)).map_err(|_| {
+                            Error::Source(format!("Invalid entry point offset {:#x}", entry_point.offset))
+                        })?;
+                        Ok(rpc::LegacyContractEntryPoint { offset, selector: entry_point.selector })
+                    })
+                    .collect::<Result<_, Error>>()
+            };
+            let entry_points_by_type = rpc::LegacyEntryPointsByType {
+                constructor: entry_points(class.entry_points_by_type.constructor)?,
+                external: entry_points(class.entry_points_by_type.external)?,
+                l1_handler: entry_points(class.entry_points_by_type.l1_handler)?,
+            };
+
+            rpc::ContractClass::Legacy(rpc::CompressedLegacyContractClass { program, entry_points_by_type, abi })
+        }
+    };
+
+    Ok(class)
+}
@@ -0,0 +1,290 @@
+//! Builds the archived blocks of the local chain.
+
+use blockifier::execution::contract_class::ContractClass;
+use indexmap::IndexMap;
+use mc_db::TransactionOutput;
+use mc_feeder_gateway::conversions;
+use mc_feeder_gateway::types::{
+    Block, BlockStatus, Event, L2ToL1Message, TransactionExecutionStatus, TransactionReceipt,
+};
+use mc_rpc_core::utils::{blockifier_to_rpc_contract_class_types, sierra_contract_class_to_flattened_sierra};
+use mp_felt::Felt252Wrapper;
+use mp_hashers::HasherT;
+use mp_storage::{
+    SN_COMPILED_CLASS_HASH_PREFIX, SN_CONTRACT_CLASS_HASH_PREFIX, SN_CONTRACT_CLASS_PREFIX, SN_NONCE_PREFIX,
+    SN_STORAGE_PREFIX,
+};
+use mp_transactions::compute_hash::ComputeTransactionHash;
+use mp_transactions::to_starknet_core_transaction::to_starknet_core_tx;
+use mp_transactions::{DeclareTransaction, Transaction};
+use pallet_starknet_runtime_api::StarknetRuntimeApi;
+use parity_scale_codec::Decode;
+use sc_client_api::backend::Backend;
+use sc_client_api::BlockBackend;
+use sp_api::{ApiExt, Core, ProvideRuntimeApi};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use starknet_api::api_core::{ClassHash, ContractAddress};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::{StorageKey, ThinStateDiff};
+use starknet_api::transaction::{MessageToL1, TransactionHash};
+use starknet_core::types::{self as rpc, FieldElement};
+
+use crate::archive::{ArchivedBlock, ArchivedClass};
+use crate::Error;
+
+/// Builds the archived block of `block_hash`, which must not be the genesis block.
+///
+/// The events, messages and revert errors of the transactions are read from the Madara database,
+/// or from the runtime state of the block when missing there. The state diff is recorded by
+/// executing the block again on top of its parent, whose state must still be available.
+pub fn export_block<B, C, BE, H>(
+    client: &C,
+    backend: &BE,
+    madara_backend: &mc_db::Backend<B>,
+    block_hash: B::Hash,
+) -> Result<ArchivedBlock, Error>
+where
+    B: BlockT,
+    C: ProvideRuntimeApi<B> + BlockBackend<B>,
+    C::Api: Core<B> + ApiExt<B> + StarknetRuntimeApi<B>,
+    BE: Backend<B>,
+    H: HasherT,
+{
+    let (header, body) = client
+        .block(block_hash)
+        .map_err(|e| Error::Client(e.to_string()))?
+        .ok_or_else(|| Error::Client(format!("missing block {block_hash}")))?
+        .block
+        .deconstruct();
+    let starknet_block = mp_digest_log::find_starknet_block(header.digest())
+        .map_err(|e| Error::Client(format!("missing Starknet block in {block_hash}: {e}")))?;
+    let starknet_header = starknet_block.header();
+    let block_number = starknet_header.block_number;
+    let export_error = |message: String| Error::Export { block_number, message };
+    if block_number == 0 {
+        return Err(export_error("the genesis block is part of the chain specification".to_string()));
+    }
+
+    let runtime_api = client.runtime_api();
+    let runtime_error = |e: sp_api::ApiError| export_error(e.to_string());
+    let chain_id = runtime_api.chain_id(block_hash).map_err(runtime_error)?;
+    let fee_disabled = runtime_api.is_transaction_fee_disabled(block_hash).map_err(runtime_error)?;
+
+    let mut transactions = Vec::with_capacity(starknet_block.transactions().len());
+    let mut receipts = Vec::with_capacity(starknet_block.transactions().len());
+    let mut classes = Vec::new();
+    for (index, tx) in starknet_block.transactions().iter().enumerate() {
+        let transaction_hash = tx.compute_hash::<H>(chain_id, false).0;
+        let tx_hash: TransactionHash = Felt252Wrapper(transaction_hash).into();
+
+        let output = match madara_backend.transaction_outputs().get_output(&tx_hash) {
+            Ok(Some(output)) => output,
+            Ok(None) => TransactionOutput {
+                events: runtime_api.get_events_for_tx_by_hash(block_hash, tx_hash).map_err(runtime_error)?,
+                messages: runtime_api.get_tx_messages_to_l1(block_hash, tx_hash).map_err(runtime_error)?,
+                revert_error: runtime_api
+                    .get_tx_execution_outcome(block_hash, tx_hash)
+                    .map_err(runtime_error)?
+                    .map(|message| String::from_utf8_lossy(&message).into_owned()),
+            },
+            Err(e) => return Err(export_error(e.to_string())),
+        };
+
+        let actual_fee = match tx {
+            Transaction::L1Handler(_) => {
+                let fee = madara_backend
+                    .l1_handler_paid_fee()
+                    .get_fee_paid_for_l1_handler_tx(tx_hash.0)
+                    .map_err(|e| export_error(e.to_string()))?;
+                Felt252Wrapper::from(fee.0).0
+            }
+            _ if fee_disabled => FieldElement::ZERO,
+            // The fee transfer is the last event of the transaction, its amount the third data
+            _ => output
+                .events
+                .last()
+                .and_then(|event| event.content.data.0.get(2))
+                .map(|amount| felt(*amount))
+                .unwrap_or_default(),
+        };
+
+        let contract_address = match tx {
+            Transaction::DeployAccount(tx) => Some(tx.get_account_address()),
+            _ => None,
+        };
+        if let Transaction::Declare(tx, class) = tx {
+            classes.push(declared_class(madara_backend, tx, class).map_err(export_error)?);
+        }
+
+        transactions
+            .push(conversions::transaction(to_starknet_core_tx(tx.clone(), transaction_hash), contract_address));
+        receipts.push(receipt(index, transaction_hash, output, actual_fee));
+    }
+
+    let block = Block {
+        block_hash: Some(starknet_header.hash::<H>().0),
+        parent_block_hash: felt(starknet_header.parent_block_hash),
+        block_number: Some(block_number),
+        state_root: Some(felt(starknet_header.global_state_root)),
+        status: BlockStatus::AcceptedOnL2,
+        timestamp: starknet_header.block_timestamp,
+        sequencer_address: felt(*starknet_header.sequencer_address.0.key()),
+        gas_price: Felt252Wrapper::from(starknet_header.l1_gas_price.price_in_wei).0,
+        strk_l1_gas_price: starknet_header.l1_gas_price.price_in_strk.map(FieldElement::from),
+        starknet_version: starknet_header.protocol_version.to_string(),
+        transactions,
+        transaction_receipts: receipts,
+    };
+    let state_diff = state_diff::<B, _, _>(client, backend, header, body).map_err(export_error)?;
+
+    Ok(ArchivedBlock { block, state_diff, classes })
+}
+
+fn felt(value: StarkFelt) -> FieldElement {
+    Felt252Wrapper::from(value).0
+}
+
+fn receipt(
+    index: usize,
+    transaction_hash: FieldElement,
+    output: TransactionOutput,
+    actual_fee: FieldElement,
+) -> TransactionReceipt {
+    let execution_status = match output.revert_error {
+        Some(_) => TransactionExecutionStatus::Reverted,
+        None => TransactionExecutionStatus::Succeeded,
+    };
+
+    TransactionReceipt {
+        status: None,
+        finality_status: None,
+        execution_status: Some(execution_status),
+        block_hash: None,
+        block_number: None,
+        transaction_index: Some(index as u64),
+        transaction_hash,
+        l2_to_l1_messages: output.messages.into_iter().map(message).collect(),
+        events: output
+            .events
+            .into_iter()
+            .map(|event| Event {
+                from_address: felt(*event.from_address.0.key()),
+                keys: event.content.keys.into_iter().map(|key| felt(key.0)).collect(),
+                data: event.content.data.0.into_iter().map(felt).collect(),
+            })
+            .collect(),
+        execution_resources: None,
+        actual_fee: Some(actual_fee),
+        revert_error: output.revert_error,
+    }
+}
+
+fn message(message: MessageToL1) -> L2ToL1Message {
+    let mut to_address = [0u8; 32];
+    to_address[12..].copy_from_slice(message.to_address.0.as_bytes());
+
+    L2ToL1Message {
+        from_address: felt(*message.from_address.0.key()),
+        to_address: FieldElement::from_bytes_be(&to_address).expect("an Ethereum address fits in a felt"),
+        payload: message.payload.0.into_iter().map(felt).collect(),
+    }
+}
+
+/// The class declared by `tx`. The Cairo 1 classes are executed compiled, their Sierra form is read
+/// from the Madara database.
+fn declared_class<B: BlockT>(
+    madara_backend: &mc_db::Backend<B>,
+    tx: &DeclareTransaction,
+    class: &ContractClass,
+) -> Result<ArchivedClass, String> {
+    let class_hash = ClassHash::from(*tx.class_hash());
+    let class = match tx {
+        DeclareTransaction::V2(_) => {
+            let sierra_class = madara_backend
+                .sierra_classes()
+                .get_sierra_class(class_hash)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("the Sierra class {} is missing from the database", class_hash.0))?;
+            rpc::ContractClass::Sierra(sierra_contract_class_to_flattened_sierra(&sierra_class))
+        }
+        DeclareTransaction::V0(_) | DeclareTransaction::V1(_) => {
+            blockifier_to_rpc_contract_class_types(class.clone()).map_err(|e| e.to_string())?
+        }
+    };
+
+    Ok(ArchivedClass { class_hash: felt(class_hash.0), class })
+}
+
+/// Executes the block on top of its parent, reading its state diff from the storage changes.
+fn state_diff<B, C, BE>(
+    client: &C,
+    backend: &BE,
+    mut header: B::Header,
+    body: Vec<B::Extrinsic>,
+) -> Result<ThinStateDiff, String>
+where
+    B: BlockT,
+    C: ProvideRuntimeApi<B>,
+    C::Api: Core<B> + ApiExt<B> + StarknetRuntimeApi<B>,
+    BE: Backend<B>,
+{
+    let parent_hash = *header.parent_hash();
+    // The seals are removed on import, the runtime executes the blocks without them
+    header.digest_mut().logs.retain(|log| log.as_seal().is_none());
+
+    let runtime_api = client.runtime_api();
+    runtime_api.execute_block(parent_hash, B::new(header, body)).map_err(|e| e.to_string())?;
+    let state = backend.state_at(parent_hash).map_err(|e| e.to_string())?;
+    let changes = runtime_api.into_storage_changes(&state, parent_hash)?;
+
+    // Queried at the parent, without the changes of the block
+    let parent_api = client.runtime_api();
+    let mut state_diff = ThinStateDiff {
+        deployed_contracts: IndexMap::new(),
+        storage_diffs: IndexMap::new(),
+        declared_classes: IndexMap::new(),
+        deprecated_declared_classes: Vec::new(),
+        nonces: IndexMap::new(),
+        replaced_classes: IndexMap::new(),
+    };
+    let mut declared_classes = Vec::new();
+    for (key, value) in changes.main_storage_changes {
+        // The Starknet storages are never removed from
+        let Some(value) = value else {
+            continue;
+        };
+
+        if let Some(key) = key.strip_prefix(SN_STORAGE_PREFIX.as_slice()) {
+            let (contract_address, storage_key) = decode::<(ContractAddress, StorageKey)>(key)?;
+            state_diff.storage_diffs.entry(contract_address).or_default().insert(storage_key, decode(&value)?);
+        } else if let Some(key) = key.strip_prefix(SN_NONCE_PREFIX.as_slice()) {
+            state_diff.nonces.insert(decode(key)?, decode(&value)?);
+        } else if let Some(key) = key.strip_prefix(SN_CONTRACT_CLASS_HASH_PREFIX.as_slice()) {
+            let contract_address: ContractAddress = decode(key)?;
+            let class_hash: ClassHash = decode(&value)?;
+            // The contracts without a class at the parent block are deployed by the block
+            let previous_class_hash =
+                parent_api.contract_class_hash_by_address(parent_hash, contract_address).map_err(|e| e.to_string())?;
+            if previous_class_hash == ClassHash::default() {
+                state_diff.deployed_contracts.insert(contract_address, class_hash);
+            } else {
+                state_diff.replaced_classes.insert(contract_address, class_hash);
+            }
+        } else if let Some(key) = key.strip_prefix(SN_COMPILED_CLASS_HASH_PREFIX.as_slice()) {
+            state_diff.declared_classes.insert(decode(key)?, decode(&value)?);
+        } else if let Some(key) = key.strip_prefix(SN_CONTRACT_CLASS_PREFIX.as_slice()) {
+            declared_classes.push(decode::<ClassHash>(key)?);
+        }
+    }
+    // The Cairo 0 classes are the ones declared without a compiled class hash
+    state_diff.deprecated_declared_classes = declared_classes
+        .into_iter()
+        .filter(|class_hash| !state_diff.declared_classes.contains_key(class_hash))
+        .collect();
+
+    Ok(state_diff)
+}
+
+fn decode<T: Decode>(mut bytes: &[u8]) -> Result<T, String> {
+    T::decode(&mut bytes).map_err(|e| format!("invalid storage entry: {e}"))
+}
@@ -0,0 +1,322 @@
+//! Builds and imports the local blocks of the source ones.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use blockifier::execution::contract_class::ContractClass;
+use mc_feeder_gateway::types::{Block, StateUpdate, Transaction};
+use mc_rpc_core::utils::flattened_sierra_to_sierra_contract_class;
+use mp_block::Block as StarknetBlock;
+use mp_felt::Felt252Wrapper;
+use mp_hashers::HasherT;
+use mp_sequencer_address::InherentDataProvider as SeqAddrInherentDataProvider;
+use mp_transactions::compute_hash::ComputeTransactionHash;
+use mp_transactions::from_broadcasted_transactions::to_blockifier_contract_class;
+use mp_transactions::UserOrL1HandlerTransaction;
+use pallet_starknet::sealing::{InherentDataProvider as SealingInherentDataProvider, SealingParameters};
+use pallet_starknet_runtime_api::{ConvertTransactionRuntimeApi, StarknetRuntimeApi};
+use sc_block_builder::BlockBuilderProvider;
+use sc_client_api::backend::Backend;
+use sc_consensus::{BlockImport, BlockImportParams, ForkChoiceStrategy, ImportResult, StateAction, StorageChanges};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockOrigin;
+use sp_inherents::InherentDataProvider;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sp_runtime::Digest;
+use starknet_api::transaction::Fee;
+use starknet_core::types::{self as rpc, FieldElement};
+
+use crate::source::{BlockSource, SourceBlock};
+use crate::{convert, verify, Error, ImportMode};
+
+pub(crate) struct BlockImporter<B: BlockT, C, BE, BI, H> {
+    client: Arc<C>,
+    block_import: BI,
+    madara_backend: Arc<mc_db::Backend<B>>,
+    mode: ImportMode,
+    /// Hash of the source block the local chain is at, resolved on the first import.
+    parent_hash: Option<FieldElement>,
+    _marker: PhantomData<(BE, H)>,
+}
+
+impl<B, C, BE, BI, H> BlockImporter<B, C, BE, BI, H>
+where
+    B: BlockT,
+    C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockBuilderProvider<BE, B, C>,
+    C::Api: BlockBuilderApi<B> + ApiExt<B> + StarknetRuntimeApi<B> + ConvertTransactionRuntimeApi<B>,
+    BE: Backend<B>,
+    BI: BlockImport<B>,
+    H: HasherT,
+{
+    pub(crate) fn new(
+        client: Arc<C>,
+        block_import: BI,
+        madara_backend: Arc<mc_db::Backend<B>>,
+        mode: ImportMode,
+    ) -> Self {
+        Self { client, block_import, madara_backend, mode, parent_hash: None, _marker: PhantomData }
+    }
+
+    /// Imports the block following the local best block, returning its number, or `None` if the
+    /// source has not built it yet.
+    pub(crate) async fn import_next(&mut self, source: &dyn BlockSource) -> Result<Option<u64>, Error> {
+        let best_hash = self.client.info().best_hash;
+        let best_block = self.starknet_block(best_hash)?;
+        let parent_hash = match self.parent_hash {
+            Some(parent_hash) => parent_hash,
+            None => self.resolve_parent_hash(source, &best_block).await?,
+        };
+
+        let block_number = best_block.header().block_number + 1;
+        let Some(SourceBlock { block, state_update }) = source.block(block_number).await? else {
+            return Ok(None);
+        };
+        let block_hash = verify::verify_block::<H>(&block, parent_hash)?;
+
+        let mut classes = HashMap::new();
+        for class_hash in self.declared_class_hashes(&block, &state_update) {
+            classes.insert(class_hash, source.class(class_hash, block_number).await?);
+        }
+
+        self.import(best_hash, block_number, block_hash, block, state_update, classes).await?;
+        self.parent_hash = Some(block_hash);
+
+        Ok(Some(block_number))
+    }
+
+    /// Follows the local chain from its best block, for sources built from the local chain itself,
+    /// such as its archives.
+    pub(crate) fn follow_local_chain(&mut self) -> Result<(), Error> {
+        let best_block = self.starknet_block(self.client.info().best_hash)?;
+        self.parent_hash = Some(best_block.header().hash::<H>().0);
+        Ok(())
+    }
+
+    /// Finds the hash of the source block the local chain is at. The local block hashes are not
+    /// the source ones, their state root is not computed.
+    async fn resolve_parent_hash(
+        &self,
+        source: &dyn BlockSource,
+        best_block: &StarknetBlock,
+    ) -> Result<FieldElement, Error> {
+        let block_number = best_block.header().block_number;
+        let SourceBlock { block, .. } = source
+            .block(block_number)
+            .await?
+            .ok_or_else(|| Error::Source(format!("The source is missing block {block_number}")))?;
+        verify::verify_block::<H>(&block, block.parent_block_hash)
+    }
+
+    /// The classes to fetch for the import of the block.
+    fn declared_class_hashes(&self, block: &Block, state_update: &StateUpdate) -> Vec<FieldElement> {
+        match self.mode {
+            ImportMode::Execute => block
+                .transactions
+                .iter()
+                .filter_map(|tx| match tx {
+                    Transaction::Declare(tx) => Some(tx.class_hash),
+                    _ => None,
+                })
+                .collect(),
+            ImportMode::TrustStateDiff => {
+                let state_diff = &state_update.state_diff;
+                state_diff
+                    .old_declared_contracts
+                    .iter()
+                    .copied()
+                    .chain(state_diff.declared_classes.iter().map(|declared| declared.class_hash))
+                    .collect()
+            }
+        }
+    }
+
+    async fn import(
+        &mut self,
+        parent: B::Hash,
+        block_number: u64,
+        block_hash: FieldElement,
+        block: Block,
+        state_update: StateUpdate,
+        classes: HashMap<FieldElement, rpc::ContractClass>,
+    ) -> Result<(), Error> {
+        let import_error = |message: String| Error::Import { block_number, message };
+
+        let mut blockifier_classes = HashMap::new();
+        for (class_hash, class) in &classes {
+            let class = to_blockifier_contract_class(class.clone())
+                .map_err(|e| import_error(format!("Invalid class {class_hash:#x}: {e}")))?;
+            blockifier_classes.insert(*class_hash, class);
+        }
+
+        let (extrinsics, l1_handler_fees) = self.extrinsics(parent, &block, &state_update, &blockifier_classes)?;
+
+        let inherent_data = (
+            sp_timestamp::InherentDataProvider::new(sp_timestamp::Timestamp::new(block.timestamp * 1000)),
+            SeqAddrInherentDataProvider::new(Felt252Wrapper(block.sequencer_address).into()),
+            // The state diffs of the source blocks are written instead of executing their transactions
+            SealingInherentDataProvider(SealingParameters { allow_state_import: true, ..Default::default() }),
+        )
+            .create_inherent_data()
+            .await
+            .map_err(|e| import_error(e.to_string()))?;
+
+        let built_block = {
+            let mut block_builder =
+                self.client.new_block_at(parent, Digest::default(), false).map_err(|e| import_error(e.to_string()))?;
+            for inherent in block_builder.create_inherents(inherent_data).map_err(|e| import_error(e.to_string()))? {
+                block_builder.push(inherent).map_err(|e| import_error(format!("Invalid inherent: {e}")))?;
+            }
+            for extrinsic in extrinsics {
+                block_builder
+                    .push(extrinsic)
+                    .map_err(|e| import_error(format!("Failed to apply a transaction: {e}")))?;
+            }
+            block_builder.build().map_err(|e| import_error(e.to_string()))?
+        };
+
+        let (header, body) = built_block.block.deconstruct();
+        let local_block = mp_digest_log::find_starknet_block(header.digest())
+            .map_err(|e| import_error(format!("Missing Starknet block: {e}")))?;
+        verify::verify_local_block::<H>(local_block.header(), &block, block_hash)?;
+        let local_hash = local_block.header().hash::<H>().0;
+
+        let mut params = BlockImportParams::new(BlockOrigin::NetworkInitialSync, header);
+        params.body = Some(body);
+        params.state_action = StateAction::ApplyChanges(StorageChanges::Changes(built_block.storage_changes));
+        params.fork_choice = Some(ForkChoiceStrategy::LongestChain);
+        params.finalized = true;
+        let substrate_hash = params.post_hash();
+
+        match self.block_import.import_block(params).await {
+            Ok(ImportResult::Imported(_) | ImportResult::AlreadyInChain) => {}
+            Ok(_) => return Err(import_error("the block was rejected".to_string())),
+            Err(e) => return Err(import_error(e.to_string())),
+        }
+
+        self.store(substrate_hash, block_hash, local_hash, classes, l1_handler_fees).map_err(import_error)
+    }
+
+    /// The extrinsics of the local block, along with the fees paid on L1 for its L1 handler
+    /// transactions.
+    #[allow(clippy::type_complexity)]
+    fn extrinsics(
+        &self,
+        parent: B::Hash,
+        block: &Block,
+        state_update: &StateUpdate,
+        classes: &HashMap<FieldElement, ContractClass>,
+    ) -> Result<(Vec<B::Extrinsic>, Vec<(FieldElement, Fee)>), Error> {
+        let runtime_error = |e: sp_api::ApiError| Error::Import {
+            block_number: block.block_number.unwrap_or_default(),
+            message: e.to_string(),
+        };
+        let runtime_api = self.client.runtime_api();
+
+        let chain_id = runtime_api.chain_id(parent).map_err(runtime_error)?;
+        let mut transactions = Vec::with_capacity(block.transactions.len());
+        let mut l1_handler_fees = Vec::new();
+        for (tx, receipt) in block.transactions.iter().zip(&block.transaction_receipts) {
+            let transaction = convert::transaction(tx, receipt, classes)?;
+            let transaction_hash = transaction.compute_hash::<H>(chain_id, false).0;
+            if transaction_hash != tx.transaction_hash() {
+                return Err(Error::InvalidTransaction(
+                    tx.transaction_hash(),
+                    format!("its content hashes to {transaction_hash:#x}"),
+                ));
+            }
+
+            if let UserOrL1HandlerTransaction::L1Handler(_, fee) = &transaction {
+                l1_handler_fees.push((transaction_hash, *fee));
+            }
+            transactions.push((transaction, transaction_hash, receipt));
+        }
+
+        if self.mode == ImportMode::TrustStateDiff {
+            let state_diff = convert::state_diff(&state_update.state_diff, classes)?;
+            let transactions = transactions
+                .into_iter()
+                .map(|(transaction, transaction_hash, receipt)| {
+                    convert::imported_transaction(transaction, transaction_hash, receipt)
+                })
+                .collect::<Result<_, _>>()?;
+            let state_root = Felt252Wrapper(verify::state_root(block)?).into();
+            // The state diffs are imported since the version 2 of the API
+            if !runtime_api
+                .has_api_with::<dyn ConvertTransactionRuntimeApi<B>, _>(parent, |version| version >= 2)
+                .map_err(runtime_error)?
+            {
+                return Err(Error::Import {
+                    block_number: block.block_number.unwrap_or_default(),
+                    message: "the runtime does not support importing state diffs".to_string(),
+                });
+            }
+            let extrinsic =
+                runtime_api.convert_state_diff(parent, state_diff, transactions, state_root).map_err(runtime_error)?;
+            return Ok((vec![extrinsic], l1_handler_fees));
+        }
+
+        let mut extrinsics = Vec::with_capacity(transactions.len());
+        for (transaction, _, _) in transactions {
+            let extrinsic = match transaction {
+                UserOrL1HandlerTransaction::User(transaction) => runtime_api.convert_transaction(parent, transaction),
+                UserOrL1HandlerTransaction::L1Handler(transaction, fee) => {
+                    runtime_api.convert_l1_transaction(parent, transaction, fee)
+                }
+            };
+            extrinsics.push(extrinsic.map_err(runtime_error)?);
+        }
+
+        Ok((extrinsics, l1_handler_fees))
+    }
+
+    /// Stores what the RPC serves of the imported block and is not part of the state.
+    fn store(
+        &self,
+        substrate_hash: B::Hash,
+        block_hash: FieldElement,
+        local_hash: FieldElement,
+        classes: HashMap<FieldElement, rpc::ContractClass>,
+        l1_handler_fees: Vec<(FieldElement, Fee)>,
+    ) -> Result<(), String> {
+        for (class_hash, class) in classes {
+            if let rpc::ContractClass::Sierra(class) = class {
+                self.madara_backend
+                    .sierra_classes()
+                    .store_sierra_class(
+                        Felt252Wrapper(class_hash).into(),
+                        flattened_sierra_to_sierra_contract_class(Arc::new(class)),
+                    )
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        for (transaction_hash, fee) in l1_handler_fees {
+            self.madara_backend
+                .l1_handler_paid_fee()
+                .store_fee_paid_for_l1_handler_tx(Felt252Wrapper(transaction_hash).into(), fee)
+                .map_err(|e| e.to_string())?;
+        }
+
+        // The mapping sync maps the block by its local hash, it is also found by its source hash
+        if local_hash != block_hash {
+            self.madara_backend
+                .mapping()
+                .write_block_hash_alias(substrate_hash, Felt252Wrapper(block_hash).into())
+                .map_err(|e| e.to_string())?;
+        }
+
+        Ok(())
+    }
+
+    fn starknet_block(&self, hash: B::Hash) -> Result<StarknetBlock, Error> {
+        let header = self
+            .client
+            .header(hash)
+            .map_err(|e| Error::Client(e.to_string()))?
+            .ok_or_else(|| Error::Client(format!("missing header of {hash}")))?;
+        mp_digest_log::find_starknet_block(header.digest())
+            .map_err(|e| Error::Client(format!("missing Starknet block in {hash}: {e}")))
+    }
+}
@@ -0,0 +1,179 @@
+//! Full node sync: follows a chain built by another sequencer.
+//!
+//! The blocks of the source, a feeder gateway or a Starknet JSON-RPC endpoint, are fetched in
+//! order and checked against the hash the source announces for them, which links each block to
+//! the previous one. They are then imported as local blocks, either re-executing their
+//! transactions or writing the state diff of the block without executing anything, and the header
+//! of the local block must match the source one.
+//!
+//! Madara does not compute state roots: the state root of the source blocks is taken as
+//! announced, and the local blocks are checked with it. The state diffs written without executing
+//! anything are not checked at all, their blocks carry the announced state root. The re-executed
+//! blocks keep the last known one, and have their own hashes: the source ones are mapped to them
+//! too.
+//!
+//! The same import, re-executing the blocks, replays the portable archives of [`archive`].
+
+#[cfg(test)]
+mod tests;
+
+pub mod archive;
+mod convert;
+mod export;
+mod import;
+pub mod source;
+mod verify;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use mp_hashers::HasherT;
+use pallet_starknet_runtime_api::{ConvertTransactionRuntimeApi, StarknetRuntimeApi};
+use sc_block_builder::BlockBuilderProvider;
+use sc_client_api::backend::Backend;
+use sc_consensus::BlockImport;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+use starknet_core::types::FieldElement;
+use url::Url;
+
+use crate::archive::{ArchiveReader, ArchiveSource};
+pub use crate::export::export_block;
+use crate::import::BlockImporter;
+pub use crate::verify::block_hash;
+
+/// Time waited for the source to build the next block once synced.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to fetch from the source: {0}")]
+    Source(String),
+    #[error("Invalid block {block_number}: {message}")]
+    InvalidBlock { block_number: u64, message: String },
+    #[error("Hash mismatch of block {block_number}: expected {expected:#x}, got {actual:#x}")]
+    HashMismatch { block_number: u64, expected: FieldElement, actual: FieldElement },
+    #[error("Block {block_number} does not follow the local chain: its parent is {actual:#x}, not {expected:#x}")]
+    ParentMismatch { block_number: u64, expected: FieldElement, actual: FieldElement },
+    #[error("Invalid transaction {0:#x}: {1}")]
+    InvalidTransaction(FieldElement, String),
+    #[error("Unsupported transaction {0:#x}: legacy deploy transactions cannot be imported")]
+    UnsupportedTransaction(FieldElement),
+    #[error("Failed to read the local chain: {0}")]
+    Client(String),
+    #[error("Failed to import block {block_number}: {message}")]
+    Import { block_number: u64, message: String },
+    #[error("Failed to export block {block_number}: {message}")]
+    Export { block_number: u64, message: String },
+    #[error("Invalid archive: {0}")]
+    InvalidArchive(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// The API the blocks are fetched from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum SourceKind {
+    /// The `/feeder_gateway/*` endpoints of a sequencer.
+    FeederGateway,
+    /// A Starknet JSON-RPC endpoint.
+    JsonRpc,
+}
+
+/// How the blocks of the source are applied to the local state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum ImportMode {
+    /// Re-executes the transactions of the blocks, checking the resulting block headers.
+    Execute,
+    /// Writes the state diffs of the blocks, trusting the source for them. The transactions of the
+    /// blocks and their outputs are stored as announced, without being executed.
+    TrustStateDiff,
+}
+
+/// Sync configuration.
+#[derive(Debug, Clone)]
+pub struct SyncConfig {
+    /// Url of the source, the root of the feeder gateway or the JSON-RPC endpoint.
+    pub url: Url,
+    pub source: SourceKind,
+    pub mode: ImportMode,
+}
+
+/// Imports the blocks of the source as they are built, for as long as the node runs.
+///
+/// A block failing to be fetched, verified or imported is retried after a while, the chain
+/// stalls on it until then.
+pub async fn run_sync<B, C, BE, BI, H>(
+    config: SyncConfig,
+    client: Arc<C>,
+    block_import: BI,
+    madara_backend: Arc<mc_db::Backend<B>>,
+) where
+    B: BlockT,
+    C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockBuilderProvider<BE, B, C> + Send + Sync + 'static,
+    C::Api: BlockBuilderApi<B> + ApiExt<B> + StarknetRuntimeApi<B> + ConvertTransactionRuntimeApi<B>,
+    BE: Backend<B>,
+    BI: BlockImport<B> + Send + Sync,
+    H: HasherT + Send + Sync + 'static,
+{
+    let source = source::block_source(config.source, config.url.clone());
+    let mut importer = BlockImporter::<B, C, BE, BI, H>::new(client, block_import, madara_backend, config.mode);
+    log::info!("Syncing from {} ({:?}, {:?})", config.url, config.source, config.mode);
+
+    loop {
+        match importer.import_next(source.as_ref()).await {
+            Ok(Some(block_number)) => {
+                log::debug!("Synced block {block_number}");
+                continue;
+            }
+            Ok(None) => {}
+            Err(e) => log::error!("Failed to sync: {e}"),
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Imports the blocks of an archive following the local best block, re-executing them. Returns
+/// the number of blocks imported.
+///
+/// The archive must be of the same chain, and cover the block following the local best block.
+pub async fn import_archive<B, C, BE, BI, H>(
+    reader: ArchiveReader,
+    client: Arc<C>,
+    block_import: BI,
+    madara_backend: Arc<mc_db::Backend<B>>,
+) -> Result<u64, Error>
+where
+    B: BlockT,
+    C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockBuilderProvider<BE, B, C>,
+    C::Api: BlockBuilderApi<B> + ApiExt<B> + StarknetRuntimeApi<B> + ConvertTransactionRuntimeApi<B>,
+    BE: Backend<B>,
+    BI: BlockImport<B>,
+    H: HasherT,
+{
+    let best_hash = client.info().best_hash;
+    let chain_id = client.runtime_api().chain_id(best_hash).map_err(|e| Error::Client(e.to_string()))?;
+    if reader.header().chain_id != chain_id.0 {
+        return Err(Error::InvalidArchive(format!(
+            "the archive is of chain {:#x}, not {:#x}",
+            reader.header().chain_id,
+            chain_id.0
+        )));
+    }
+
+    let source = ArchiveSource::new(reader);
+    let mut importer = BlockImporter::<B, C, BE, BI, H>::new(client, block_import, madara_backend, ImportMode::Execute);
+    // The archived blocks are local ones, their hashes are the local hashes
+    importer.follow_local_chain()?;
+    let mut imported = 0;
+    while let Some(block_number) = importer.import_next(&source).await? {
+        log::debug!("Imported block {block_number}");
+        imported += 1;
+    }
+
+    Ok(imported)
+}
@@ -0,0 +1,170 @@
+//! The APIs the blocks are fetched from.
+
+use futures::future::BoxFuture;
+use mc_feeder_gateway::conversions;
+use mc_feeder_gateway::types::{Block, ContractClass, ErrorCode, GatewayError, StateUpdate};
+use serde::de::DeserializeOwned;
+use starknet_core::types::{self as rpc, BlockId, FieldElement, StarknetError};
+use starknet_providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use starknet_providers::{MaybeUnknownErrorCode, Provider, ProviderError, StarknetErrorWithMessage};
+use url::Url;
+
+use crate::{convert, Error, SourceKind};
+
+/// A block of the source, in the shapes of the feeder gateway.
+#[derive(Debug, Clone)]
+pub struct SourceBlock {
+    pub block: Block,
+    pub state_update: StateUpdate,
+}
+
+pub trait BlockSource: Send + Sync {
+    /// Fetches a block, `None` until the source builds it.
+    fn block(&self, block_number: u64) -> BoxFuture<'_, Result<Option<SourceBlock>, Error>>;
+
+    /// Fetches a class declared at or before `block_number`.
+    fn class(&self, class_hash: FieldElement, block_number: u64) -> BoxFuture<'_, Result<rpc::ContractClass, Error>>;
+}
+
+pub fn block_source(kind: SourceKind, url: Url) -> Box<dyn BlockSource> {
+    match kind {
+        SourceKind::FeederGateway => Box::new(FeederGatewaySource::new(url)),
+        SourceKind::JsonRpc => Box::new(JsonRpcSource::new(url)),
+    }
+}
+
+/// Reads the `/feeder_gateway/*` endpoints of a sequencer.
+pub struct FeederGatewaySource {
+    client: reqwest::Client,
+    url: Url,
+}
+
+impl FeederGatewaySource {
+    pub fn new(mut url: Url) -> Self {
+        // The endpoints are joined to the url, which would replace its last segment otherwise
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+        Self { client: reqwest::Client::new(), url }
+    }
+
+    /// Requests a gateway endpoint, `None` when the block is not found.
+    async fn get<T: DeserializeOwned>(&self, endpoint: &str, params: &[(&str, String)]) -> Result<Option<T>, Error> {
+        let url = self
+            .url
+            .join(&format!("feeder_gateway/{endpoint}"))
+            .map_err(|e| Error::Source(format!("Invalid url: {e}")))?;
+        let response = self.client.get(url).query(params).send().await.map_err(|e| Error::Source(e.to_string()))?;
+
+        let status = response.status();
+        let body = response.bytes().await.map_err(|e| Error::Source(e.to_string()))?;
+        if status.is_success() {
+            return serde_json::from_slice(&body)
+                .map(Some)
+                .map_err(|e| Error::Source(format!("Invalid {endpoint} response: {e}")));
+        }
+
+        match serde_json::from_slice::<GatewayError>(&body) {
+            Ok(error) if error.code == ErrorCode::BlockNotFound => Ok(None),
+            Ok(error) => Err(Error::Source(error.to_string())),
+            Err(_) => Err(Error::Source(format!("{endpoint} answered {status}"))),
+        }
+    }
+}
+
+impl BlockSource for FeederGatewaySource {
+    fn block(&self, block_number: u64) -> BoxFuture<'_, Result<Option<SourceBlock>, Error>> {
+        Box::pin(async move {
+            let params = [("blockNumber", block_number.to_string())];
+            let Some(block) = self.get::<Block>("get_block", &params).await? else {
+                return Ok(None);
+            };
+            // The block exists, so does its state update
+            let state_update = self
+                .get::<StateUpdate>("get_state_update", &params)
+                .await?
+                .ok_or_else(|| Error::Source(format!("Missing state update of block {block_number}")))?;
+
+            Ok(Some(SourceBlock { block, state_update }))
+        })
+    }
+
+    fn class(&self, class_hash: FieldElement, block_number: u64) -> BoxFuture<'_, Result<rpc::ContractClass, Error>> {
+        Box::pin(async move {
+            let params = [("classHash", format!("{class_hash:#x}")), ("blockNumber", block_number.to_string())];
+            let class = self
+                .get::<ContractClass>("get_class_by_hash", &params)
+                .await?
+                .ok_or_else(|| Error::Source(format!("Missing class {class_hash:#x}")))?;
+            convert::contract_class(class)
+        })
+    }
+}
+
+/// Reads a Starknet JSON-RPC endpoint.
+pub struct JsonRpcSource {
+    provider: JsonRpcClient<HttpTransport>,
+}
+
+impl JsonRpcSource {
+    pub fn new(url: Url) -> Self {
+        Self { provider: JsonRpcClient::new(HttpTransport::new(url)) }
+    }
+}
+
+type RpcError = ProviderError<<JsonRpcClient<HttpTransport> as Provider>::Error>;
+
+fn is_block_not_found(error: &RpcError) -> bool {
+    matches!(
+        error,
+        ProviderError::StarknetError(StarknetErrorWithMessage {
+            code: MaybeUnknownErrorCode::Known(StarknetError::BlockNotFound),
+            ..
+        })
+    )
+}
+
+impl BlockSource for JsonRpcSource {
+    fn block(&self, block_number: u64) -> BoxFuture<'_, Result<Option<SourceBlock>, Error>> {
+        Box::pin(async move {
+            let block_id = BlockId::Number(block_number);
+            let block = match self.provider.get_block_with_txs(block_id).await {
+                Ok(block) => block,
+                Err(e) if is_block_not_found(&e) => return Ok(None),
+                Err(e) => return Err(Error::Source(e.to_string())),
+            };
+
+            let transactions = match &block {
+                rpc::MaybePendingBlockWithTxs::Block(block) => &block.transactions,
+                rpc::MaybePendingBlockWithTxs::PendingBlock(_) => {
+                    return Err(Error::Source(format!("Block {block_number} is pending")));
+                }
+            };
+            let mut receipts = Vec::with_capacity(transactions.len());
+            for transaction in transactions {
+                let receipt = self
+                    .provider
+                    .get_transaction_receipt(*transaction.transaction_hash())
+                    .await
+                    .map_err(|e| Error::Source(e.to_string()))?;
+                receipts.push(receipt);
+            }
+            let state_update =
+                self.provider.get_state_update(block_id).await.map_err(|e| Error::Source(e.to_string()))?;
+
+            Ok(Some(SourceBlock {
+                block: conversions::block(block, receipts),
+                state_update: conversions::state_update(state_update),
+            }))
+        })
+    }
+
+    fn class(&self, class_hash: FieldElement, block_number: u64) -> BoxFuture<'_, Result<rpc::ContractClass, Error>> {
+        Box::pin(async move {
+            self.provider
+                .get_class(BlockId::Number(block_number), class_hash)
+                .await
+                .map_err(|e| Error::Source(format!("Failed to fetch the class {class_hash:#x}: {e}")))
+        })
+    }
+}
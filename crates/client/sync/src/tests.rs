@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+
+use flate2::read::GzDecoder;
+use mc_feeder_gateway::types::{
+    self as gateway, Block, BlockStatus, InvokeTransaction, Transaction, TransactionReceipt,
+};
+use mp_block::Header;
+use mp_felt::Felt252Wrapper;
+use mp_hashers::pedersen::PedersenHasher;
+use mp_transactions::{
+    InvokeTransaction as MpInvokeTransaction, Transaction as MpTransaction, UserOrL1HandlerTransaction, UserTransaction,
+};
+use serde_json::{json, Value};
+use starknet_api::state::ThinStateDiff;
+use starknet_core::types::{ContractClass, FieldElement};
+
+use crate::archive::{ArchiveFormat, ArchiveHeader, ArchiveReader, ArchiveWriter, ArchivedBlock, ARCHIVE_VERSION};
+use crate::source::{BlockSource, FeederGatewaySource};
+use crate::{convert, verify, Error};
+
+fn felt(value: u64) -> FieldElement {
+    FieldElement::from(value)
+}
+
+/// Recorded responses of a feeder gateway, by path and query.
+fn recorded_response(target: &str) -> (&'static str, Value) {
+    let not_found = json!({ "code": "StarknetErrorCode.BLOCK_NOT_FOUND", "message": "Block not found" });
+    match target {
+        "/feeder_gateway/get_block?blockNumber=1" => ("200 OK", serde_json::to_value(block()).unwrap()),
+        "/feeder_gateway/get_state_update?blockNumber=1" => (
+            "200 OK",
+            json!({
+                "block_hash": "0x1",
+                "new_root": "0x2",
+                "old_root": "0x3",
+                "state_diff": {
+                    "storage_diffs": { "0xa": [{ "key": "0xb", "value": "0xc" }] },
+                    "nonces": { "0xa": "0x1" },
+                    "deployed_contracts": [],
+                    "old_declared_contracts": [],
+                    "declared_classes": [],
+                    "replaced_classes": [],
+                },
+            }),
+        ),
+        _ => ("400 Bad Request", not_found),
+    }
+}
+
+/// Serves the recorded responses over HTTP, returning the url of the server.
+fn stand_in_gateway() -> url::Url {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap()).parse().unwrap();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+
+            let target = request_line.split_whitespace().nth(1).unwrap();
+            let (status, response) = recorded_response(target);
+            let response = response.to_string();
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                response.len(),
+                response
+            )
+            .unwrap();
+        }
+    });
+
+    url
+}
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(future)
+}
+
+fn invoke_transaction() -> Transaction {
+    Transaction::Invoke(InvokeTransaction {
+        transaction_hash: felt(0x100),
+        version: FieldElement::ONE,
+        max_fee: felt(1000),
+        signature: vec![felt(1), felt(2)],
+        nonce: Some(felt(3)),
+        sender_address: Some(felt(4)),
+        contract_address: None,
+        entry_point_selector: None,
+        calldata: vec![felt(5)],
+    })
+}
+
+fn receipt(events: usize) -> TransactionReceipt {
+    TransactionReceipt {
+        events: vec![gateway::Event { from_address: felt(4), keys: Vec::new(), data: Vec::new() }; events],
+        actual_fee: Some(felt(10)),
+        ..TransactionReceipt::not_received(felt(0x100))
+    }
+}
+
+/// A block, announcing the hash of its content.
+fn block() -> Block {
+    let mut block = Block {
+        block_hash: None,
+        parent_block_hash: felt(0xbeef),
+        block_number: Some(1),
+        state_root: Some(felt(2)),
+        status: BlockStatus::AcceptedOnL2,
+        timestamp: 1700000000,
+        sequencer_address: felt(0x5e0),
+        gas_price: felt(10),
+        strk_l1_gas_price: None,
+        starknet_version: "0".to_string(),
+        transactions: vec![invoke_transaction()],
+        transaction_receipts: vec![receipt(2)],
+    };
+    block.block_hash = Some(verify::block_hash::<PedersenHasher>(&block).unwrap());
+    block
+}
+
+/// The header of `block()`.
+fn header() -> Header {
+    let signature = vec![Felt252Wrapper(felt(1)), Felt252Wrapper(felt(2))];
+    let transaction_commitment =
+        mp_block::calculate_transaction_commitment(&[(Felt252Wrapper(felt(0x100)), signature)]);
+    let event_hash = mp_block::event_hash(Felt252Wrapper(felt(4)), &[], &[]);
+    let event_commitment = mp_block::calculate_event_commitment(&[event_hash, event_hash]);
+    Header {
+        parent_block_hash: Felt252Wrapper(felt(0xbeef)).into(),
+        block_number: 1,
+        global_state_root: Felt252Wrapper(felt(2)).into(),
+        sequencer_address: Felt252Wrapper(felt(0x5e0)).into(),
+        block_timestamp: 1700000000,
+        transaction_count: 1,
+        transaction_commitment: transaction_commitment.into(),
+        event_count: 2,
+        event_commitment: event_commitment.into(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn block_hashes_are_the_starknet_header_hashes() {
+    assert_eq!(verify::block_hash::<PedersenHasher>(&block()).unwrap(), header().hash::<PedersenHasher>().0);
+}
+
+#[test]
+fn local_blocks_must_match_the_source_ones_but_their_state_root_and_parent() {
+    let block = block();
+    let block_hash = block.block_hash.unwrap();
+    let local_header = Header {
+        parent_block_hash: Felt252Wrapper(felt(0x1234)).into(),
+        global_state_root: Default::default(),
+        ..header()
+    };
+    assert!(verify::verify_local_block::<PedersenHasher>(&local_header, &block, block_hash).is_ok());
+
+    let missing_event = Header { event_count: 1, ..local_header };
+    assert!(matches!(
+        verify::verify_local_block::<PedersenHasher>(&missing_event, &block, block_hash),
+        Err(Error::HashMismatch { block_number: 1, .. })
+    ));
+}
+
+#[test]
+fn blocks_must_match_their_hash_and_parent() {
+    let block = block();
+    assert_eq!(verify::verify_block::<PedersenHasher>(&block, felt(0xbeef)).unwrap(), block.block_hash.unwrap());
+
+    assert!(matches!(
+        verify::verify_block::<PedersenHasher>(&block, felt(0xdead)),
+        Err(Error::ParentMismatch { block_number: 1, .. })
+    ));
+
+    let mut tampered = block.clone();
+    tampered.timestamp += 1;
+    assert!(matches!(
+        verify::verify_block::<PedersenHasher>(&tampered, felt(0xbeef)),
+        Err(Error::HashMismatch { block_number: 1, .. })
+    ));
+
+    let mut tampered = block.clone();
+    tampered.transaction_receipts[0].events[0].data.push(felt(1));
+    assert!(matches!(
+        verify::verify_block::<PedersenHasher>(&tampered, felt(0xbeef)),
+        Err(Error::HashMismatch { block_number: 1, .. })
+    ));
+}
+
+#[test]
+fn invoke_transactions_are_converted() {
+    let transaction = convert::transaction(&invoke_transaction(), &receipt(0), &HashMap::new()).unwrap();
+
+    let UserOrL1HandlerTransaction::User(UserTransaction::Invoke(MpInvokeTransaction::V1(tx))) = transaction else {
+        panic!("Expected an invoke v1 transaction");
+    };
+    assert_eq!(tx.max_fee, 1000);
+    assert_eq!(tx.nonce, Felt252Wrapper(felt(3)));
+    assert_eq!(tx.sender_address, Felt252Wrapper(felt(4)));
+    assert_eq!(tx.calldata, vec![Felt252Wrapper(felt(5))]);
+}
+
+#[test]
+fn imported_transactions_carry_their_receipt_outputs() {
+    let mut receipt = receipt(1);
+    receipt.l2_to_l1_messages =
+        vec![gateway::L2ToL1Message { from_address: felt(4), to_address: felt(0xe7), payload: vec![felt(6)] }];
+    receipt.revert_error = Some("reverted".to_string());
+    let transaction = convert::transaction(&invoke_transaction(), &receipt, &HashMap::new()).unwrap();
+
+    let imported = convert::imported_transaction(transaction, felt(0x100), &receipt).unwrap();
+    assert_eq!(imported.transaction_hash, Felt252Wrapper(felt(0x100)).into());
+    assert!(matches!(imported.transaction, MpTransaction::Invoke(MpInvokeTransaction::V1(_))));
+    assert_eq!(imported.events.len(), 1);
+    assert_eq!(imported.events[0].from_address, Felt252Wrapper(felt(4)).into());
+    assert_eq!(imported.messages.len(), 1);
+    assert_eq!(imported.messages[0].payload.0, vec![Felt252Wrapper(felt(6)).into()]);
+    assert_eq!(imported.revert_error.as_deref(), Some("reverted"));
+
+    receipt.l2_to_l1_messages[0].to_address = FieldElement::MAX;
+    let transaction = convert::transaction(&invoke_transaction(), &receipt, &HashMap::new()).unwrap();
+    assert!(matches!(
+        convert::imported_transaction(transaction, felt(0x100), &receipt),
+        Err(Error::InvalidTransaction(hash, _)) if hash == felt(0x100)
+    ));
+}
+
+#[test]
+fn legacy_deploy_transactions_are_unsupported() {
+    let deploy = Transaction::Deploy(gateway::DeployTransaction {
+        transaction_hash: felt(0x200),
+        version: FieldElement::ZERO,
+        contract_address: Some(felt(1)),
+        contract_address_salt: felt(2),
+        class_hash: felt(3),
+        constructor_calldata: Vec::new(),
+    });
+
+    assert!(matches!(
+        convert::transaction(&deploy, &receipt(0), &HashMap::new()),
+        Err(Error::UnsupportedTransaction(hash)) if hash == felt(0x200)
+    ));
+}
+
+#[test]
+fn legacy_programs_are_compressed_back() {
+    let program = json!({ "builtins": ["pedersen"], "data": ["0x1"] });
+    let class = gateway::ContractClass::Legacy(gateway::LegacyContractClass {
+        program: program.clone(),
+        entry_points_by_type: gateway::LegacyEntryPointsByType {
+            constructor: Vec::new(),
+            external: vec![gateway::LegacyEntryPoint { selector: felt(1), offset: felt(58) }],
+            l1_handler: Vec::new(),
+        },
+        abi: None,
+    });
+
+    let ContractClass::Legacy(class) = convert::contract_class(class).unwrap() else {
+        panic!("Expected a legacy class");
+    };
+    let mut decompressed = Vec::new();
+    GzDecoder::new(&class.program[..]).read_to_end(&mut decompressed).unwrap();
+    assert_eq!(serde_json::from_slice::<Value>(&decompressed).unwrap(), program);
+    assert_eq!(class.entry_points_by_type.external[0].offset, 58);
+}
+
+#[test]
+fn feeder_gateway_blocks_are_fetched_until_not_found() {
+    let source = FeederGatewaySource::new(stand_in_gateway());
+
+    let fetched = block_on(source.block(1)).unwrap().unwrap();
+    assert_eq!(fetched.block, block());
+    assert_eq!(fetched.state_update.state_diff.nonces.get(&felt(0xa)), Some(&felt(1)));
+
+    assert!(block_on(source.block(2)).unwrap().is_none());
+}
+
+fn archived_block() -> ArchivedBlock {
+    let mut state_diff = ThinStateDiff::default();
+    state_diff.nonces.insert(Felt252Wrapper(felt(0xa)).into(), Felt252Wrapper(felt(1)).into());
+    ArchivedBlock { block: block(), state_diff, classes: Vec::new() }
+}
+
+fn archive(format: ArchiveFormat) -> Vec<u8> {
+    let header = ArchiveHeader { version: ARCHIVE_VERSION, chain_id: felt(0x534e), first_block: 1, last_block: 1 };
+    let mut writer = ArchiveWriter::new(Vec::new(), format, &header).unwrap();
+    writer.write_block(&archived_block()).unwrap();
+    writer.finish().unwrap()
+}
+
+#[test]
+fn archives_are_read_back_in_any_format() {
+    for format in [ArchiveFormat::JsonLines, ArchiveFormat::Binary] {
+        let mut reader = ArchiveReader::new(std::io::Cursor::new(archive(format))).unwrap();
+        assert_eq!(reader.header().chain_id, felt(0x534e));
+
+        let archived = reader.next_block().unwrap().unwrap();
+        assert_eq!(archived.block, block());
+        assert_eq!(archived.state_diff, archived_block().state_diff);
+        assert!(reader.next_block().unwrap().is_none());
+    }
+}
+
+#[test]
+fn corrupted_archives_are_rejected() {
+    let archive = String::from_utf8(archive(ArchiveFormat::JsonLines)).unwrap();
+
+    let tampered = archive.replace("\"timestamp\":1700000000", "\"timestamp\":1700000001");
+    assert_ne!(tampered, archive);
+    let mut reader = ArchiveReader::new(std::io::Cursor::new(tampered)).unwrap();
+    assert!(matches!(reader.next_block(), Err(Error::InvalidArchive(message)) if message.contains("checksum")));
+
+    let truncated = archive.lines().next().unwrap().to_string();
+    let mut reader = ArchiveReader::new(std::io::Cursor::new(truncated)).unwrap();
+    assert!(matches!(reader.next_block(), Err(Error::InvalidArchive(message)) if message.contains("truncated")));
+}
@@ -0,0 +1,112 @@
+//! Checks of the blocks fetched from the source.
+
+use mc_feeder_gateway::types::Block;
+use mp_block::Header;
+use mp_felt::Felt252Wrapper;
+use mp_hashers::HasherT;
+use starknet_core::types::FieldElement;
+
+use crate::Error;
+
+/// Computes the hash of a block of the source from its content, as Starknet does.
+///
+/// The transaction and event commitments are computed from the transactions and receipts of the
+/// block, the state root is taken as announced.
+pub fn block_hash<H: HasherT>(block: &Block) -> Result<FieldElement, Error> {
+    let block_number = block_number(block)?;
+    let global_state_root = state_root(block)?;
+
+    let transactions: Vec<(Felt252Wrapper, Vec<Felt252Wrapper>)> = block
+        .transactions
+        .iter()
+        .map(|tx| (Felt252Wrapper(tx.transaction_hash()), tx.signature().iter().copied().map(Felt252Wrapper).collect()))
+        .collect();
+    let event_hashes: Vec<Felt252Wrapper> = block
+        .transaction_receipts
+        .iter()
+        .flat_map(|receipt| &receipt.events)
+        .map(|event| {
+            let keys: Vec<Felt252Wrapper> = event.keys.iter().copied().map(Felt252Wrapper).collect();
+            let data: Vec<Felt252Wrapper> = event.data.iter().copied().map(Felt252Wrapper).collect();
+            mp_block::event_hash(Felt252Wrapper(event.from_address), &keys, &data)
+        })
+        .collect();
+
+    let header = Header {
+        parent_block_hash: Felt252Wrapper(block.parent_block_hash).into(),
+        block_number,
+        global_state_root: Felt252Wrapper(global_state_root).into(),
+        sequencer_address: Felt252Wrapper(block.sequencer_address).into(),
+        block_timestamp: block.timestamp,
+        transaction_count: transactions.len() as u128,
+        transaction_commitment: mp_block::calculate_transaction_commitment(&transactions).into(),
+        event_count: event_hashes.len() as u128,
+        event_commitment: mp_block::calculate_event_commitment(&event_hashes).into(),
+        ..Default::default()
+    };
+
+    Ok(header.hash::<H>().0)
+}
+
+/// Checks that the block follows `parent_hash` and is the block its announced hash commits to,
+/// returning that hash.
+pub(crate) fn verify_block<H: HasherT>(block: &Block, parent_hash: FieldElement) -> Result<FieldElement, Error> {
+    let block_number = block_number(block)?;
+    let announced_hash = block
+        .block_hash
+        .ok_or_else(|| Error::InvalidBlock { block_number, message: "the block has no hash".to_string() })?;
+    if block.transactions.len() != block.transaction_receipts.len() {
+        return Err(Error::InvalidBlock {
+            block_number,
+            message: "the transactions and their receipts do not match".to_string(),
+        });
+    }
+
+    if block.parent_block_hash != parent_hash {
+        return Err(Error::ParentMismatch { block_number, expected: parent_hash, actual: block.parent_block_hash });
+    }
+
+    let computed_hash = block_hash::<H>(block)?;
+    if computed_hash != announced_hash {
+        return Err(Error::HashMismatch { block_number, expected: announced_hash, actual: computed_hash });
+    }
+
+    Ok(announced_hash)
+}
+
+/// Checks that the header of the local block built from `block` is the one of the source, whose
+/// hash is `block_hash`.
+///
+/// Madara does not compute the state root of its blocks, and the local blocks follow the local
+/// chain: the local header is hashed with the state root and the parent of the source block. All
+/// of the header is checked but the state itself, whose root is taken as announced.
+pub(crate) fn verify_local_block<H: HasherT>(
+    local_header: &Header,
+    block: &Block,
+    block_hash: FieldElement,
+) -> Result<(), Error> {
+    let block_number = block_number(block)?;
+    let header = Header {
+        parent_block_hash: Felt252Wrapper(block.parent_block_hash).into(),
+        global_state_root: Felt252Wrapper(state_root(block)?).into(),
+        ..local_header.clone()
+    };
+
+    let local_hash = header.hash::<H>().0;
+    if local_hash != block_hash {
+        return Err(Error::HashMismatch { block_number, expected: block_hash, actual: local_hash });
+    }
+
+    Ok(())
+}
+
+pub(crate) fn state_root(block: &Block) -> Result<FieldElement, Error> {
+    let block_number = block_number(block)?;
+    block
+        .state_root
+        .ok_or_else(|| Error::InvalidBlock { block_number, message: "the block has no state root".to_string() })
+}
+
+fn block_number(block: &Block) -> Result<u64, Error> {
+    block.block_number.ok_or_else(|| Error::Source("the source answered a pending block".to_string()))
+}
@@ -1,14 +1,16 @@
 use std::path::PathBuf;
 
-use madara_runtime::{AuraConfig, GrandpaConfig, RuntimeGenesisConfig, SealingMode, SystemConfig, WASM_BINARY};
-use mp_felt::Felt252Wrapper;
-use pallet_starknet::genesis_loader::{GenesisData, GenesisLoader, HexFelt};
+use madara_runtime::{
+    AccountId, AuraConfig, GrandpaConfig, RuntimeGenesisConfig, SealingMode, Signature, SystemConfig, WASM_BINARY,
+};
+use pallet_starknet::genesis_loader::{GenesisData, GenesisLoader};
 use sc_service::{BasePath, ChainType};
 use serde::{Deserialize, Serialize};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use sp_core::storage::Storage;
-use sp_core::{Pair, Public};
+use sp_core::{sr25519, Pair, Public};
+use sp_runtime::traits::{IdentifyAccount, Verify};
 use sp_state_machine::BasicExternalities;
 
 use crate::constants::DEV_CHAIN_ID;
@@ -50,6 +52,14 @@ pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Pu
     TPublic::Pair::from_string(&format!("//{seed}"), None).expect("static values are valid; qed").public()
 }
 
+/// Generate an account ID from seed.
+pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId
+where
+    <Signature as Verify>::Signer: From<<TPublic::Pair as Pair>::Public>,
+{
+    <Signature as Verify>::Signer::from(get_from_seed::<TPublic>(seed)).into_account()
+}
+
 /// Generate an Aura authority key.
 pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
     (get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
@@ -77,6 +87,7 @@ pub fn development_config(sealing: SealingMode, base_path: BasePath) -> Result<D
                     wasm_binary,
                     // Initial PoA authorities
                     vec![authority_keys_from_seed("Alice")],
+                    get_account_id_from_seed::<sr25519::Public>("Alice"),
                     true,
                 ),
                 sealing: sealing.clone(),
@@ -96,25 +107,21 @@ pub fn development_config(sealing: SealingMode, base_path: BasePath) -> Result<D
     ))
 }
 
-// helper to print development accounts info
-// accounts with addresses 0x1 and 0x4 are NO VALIDATE accounts (don't require PK)
-// accounts with addresses 0x2 and 0x3 have the same PK
+/// Logs the predeployed accounts of the genesis, to be used on development chains.
 pub fn print_development_accounts(genesis_loader: &GenesisLoader) {
-    // TODO: this is only true by luck. It's not enforced by anything
-    let no_validate_account_address = genesis_loader.data().contracts[0].0.0;
-    let argent_account_address = genesis_loader.data().contracts[1].0.0;
-    let oz_account_address = genesis_loader.data().contracts[2].0.0;
-    let cairo_1_no_validate_account_address = genesis_loader.data().contracts[3].0.0;
-
-    let argent_pk: HexFelt =
-        Felt252Wrapper::from_hex_be("0x00c1cf1490de1352865301bb8705143f3ef938f97fdf892f1090dcb5ac7bcd1d")
-            .unwrap()
-            .into();
     log::info!("🧪 Using the following development accounts:");
-    log::info!("🧪 NO VALIDATE with address: {no_validate_account_address:#x} and no pk");
-    log::info!("🧪 ARGENT with address: {argent_account_address:#x} and pk: {argent_pk:#x}");
-    log::info!("🧪 OZ with address: {oz_account_address:#x} and pk: {argent_pk:#x}");
-    log::info!("🧪 CAIRO 1 with address: {cairo_1_no_validate_account_address:#x} and no pk");
+    for account in genesis_loader.data().predeployed_accounts.iter() {
+        let role = account.role.as_ref().map(|role| format!(" ({role})")).unwrap_or_default();
+        match &account.private_key {
+            Some(private_key) => log::info!(
+                "🧪 {}{role} with address: {:#x} and pk: 0x{}",
+                account.name,
+                account.contract_address,
+                hex::encode(private_key)
+            ),
+            None => log::info!("🧪 {}{role} with address: {:#x} and no pk", account.name, account.contract_address),
+        }
+    }
 }
 
 pub fn local_testnet_config(base_path: BasePath, chain_id: &str) -> Result<ChainSpec, String> {
@@ -135,6 +142,7 @@ pub fn local_testnet_config(base_path: BasePath, chain_id: &str) -> Result<Chain
                 // Initial PoA authorities
                 // Intended to be only 2
                 vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
+                get_account_id_from_seed::<sr25519::Public>("Alice"),
                 true,
             )
         },
@@ -167,9 +175,12 @@ fn testnet_genesis(
     genesis_loader: GenesisLoader,
     wasm_binary: &[u8],
     initial_authorities: Vec<(AuraId, GrandpaId)>,
+    admin: AccountId,
     _enable_println: bool,
 ) -> RuntimeGenesisConfig {
-    let starknet_genesis_config: madara_runtime::pallet_starknet::GenesisConfig<_> = genesis_loader.into();
+    let mut starknet_genesis_config: madara_runtime::pallet_starknet::GenesisConfig<_> = genesis_loader.into();
+    // Account allowed to update the chain parameters, unless the genesis restores another one
+    starknet_genesis_config.admin.get_or_insert(admin);
 
     RuntimeGenesisConfig {
         system: SystemConfig {
@@ -1,4 +1,6 @@
-use crate::commands::{ExtendedRunCmd, SetupCmd};
+use crate::commands::{
+    DumpStateCmd, ExportStarknetCmd, ExtendedRunCmd, GenesisCmd, ImportStarknetCmd, LoadStateCmd, SetupCmd,
+};
 
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -22,18 +24,33 @@ pub enum Subcommand {
     /// Db meta columns information.
     ChainInfo(sc_cli::ChainInfoCmd),
 
+    /// Dump the Starknet state of a block into a genesis file.
+    DumpState(DumpStateCmd),
+
     /// Validate blocks.
     CheckBlock(sc_cli::CheckBlockCmd),
 
     /// Export blocks.
     ExportBlocks(sc_cli::ExportBlocksCmd),
 
+    /// Export Starknet blocks into a portable archive.
+    ExportStarknet(ExportStarknetCmd),
+
     /// Export the state of a given block into a chain spec.
     ExportState(sc_cli::ExportStateCmd),
 
+    /// Build a genesis file from a manifest of its classes, accounts and fee token.
+    Genesis(GenesisCmd),
+
     /// Import blocks.
     ImportBlocks(sc_cli::ImportBlocksCmd),
 
+    /// Import the Starknet blocks of a portable archive, re-executing them.
+    ImportStarknet(ImportStarknetCmd),
+
+    /// Load a state dump as the genesis of a chain.
+    LoadState(LoadStateCmd),
+
     /// Key management cli utilities
     #[command(subcommand)]
     Key(sc_cli::KeySubcommand),
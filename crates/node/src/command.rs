@@ -2,6 +2,7 @@ use std::env;
 
 use frame_benchmarking_cli::{BenchmarkCmd, ExtrinsicFactory, SUBSTRATE_REFERENCE_HARDWARE};
 use madara_runtime::Block;
+use mc_genesis_data_provider::{GenesisProvider, OnDiskGenesisConfig};
 use sc_cli::{ChainSpec, SubstrateCli};
 
 use crate::benchmarking::{inherent_benchmark_data, RemarkBuilder};
@@ -40,7 +41,7 @@ impl SubstrateCli for Cli {
     fn load_spec(&self, id: &str) -> Result<Box<dyn ChainSpec>, String> {
         Ok(match id {
             DEV_CHAIN_ID => {
-                let sealing = self.run.sealing.map(Into::into).unwrap_or_default();
+                let sealing = self.run.sealing_mode();
                 let base_path = self.run.base_path().map_err(|e| e.to_string())?;
                 Box::new(chain_spec::development_config(sealing, base_path)?)
             }
@@ -81,6 +82,13 @@ pub fn run() -> sc_cli::Result<()> {
                 Ok((cmd.run(client, config.database), task_manager))
             })
         }
+        Some(Subcommand::ExportStarknet(ref cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.sync_run(|mut config| {
+                let (client, backend, _, _, madara_backend) = service::new_chain_ops(&mut config, cli.run.cache)?;
+                cmd.run(client, backend, madara_backend)
+            })
+        }
         Some(Subcommand::ExportState(ref cmd)) => {
             let runner = cli.create_runner(cmd)?;
             runner.async_run(|mut config| {
@@ -95,6 +103,13 @@ pub fn run() -> sc_cli::Result<()> {
                 Ok((cmd.run(client, import_queue), task_manager))
             })
         }
+        Some(Subcommand::ImportStarknet(ref cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.async_run(|mut config| {
+                let (client, _, _, task_manager, madara_backend) = service::new_chain_ops(&mut config, cli.run.cache)?;
+                Ok((cmd.run(client, madara_backend), task_manager))
+            })
+        }
         Some(Subcommand::PurgeChain(ref cmd)) => {
             let runner = cli.create_runner(cmd)?;
             runner.sync_run(|config| cmd.run(config.database))
@@ -124,7 +139,9 @@ pub fn run() -> sc_cli::Result<()> {
                                 .into());
                         }
 
-                        cmd.run::<Block, sp_statement_store::runtime_api::HostFunctions>(config)
+                        cmd.run::<Block, (sp_statement_store::runtime_api::HostFunctions, mp_fork::forked_state::HostFunctions)>(
+                            config,
+                        )
                     }
                     BenchmarkCmd::Block(cmd) => {
                         let (client, _, _, _, _) = service::new_chain_ops(&mut config, cli.run.cache)?;
@@ -180,6 +197,23 @@ pub fn run() -> sc_cli::Result<()> {
             runner.sync_run(|config| cmd.run::<Block>(&config))
         }
         Some(Subcommand::Setup(ref cmd)) => cmd.run(),
+        Some(Subcommand::DumpState(ref cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.sync_run(|mut config| {
+                // The predeployed accounts are only known from the genesis file of the chain
+                let genesis = OnDiskGenesisConfig(cli.run.base_path()?.config_dir(config.chain_spec.id()))
+                    .load_genesis_data()
+                    .ok();
+                let (client, _, _, _, madara_backend) = service::new_chain_ops(&mut config, cli.run.cache)?;
+                cmd.run(client, madara_backend, genesis)
+            })
+        }
+        Some(Subcommand::LoadState(ref cmd)) => cmd.run(),
+        Some(Subcommand::Genesis(ref cmd)) => {
+            // There is no runner to set the logger up
+            sc_cli::LoggerBuilder::new("").init()?;
+            cmd.run()
+        }
         None => run_node(cli),
     }
 }
@@ -0,0 +1,163 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use madara_runtime::StarknetHasher;
+use mc_sync::archive::{ArchiveFormat, ArchiveHeader, ArchiveReader, ArchiveWriter, ARCHIVE_VERSION};
+use pallet_starknet_runtime_api::StarknetRuntimeApi;
+use sc_cli::{CliConfiguration, DatabaseParams, Error, PruningParams, Result, SharedParams};
+use sc_client_api::UsageProvider;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+
+use crate::service::{FullBackend, FullClient};
+use crate::starknet::MadaraBackend;
+
+/// Export Starknet blocks into a portable archive.
+#[derive(Debug, clap::Args)]
+pub struct ExportStarknetCmd {
+    /// Path of the archive.
+    #[arg(value_name = "PATH")]
+    pub output: PathBuf,
+
+    /// Number of the first block to export.
+    #[arg(long, value_name = "NUMBER", default_value_t = 1)]
+    pub from: u64,
+
+    /// Number of the last block to export, the best block when not set.
+    #[arg(long, value_name = "NUMBER")]
+    pub to: Option<u64>,
+
+    /// Format of the archive.
+    #[arg(long, value_enum, default_value_t = ArchiveFormat::JsonLines)]
+    pub format: ArchiveFormat,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub pruning_params: PruningParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub database_params: DatabaseParams,
+}
+
+impl ExportStarknetCmd {
+    pub fn run(
+        &self,
+        client: Arc<FullClient>,
+        backend: Arc<FullBackend>,
+        madara_backend: Arc<MadaraBackend>,
+    ) -> Result<()> {
+        let chain = client.usage_info().chain;
+        let best_number = u64::from(chain.best_number);
+        let to = self.to.unwrap_or(best_number);
+        if self.from == 0 || self.from > to || to > best_number {
+            return Err(Error::Input(format!(
+                "Invalid block range {}..={to}, the blocks from 1 to {best_number} can be exported",
+                self.from
+            )));
+        }
+        log::info!("Exporting blocks {}..={to} to '{}'", self.from, self.output.display());
+
+        let chain_id = client.runtime_api().chain_id(chain.best_hash).map_err(|e| Error::Application(Box::new(e)))?;
+        let header =
+            ArchiveHeader { version: ARCHIVE_VERSION, chain_id: chain_id.0, first_block: self.from, last_block: to };
+        let output = BufWriter::new(File::create(&self.output)?);
+        let mut writer =
+            ArchiveWriter::new(output, self.format, &header).map_err(|e| Error::Application(Box::new(e)))?;
+
+        for block_number in self.from..=to {
+            // The range is bounded by the best block, whose number fits
+            let block_hash = client
+                .hash(block_number as u32)?
+                .ok_or_else(|| Error::Input(format!("Block {block_number} not found")))?;
+            let block = mc_sync::export_block::<_, _, _, StarknetHasher>(
+                client.as_ref(),
+                backend.as_ref(),
+                madara_backend.as_ref(),
+                block_hash,
+            )
+            .map_err(|e| Error::Application(Box::new(e)))?;
+            writer.write_block(&block).map_err(|e| Error::Application(Box::new(e)))?;
+        }
+        writer.finish().map_err(|e| Error::Application(Box::new(e)))?;
+
+        Ok(())
+    }
+}
+
+impl CliConfiguration for ExportStarknetCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn pruning_params(&self) -> Option<&PruningParams> {
+        Some(&self.pruning_params)
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+}
+
+/// Import the Starknet blocks of a portable archive, re-executing them.
+///
+/// The blocks following the best block are imported, each of them has to hash to the hash it
+/// is archived with.
+#[derive(Debug, clap::Args)]
+pub struct ImportStarknetCmd {
+    /// Path of the archive, in any of the formats of `export-starknet`.
+    #[arg(value_name = "PATH")]
+    pub input: PathBuf,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub pruning_params: PruningParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub database_params: DatabaseParams,
+}
+
+impl ImportStarknetCmd {
+    pub async fn run(&self, client: Arc<FullClient>, madara_backend: Arc<MadaraBackend>) -> Result<()> {
+        let reader = ArchiveReader::new(File::open(&self.input)?).map_err(|e| Error::Input(e.to_string()))?;
+        let header = reader.header();
+        log::info!("Importing blocks {}..={} from '{}'", header.first_block, header.last_block, self.input.display());
+
+        let imported = mc_sync::import_archive::<_, _, FullBackend, _, StarknetHasher>(
+            reader,
+            client.clone(),
+            client,
+            madara_backend,
+        )
+        .await
+        .map_err(|e| Error::Application(Box::new(e)))?;
+        log::info!("Imported {imported} blocks");
+
+        Ok(())
+    }
+}
+
+impl CliConfiguration for ImportStarknetCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn pruning_params(&self) -> Option<&PruningParams> {
+        Some(&self.pruning_params)
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+}
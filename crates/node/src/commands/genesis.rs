@@ -0,0 +1,409 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use mc_rpc_core::utils::flattened_sierra_to_sierra_contract_class;
+use mp_genesis_config::{ContractClass, GenesisData, HexFelt, PredeployedAccount};
+use sc_cli::{Error, Result};
+use serde::Deserialize;
+use starknet_core::types::contract::legacy::LegacyContractClass;
+use starknet_core::types::contract::{CompiledClass, SierraClass};
+use starknet_core::types::FieldElement;
+use starknet_core::utils::{cairo_short_string_to_felt, get_storage_var_address};
+
+use crate::chain_spec::{GENESIS_ASSETS_DIR, GENESIS_ASSETS_FILE};
+
+/// Build a genesis file from a manifest of its classes, accounts and fee token.
+#[derive(Debug, clap::Args)]
+pub struct GenesisCmd {
+    /// Path of the genesis manifest. The paths of the artifacts it lists are relative to its
+    /// directory.
+    #[arg(value_name = "MANIFEST")]
+    pub manifest: PathBuf,
+
+    /// Path of the genesis file, `genesis-assets/genesis.json` next to the manifest by default.
+    /// The paths of the classes it lists are relative to the parent of its directory, the config
+    /// directory of a chain for a genesis at `<config dir>/genesis-assets/genesis.json`.
+    #[arg(long, short = 'o', value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+/// The content of a genesis.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GenesisManifest {
+    chain_id: String,
+    classes: Vec<ClassManifest>,
+    fee_token: FeeTokenManifest,
+    #[serde(default)]
+    accounts: Vec<AccountManifest>,
+    #[serde(default)]
+    contracts: Vec<ContractManifest>,
+}
+
+/// A class to declare, referred to by its name in the rest of the manifest.
+#[derive(Debug, Deserialize)]
+struct ClassManifest {
+    name: String,
+    #[serde(flatten)]
+    artifacts: ClassArtifacts,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ClassArtifacts {
+    /// The Sierra and CASM artifacts of a Cairo 1 class.
+    Cairo1 { sierra_path: String, casm_path: String },
+    /// A compiled Cairo 0 class.
+    Cairo0 { path: String },
+}
+
+/// The ERC20 contract the fees are paid with.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FeeTokenManifest {
+    address: HexFelt,
+    class: String,
+    name: String,
+    symbol: String,
+    decimals: u8,
+}
+
+/// The way an account contract stores its public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AccountKind {
+    /// Accounts not validating the transactions, without key.
+    NoValidate,
+    /// Argent accounts, storing the key in `_signer`.
+    Argent,
+    /// OpenZeppelin accounts, storing the key in `Account_public_key`.
+    OpenZeppelin,
+}
+
+impl AccountKind {
+    fn public_key_storage_var(&self) -> Option<&'static str> {
+        match self {
+            Self::NoValidate => None,
+            Self::Argent => Some("_signer"),
+            Self::OpenZeppelin => Some("Account_public_key"),
+        }
+    }
+}
+
+/// An account to predeploy.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AccountManifest {
+    name: String,
+    /// What the account is meant for, e.g. `deployer` or `faucet`.
+    role: Option<String>,
+    address: HexFelt,
+    class: String,
+    kind: AccountKind,
+    /// Derived from the private key when not set.
+    public_key: Option<HexFelt>,
+    private_key: Option<HexFelt>,
+    /// Fee token balance.
+    balance: Option<HexFelt>,
+}
+
+/// Any other contract to predeploy.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ContractManifest {
+    address: HexFelt,
+    class: String,
+    #[serde(default)]
+    storage: Vec<(HexFelt, HexFelt)>,
+}
+
+impl GenesisCmd {
+    pub fn run(&self) -> Result<()> {
+        let manifest_dir = self.manifest.parent().unwrap_or(Path::new("")).to_path_buf();
+        let manifest: GenesisManifest = serde_json::from_slice(&std::fs::read(&self.manifest)?)
+            .map_err(|e| Error::Input(format!("Invalid genesis manifest: {e}")))?;
+
+        let output =
+            self.output.clone().unwrap_or_else(|| manifest_dir.join(GENESIS_ASSETS_DIR).join(GENESIS_ASSETS_FILE));
+        let output_dir = output.parent().unwrap_or(Path::new("")).to_path_buf();
+        std::fs::create_dir_all(&output_dir)?;
+        // The genesis loader reads the classes relative to the parent of the genesis directory
+        let output_dir = output_dir.canonicalize()?;
+        let classes_dir = output_dir.parent().unwrap_or(&output_dir);
+
+        let genesis = build_genesis(manifest, &manifest_dir, classes_dir)?;
+
+        let content = serde_json::to_vec_pretty(&genesis).map_err(|e| Error::Application(Box::new(e)))?;
+        std::fs::write(&output, content)?;
+        log::info!("Genesis written to '{}'", output.display());
+
+        Ok(())
+    }
+}
+
+/// Builds the genesis of a manifest, whose class paths are relative to `classes_dir`.
+fn build_genesis(manifest: GenesisManifest, manifest_dir: &Path, classes_dir: &Path) -> Result<GenesisData> {
+    let chain_id = manifest.chain_id;
+    cairo_short_string_to_felt(&chain_id).map_err(|e| Error::Input(format!("Invalid chain id '{chain_id}': {e}")))?;
+
+    let mut class_hashes = HashMap::new();
+    let mut contract_classes = Vec::new();
+    let mut sierra_class_hash_to_casm_class_hash = Vec::new();
+    let mut sierra_classes = Vec::new();
+    for class in manifest.classes {
+        let class_hash = match class.artifacts {
+            ClassArtifacts::Cairo0 { path } => {
+                let legacy_class: LegacyContractClass = read_artifact(manifest_dir, &path)?;
+                let class_hash = legacy_class
+                    .class_hash()
+                    .map_err(|e| Error::Input(format!("Failed to compute the hash of class '{}': {e}", class.name)))?;
+                let path = class_path(manifest_dir, &path, classes_dir)?;
+                contract_classes.push((class_hash.into(), ContractClass::Path { path, version: 0 }));
+                class_hash
+            }
+            ClassArtifacts::Cairo1 { sierra_path, casm_path } => {
+                let sierra_class: SierraClass = read_artifact(manifest_dir, &sierra_path)?;
+                let compiled_class: CompiledClass = read_artifact(manifest_dir, &casm_path)?;
+                let class_hash = sierra_class
+                    .class_hash()
+                    .map_err(|e| Error::Input(format!("Failed to compute the hash of class '{}': {e}", class.name)))?;
+                let compiled_class_hash = compiled_class.class_hash().map_err(|e| {
+                    Error::Input(format!("Failed to compute the compiled class hash of class '{}': {e}", class.name))
+                })?;
+                let flattened_class = sierra_class
+                    .flatten()
+                    .map_err(|e| Error::Input(format!("Failed to flatten the Sierra class '{}': {e}", class.name)))?;
+
+                let path = class_path(manifest_dir, &casm_path, classes_dir)?;
+                contract_classes.push((class_hash.into(), ContractClass::Path { path, version: 1 }));
+                sierra_class_hash_to_casm_class_hash.push((class_hash.into(), compiled_class_hash.into()));
+                sierra_classes
+                    .push((class_hash.into(), flattened_sierra_to_sierra_contract_class(Arc::new(flattened_class))));
+                class_hash
+            }
+        };
+        if class_hashes.insert(class.name.clone(), class_hash).is_some() {
+            return Err(Error::Input(format!("Class '{}' is declared twice", class.name)));
+        }
+    }
+    let class_hash =
+        |name: &str| class_hashes.get(name).copied().ok_or_else(|| Error::Input(format!("Unknown class '{name}'")));
+
+    let fee_token = manifest.fee_token;
+    let fee_token_address = fee_token.address.0;
+    let mut deployed_addresses = HashSet::new();
+    let mut deploy = |address: FieldElement, class: &str| -> Result<(HexFelt, HexFelt)> {
+        if !deployed_addresses.insert(address) {
+            return Err(Error::Input(format!("Several contracts are deployed at {address:#x}")));
+        }
+        Ok((address.into(), class_hash(class)?.into()))
+    };
+
+    let mut contracts = Vec::new();
+    let mut storage = Vec::new();
+    let mut predeployed_accounts = Vec::new();
+    for account in manifest.accounts {
+        contracts.push(deploy(account.address.0, &account.class)?);
+
+        let public_key = match (account.public_key, account.private_key) {
+            (public_key, Some(private_key)) => {
+                let derived_public_key = starknet_crypto::get_public_key(&private_key.0);
+                if public_key.is_some_and(|public_key| public_key.0 != derived_public_key) {
+                    return Err(Error::Input(format!(
+                        "The public key of account '{}' does not match its private key",
+                        account.name
+                    )));
+                }
+                Some(derived_public_key)
+            }
+            (public_key, None) => public_key.map(|public_key| public_key.0),
+        };
+        if let Some(storage_var) = account.kind.public_key_storage_var() {
+            let public_key = public_key
+                .ok_or_else(|| Error::Input(format!("Account '{}' requires a public or private key", account.name)))?;
+            storage.push(((account.address, storage_var_address(storage_var, &[])?.into()), public_key.into()));
+        }
+
+        if let Some(balance) = account.balance {
+            let low_key = storage_var_address("ERC20_balances", &[account.address.0])?;
+            let high_key = low_key + FieldElement::ONE;
+            let balance = balance.0.to_bytes_be();
+            let (high, low) = balance.split_at(16);
+            let to_felt = |bytes: &[u8]| FieldElement::from(u128::from_be_bytes(bytes.try_into().expect("16 bytes")));
+            storage.push(((fee_token.address, low_key.into()), to_felt(low).into()));
+            storage.push(((fee_token.address, high_key.into()), to_felt(high).into()));
+        }
+
+        predeployed_accounts.push(PredeployedAccount {
+            contract_address: account.address,
+            class_hash: class_hash(&account.class)?.into(),
+            name: account.name,
+            role: account.role,
+            private_key: account.private_key.map(|private_key| private_key.0.to_bytes_be().to_vec()),
+            public_key: public_key.unwrap_or(FieldElement::ZERO).into(),
+        });
+    }
+
+    contracts.push(deploy(fee_token_address, &fee_token.class)?);
+    for (storage_var, value) in [
+        ("ERC20_name", short_string(&fee_token.name)?),
+        ("ERC20_symbol", short_string(&fee_token.symbol)?),
+        ("ERC20_decimals", FieldElement::from(fee_token.decimals)),
+    ] {
+        storage.push(((fee_token.address, storage_var_address(storage_var, &[])?.into()), value.into()));
+    }
+
+    for contract in manifest.contracts {
+        contracts.push(deploy(contract.address.0, &contract.class)?);
+        storage.extend(contract.storage.into_iter().map(|(key, value)| ((contract.address, key), value)));
+    }
+
+    Ok(GenesisData {
+        contract_classes,
+        sierra_class_hash_to_casm_class_hash,
+        contracts,
+        predeployed_accounts,
+        storage,
+        nonces: Vec::new(),
+        sierra_classes,
+        fee_token_address: fee_token.address,
+        chain_id,
+        chain_parameters: Default::default(),
+        admin: None,
+        sequencers: Vec::new(),
+        l1_messages: Vec::new(),
+    })
+}
+
+fn read_artifact<T: serde::de::DeserializeOwned>(manifest_dir: &Path, path: &str) -> Result<T> {
+    let content = std::fs::read(manifest_dir.join(path))
+        .map_err(|e| Error::Input(format!("Failed to read the artifact '{path}': {e}")))?;
+    serde_json::from_slice(&content).map_err(|e| Error::Input(format!("Invalid artifact '{path}': {e}")))
+}
+
+/// The path of an artifact of the manifest, relative to `classes_dir`.
+fn class_path(manifest_dir: &Path, path: &str, classes_dir: &Path) -> Result<String> {
+    let artifact = manifest_dir
+        .join(path)
+        .canonicalize()
+        .map_err(|e| Error::Input(format!("Failed to read the artifact '{path}': {e}")))?;
+    let common = artifact.components().zip(classes_dir.components()).take_while(|(a, b)| a == b).count();
+    let relative_path: PathBuf = classes_dir
+        .components()
+        .skip(common)
+        .map(|_| Component::ParentDir)
+        .chain(artifact.components().skip(common))
+        .collect();
+    relative_path
+        .to_str()
+        .map(ToString::to_string)
+        .ok_or_else(|| Error::Input(format!("The path of the artifact '{path}' is not valid UTF-8")))
+}
+
+fn storage_var_address(name: &str, keys: &[FieldElement]) -> Result<FieldElement> {
+    get_storage_var_address(name, keys).map_err(|e| Error::Input(format!("Invalid storage variable '{name}': {e}")))
+}
+
+fn short_string(value: &str) -> Result<FieldElement> {
+    cairo_short_string_to_felt(value).map_err(|e| Error::Input(format!("Invalid short string '{value}': {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use madara_runtime::pallet_starknet::GenesisConfig;
+    use madara_runtime::Runtime;
+    use mp_genesis_config::GenesisLoader;
+
+    use super::*;
+
+    fn write_manifest(dir: &Path, classes: serde_json::Value) -> PathBuf {
+        let manifest = serde_json::json!({
+            "chain_id": "MADARA",
+            "classes": classes,
+            "fee_token": {
+                "address": "0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7",
+                "class": "ERC20",
+                "name": "Ether",
+                "symbol": "ETH",
+                "decimals": 18
+            },
+            "accounts": [{
+                "name": "Cairo 1 Account",
+                "address": "0x2",
+                "class": "OpenZeppelinAccountCairoOne",
+                "kind": "no_validate",
+                "balance": "0xffffffffffffffffffffffffffffffff"
+            }]
+        });
+        let manifest_path = dir.join("manifest.json");
+        std::fs::write(&manifest_path, manifest.to_string()).unwrap();
+        manifest_path
+    }
+
+    fn config_classes() -> serde_json::Value {
+        let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../configs/genesis-assets");
+        let asset = |name: &str| assets.join(name).to_str().unwrap().to_string();
+        serde_json::json!([
+            { "name": "ERC20", "path": asset("ERC20.json") },
+            {
+                "name": "OpenZeppelinAccountCairoOne",
+                "sierra_path": asset("OpenZeppelinAccountCairoOne.sierra.json"),
+                "casm_path": asset("OpenZeppelinAccountCairoOne.casm.json")
+            }
+        ])
+    }
+
+    /// Loads a genesis file the way the chain spec does, from the config directory of its chain.
+    fn load_genesis(output: &Path) -> GenesisConfig<Runtime> {
+        let genesis_data: GenesisData = serde_json::from_slice(&std::fs::read(output).unwrap()).unwrap();
+        let config_dir = output.parent().unwrap().parent().unwrap().to_path_buf();
+        GenesisLoader::new(config_dir, genesis_data).into()
+    }
+
+    #[test]
+    fn genesis_written_next_to_the_manifest_is_loadable() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = write_manifest(dir.path(), config_classes());
+
+        GenesisCmd { manifest, output: None }.run().unwrap();
+
+        let genesis = load_genesis(&dir.path().join(GENESIS_ASSETS_DIR).join(GENESIS_ASSETS_FILE));
+        assert_eq!(genesis.contract_classes.len(), 2);
+        assert_eq!(genesis.sierra_to_casm_class_hash.len(), 1);
+        assert_eq!(genesis.contracts.len(), 2);
+        // The balance of the account, as its low and high parts, and the metadata of the fee token
+        assert_eq!(genesis.storage.len(), 5);
+    }
+
+    #[test]
+    fn genesis_written_to_another_directory_is_loadable() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = write_manifest(dir.path(), config_classes());
+        let output = dir.path().join("chains/madara").join(GENESIS_ASSETS_DIR).join(GENESIS_ASSETS_FILE);
+
+        GenesisCmd { manifest, output: Some(output.clone()) }.run().unwrap();
+
+        let genesis_data: GenesisData = serde_json::from_slice(&std::fs::read(&output).unwrap()).unwrap();
+        for (_, class) in genesis_data.contract_classes {
+            match class {
+                ContractClass::Path { path, .. } => assert!(Path::new(&path).is_relative()),
+                ContractClass::Class(_) => panic!("Expected the path of the class"),
+            }
+        }
+        assert_eq!(load_genesis(&output).contract_classes.len(), 2);
+    }
+
+    #[test]
+    fn classes_declared_twice_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut classes = config_classes();
+        let erc20 = classes[0].clone();
+        classes.as_array_mut().unwrap().push(erc20);
+        let manifest = write_manifest(dir.path(), classes);
+
+        let error = GenesisCmd { manifest, output: None }.run().unwrap_err();
+
+        assert!(error.to_string().contains("Class 'ERC20' is declared twice"));
+    }
+}
@@ -1,5 +1,11 @@
+mod archive;
+mod genesis;
 mod run;
 mod setup;
+mod state;
 
+pub use archive::*;
+pub use genesis::*;
 pub use run::*;
 pub use setup::*;
+pub use state::*;
@@ -10,12 +10,16 @@ use mc_data_availability::celestia::{config::CelestiaConfig, CelestiaClient};
 use mc_data_availability::ethereum::config::EthereumDaConfig;
 use mc_data_availability::ethereum::EthereumDaClient;
 use mc_data_availability::{DaClient, DaLayer};
+use mc_fork::ForkConfig;
 use mc_settlement::SettlementLayer;
+use mc_sync::{ImportMode, SourceKind, SyncConfig};
 use sc_cli::{Result, RpcMethods, RunCmd, SubstrateCli};
 use sc_service::BasePath;
 use serde::{Deserialize, Serialize};
+use url::Url;
 
 use crate::cli::Cli;
+use crate::constants::DEV_CHAIN_ID;
 use crate::service;
 
 /// Available Sealing methods.
@@ -29,16 +33,11 @@ pub enum Sealing {
     Instant,
     /// Seal when transaction is executed with finalization.
     InstantFinality,
-}
-
-impl From<Sealing> for SealingMode {
-    fn from(value: Sealing) -> Self {
-        match value {
-            Sealing::Manual => SealingMode::Manual,
-            Sealing::Instant => SealingMode::Instant { finalize: false },
-            Sealing::InstantFinality => SealingMode::Instant { finalize: true },
-        }
-    }
+    /// Seal with finalization once `--block-time` has elapsed or `--block-max-transactions` are
+    /// ready, whichever comes first. Blocks hold at most `--block-max-transactions`, and stop
+    /// taking transactions once `--block-max-steps` or `--block-max-state-diff-size` is reached.
+    /// Empty blocks are not produced.
+    Batch,
 }
 
 #[derive(Clone, Debug, clap::Args)]
@@ -50,6 +49,25 @@ pub struct ExtendedRunCmd {
     #[clap(long, value_enum, ignore_case = true)]
     pub sealing: Option<Sealing>,
 
+    /// Maximum time, in milliseconds, a block stays open when using `--sealing=batch`.
+    #[clap(long, value_name = "MILLISECONDS", default_value_t = madara_runtime::MILLISECS_PER_BLOCK)]
+    pub block_time: u64,
+
+    /// Maximum number of transactions of a block when using `--sealing=batch`. A block is closed
+    /// as soon as that many transactions are ready in the pool.
+    #[clap(long, value_name = "COUNT", default_value_t = 1000)]
+    pub block_max_transactions: u32,
+
+    /// Cairo steps budget of a block when using `--sealing=batch`, lowering the limit of the
+    /// runtime.
+    #[clap(long, value_name = "STEPS")]
+    pub block_max_steps: Option<u64>,
+
+    /// Budget, in felts published to L1, of the state diff of a block when using
+    /// `--sealing=batch`, lowering the limit of the runtime.
+    #[clap(long, value_name = "FELTS")]
+    pub block_max_state_diff_size: Option<u64>,
+
     /// Choose a supported DA Layer
     #[clap(long, ignore_case = true)]
     pub da_layer: Option<DaLayer>,
@@ -81,9 +99,98 @@ pub struct ExtendedRunCmd {
     /// increases the memory footprint of the node.
     #[clap(long)]
     pub cache: bool,
+
+    /// When enabled, the state diff of every block is archived in the database.
+    ///
+    /// This allows `starknet_getStorageAt`, `starknet_getNonce` and `starknet_getClassHashAt` to
+    /// be answered for blocks whose state has been pruned.
+    #[clap(long)]
+    pub state_archive: bool,
+
+    /// Port of the server mounting every supported Starknet RPC spec version on its own path,
+    /// e.g. `/rpc/v0_5` and `/rpc/v0_6`.
+    ///
+    /// The server shares the interface, the connections and payload limits and the CORS policy of
+    /// the substrate RPC server. It is not started if no port is given.
+    #[clap(long, value_name = "PORT")]
+    pub versioned_rpc_port: Option<u16>,
+
+    /// Port of the Starknet feeder gateway API, serving `/feeder_gateway/get_block` and the like
+    /// for the tools and full nodes reading it.
+    ///
+    /// The server is not started if no port is given.
+    #[clap(long, value_name = "PORT")]
+    pub feeder_gateway_port: Option<u16>,
+
+    /// Starknet JSON-RPC endpoint of a chain to fork.
+    ///
+    /// The state missing locally is read from the forked chain at `--fork-block`, and cached
+    /// locally. The new blocks are built on top of it. As the blocks depend on the remote state,
+    /// a forked chain is meant to be run by a single node.
+    #[clap(long, value_name = "URL")]
+    pub fork_url: Option<Url>,
+
+    /// Block of the forked chain the state is read at, the latest one by default.
+    #[clap(long, value_name = "BLOCK NUMBER", requires = "fork_url")]
+    pub fork_block: Option<u64>,
+
+    /// Url of a sequencer to sync the chain from, as a full node.
+    ///
+    /// No block is authored, the blocks of the sequencer are imported instead, once their hash
+    /// is checked. The node must run the dev chain, with the genesis of the sequencer.
+    #[clap(long, value_name = "URL", conflicts_with_all = ["sealing", "fork_url"])]
+    pub sync_url: Option<Url>,
+
+    /// API the blocks are read from at `--sync-url`.
+    #[clap(long, value_enum, default_value_t = SourceKind::FeederGateway, requires = "sync_url")]
+    pub sync_source: SourceKind,
+
+    /// How the blocks of the sequencer are imported: re-executing their transactions, or writing
+    /// their state diffs without checking them.
+    #[clap(long, value_enum, default_value_t = ImportMode::Execute, requires = "sync_url")]
+    pub sync_mode: ImportMode,
+
+    /// Accept the unverified state of `--sync-mode=trust-state-diff`.
+    ///
+    /// The state diffs of the sequencer are written as they are announced: nothing checks them,
+    /// the state root of the blocks not being computed.
+    #[clap(long, requires = "sync_url", required_if_eq("sync_mode", "trust-state-diff"))]
+    pub sync_unverified_state: bool,
 }
 
 impl ExtendedRunCmd {
+    /// The sealing mode selected by the command line
+    ///
+    /// Will be `SealingMode::Default` if no `--sealing` is provided, and `SealingMode::Sync` when
+    /// syncing from another sequencer
+    pub fn sealing_mode(&self) -> SealingMode {
+        if self.sync_url.is_some() {
+            return SealingMode::Sync;
+        }
+        match self.sealing {
+            None => SealingMode::Default,
+            Some(Sealing::Manual) => SealingMode::Manual,
+            Some(Sealing::Instant) => SealingMode::Instant { finalize: false },
+            Some(Sealing::InstantFinality) => SealingMode::Instant { finalize: true },
+            Some(Sealing::Batch) => SealingMode::Batch {
+                block_time: self.block_time,
+                max_transactions: self.block_max_transactions,
+                max_steps: self.block_max_steps,
+                max_state_diff_size: self.block_max_state_diff_size,
+            },
+        }
+    }
+
+    /// The forked chain selected by the command line, if any
+    pub fn fork_config(&self) -> Option<ForkConfig> {
+        self.fork_url.clone().map(|url| ForkConfig { url, block_number: self.fork_block })
+    }
+
+    /// The sequencer to sync from selected by the command line, if any
+    pub fn sync_config(&self) -> Option<SyncConfig> {
+        self.sync_url.clone().map(|url| SyncConfig { url, source: self.sync_source, mode: self.sync_mode })
+    }
+
     /// The substrate base directory on your machine
     ///
     /// Will be different depending on your OS
@@ -145,6 +252,10 @@ pub fn run_node(mut cli: Cli) -> Result<()> {
     if cli.run.base.shared_params.dev {
         override_dev_environment(&mut cli.run);
     }
+    // Only the dev chain spec passes the sync sealing to the runtime, allowing the imports
+    if cli.run.sync_url.is_some() && cli.run.chain_id() != DEV_CHAIN_ID {
+        return Err(sc_cli::Error::Input("--sync-url requires the dev chain".to_string()));
+    }
     let runner = cli.create_runner(&cli.run.base)?;
 
     let chain_config_dir = cli.run.chain_config_dir()?;
@@ -201,9 +312,26 @@ pub fn run_node(mut cli: Cli) -> Result<()> {
     };
 
     runner.run_node_until_exit(|config| async move {
-        let sealing = cli.run.sealing.map(Into::into).unwrap_or_default();
+        let sealing = cli.run.sealing_mode();
         let cache = cli.run.cache;
-        service::new_full(config, sealing, da_client, cache, settlement_config).map_err(sc_cli::Error::Service)
+        let state_archive = cli.run.state_archive;
+        let versioned_rpc_port = cli.run.versioned_rpc_port;
+        let feeder_gateway_port = cli.run.feeder_gateway_port;
+        let fork_config = cli.run.fork_config();
+        let sync_config = cli.run.sync_config();
+        service::new_full(
+            config,
+            sealing,
+            da_client,
+            cache,
+            state_archive,
+            versioned_rpc_port,
+            feeder_gateway_port,
+            settlement_config,
+            fork_config,
+            sync_config,
+        )
+        .map_err(sc_cli::Error::Service)
     })
 }
 
@@ -0,0 +1,343 @@
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use madara_runtime::Block;
+use mp_fee::ResourcePrice;
+use mp_felt::Felt252Wrapper;
+use mp_genesis_config::{ChainParameters, ContractClass, GenesisData, HexBytes, HexFelt, PredeployedAccount};
+use mp_storage::{
+    PALLET_STARKNET, SN_COMPILED_CLASS_HASH_PREFIX, SN_CONTRACT_CLASS_HASH_PREFIX, SN_CONTRACT_CLASS_PREFIX,
+    SN_NONCE_PREFIX, SN_STORAGE_PREFIX,
+};
+use pallet_starknet_runtime_api::StarknetRuntimeApi;
+use parity_scale_codec::Decode;
+use sc_cli::{
+    BlockNumberOrHash, CliConfiguration, DatabaseParams, Error, PruningParams, Result, SharedParams, SubstrateCli,
+};
+use sc_client_api::{StorageProvider, UsageProvider};
+use sc_service::BasePath;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::hashing::twox_128;
+use sp_core::storage::StorageKey;
+use starknet_api::api_core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_core::utils::parse_cairo_short_string;
+
+use crate::chain_spec::{GENESIS_ASSETS_DIR, GENESIS_ASSETS_FILE};
+use crate::cli::Cli;
+use crate::constants::DEV_CHAIN_ID;
+use crate::service::FullClient;
+use crate::starknet::MadaraBackend;
+
+/// The gzip magic number, starting the state dumps in the binary format.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StateDumpFormat {
+    /// A genesis file.
+    Json,
+    /// A gzip-compressed genesis file.
+    Binary,
+}
+
+/// Dump the Starknet state of a block into a genesis file.
+#[derive(Debug, clap::Args)]
+pub struct DumpStateCmd {
+    /// Path of the state dump.
+    #[arg(value_name = "PATH")]
+    pub output: PathBuf,
+
+    /// Block hash or number to dump the state of, the best block when not set.
+    #[arg(long, value_name = "HASH or NUMBER")]
+    pub at: Option<BlockNumberOrHash>,
+
+    /// Format of the state dump.
+    #[arg(long, value_enum, default_value_t = StateDumpFormat::Json)]
+    pub format: StateDumpFormat,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub pruning_params: PruningParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub database_params: DatabaseParams,
+}
+
+impl DumpStateCmd {
+    /// Dumps the state, with the predeployed accounts of the `genesis` of the chain.
+    pub fn run(
+        &self,
+        client: Arc<FullClient>,
+        madara_backend: Arc<MadaraBackend>,
+        genesis: Option<GenesisData>,
+    ) -> Result<()> {
+        let block_hash = match &self.at {
+            Some(block) => client.expect_block_hash_from_id(&block.parse()?)?,
+            None => client.usage_info().chain.best_hash,
+        };
+        log::info!("Dumping the state of block {block_hash:?} to '{}'", self.output.display());
+
+        let contract_classes = storage_map::<ClassHash, blockifier::execution::contract_class::ContractClass>(
+            &client,
+            block_hash,
+            &SN_CONTRACT_CLASS_PREFIX,
+            0,
+        )?
+        .into_iter()
+        .map(|(class_hash, class)| (hex_felt(class_hash.0), ContractClass::Class(class)))
+        .collect();
+        let compiled_class_hashes =
+            storage_map::<ClassHash, CompiledClassHash>(&client, block_hash, &SN_COMPILED_CLASS_HASH_PREFIX, 0)?;
+        let contracts =
+            storage_map::<ContractAddress, ClassHash>(&client, block_hash, &SN_CONTRACT_CLASS_HASH_PREFIX, 0)?
+                .into_iter()
+                .map(|(address, class_hash)| (hex_felt(*address.0.key()), hex_felt(class_hash.0)))
+                .collect();
+        let storage = storage_map::<(ContractAddress, starknet_api::state::StorageKey), StarkFelt>(
+            &client,
+            block_hash,
+            &SN_STORAGE_PREFIX,
+            0,
+        )?
+        .into_iter()
+        .map(|((address, key), value)| ((hex_felt(*address.0.key()), hex_felt(*key.0.key())), hex_felt(value)))
+        .collect();
+        let nonces = storage_map::<ContractAddress, Nonce>(&client, block_hash, &SN_NONCE_PREFIX, 0)?
+            .into_iter()
+            .map(|(address, nonce)| (hex_felt(*address.0.key()), hex_felt(nonce.0)))
+            .collect();
+
+        // The Sierra classes are not part of the runtime state, but of the Madara database
+        let mut sierra_classes = Vec::new();
+        for (class_hash, _) in compiled_class_hashes.iter() {
+            let sierra_class = madara_backend
+                .sierra_classes()
+                .get_sierra_class(*class_hash)
+                .map_err(|e| Error::Application(Box::new(e)))?;
+            match sierra_class {
+                Some(sierra_class) => sierra_classes.push((hex_felt(class_hash.0), sierra_class)),
+                None => log::warn!("The Sierra class {} is missing from the database", class_hash.0),
+            }
+        }
+
+        let runtime_api = client.runtime_api();
+        let fee_token_address =
+            runtime_api.fee_token_address(block_hash).map_err(|e| Error::Application(Box::new(e)))?;
+        let chain_id = runtime_api.chain_id(block_hash).map_err(|e| Error::Application(Box::new(e)))?;
+        let chain_id = parse_cairo_short_string(&chain_id.0).map_err(|e| Error::Application(Box::new(e)))?;
+
+        // The parameters left unset in the storage keep using the value of the runtime config
+        let chain_parameters = ChainParameters {
+            strk_fee_token_address: storage_value::<ContractAddress>(&client, block_hash, b"StrkFeeTokenAddress")?
+                .map(|address| hex_felt(*address.0.key())),
+            invoke_tx_max_n_steps: storage_value(&client, block_hash, b"InvokeTxMaxNSteps")?,
+            validate_max_n_steps: storage_value(&client, block_hash, b"ValidateMaxNSteps")?,
+            l1_gas_price: storage_value::<ResourcePrice>(&client, block_hash, b"L1GasPrice")?,
+            protocol_version: storage_value(&client, block_hash, b"ProtocolVersion")?,
+            disable_transaction_fee: storage_value(&client, block_hash, b"DisableTransactionFee")?,
+        };
+        // The runtime types of the accounts are kept SCALE encoded
+        let admin = raw_storage_value(&client, block_hash, b"Admin")?.map(HexBytes);
+        let sequencers =
+            raw_storage_map(&client, block_hash, &starknet_storage_key(b"Sequencers"), BLAKE2_128_CONCAT_LEN)?
+                .into_iter()
+                .map(|(authority, address)| {
+                    let address = ContractAddress::decode(&mut &address[..])
+                        .map_err(|e| Error::Input(format!("Invalid sequencer address: {e}")))?;
+                    Ok((HexBytes(authority), hex_felt(*address.0.key())))
+                })
+                .collect::<Result<Vec<_>>>()?;
+        let l1_messages =
+            storage_map::<Nonce, ()>(&client, block_hash, &starknet_storage_key(b"L1Messages"), TWOX_64_CONCAT_LEN)?
+                .into_iter()
+                .map(|(nonce, ())| hex_felt(nonce.0))
+                .collect();
+
+        let predeployed_accounts: Vec<PredeployedAccount> =
+            genesis.map(|genesis| genesis.predeployed_accounts).unwrap_or_default();
+
+        let state = GenesisData {
+            contract_classes,
+            sierra_class_hash_to_casm_class_hash: compiled_class_hashes
+                .into_iter()
+                .map(|(class_hash, compiled_class_hash)| (hex_felt(class_hash.0), hex_felt(compiled_class_hash.0)))
+                .collect(),
+            contracts,
+            predeployed_accounts,
+            storage,
+            nonces,
+            sierra_classes,
+            fee_token_address: hex_felt(*fee_token_address.0.key()),
+            chain_id,
+            chain_parameters,
+            admin,
+            sequencers,
+            l1_messages,
+        };
+
+        let content = serde_json::to_vec(&state).map_err(|e| Error::Application(Box::new(e)))?;
+        let content = match self.format {
+            StateDumpFormat::Json => content,
+            StateDumpFormat::Binary => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(&content)?;
+                encoder.finish()?
+            }
+        };
+        std::fs::write(&self.output, content)?;
+
+        Ok(())
+    }
+}
+
+impl CliConfiguration for DumpStateCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn pruning_params(&self) -> Option<&PruningParams> {
+        Some(&self.pruning_params)
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+}
+
+/// Load a state dump as the genesis of a chain.
+///
+/// The chain has to be purged for the new genesis to be used.
+#[derive(Debug, clap::Args)]
+pub struct LoadStateCmd {
+    /// Path of the state dump, in any of the formats of `dump-state`.
+    #[arg(value_name = "PATH")]
+    pub input: PathBuf,
+
+    /// Id of the chain to load the state dump as the genesis of.
+    #[arg(long, value_name = "CHAIN_SPEC", default_value = DEV_CHAIN_ID)]
+    pub chain: String,
+
+    /// Specify custom base path.
+    #[arg(long, short = 'd', value_name = "PATH")]
+    pub base_path: Option<PathBuf>,
+}
+
+impl LoadStateCmd {
+    pub fn run(&self) -> Result<()> {
+        let content = std::fs::read(&self.input)?;
+        let content = if content.starts_with(&GZIP_MAGIC) {
+            let mut decompressed = Vec::new();
+            GzDecoder::new(&content[..]).read_to_end(&mut decompressed)?;
+            decompressed
+        } else {
+            content
+        };
+        // Make sure it is a valid genesis before writing it to disk
+        serde_json::from_slice::<GenesisData>(&content)
+            .map_err(|e| Error::Input(format!("Invalid state dump: {e}")))?;
+
+        let config_dir = {
+            let base_path = self
+                .base_path
+                .clone()
+                .map(BasePath::from)
+                .unwrap_or_else(|| BasePath::from_project("", "", &Cli::executable_name()));
+            base_path.config_dir(&self.chain)
+        };
+        let genesis_dir = config_dir.join(GENESIS_ASSETS_DIR);
+        std::fs::create_dir_all(&genesis_dir)?;
+        let genesis_path = genesis_dir.join(GENESIS_ASSETS_FILE);
+        std::fs::write(&genesis_path, content)?;
+        println!("Loaded '{}' as the genesis at '{}'", self.input.display(), genesis_path.display());
+
+        Ok(())
+    }
+}
+
+/// Length of the hash prefixing the keys of the `Twox64Concat` storage maps.
+const TWOX_64_CONCAT_LEN: usize = 8;
+/// Length of the hash prefixing the keys of the `Blake2_128Concat` storage maps.
+const BLAKE2_128_CONCAT_LEN: usize = 16;
+
+/// Reads the entries of a storage map of pallet Starknet, whose keys are prefixed by a hash of
+/// `hasher_len` bytes, `0` for the `Identity` hasher.
+fn storage_map<K: Decode, V: Decode>(
+    client: &FullClient,
+    block_hash: <Block as sp_runtime::traits::Block>::Hash,
+    prefix: &[u8],
+    hasher_len: usize,
+) -> Result<Vec<(K, V)>> {
+    raw_storage_map(client, block_hash, prefix, hasher_len)?
+        .into_iter()
+        .map(|(key, value)| {
+            let decoded_key = K::decode(&mut &key[..])
+                .map_err(|e| Error::Input(format!("Invalid storage key {}: {e}", hex::encode(&key))))?;
+            let decoded_value = V::decode(&mut &value[..])
+                .map_err(|e| Error::Input(format!("Invalid storage value at {}: {e}", hex::encode(&key))))?;
+            Ok((decoded_key, decoded_value))
+        })
+        .collect()
+}
+
+/// Reads the entries of a storage map of pallet Starknet, with their keys still encoded.
+fn raw_storage_map(
+    client: &FullClient,
+    block_hash: <Block as sp_runtime::traits::Block>::Hash,
+    prefix: &[u8],
+    hasher_len: usize,
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    let prefix = StorageKey(prefix.to_vec());
+    client
+        .storage_pairs(block_hash, Some(&prefix), None)?
+        .map(|pair| {
+            let (key, value) = pair?;
+            let encoded_key = key
+                .0
+                .get(prefix.0.len() + hasher_len..)
+                .ok_or_else(|| Error::Input(format!("Invalid storage key {}", hex::encode(&key.0))))?;
+            Ok((encoded_key.to_vec(), value.0))
+        })
+        .collect()
+}
+
+/// Reads a storage value of pallet Starknet, `None` when it is not set.
+fn storage_value<V: Decode>(
+    client: &FullClient,
+    block_hash: <Block as sp_runtime::traits::Block>::Hash,
+    name: &[u8],
+) -> Result<Option<V>> {
+    raw_storage_value(client, block_hash, name)?
+        .map(|value| {
+            V::decode(&mut &value[..])
+                .map_err(|e| Error::Input(format!("Invalid storage value {}: {e}", String::from_utf8_lossy(name))))
+        })
+        .transpose()
+}
+
+/// Reads a storage value of pallet Starknet, still encoded.
+fn raw_storage_value(
+    client: &FullClient,
+    block_hash: <Block as sp_runtime::traits::Block>::Hash,
+    name: &[u8],
+) -> Result<Option<Vec<u8>>> {
+    Ok(client.storage(block_hash, &StorageKey(starknet_storage_key(name)))?.map(|value| value.0))
+}
+
+/// The key of a storage item of pallet Starknet, or the prefix of the keys of a storage map.
+fn starknet_storage_key(name: &[u8]) -> Vec<u8> {
+    [twox_128(PALLET_STARKNET), twox_128(name)].concat()
+}
+
+fn hex_felt(felt: StarkFelt) -> HexFelt {
+    Felt252Wrapper::from(felt).into()
+}
@@ -6,6 +6,7 @@
 #![warn(missing_docs)]
 
 mod starknet;
+mod versioned;
 use std::sync::Arc;
 
 use futures::channel::mpsc;
@@ -13,6 +14,7 @@ use jsonrpsee::RpcModule;
 use madara_runtime::opaque::Block;
 use madara_runtime::{AccountId, Hash, Index, StarknetHasher};
 use mc_genesis_data_provider::GenesisProvider;
+use mc_rpc::dev::DevClock;
 use mc_rpc::starknetrpcwrapper::StarknetRpcWrapper;
 use sc_client_api::{Backend, BlockBackend, StorageProvider};
 use sc_consensus_manual_seal::rpc::EngineCommand;
@@ -23,9 +25,18 @@ use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 pub use starknet::StarknetDeps;
+pub use versioned::{run_versioned_rpc_server, VersionedRpcConfig};
+
+/// Development chain dependencies.
+pub struct DevDeps<BE> {
+    /// The backend instance, to revert to the snapshots.
+    pub backend: Arc<BE>,
+    /// Clock of the timestamp inherent.
+    pub clock: DevClock,
+}
 
 /// Full client dependencies.
-pub struct FullDeps<A: ChainApi, C, G: GenesisProvider, P> {
+pub struct FullDeps<A: ChainApi, C, G: GenesisProvider, P, BE> {
     /// The client instance to use.
     pub client: Arc<C>,
     /// Transaction pool instance.
@@ -38,11 +49,13 @@ pub struct FullDeps<A: ChainApi, C, G: GenesisProvider, P> {
     pub command_sink: Option<mpsc::Sender<EngineCommand<Hash>>>,
     /// Starknet dependencies
     pub starknet: StarknetDeps<C, G, Block>,
+    /// Development chain dependencies, set when the blocks are sealed manually or instantly
+    pub dev: Option<DevDeps<BE>>,
 }
 
 /// Instantiate all full RPC extensions.
 pub fn create_full<A, C, G, P, BE>(
-    deps: FullDeps<A, C, G, P>,
+    deps: FullDeps<A, C, G, P, BE>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
     A: ChainApi<Block = Block> + 'static,
@@ -61,33 +74,32 @@ where
     P: TransactionPool<Block = Block> + 'static,
     BE: Backend<Block> + 'static,
 {
-    use mc_rpc::{
-        MadaraRpcApiServer, Starknet, StarknetReadRpcApiServer, StarknetTraceRpcApiServer, StarknetWriteRpcApiServer,
-    };
+    use mc_rpc::dev::MadaraDev;
+    use mc_rpc::{MadaraDevRpcApiServer, MadaraRpcApiServer};
     use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApiServer};
     use substrate_frame_rpc_system::{System, SystemApiServer};
 
     let mut module = RpcModule::new(());
-    let FullDeps { client, pool, deny_unsafe, starknet: starknet_params, command_sink, graph, .. } = deps;
+    let FullDeps { client, pool, deny_unsafe, starknet: starknet_params, command_sink, graph, dev } = deps;
 
     module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
 
-    let rpc_instance: StarknetRpcWrapper<_, _, _, _, _, _, StarknetHasher> =
-        StarknetRpcWrapper(Arc::new(Starknet::<_, _, _, _, _, _, StarknetHasher>::new(
-            client,
-            starknet_params.madara_backend,
-            starknet_params.overrides,
-            pool,
-            graph,
-            starknet_params.sync_service,
-            starknet_params.starting_block,
-            starknet_params.genesis_provider,
-        )));
+    if let Some(DevDeps { backend, clock }) = dev {
+        module.merge(MadaraDevRpcApiServer::into_rpc(MadaraDev::<_, _, _, _, StarknetHasher>::new(
+            client.clone(),
+            backend,
+            starknet_params.madara_backend.clone(),
+            pool.clone(),
+            command_sink.clone(),
+            clock,
+            deny_unsafe,
+        )))?;
+    }
+
+    let rpc_instance = create_starknet::<_, _, _, _, BE>(client, pool, graph, starknet_params);
 
     module.merge(MadaraRpcApiServer::into_rpc(rpc_instance.clone()))?;
-    module.merge(StarknetReadRpcApiServer::into_rpc(rpc_instance.clone()))?;
-    module.merge(StarknetWriteRpcApiServer::into_rpc(rpc_instance.clone()))?;
-    module.merge(StarknetTraceRpcApiServer::into_rpc(rpc_instance.clone()))?;
+    module.merge(create_starknet_module(rpc_instance)?)?;
 
     if let Some(command_sink) = command_sink {
         module.merge(
@@ -99,3 +111,55 @@ where
 
     Ok(module)
 }
+
+/// Instantiate the module of the `starknet_*` methods.
+pub fn create_starknet_module<A, C, G, P, BE>(
+    rpc_instance: StarknetRpcWrapper<A, Block, BE, G, C, P, StarknetHasher>,
+) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
+where
+    A: ChainApi<Block = Block> + 'static,
+    C: ProvideRuntimeApi<Block>,
+    C: HeaderBackend<Block>
+        + BlockBackend<Block>
+        + HeaderMetadata<Block, Error = BlockChainError>
+        + StorageProvider<Block, BE>
+        + 'static,
+    C: Send + Sync + 'static,
+    C::Api: pallet_starknet_runtime_api::StarknetRuntimeApi<Block>
+        + pallet_starknet_runtime_api::ConvertTransactionRuntimeApi<Block>,
+    G: GenesisProvider + Send + Sync + 'static,
+    P: TransactionPool<Block = Block> + 'static,
+    BE: Backend<Block> + 'static,
+{
+    use mc_rpc::{StarknetReadRpcApiServer, StarknetTraceRpcApiServer, StarknetWriteRpcApiServer};
+
+    let mut module = RpcModule::new(());
+    module.merge(StarknetReadRpcApiServer::into_rpc(rpc_instance.clone()))?;
+    module.merge(StarknetWriteRpcApiServer::into_rpc(rpc_instance.clone()))?;
+    module.merge(StarknetTraceRpcApiServer::into_rpc(rpc_instance))?;
+
+    Ok(module)
+}
+
+/// Instantiate the Starknet RPC, also answering the feeder gateway.
+pub fn create_starknet<A, C, G, P, BE>(
+    client: Arc<C>,
+    pool: Arc<P>,
+    graph: Arc<Pool<A>>,
+    starknet_params: StarknetDeps<C, G, Block>,
+) -> StarknetRpcWrapper<A, Block, BE, G, C, P, StarknetHasher>
+where
+    A: ChainApi<Block = Block> + 'static,
+    G: GenesisProvider + Send + Sync + 'static,
+{
+    StarknetRpcWrapper(Arc::new(mc_rpc::Starknet::new(
+        client,
+        starknet_params.madara_backend,
+        starknet_params.overrides,
+        pool,
+        graph,
+        starknet_params.sync_service,
+        starknet_params.starting_block,
+        starknet_params.genesis_provider,
+    )))
+}
@@ -0,0 +1,212 @@
+//! Server mounting every supported Starknet RPC spec version on its own path, e.g. `/rpc/v0_5`.
+//!
+//! Substrate does not allow adding paths to its own server, so the versions are served by a
+//! jsonrpsee server of their own, configured as the substrate one: same request and response
+//! sizes, connections limit and CORS policy. The calls received on the path of a version are
+//! routed to the methods of that version, registered by [`mc_rpc::versioned::versioned_module`].
+
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use hyper::body::HttpBody;
+use hyper::header::{HeaderValue, CONTENT_LENGTH};
+use hyper::{Body, Request, Response, StatusCode};
+use jsonrpsee::server::ServerBuilder;
+use jsonrpsee::{Methods, RpcModule};
+use mc_rpc::versioned::RpcVersion;
+use serde_json::Value;
+use tower::{Layer, Service, ServiceBuilder};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+const MEGABYTE: u32 = 1024 * 1024;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Settings of the versioned server, taken from the ones of the substrate server.
+pub struct VersionedRpcConfig {
+    pub addr: SocketAddr,
+    /// The origins allowed to call the server, all of them if `None`.
+    pub cors: Option<Vec<String>>,
+    pub max_connections: u32,
+    /// Maximum size of the requests, in megabytes.
+    pub max_request_size: u32,
+    /// Maximum size of the responses, in megabytes.
+    pub max_response_size: u32,
+}
+
+/// Serve every version registered in `module` on its path.
+pub async fn run_versioned_rpc_server(config: VersionedRpcConfig, module: RpcModule<Methods>) {
+    let cors = match cors_layer(config.cors.as_ref()) {
+        Ok(cors) => cors,
+        Err(e) => {
+            log::error!("Invalid CORS origin for the versioned RPC server: {e}");
+            return;
+        }
+    };
+    let max_request_size = config.max_request_size.saturating_mul(MEGABYTE);
+    let middleware = ServiceBuilder::new().layer(cors).layer(VersionRoutingLayer { max_request_size });
+
+    let server = ServerBuilder::default()
+        .max_request_body_size(max_request_size)
+        .max_response_body_size(config.max_response_size.saturating_mul(MEGABYTE))
+        .max_connections(config.max_connections)
+        .http_only()
+        .set_middleware(middleware)
+        .build(config.addr)
+        .await;
+    let handle = match server.and_then(|server| server.start(module)) {
+        Ok(handle) => handle,
+        Err(e) => {
+            log::error!("Failed to start the versioned RPC server on {}: {e}", config.addr);
+            return;
+        }
+    };
+    let paths: Vec<_> = RpcVersion::ALL.iter().map(|version| version.path()).collect();
+    log::info!("Versioned Starknet RPC server listening on {}, paths: {}", config.addr, paths.join(", "));
+
+    handle.stopped().await;
+}
+
+/// The CORS policy of the substrate server.
+fn cors_layer(cors: Option<&Vec<String>>) -> Result<CorsLayer, hyper::header::InvalidHeaderValue> {
+    let Some(cors) = cors else {
+        return Ok(CorsLayer::permissive());
+    };
+    let origins = cors.iter().map(|origin| HeaderValue::from_str(origin)).collect::<Result<Vec<_>, _>>()?;
+
+    Ok(CorsLayer::new().allow_origin(AllowOrigin::list(origins)))
+}
+
+/// Routes the calls received on the path of a version to the methods of that version.
+#[derive(Clone)]
+struct VersionRoutingLayer {
+    max_request_size: u32,
+}
+
+impl<S> Layer<S> for VersionRoutingLayer {
+    type Service = VersionRouting<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        VersionRouting { inner, max_request_size: self.max_request_size }
+    }
+}
+
+#[derive(Clone)]
+struct VersionRouting<S> {
+    inner: S,
+    max_request_size: u32,
+}
+
+impl<S> Service<Request<Body>> for VersionRouting<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // The service driven to readiness is the one called, its clone is kept for the next call
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let max_request_size = self.max_request_size as usize;
+
+        Box::pin(async move {
+            let Some(version) = RpcVersion::from_path(request.uri().path()) else {
+                return Ok(empty_response(StatusCode::NOT_FOUND));
+            };
+            let (mut parts, body) = request.into_parts();
+            let body = match read_body(body, max_request_size).await {
+                Ok(body) => rename_methods(version, body),
+                Err(status) => return Ok(empty_response(status)),
+            };
+            parts.headers.insert(CONTENT_LENGTH, HeaderValue::from(body.len()));
+
+            inner.call(Request::from_parts(parts, Body::from(body))).await.map_err(Into::into)
+        })
+    }
+}
+
+/// Read the body of a request, failing if it is larger than `max_size` bytes.
+async fn read_body(mut body: Body, max_size: usize) -> Result<Vec<u8>, StatusCode> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|_| StatusCode::BAD_REQUEST)?;
+        if bytes.len() + chunk.len() > max_size {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+/// Rename the methods called by `body`, a single call or a batch, to the methods of `version`.
+///
+/// A body which is not JSON is left untouched, for the server to answer the parse error.
+fn rename_methods(version: RpcVersion, body: Vec<u8>) -> Vec<u8> {
+    let Ok(mut calls) = serde_json::from_slice::<Value>(&body) else {
+        return body;
+    };
+    match &mut calls {
+        Value::Array(calls) => calls.iter_mut().for_each(|call| rename_method(version, call)),
+        call => rename_method(version, call),
+    }
+
+    serde_json::to_vec(&calls).unwrap_or(body)
+}
+
+fn rename_method(version: RpcVersion, call: &mut Value) {
+    if let Some(Value::String(method)) = call.get_mut("method") {
+        *method = version.method_name(method);
+    }
+}
+
+fn empty_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn calls_are_routed_to_the_methods_of_their_version() {
+        let call = json!({ "jsonrpc": "2.0", "method": "starknet_blockNumber", "id": 1 });
+        let body = rename_methods(RpcVersion::V0_6, serde_json::to_vec(&call).unwrap());
+        assert_eq!(
+            serde_json::from_slice::<Value>(&body).unwrap(),
+            json!({ "jsonrpc": "2.0", "method": "v0_6/starknet_blockNumber", "id": 1 })
+        );
+
+        // Notifications, without id, are renamed as well, the server not answering them
+        let batch = json!([
+            { "jsonrpc": "2.0", "method": "starknet_chainId", "id": 1 },
+            { "jsonrpc": "2.0", "method": "starknet_syncing" }
+        ]);
+        let body = rename_methods(RpcVersion::V0_5, serde_json::to_vec(&batch).unwrap());
+        assert_eq!(
+            serde_json::from_slice::<Value>(&body).unwrap(),
+            json!([
+                { "jsonrpc": "2.0", "method": "v0_5/starknet_chainId", "id": 1 },
+                { "jsonrpc": "2.0", "method": "v0_5/starknet_syncing" }
+            ])
+        );
+    }
+
+    #[test]
+    fn invalid_json_is_left_to_the_server() {
+        assert_eq!(rename_methods(RpcVersion::V0_5, b"{".to_vec()), b"{".to_vec());
+    }
+}
@@ -1,28 +1,35 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use std::cell::RefCell;
+use std::collections::HashSet;
+use std::net::{Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use futures::channel::mpsc;
+use futures::channel::{mpsc, oneshot};
 use futures::future;
 use futures::future::BoxFuture;
 use futures::prelude::*;
 use madara_runtime::opaque::Block;
 use madara_runtime::{self, Hash, RuntimeApi, SealingMode, StarknetHasher};
-use mc_commitment_state_diff::CommitmentStateDiffWorker;
+use mc_commitment_state_diff::{archive_state_diffs, CommitmentStateDiffWorker};
 use mc_data_availability::{DaClient, DataAvailabilityWorker};
 use mc_eth_client::config::EthereumClientConfig;
-use mc_genesis_data_provider::OnDiskGenesisConfig;
+use mc_fork::{ForkConfig, ForkedState};
+use mc_genesis_data_provider::{GenesisProvider, OnDiskGenesisConfig};
 use mc_mapping_sync::MappingSyncWorker;
+use mc_rpc::dev::DevClock;
+use mc_rpc::versioned::versioned_module;
 use mc_settlement::errors::RetryOnRecoverableErrors;
 use mc_settlement::ethereum::StarknetContractClient;
 use mc_settlement::{SettlementLayer, SettlementProvider, SettlementWorker};
 use mc_storage::overrides_handle;
+use mc_sync::SyncConfig;
+use mp_felt::Felt252Wrapper;
 use mp_sequencer_address::{
     InherentDataProvider as SeqAddrInherentDataProvider, DEFAULT_SEQUENCER_ADDRESS, SEQ_ADDR_STORAGE_KEY,
 };
+use pallet_starknet::sealing::InherentDataProvider as SealingInherentDataProvider;
 use prometheus_endpoint::Registry;
 use sc_basic_authorship::ProposerFactory;
 use sc_client_api::{Backend, BlockBackend, BlockchainEvents, HeaderBackend};
@@ -34,14 +41,14 @@ use sc_service::error::Error as ServiceError;
 use sc_service::{new_db_backend, Configuration, TaskManager, WarpSyncParams};
 use sc_telemetry::{Telemetry, TelemetryHandle, TelemetryWorker};
 use sc_transaction_pool::FullPool;
-use sc_transaction_pool_api::OffchainTransactionPoolFactory;
+use sc_transaction_pool_api::{InPoolTransaction, OffchainTransactionPoolFactory, TransactionPool, TxHash};
 use sp_api::offchain::OffchainStorage;
 use sp_api::ConstructRuntimeApi;
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
 use sp_offchain::STORAGE_PREFIX;
 
 use crate::genesis_block::MadaraGenesisBlockBuilder;
-use crate::rpc::StarknetDeps;
+use crate::rpc::{DevDeps, StarknetDeps};
 use crate::starknet::{db_config_dir, MadaraBackend};
 // Our native executor instance.
 pub struct ExecutorDispatch;
@@ -52,10 +59,10 @@ const DEFAULT_SETTLEMENT_RETRY_INTERVAL: Duration = Duration::from_millis(100);
 impl sc_executor::NativeExecutionDispatch for ExecutorDispatch {
     /// Only enable the benchmarking host functions when we actually want to benchmark.
     #[cfg(feature = "runtime-benchmarks")]
-    type ExtendHostFunctions = frame_benchmarking::benchmarking::HostFunctions;
-    /// Otherwise we only use the default Substrate host functions.
+    type ExtendHostFunctions = (frame_benchmarking::benchmarking::HostFunctions, mp_fork::forked_state::HostFunctions);
+    /// Otherwise we only add the forked state host functions to the default Substrate ones.
     #[cfg(not(feature = "runtime-benchmarks"))]
-    type ExtendHostFunctions = ();
+    type ExtendHostFunctions = mp_fork::forked_state::HostFunctions;
 
     fn dispatch(method: &str, data: &[u8]) -> Option<Vec<u8>> {
         madara_runtime::api::dispatch(method, data)
@@ -67,7 +74,7 @@ impl sc_executor::NativeExecutionDispatch for ExecutorDispatch {
 }
 
 pub(crate) type FullClient = sc_service::TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<ExecutorDispatch>>;
-type FullBackend = sc_service::TFullBackend<Block>;
+pub(crate) type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 
 type BasicImportQueue = sc_consensus::DefaultImportQueue<Block>;
@@ -263,12 +270,25 @@ where
 /// # Arguments
 ///
 /// - `cache`: whether more information should be cached when storing the block in the database.
+/// - `state_archive`: whether the blocks state diffs should be archived to serve historical state
+///   queries.
+/// - `versioned_rpc_port`: port of the server mounting every supported Starknet RPC version on its
+///   own path, if any.
+/// - `feeder_gateway_port`: port of the Starknet feeder gateway API, if any.
+/// - `fork_config`: remote chain whose state is read when missing locally, if any.
+/// - `sync_config`: sequencer whose blocks are imported instead of authoring any, if any.
+#[allow(clippy::too_many_arguments)]
 pub fn new_full(
     config: Configuration,
     sealing: SealingMode,
     da_client: Option<Box<dyn DaClient + Send + Sync>>,
     cache_more_things: bool,
+    state_archive: bool,
+    versioned_rpc_port: Option<u16>,
+    feeder_gateway_port: Option<u16>,
     settlement_config: Option<(SettlementLayer, PathBuf)>,
+    fork_config: Option<ForkConfig>,
+    sync_config: Option<SyncConfig>,
 ) -> Result<TaskManager, ServiceError> {
     let build_import_queue =
         if sealing.is_default() { build_aura_grandpa_import_queue } else { build_manual_seal_import_queue };
@@ -284,6 +304,14 @@ pub fn new_full(
         other: (block_import, grandpa_link, mut telemetry, madara_backend),
     } = new_partial(&config, build_import_queue, cache_more_things)?;
 
+    if let Some(fork_config) = fork_config {
+        let url = fork_config.url.clone();
+        let forked_state = ForkedState::new(fork_config).map_err(|e| ServiceError::Other(e.to_string()))?;
+        log::info!("Forking {} at block {}", url, forked_state.block_number());
+        // Registers the forked state on the runtime calls, answering the state reads missing locally
+        client.execution_extensions().set_extensions_factory(forked_state);
+    }
+
     let mut net_config = sc_network::config::FullNetworkConfiguration::new(&config.network);
 
     let grandpa_protocol_name = sc_consensus_grandpa::protocol_standard_name(
@@ -356,6 +384,18 @@ pub fn new_full(
     let overrides = overrides_handle(client.clone());
     let config_dir: PathBuf = config.data_path.clone();
     let genesis_data = OnDiskGenesisConfig(config_dir);
+    // The Sierra classes of a genesis loaded from a state dump are served by the RPC
+    if let Ok(genesis) = genesis_data.load_genesis_data() {
+        for (class_hash, sierra_class) in genesis.sierra_classes {
+            let class_hash = Felt252Wrapper(class_hash.0).into();
+            if madara_backend.sierra_classes().get_sierra_class(class_hash).map_err(|e| e.to_string())?.is_none() {
+                madara_backend
+                    .sierra_classes()
+                    .store_sierra_class(class_hash, sierra_class)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
     let starknet_rpc_params = StarknetDeps {
         client: client.clone(),
         madara_backend: madara_backend.clone(),
@@ -365,8 +405,58 @@ pub fn new_full(
         genesis_provider: genesis_data.into(),
     };
 
+    // Timestamps of the manually or instantly sealed blocks, moved by the `madara_dev` RPC
+    let dev_clock = match sealing {
+        SealingMode::Manual | SealingMode::Instant { .. } => Some(DevClock::new(madara_runtime::SLOT_DURATION)),
+        _ => None,
+    };
+
+    if let Some(port) = feeder_gateway_port {
+        let starknet = crate::rpc::create_starknet::<_, _, _, _, FullBackend>(
+            client.clone(),
+            transaction_pool.clone(),
+            transaction_pool.pool().clone(),
+            starknet_rpc_params.clone(),
+        );
+        let ip = config.rpc_addr.map(|addr| addr.ip()).unwrap_or(Ipv4Addr::LOCALHOST.into());
+        task_manager.spawn_handle().spawn(
+            "feeder-gateway",
+            Some(MADARA_TASK_GROUP),
+            mc_feeder_gateway::run_feeder_gateway(SocketAddr::new(ip, port), Arc::new(starknet)),
+        );
+    }
+
+    // The versioned paths are served by a server of their own, substrate not allowing to add
+    // paths to its server
+    if let Some(port) = versioned_rpc_port {
+        let starknet = crate::rpc::create_starknet::<_, _, _, _, FullBackend>(
+            client.clone(),
+            transaction_pool.clone(),
+            transaction_pool.pool().clone(),
+            starknet_rpc_params.clone(),
+        );
+        let starknet_module =
+            crate::rpc::create_starknet_module(starknet).map_err(|e| ServiceError::Other(e.to_string()))?;
+        let module = versioned_module(&starknet_module).map_err(|e| ServiceError::Other(e.to_string()))?;
+        let ip = config.rpc_addr.map(|addr| addr.ip()).unwrap_or(Ipv4Addr::LOCALHOST.into());
+        let versioned_config = crate::rpc::VersionedRpcConfig {
+            addr: SocketAddr::new(ip, port),
+            cors: config.rpc_cors.clone(),
+            max_connections: config.rpc_max_connections,
+            max_request_size: config.rpc_max_request_size,
+            max_response_size: config.rpc_max_response_size,
+        };
+        task_manager.spawn_handle().spawn(
+            "versioned-rpc-server",
+            Some(MADARA_TASK_GROUP),
+            crate::rpc::run_versioned_rpc_server(versioned_config, module),
+        );
+    }
+
     let rpc_extensions_builder = {
         let client = client.clone();
+        let backend = backend.clone();
+        let dev_clock = dev_clock.clone();
         let pool = transaction_pool.clone();
         let graph = transaction_pool.pool().clone();
 
@@ -378,6 +468,7 @@ pub fn new_full(
                 deny_unsafe,
                 starknet: starknet_rpc_params.clone(),
                 command_sink: command_sink.clone(),
+                dev: dev_clock.clone().map(|clock| DevDeps { backend: backend.clone(), clock }),
             };
             crate::rpc::create_full(deps).map_err(Into::into)
         })
@@ -416,8 +507,8 @@ pub fn new_full(
 
     let (commitment_state_diff_tx, commitment_state_diff_rx) = mpsc::channel(5);
 
-    // initialize data availability worker
-    if let Some(da_client) = da_client {
+    // initialize the workers consuming the blocks state diffs
+    if da_client.is_some() || state_archive {
         task_manager.spawn_essential_handle().spawn(
             "commitment-state-diff",
             Some("madara"),
@@ -428,13 +519,34 @@ pub fn new_full(
             )
             .for_each(|()| future::ready(())),
         );
+    }
+
+    let da_rx = if state_archive {
+        let (da_tx, da_rx) = mpsc::channel(5);
+        task_manager.spawn_essential_handle().spawn(
+            "state-archive",
+            Some(MADARA_TASK_GROUP),
+            archive_state_diffs(
+                client.clone(),
+                madara_backend.clone(),
+                commitment_state_diff_rx,
+                da_client.is_some().then_some(da_tx),
+            ),
+        );
+        da_rx
+    } else {
+        commitment_state_diff_rx
+    };
+
+    // initialize data availability worker
+    if let Some(da_client) = da_client {
         task_manager.spawn_essential_handle().spawn(
             "da-worker",
             Some(MADARA_TASK_GROUP),
             DataAvailabilityWorker::<_, StarknetHasher>::prove_current_block(
                 da_client.into(),
                 prometheus_registry.clone(),
-                commitment_state_diff_rx,
+                da_rx,
                 madara_backend.clone(),
             ),
         );
@@ -482,6 +594,23 @@ pub fn new_full(
         }
     }
 
+    // A syncing node imports the blocks of the sequencer, it does not author any
+    if let Some(sync_config) = sync_config {
+        task_manager.spawn_essential_handle().spawn(
+            "sync-worker",
+            Some(MADARA_TASK_GROUP),
+            mc_sync::run_sync::<_, _, FullBackend, _, StarknetHasher>(
+                sync_config,
+                client,
+                block_import,
+                madara_backend,
+            ),
+        );
+
+        network_starter.start_network();
+        return Ok(task_manager);
+    }
+
     if role.is_authority() {
         // manual-seal authorship
         if !sealing.is_default() {
@@ -496,6 +625,7 @@ pub fn new_full(
                 &task_manager,
                 prometheus_registry.as_ref(),
                 commands_stream,
+                dev_clock,
                 telemetry,
             )?;
 
@@ -620,6 +750,7 @@ fn run_manual_seal_authorship(
     task_manager: &TaskManager,
     prometheus_registry: Option<&Registry>,
     commands_stream: Option<mpsc::Receiver<sc_consensus_manual_seal::rpc::EngineCommand<Hash>>>,
+    dev_clock: Option<DevClock>,
     telemetry: Option<Telemetry>,
 ) -> Result<(), ServiceError>
 where
@@ -634,11 +765,12 @@ where
         telemetry.as_ref().map(|x| x.handle()),
     );
 
-    thread_local!(static TIMESTAMP: RefCell<u64> = RefCell::new(0));
+    let clock = dev_clock.unwrap_or_else(|| DevClock::new(madara_runtime::SLOT_DURATION));
 
     /// Provide a mock duration starting at 0 in millisecond for timestamp inherent.
-    /// Each call will increment timestamp by slot_duration making Aura think time has passed.
-    struct MockTimestampInherentDataProvider;
+    /// Each call moves the clock to its next block timestamp, by default incrementing it by
+    /// slot_duration making Aura think time has passed.
+    struct MockTimestampInherentDataProvider(DevClock);
 
     #[async_trait::async_trait]
     impl sp_inherents::InherentDataProvider for MockTimestampInherentDataProvider {
@@ -646,10 +778,7 @@ where
             &self,
             inherent_data: &mut sp_inherents::InherentData,
         ) -> Result<(), sp_inherents::Error> {
-            TIMESTAMP.with(|x| {
-                *x.borrow_mut() += madara_runtime::SLOT_DURATION;
-                inherent_data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &*x.borrow())
-            })
+            inherent_data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &self.0.advance())
         }
 
         async fn try_handle_error(
@@ -662,9 +791,13 @@ where
         }
     }
 
-    let create_inherent_data_providers = move |_, ()| async move {
-        let timestamp = MockTimestampInherentDataProvider;
-        Ok(timestamp)
+    let sealing_parameters = sealing.sealing_parameters();
+    let create_inherent_data_providers = move |_, ()| {
+        let clock = clock.clone();
+        async move {
+            let timestamp = MockTimestampInherentDataProvider(clock);
+            Ok((timestamp, SealingInherentDataProvider(sealing_parameters)))
+        }
     };
 
     let manual_seal: BoxFuture<_> = match sealing {
@@ -696,6 +829,24 @@ where
                 Box::pin(sc_consensus_manual_seal::run_instant_seal(instant_seal_params))
             }
         }
+        SealingMode::Batch { block_time, max_transactions, .. } => {
+            let commands_stream = batch_seal_commands(
+                transaction_pool.clone(),
+                client.clone(),
+                Duration::from_millis(block_time),
+                max_transactions as usize,
+            );
+            Box::pin(sc_consensus_manual_seal::run_manual_seal(sc_consensus_manual_seal::ManualSealParams {
+                block_import,
+                env: proposer_factory,
+                client,
+                pool: transaction_pool,
+                commands_stream,
+                select_chain,
+                consensus_data_provider: None,
+                create_inherent_data_providers,
+            }))
+        }
         _ => unreachable!("Other sealing modes are not expected in manual-seal."),
     };
 
@@ -704,6 +855,92 @@ where
     Ok(())
 }
 
+/// Emits a seal command once `block_time` has elapsed since the last block or `max_transactions`
+/// are ready in the pool, whichever comes first.
+///
+/// The window is restarted without sealing when it elapses on an empty pool. Each command waits
+/// for the previous block to be sealed: if transactions which were ready when sealing it are still
+/// ready once the pool is updated, the block ran out of resources and the next one is sealed right
+/// away.
+fn batch_seal_commands<P, C>(
+    pool: Arc<P>,
+    client: Arc<C>,
+    block_time: Duration,
+    max_transactions: usize,
+) -> stream::BoxStream<'static, sc_consensus_manual_seal::rpc::EngineCommand<Hash>>
+where
+    P: TransactionPool<Block = Block> + 'static,
+    C: HeaderBackend<Block> + 'static,
+{
+    let import_notifications = pool.import_notification_stream();
+
+    stream::unfold(
+        (pool, client, import_notifications, Instant::now() + block_time, None),
+        move |(pool, client, mut import_notifications, mut deadline, previous_seal)| async move {
+            let mut exhausted = false;
+            if let Some((previous_seal, sealed_transactions)) = previous_seal {
+                // The outcome of the seal is already logged by the authorship task
+                if let Ok(Ok(created_block)) = previous_seal.await {
+                    exhausted = left_over_transactions(&*pool, &*client, created_block.hash, sealed_transactions).await;
+                }
+                deadline = Instant::now() + block_time;
+            }
+
+            // The transactions left over by a block which ran out of resources are sealed right away
+            if !exhausted {
+                loop {
+                    let ready = pool.status().ready;
+                    let now = Instant::now();
+                    if ready >= max_transactions || (ready > 0 && now >= deadline) {
+                        break;
+                    }
+                    if now >= deadline {
+                        // Nothing to seal, skip the empty block
+                        deadline = now + block_time;
+                    }
+
+                    let window = futures_timer::Delay::new(deadline - now);
+                    if let future::Either::Left((None, _)) = future::select(import_notifications.next(), window).await {
+                        // The pool has been dropped
+                        return None;
+                    }
+                }
+            }
+
+            let sealed_transactions: Vec<_> = pool.ready().map(|transaction| transaction.hash().clone()).collect();
+            let (sender, receiver) = oneshot::channel();
+            let command = sc_consensus_manual_seal::rpc::EngineCommand::SealNewBlock {
+                create_empty: false,
+                finalize: true,
+                parent_hash: None,
+                sender: Some(sender),
+            };
+            Some((command, (pool, client, import_notifications, deadline, Some((receiver, sealed_transactions)))))
+        },
+    )
+    .boxed()
+}
+
+/// Whether some of `sealed_transactions`, ready when `block_hash` was sealed, are still ready once
+/// the pool is updated with that block, which means they did not fit in it.
+async fn left_over_transactions<P, C>(
+    pool: &P,
+    client: &C,
+    block_hash: Hash,
+    sealed_transactions: Vec<TxHash<P>>,
+) -> bool
+where
+    P: TransactionPool<Block = Block>,
+    C: HeaderBackend<Block>,
+{
+    let Ok(Some(block_number)) = client.number(block_hash) else {
+        return false;
+    };
+    let ready: HashSet<_> = pool.ready_at(block_number).await.map(|transaction| transaction.hash().clone()).collect();
+
+    sealed_transactions.iter().any(|hash| ready.contains(hash))
+}
+
 type ChainOpsResult =
     Result<(Arc<FullClient>, Arc<FullBackend>, BasicQueue<Block>, TaskManager, Arc<MadaraBackend>), ServiceError>;
 
@@ -11,15 +11,21 @@ use blockifier::execution::contract_class::ContractClass;
 use blockifier::state::cached_state::CommitmentStateDiff;
 use blockifier::transaction::objects::TransactionExecutionInfo;
 use mp_felt::Felt252Wrapper;
-use mp_transactions::{HandleL1MessageTransaction, Transaction, UserOrL1HandlerTransaction, UserTransaction};
+use mp_state::BlockStateDiff;
+use mp_transactions::{
+    HandleL1MessageTransaction, ImportedTransaction, Transaction, UserOrL1HandlerTransaction, UserTransaction,
+};
 use sp_api::BlockT;
 pub extern crate alloc;
 use alloc::string::String;
 use alloc::vec::Vec;
 
-use mp_simulations::{PlaceHolderErrorTypeForFailedStarknetExecution, SimulationFlags, TransactionSimulationResult};
+use mp_simulations::{
+    PlaceHolderErrorTypeForFailedStarknetExecution, SimulatedBlock, SimulationFlags, StateOverrides,
+    TransactionSimulationResult,
+};
 use sp_runtime::DispatchError;
-use starknet_api::api_core::{ChainId, ClassHash, ContractAddress, EntryPointSelector, Nonce};
+use starknet_api::api_core::{ChainId, ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, Nonce};
 use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
@@ -35,6 +41,7 @@ pub enum StarknetTransactionExecutionError {
 }
 
 sp_api::decl_runtime_apis! {
+    #[api_version(2)]
     pub trait StarknetRuntimeApi {
         /// Returns the nonce associated with the given address in the given block
         fn nonce(contract_address: ContractAddress) -> Nonce;
@@ -46,6 +53,9 @@ sp_api::decl_runtime_apis! {
         fn contract_class_hash_by_address(address: ContractAddress) -> ClassHash;
         /// Returns the contract class for the given class hash.
         fn contract_class_by_class_hash(class_hash: ClassHash) -> Option<ContractClass>;
+        /// Returns the compiled class hash of the given Sierra class hash.
+        #[api_version(2)]
+        fn compiled_class_hash_by_class_hash(class_hash: ClassHash) -> Option<CompiledClassHash>;
         /// Returns the chain id.
         fn chain_id() -> Felt252Wrapper;
         /// Returns the Starknet OS Cairo program hash.
@@ -62,6 +72,18 @@ sp_api::decl_runtime_apis! {
         fn simulate_message(message: HandleL1MessageTransaction, simulation_flags: SimulationFlags) -> Result<Result<TransactionExecutionInfo, PlaceHolderErrorTypeForFailedStarknetExecution>, DispatchError>;
         /// Simulates transactions and returns their trace
         fn simulate_transactions(transactions: Vec<UserTransaction>, simulation_flags: SimulationFlags) -> Result<Vec<(CommitmentStateDiff, TransactionSimulationResult)>, DispatchError>;
+        /// Simulates a sequence of blocks and returns the trace of their transactions, block by block
+        #[api_version(2)]
+        fn simulate_blocks(blocks: Vec<SimulatedBlock>, simulation_flags: SimulationFlags) -> Result<Vec<Vec<(CommitmentStateDiff, TransactionSimulationResult)>>, DispatchError>;
+        /// Returns a `Call` response, executed against the state modified by the overrides.
+        #[api_version(2)]
+        fn call_with_overrides(address: ContractAddress, function_selector: EntryPointSelector, calldata: Calldata, state_overrides: StateOverrides) -> Result<Vec<Felt252Wrapper>, DispatchError>;
+        /// Returns fee estimate, executed against the state modified by the overrides.
+        #[api_version(2)]
+        fn estimate_fee_with_overrides(transactions: Vec<UserTransaction>, state_overrides: StateOverrides) -> Result<Vec<(u64, u64)>, DispatchError>;
+        /// Simulates transactions against the state modified by the overrides and returns their trace
+        #[api_version(2)]
+        fn simulate_transactions_with_overrides(transactions: Vec<UserTransaction>, simulation_flags: SimulationFlags, state_overrides: StateOverrides) -> Result<Vec<(CommitmentStateDiff, TransactionSimulationResult)>, DispatchError>;
         /// Filters extrinsic transactions to return only Starknet transactions
         ///
         /// To support runtime upgrades, the client must be unaware of the specific extrinsic
@@ -99,6 +121,7 @@ sp_api::decl_runtime_apis! {
         fn l1_nonce_unused(nonce: Nonce) -> bool;
     }
 
+    #[api_version(2)]
     pub trait ConvertTransactionRuntimeApi {
         /// Converts the transaction to an UncheckedExtrinsic for submission to the pool.
         fn convert_transaction(transaction: UserTransaction) -> <Block as BlockT>::Extrinsic;
@@ -106,6 +129,18 @@ sp_api::decl_runtime_apis! {
         /// Converts the L1 Message transaction to an UncheckedExtrinsic for submission to the pool.
         fn convert_l1_transaction(transaction: HandleL1MessageTransaction, fee: Fee) -> <Block as BlockT>::Extrinsic;
 
+        /// Converts the state edits of a development chain to an UncheckedExtrinsic for submission to the pool.
+        #[api_version(2)]
+        fn convert_state_edits(state_edits: StateOverrides) -> <Block as BlockT>::Extrinsic;
+
+        /// Converts the fee token mint of a development chain to an UncheckedExtrinsic for submission to the pool.
+        #[api_version(2)]
+        fn convert_mint(address: ContractAddress, amount: StarkFelt) -> <Block as BlockT>::Extrinsic;
+
+        /// Converts the state diff, the transactions and the state root of a block imported from another sequencer to an UncheckedExtrinsic for inclusion in the imported block.
+        #[api_version(2)]
+        fn convert_state_diff(state_diff: BlockStateDiff, transactions: Vec<ImportedTransaction>, state_root: StarkHash) -> <Block as BlockT>::Extrinsic;
+
         /// Converts the DispatchError to an understandable error for the client
         fn convert_error(error: DispatchError) -> StarknetTransactionExecutionError;
     }
@@ -0,0 +1,36 @@
+//! Benchmarks of the Starknet pallet.
+use frame_benchmarking::v2::*;
+use starknet_api::api_core::Nonce;
+use starknet_api::hash::StarkFelt;
+
+use crate::{Config, L1Messages, Pallet};
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    /// Book-keeping of a consumed L1 message after `n` other messages were consumed.
+    ///
+    /// Its cost must not depend on `n`.
+    #[benchmark]
+    fn mark_l1_message_executed(n: Linear<0, 10_000>) {
+        for nonce in 0..n {
+            L1Messages::<T>::insert(Nonce(StarkFelt::from(nonce as u64)), ());
+        }
+        let nonce = Nonce(StarkFelt::from(n as u64));
+
+        #[block]
+        {
+            Pallet::<T>::ensure_l1_message_not_executed(&nonce).unwrap();
+            L1Messages::<T>::insert(nonce, ());
+        }
+
+        assert!(L1Messages::<T>::contains_key(nonce));
+    }
+
+    impl_benchmark_test_suite!(
+        Pallet,
+        crate::tests::mock::new_test_ext::<crate::tests::mock::default_mock::MockRuntime>(),
+        crate::tests::mock::default_mock::MockRuntime
+    );
+}
@@ -0,0 +1,176 @@
+//! Starknet block capacity accounting.
+//!
+//! Mirrors the Starknet bouncer: the resources consumed by the transactions of a block are summed
+//! up, and a transaction is only included if its own resources fit in what is left of the block.
+use blockifier::execution::entry_point::CallInfo;
+use blockifier::state::cached_state::StateChangesCount;
+use blockifier::transaction::objects::TransactionExecutionInfo;
+
+/// Code of the `InvalidTransaction::Custom` error of a transaction consuming more resources than a
+/// whole block can hold, which could never be included.
+pub const TRANSACTION_TOO_LARGE: u8 = 0;
+
+/// Starknet resources consumed by the transactions of a block.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    parity_scale_codec::Encode,
+    parity_scale_codec::Decode,
+    scale_info::TypeInfo,
+    parity_scale_codec::MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockResources {
+    /// Cairo steps
+    pub n_steps: u64,
+    pub pedersen_builtin: u64,
+    pub range_check_builtin: u64,
+    pub ecdsa_builtin: u64,
+    pub bitwise_builtin: u64,
+    pub ec_op_builtin: u64,
+    pub poseidon_builtin: u64,
+    pub keccak_builtin: u64,
+    /// Number of felts published to L1 for the state diff
+    pub state_diff_size: u64,
+    pub n_events: u64,
+    /// Number of messages sent to L1
+    pub n_messages: u64,
+    pub n_transactions: u64,
+}
+
+impl BlockResources {
+    /// No limit at all.
+    pub const MAX: Self = Self {
+        n_steps: u64::MAX,
+        pedersen_builtin: u64::MAX,
+        range_check_builtin: u64::MAX,
+        ecdsa_builtin: u64::MAX,
+        bitwise_builtin: u64::MAX,
+        ec_op_builtin: u64::MAX,
+        poseidon_builtin: u64::MAX,
+        keccak_builtin: u64::MAX,
+        state_diff_size: u64::MAX,
+        n_events: u64::MAX,
+        n_messages: u64::MAX,
+        n_transactions: u64::MAX,
+    };
+
+    /// Resources consumed by a transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `execution_info` - The execution info of the transaction.
+    /// * `state_changes` - The changes the transaction made to the state.
+    pub fn from_execution_info(execution_info: &TransactionExecutionInfo, state_changes: StateChangesCount) -> Self {
+        let resource = |name: &str| {
+            execution_info
+                .actual_resources
+                .0
+                .iter()
+                .find(|(resource, _)| resource.eq_ignore_ascii_case(name))
+                .map_or(0, |(_, amount)| *amount as u64)
+        };
+        let (n_events, n_messages) = [
+            &execution_info.validate_call_info,
+            &execution_info.execute_call_info,
+            &execution_info.fee_transfer_call_info,
+        ]
+        .into_iter()
+        .flatten()
+        .map(count_events_and_messages)
+        .fold((0, 0), |(events, messages), (call_events, call_messages)| {
+            (events + call_events, messages + call_messages)
+        });
+
+        Self {
+            n_steps: resource("n_steps"),
+            pedersen_builtin: resource("pedersen_builtin"),
+            range_check_builtin: resource("range_check_builtin"),
+            ecdsa_builtin: resource("ecdsa_builtin"),
+            bitwise_builtin: resource("bitwise_builtin"),
+            ec_op_builtin: resource("ec_op_builtin"),
+            poseidon_builtin: resource("poseidon_builtin"),
+            keccak_builtin: resource("keccak_builtin"),
+            state_diff_size: state_diff_size(&state_changes),
+            n_events,
+            n_messages,
+            n_transactions: 1,
+        }
+    }
+
+    /// Sum of `self` and `other`, saturating at `u64::MAX`.
+    pub fn saturating_add(&self, other: &Self) -> Self {
+        Self {
+            n_steps: self.n_steps.saturating_add(other.n_steps),
+            pedersen_builtin: self.pedersen_builtin.saturating_add(other.pedersen_builtin),
+            range_check_builtin: self.range_check_builtin.saturating_add(other.range_check_builtin),
+            ecdsa_builtin: self.ecdsa_builtin.saturating_add(other.ecdsa_builtin),
+            bitwise_builtin: self.bitwise_builtin.saturating_add(other.bitwise_builtin),
+            ec_op_builtin: self.ec_op_builtin.saturating_add(other.ec_op_builtin),
+            poseidon_builtin: self.poseidon_builtin.saturating_add(other.poseidon_builtin),
+            keccak_builtin: self.keccak_builtin.saturating_add(other.keccak_builtin),
+            state_diff_size: self.state_diff_size.saturating_add(other.state_diff_size),
+            n_events: self.n_events.saturating_add(other.n_events),
+            n_messages: self.n_messages.saturating_add(other.n_messages),
+            n_transactions: self.n_transactions.saturating_add(other.n_transactions),
+        }
+    }
+
+    /// The lowest of `self` and `other`, resource by resource.
+    pub fn min(&self, other: &Self) -> Self {
+        Self {
+            n_steps: self.n_steps.min(other.n_steps),
+            pedersen_builtin: self.pedersen_builtin.min(other.pedersen_builtin),
+            range_check_builtin: self.range_check_builtin.min(other.range_check_builtin),
+            ecdsa_builtin: self.ecdsa_builtin.min(other.ecdsa_builtin),
+            bitwise_builtin: self.bitwise_builtin.min(other.bitwise_builtin),
+            ec_op_builtin: self.ec_op_builtin.min(other.ec_op_builtin),
+            poseidon_builtin: self.poseidon_builtin.min(other.poseidon_builtin),
+            keccak_builtin: self.keccak_builtin.min(other.keccak_builtin),
+            state_diff_size: self.state_diff_size.min(other.state_diff_size),
+            n_events: self.n_events.min(other.n_events),
+            n_messages: self.n_messages.min(other.n_messages),
+            n_transactions: self.n_transactions.min(other.n_transactions),
+        }
+    }
+
+    /// Returns true if any of the resources is over its limit.
+    pub fn exceeds(&self, limits: &Self) -> bool {
+        self.n_steps > limits.n_steps
+            || self.pedersen_builtin > limits.pedersen_builtin
+            || self.range_check_builtin > limits.range_check_builtin
+            || self.ecdsa_builtin > limits.ecdsa_builtin
+            || self.bitwise_builtin > limits.bitwise_builtin
+            || self.ec_op_builtin > limits.ec_op_builtin
+            || self.poseidon_builtin > limits.poseidon_builtin
+            || self.keccak_builtin > limits.keccak_builtin
+            || self.state_diff_size > limits.state_diff_size
+            || self.n_events > limits.n_events
+            || self.n_messages > limits.n_messages
+            || self.n_transactions > limits.n_transactions
+    }
+}
+
+/// Number of felts published to L1 for the given state changes.
+///
+/// Each modified contract publishes its address and its new nonce, each storage update its key
+/// and value, each class hash update its new class hash and each declared class its compiled
+/// class hash along with its class hash.
+fn state_diff_size(state_changes: &StateChangesCount) -> u64 {
+    (state_changes.n_modified_contracts * 2
+        + state_changes.n_storage_updates * 2
+        + state_changes.n_class_hash_updates
+        + state_changes.n_compiled_class_hash_updates * 2) as u64
+}
+
+/// Number of events emitted and messages sent to L1 by a call and its inner calls.
+fn count_events_and_messages(call_info: &CallInfo) -> (u64, u64) {
+    call_info.inner_calls.iter().map(count_events_and_messages).fold(
+        (call_info.execution.events.len() as u64, call_info.execution.l2_to_l1_messages.len() as u64),
+        |(events, messages), (inner_events, inner_messages)| (events + inner_events, messages + inner_messages),
+    )
+}
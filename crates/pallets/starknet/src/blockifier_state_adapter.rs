@@ -1,4 +1,6 @@
 use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::rc::Rc;
+use core::cell::RefCell;
 use core::marker::PhantomData;
 use std::collections::HashMap;
 
@@ -26,9 +28,34 @@ pub struct BlockifierStateAdapter<T: Config> {
     class_hash_update: usize,
     compiled_class_hash_update: usize,
     state_cache: StateCache,
+    contract_classes: ContractClassCache,
     _phantom: PhantomData<T>,
 }
 
+/// The classes read by the adapters executing the transactions of a runtime call, decoded from
+/// the storage once for all of them.
+///
+/// A declared class never changes, so the classes are kept for as long as the call lasts.
+#[derive(Clone, Default)]
+pub struct ContractClassCache(Rc<RefCell<BTreeMap<ClassHash, ContractClass>>>);
+
+impl ContractClassCache {
+    fn get(&self, class_hash: &ClassHash) -> Option<ContractClass> {
+        self.0.borrow().get(class_hash).cloned()
+    }
+
+    fn insert(&self, class_hash: ClassHash, contract_class: ContractClass) {
+        self.0.borrow_mut().insert(class_hash, contract_class);
+    }
+}
+
+impl<T: Config> BlockifierStateAdapter<T> {
+    /// Returns an adapter sharing the classes read by the other adapters of the runtime call.
+    pub fn with_contract_class_cache(contract_classes: ContractClassCache) -> Self {
+        Self { contract_classes, ..Default::default() }
+    }
+}
+
 impl<T> StateChanges for BlockifierStateAdapter<T>
 where
     T: Config,
@@ -52,31 +79,91 @@ impl<T: Config> Default for BlockifierStateAdapter<T> {
             class_hash_update: usize::default(),
             compiled_class_hash_update: usize::default(),
             state_cache: StateCache::default(),
+            contract_classes: ContractClassCache::default(),
             _phantom: PhantomData,
         }
     }
 }
 
+/// The reads missing from the local state fall back to the state of the forked chain, if any.
+/// The values found there are cached in the local state, the default ones being read as such
+/// anyway.
 impl<T: Config> StateReader for BlockifierStateAdapter<T> {
     fn get_storage_at(&mut self, contract_address: ContractAddress, key: StorageKey) -> StateResult<StarkFelt> {
         let contract_storage_key: ContractStorageKey = (contract_address, key);
-        Ok(Pallet::<T>::storage(contract_storage_key))
+        if let Ok(value) = crate::StorageView::<T>::try_get(contract_storage_key) {
+            return Ok(value);
+        }
+
+        let value = mp_fork::storage_at(contract_address, key).map_err(StateError::StateReadError)?;
+        match value {
+            Some(value) if value != StarkFelt::default() => {
+                crate::StorageView::<T>::insert(contract_storage_key, value);
+                Ok(value)
+            }
+            _ => Ok(StarkFelt::default()),
+        }
     }
 
     fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
-        Ok(Pallet::<T>::nonce(contract_address))
+        if let Ok(nonce) = crate::Nonces::<T>::try_get(contract_address) {
+            return Ok(nonce);
+        }
+
+        let nonce = mp_fork::nonce_at(contract_address).map_err(StateError::StateReadError)?;
+        match nonce {
+            Some(nonce) if nonce != Nonce::default() => {
+                crate::Nonces::<T>::insert(contract_address, nonce);
+                Ok(nonce)
+            }
+            _ => Ok(Nonce::default()),
+        }
     }
 
     fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
-        Ok(Pallet::<T>::contract_class_hash_by_address(contract_address))
+        if let Ok(class_hash) = crate::ContractClassHashes::<T>::try_get(contract_address) {
+            return Ok(class_hash);
+        }
+
+        let class_hash = mp_fork::class_hash_at(contract_address).map_err(StateError::StateReadError)?;
+        match class_hash {
+            Some(class_hash) if class_hash != ClassHash::default() => {
+                crate::ContractClassHashes::<T>::insert(contract_address, class_hash);
+                Ok(class_hash)
+            }
+            _ => Ok(ClassHash::default()),
+        }
     }
 
     fn get_compiled_contract_class(&mut self, class_hash: &ClassHash) -> StateResult<ContractClass> {
-        Pallet::<T>::contract_class_by_class_hash(class_hash).ok_or(StateError::UndeclaredClassHash(*class_hash))
+        if let Some(contract_class) = self.contract_classes.get(class_hash) {
+            return Ok(contract_class);
+        }
+
+        let contract_class = match Pallet::<T>::contract_class_by_class_hash(class_hash) {
+            Some(contract_class) => contract_class,
+            None => {
+                let contract_class = mp_fork::contract_class(*class_hash)
+                    .map_err(StateError::StateReadError)?
+                    .ok_or(StateError::UndeclaredClassHash(*class_hash))?;
+                crate::ContractClasses::<T>::insert(class_hash, contract_class.clone());
+                contract_class
+            }
+        };
+        self.contract_classes.insert(*class_hash, contract_class.clone());
+        Ok(contract_class)
     }
 
     fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
-        Pallet::<T>::compiled_class_hash_by_class_hash(class_hash).ok_or(StateError::UndeclaredClassHash(class_hash))
+        if let Some(compiled_class_hash) = Pallet::<T>::compiled_class_hash_by_class_hash(class_hash) {
+            return Ok(compiled_class_hash);
+        }
+
+        let compiled_class_hash = mp_fork::compiled_class_hash(class_hash)
+            .map_err(StateError::StateReadError)?
+            .ok_or(StateError::UndeclaredClassHash(class_hash))?;
+        crate::CompiledClassHashes::<T>::insert(class_hash, compiled_class_hash);
+        Ok(compiled_class_hash)
     }
 }
 
@@ -108,7 +195,8 @@ impl<T: Config> State for BlockifierStateAdapter<T> {
     }
 
     fn set_contract_class(&mut self, class_hash: &ClassHash, contract_class: ContractClass) -> StateResult<()> {
-        crate::ContractClasses::<T>::insert(class_hash, contract_class);
+        crate::ContractClasses::<T>::insert(class_hash, contract_class.clone());
+        self.contract_classes.insert(*class_hash, contract_class);
 
         Ok(())
     }
@@ -138,7 +226,7 @@ impl<T: Config> State for BlockifierStateAdapter<T> {
 }
 
 #[derive(Debug, Default, PartialEq)]
-struct StateCache {
+pub(crate) struct StateCache {
     // Reader's cached information; initial values, read before any write operation (per cell).
     nonce_initial_values: IndexMap<ContractAddress, Nonce>,
     class_hash_initial_values: IndexMap<ContractAddress, ClassHash>,
@@ -153,58 +241,71 @@ struct StateCache {
 }
 
 impl StateCache {
-    fn get_storage_at(&self, contract_address: ContractAddress, key: StorageKey) -> Option<&StarkFelt> {
+    pub(crate) fn get_storage_at(&self, contract_address: ContractAddress, key: StorageKey) -> Option<&StarkFelt> {
         let contract_storage_key = (contract_address, key);
         self.storage_writes
             .get(&contract_storage_key)
             .or_else(|| self.storage_initial_values.get(&contract_storage_key))
     }
 
-    fn get_nonce_at(&self, contract_address: ContractAddress) -> Option<&Nonce> {
+    pub(crate) fn get_nonce_at(&self, contract_address: ContractAddress) -> Option<&Nonce> {
         self.nonce_writes.get(&contract_address).or_else(|| self.nonce_initial_values.get(&contract_address))
     }
 
-    pub fn set_storage_initial_value(&mut self, contract_address: ContractAddress, key: StorageKey, value: StarkFelt) {
+    pub(crate) fn set_storage_initial_value(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        value: StarkFelt,
+    ) {
         let contract_storage_key = (contract_address, key);
         self.storage_initial_values.insert(contract_storage_key, value);
     }
 
-    fn set_storage_value(&mut self, contract_address: ContractAddress, key: StorageKey, value: StarkFelt) {
+    pub(crate) fn set_storage_value(&mut self, contract_address: ContractAddress, key: StorageKey, value: StarkFelt) {
         let contract_storage_key = (contract_address, key);
         self.storage_writes.insert(contract_storage_key, value);
     }
 
-    fn set_nonce_initial_value(&mut self, contract_address: ContractAddress, nonce: Nonce) {
+    pub(crate) fn set_nonce_initial_value(&mut self, contract_address: ContractAddress, nonce: Nonce) {
         self.nonce_initial_values.insert(contract_address, nonce);
     }
 
-    fn set_nonce_value(&mut self, contract_address: ContractAddress, nonce: Nonce) {
+    pub(crate) fn set_nonce_value(&mut self, contract_address: ContractAddress, nonce: Nonce) {
         self.nonce_writes.insert(contract_address, nonce);
     }
 
-    fn get_class_hash_at(&self, contract_address: ContractAddress) -> Option<&ClassHash> {
+    pub(crate) fn get_class_hash_at(&self, contract_address: ContractAddress) -> Option<&ClassHash> {
         self.class_hash_writes.get(&contract_address).or_else(|| self.class_hash_initial_values.get(&contract_address))
     }
 
-    fn set_class_hash_initial_value(&mut self, contract_address: ContractAddress, class_hash: ClassHash) {
+    pub(crate) fn set_class_hash_initial_value(&mut self, contract_address: ContractAddress, class_hash: ClassHash) {
         self.class_hash_initial_values.insert(contract_address, class_hash);
     }
 
-    fn set_class_hash_write(&mut self, contract_address: ContractAddress, class_hash: ClassHash) {
+    pub(crate) fn set_class_hash_write(&mut self, contract_address: ContractAddress, class_hash: ClassHash) {
         self.class_hash_writes.insert(contract_address, class_hash);
     }
 
-    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> Option<&CompiledClassHash> {
+    pub(crate) fn get_compiled_class_hash(&self, class_hash: ClassHash) -> Option<&CompiledClassHash> {
         self.compiled_class_hash_writes
             .get(&class_hash)
             .or_else(|| self.compiled_class_hash_initial_values.get(&class_hash))
     }
 
-    fn set_compiled_class_hash_initial_value(&mut self, class_hash: ClassHash, compiled_class_hash: CompiledClassHash) {
+    pub(crate) fn set_compiled_class_hash_initial_value(
+        &mut self,
+        class_hash: ClassHash,
+        compiled_class_hash: CompiledClassHash,
+    ) {
         self.compiled_class_hash_initial_values.insert(class_hash, compiled_class_hash);
     }
 
-    fn set_compiled_class_hash_write(&mut self, class_hash: ClassHash, compiled_class_hash: CompiledClassHash) {
+    pub(crate) fn set_compiled_class_hash_write(
+        &mut self,
+        class_hash: ClassHash,
+        compiled_class_hash: CompiledClassHash,
+    ) {
         self.compiled_class_hash_writes.insert(class_hash, compiled_class_hash);
     }
 
@@ -223,6 +324,18 @@ impl StateCache {
     fn get_compiled_class_hash_updates(&self) -> IndexMap<ClassHash, CompiledClassHash> {
         subtract_mappings(&self.compiled_class_hash_writes, &self.compiled_class_hash_initial_values)
     }
+
+    /// The values written which differ from the ones read before.
+    pub(crate) fn to_state_diff(&self) -> CommitmentStateDiff {
+        type StorageDiff = IndexMap<ContractAddress, IndexMap<StorageKey, StarkFelt>>;
+
+        CommitmentStateDiff {
+            address_to_class_hash: self.get_class_hash_updates(),
+            storage_updates: StorageDiff::from(StorageView(self.get_storage_updates())),
+            class_hash_to_compiled_class_hash: self.get_compiled_class_hash_updates(),
+            address_to_nonce: self.get_nonce_updates(),
+        }
+    }
 }
 
 pub struct CachedBlockifierStateAdapter<T: Config>(pub BlockifierStateAdapter<T>);
@@ -272,20 +385,7 @@ where
     }
 
     fn to_state_diff(&self) -> CommitmentStateDiff {
-        type StorageDiff = IndexMap<ContractAddress, IndexMap<StorageKey, StarkFelt>>;
-
-        let state_cache = &self.0.state_cache;
-        let class_hash_updates = state_cache.get_class_hash_updates();
-        let storage_diffs = state_cache.get_storage_updates();
-        let nonces = state_cache.get_nonce_updates();
-        let declared_classes = state_cache.get_compiled_class_hash_updates();
-
-        CommitmentStateDiff {
-            address_to_class_hash: class_hash_updates,
-            storage_updates: StorageDiff::from(StorageView(storage_diffs)),
-            class_hash_to_compiled_class_hash: declared_classes,
-            address_to_nonce: nonces,
-        }
+        self.0.state_cache.to_state_diff()
     }
 }
 
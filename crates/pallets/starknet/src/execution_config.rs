@@ -16,7 +16,7 @@ impl RuntimeExecutionConfigBuilder {
             disable_fee_charge: false,
             disable_validation: false,
             disable_nonce_validation: T::DisableNonceValidation::get(),
-            disable_transaction_fee: T::DisableTransactionFee::get(),
+            disable_transaction_fee: crate::Pallet::<T>::is_transaction_fee_disabled(),
             offset_version: false,
         })
     }
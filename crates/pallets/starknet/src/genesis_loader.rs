@@ -4,6 +4,7 @@ use blockifier::execution::contract_class::ContractClass as StarknetContractClas
 use mp_felt::Felt252Wrapper;
 use mp_genesis_config::ContractClass;
 pub use mp_genesis_config::{GenesisData, GenesisLoader, HexFelt, PredeployedAccount};
+use parity_scale_codec::Decode;
 
 use crate::GenesisConfig;
 
@@ -64,6 +65,17 @@ impl<T: crate::Config> From<GenesisLoader> for GenesisConfig<T> {
                 (key, value)
             })
             .collect::<Vec<_>>();
+        let nonces = loader
+            .data()
+            .nonces
+            .clone()
+            .into_iter()
+            .map(|(address, nonce)| {
+                let address = Felt252Wrapper(address.0).into();
+                let nonce = Felt252Wrapper(nonce.0).into();
+                (address, nonce)
+            })
+            .collect::<Vec<_>>();
         let fee_token_address = Felt252Wrapper(loader.data().fee_token_address.0).into();
 
         let chain_id = loader
@@ -76,13 +88,43 @@ impl<T: crate::Config> From<GenesisLoader> for GenesisConfig<T> {
             .try_into()
             .expect("Failed to convert chain id to felt");
 
+        let chain_parameters = &loader.data().chain_parameters;
+
+        let admin = loader.data().admin.as_ref().map(|admin| {
+            T::AccountId::decode(&mut &admin.0[..]).expect("Failed to decode the admin account of the genesis")
+        });
+        let sequencers = loader
+            .data()
+            .sequencers
+            .iter()
+            .map(|(authority, address)| {
+                let authority = T::AuthorityId::decode(&mut &authority.0[..])
+                    .expect("Failed to decode a sequencer authority of the genesis");
+                (authority, Felt252Wrapper(address.0).into())
+            })
+            .collect::<Vec<_>>();
+        let l1_messages =
+            loader.data().l1_messages.iter().map(|nonce| Felt252Wrapper(nonce.0).into()).collect::<Vec<_>>();
+
         GenesisConfig {
             contracts,
             contract_classes,
             sierra_to_casm_class_hash,
             storage,
+            nonces,
             fee_token_address,
             chain_id,
+            strk_fee_token_address: chain_parameters
+                .strk_fee_token_address
+                .map(|address| Felt252Wrapper(address.0).into()),
+            invoke_tx_max_n_steps: chain_parameters.invoke_tx_max_n_steps,
+            validate_max_n_steps: chain_parameters.validate_max_n_steps,
+            l1_gas_price: chain_parameters.l1_gas_price,
+            protocol_version: chain_parameters.protocol_version,
+            disable_transaction_fee: chain_parameters.disable_transaction_fee,
+            admin,
+            sequencers,
+            l1_messages,
             ..Default::default()
         }
     }
@@ -114,6 +156,7 @@ pub(crate) fn read_contract_class_from_json(json_str: &str, version: u8) -> Star
 
 #[cfg(test)]
 mod tests {
+    use mp_genesis_config::HexBytes;
     use starknet_crypto::FieldElement;
 
     use super::*;
@@ -147,8 +190,14 @@ mod tests {
             contracts: vec![(contract_address, class_hash)],
             predeployed_accounts: Vec::new(),
             storage: vec![((contract_address, storage_key), storage_value)],
+            nonces: Vec::new(),
+            sierra_classes: Vec::new(),
             fee_token_address,
             chain_id: String::from("MADARA"),
+            chain_parameters: Default::default(),
+            admin: None,
+            sequencers: Vec::new(),
+            l1_messages: Vec::new(),
         };
 
         // When
@@ -158,4 +207,47 @@ mod tests {
         let expected = r#"{"contract_classes":[["0x1",{"path":"cairo-contracts/ERC20.json","version":0}]],"sierra_class_hash_to_casm_class_hash":[["0x2a","0x1"]],"contracts":[["0x2","0x1"]],"predeployed_accounts":[],"storage":[[["0x2","0x3"],"0x4"]],"fee_token_address":"0x5","chain_id":"MADARA"}"#;
         assert_eq!(expected, serialized_loader);
     }
+
+    #[test]
+    fn test_predeployed_account_private_key_round_trip() {
+        // Given
+        let account = PredeployedAccount {
+            contract_address: FieldElement::from(2u8).into(),
+            class_hash: FieldElement::from(1u8).into(),
+            name: String::from("Argent Account"),
+            role: None,
+            private_key: Some(vec![0, 193, 207]),
+            public_key: FieldElement::from(3u8).into(),
+        };
+
+        // When
+        let serialized_account = serde_json::to_string(&account).unwrap();
+        let deserialized_account: PredeployedAccount = serde_json::from_str(&serialized_account).unwrap();
+        let bytes_account: PredeployedAccount = serde_json::from_str(
+            r#"{"contract_address":"0x2","class_hash":"0x1","name":"Argent Account","private_key":[0,193,207],"public_key":"0x3"}"#,
+        )
+        .unwrap();
+
+        // Then
+        assert_eq!(account, deserialized_account);
+        assert_eq!(account, bytes_account);
+    }
+
+    #[test]
+    fn test_runtime_accounts_round_trip() {
+        // Given
+        let json = r#"{"contract_classes":[],"sierra_class_hash_to_casm_class_hash":[],"contracts":[],"predeployed_accounts":[],"storage":[],"fee_token_address":"0x5","chain_id":"MADARA","admin":"0x0100000000000000","sequencers":[["0x0300000000000000","0x7"]],"l1_messages":["0x2"]}"#;
+
+        // When
+        let genesis_data: GenesisData = serde_json::from_str(json).unwrap();
+
+        // Then
+        assert_eq!(genesis_data.admin, Some(HexBytes(vec![1, 0, 0, 0, 0, 0, 0, 0])));
+        assert_eq!(
+            genesis_data.sequencers,
+            vec![(HexBytes(vec![3, 0, 0, 0, 0, 0, 0, 0]), FieldElement::from(7u8).into())]
+        );
+        assert_eq!(genesis_data.l1_messages, vec![FieldElement::from(2u8).into()]);
+        assert_eq!(json, serde_json::to_string(&genesis_data).unwrap());
+    }
 }
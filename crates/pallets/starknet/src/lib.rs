@@ -38,12 +38,22 @@
 /// Learn more about FRAME and the core library of Substrate FRAME pallets:
 /// <https://docs.substrate.io/reference/frame-pallets/>
 pub use pallet::*;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+/// Starknet block capacity accounting.
+pub mod block_limits;
 /// An adapter for the blockifier state related traits
 pub mod blockifier_state_adapter;
 /// The implementation of the execution configuration.
 pub mod execution_config;
 #[cfg(feature = "std")]
 pub mod genesis_loader;
+/// Storage migrations.
+pub mod migrations;
+/// Optimistic parallel execution of transactions.
+pub mod parallel_execution;
+/// Block parameters set by the node sealing the block.
+pub mod sealing;
 /// Simulation, estimations and execution trace logic.
 pub mod simulations;
 /// Transaction validation logic.
@@ -57,38 +67,46 @@ mod tests;
 #[macro_use]
 pub extern crate alloc;
 
-use alloc::collections::BTreeSet;
 use alloc::str::from_utf8_unchecked;
 use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 
+use block_limits::BlockResources;
 use blockifier::block_context::BlockContext;
 use blockifier::execution::contract_class::ContractClass;
 use blockifier::execution::entry_point::{
     CallEntryPoint, CallInfo, CallType, EntryPointExecutionContext, ExecutionResources,
 };
 use blockifier::execution::errors::{EntryPointExecutionError, PreExecutionError};
-use blockifier::state::cached_state::ContractStorageKey;
-use blockifier_state_adapter::BlockifierStateAdapter;
+use blockifier::state::cached_state::{ContractStorageKey, StateChangesCount};
+use blockifier::state::state_api::State;
+use blockifier::transaction::objects::TransactionExecutionInfo;
+use blockifier_state_adapter::{BlockifierStateAdapter, CachedBlockifierStateAdapter};
 use frame_support::pallet_prelude::*;
-use frame_support::traits::Time;
+use frame_support::traits::{FindAuthor, Time};
 use frame_system::pallet_prelude::*;
 use mp_block::{Block as StarknetBlock, Header as StarknetHeader};
 use mp_digest_log::MADARA_ENGINE_ID;
-use mp_fee::{ResourcePrice, INITIAL_GAS};
+use mp_fee::{PriceUnit, ResourcePrice, INITIAL_GAS};
 use mp_felt::Felt252Wrapper;
 use mp_hashers::HasherT;
-use mp_sequencer_address::{InherentError, InherentType, DEFAULT_SEQUENCER_ADDRESS, INHERENT_IDENTIFIER};
+use mp_sequencer_address::{
+    InherentError, InherentType, BLOCK_DIGEST_INHERENT_IDENTIFIER, DEFAULT_SEQUENCER_ADDRESS, INHERENT_IDENTIFIER,
+};
+use mp_simulations::StateOverrides;
+use mp_state::{BlockStateDiff, StateChanges};
 use mp_storage::{StarknetStorageSchemaVersion, PALLET_STARKNET_SCHEMA};
 use mp_transactions::execution::Execute;
+use mp_transactions::getters::TransactionVersion;
 use mp_transactions::{
-    DeclareTransaction, DeployAccountTransaction, HandleL1MessageTransaction, InvokeTransaction, Transaction,
-    UserOrL1HandlerTransaction, UserTransaction,
+    DeclareTransaction, DeployAccountTransaction, HandleL1MessageTransaction, ImportedTransaction, InvokeTransaction,
+    Transaction, UserOrL1HandlerTransaction, UserTransaction,
 };
+use sealing::SealingParameters;
 use sp_runtime::traits::UniqueSaturatedInto;
-use sp_runtime::DigestItem;
-use starknet_api::api_core::{ChainId, CompiledClassHash, ContractAddress, EntryPointSelector, Nonce};
+use sp_runtime::{Digest, DigestItem};
+use starknet_api::api_core::{ChainId, ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, Nonce};
 use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::{StarkFelt, StarkHash};
@@ -126,7 +144,11 @@ pub mod pallet {
 
     use super::*;
 
+    /// The in-code storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
@@ -134,11 +156,15 @@ pub mod pallet {
     /// mechanism and comply with starknet which uses an ER20 as fee token
     #[pallet::config]
     pub trait Config: frame_system::Config {
+        /// The overarching event type.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// The origin allowed to update the chain parameters.
+        type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
         /// The hashing function to use.
         type SystemHash: HasherT;
         /// The block time
         type TimestampProvider: Time;
-        /// The gas price
+        /// The gas price, until updated with `set_l1_gas_price`
         #[pallet::constant]
         type L1GasPrice: Get<ResourcePrice>;
         /// A configuration for base priority of unsigned transactions.
@@ -153,21 +179,52 @@ pub mod pallet {
         /// set how long transactions are kept in the mempool.
         #[pallet::constant]
         type TransactionLongevity: Get<TransactionLongevity>;
-        /// A bool to disable transaction fees and make all transactions free
+        /// The maximum number of transactions an account can have in the mempool.
+        ///
+        /// Transactions whose nonce is this far or further ahead of the account's nonce are
+        /// rejected, which keeps accounts from filling the future queue.
+        #[pallet::constant]
+        type MaxPendingTransactionsPerAccount: Get<u64>;
+        /// A bool to disable transaction fees and make all transactions free, until updated with
+        /// `set_disable_transaction_fee`
         #[pallet::constant]
         type DisableTransactionFee: Get<bool>;
         /// A bool to disable Nonce validation
         type DisableNonceValidation: Get<bool>;
+        /// The maximum number of steps of an invoke transaction, until updated with
+        /// `set_invoke_tx_max_n_steps`
         #[pallet::constant]
         type InvokeTxMaxNSteps: Get<u32>;
+        /// The maximum number of steps of a validation, until updated with
+        /// `set_validate_max_n_steps`
         #[pallet::constant]
         type ValidateMaxNSteps: Get<u32>;
+        /// The protocol version written in the block headers, until updated with
+        /// `set_protocol_version`
         #[pallet::constant]
         type ProtocolVersion: Get<u8>;
         #[pallet::constant]
         type MaxRecursionDepth: Get<u32>;
         #[pallet::constant]
         type ProgramHash: Get<Felt252Wrapper>;
+        /// The Starknet resources a block can hold, whatever the budgets set by the node sealing
+        /// it.
+        ///
+        /// A transaction is only included in a block if its resources fit in what is left of both.
+        #[pallet::constant]
+        type MaxBlockResources: Get<BlockResources>;
+        /// The number of blocks the events, messages to L1 and revert errors of the transactions
+        /// are kept in the state for, or `None` to keep them forever.
+        ///
+        /// The client copies them to its own database when importing a block.
+        #[pallet::constant]
+        type TxOutputsRetention: Get<Option<u64>>;
+        /// The identifier of the block authors, e.g. their Aura authority id.
+        type AuthorityId: Member + Parameter + MaybeSerializeDeserialize;
+        /// Finds the author of the current block from its pre-runtime digests.
+        ///
+        /// The author's address in the sequencer registry receives the fees of the block.
+        type FindAuthor: FindAuthor<Self::AuthorityId>;
     }
 
     /// The Starknet pallet hooks.
@@ -179,6 +236,8 @@ pub mod pallet {
         /// The block is being finalized.
         fn on_finalize(_n: BlockNumberFor<T>) {
             assert!(SeqAddrUpdate::<T>::take(), "Sequencer address must be set for the block");
+            BlockResourcesUsed::<T>::kill();
+            BlockSealingParameters::<T>::kill();
 
             // Create a new Starknet block and store it.
             <Pallet<T>>::store_block(UniqueSaturatedInto::<u64>::unique_saturated_into(
@@ -186,14 +245,17 @@ pub mod pallet {
             ));
         }
 
-        /// The block is being initialized. Implement to have something happen.
-        fn on_initialize(_: BlockNumberFor<T>) -> Weight {
-            Weight::zero()
-        }
-
-        /// Perform a module upgrade.
-        fn on_runtime_upgrade() -> Weight {
-            Weight::zero()
+        /// The block is being initialized.
+        ///
+        /// Prunes the transaction outputs of the block leaving the retention window.
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let block_number = UniqueSaturatedInto::<u64>::unique_saturated_into(n);
+            match T::TxOutputsRetention::get() {
+                Some(retention) if block_number > retention => {
+                    Self::prune_transaction_outputs(block_number - retention - 1)
+                }
+                _ => Weight::zero(),
+            }
         }
     }
 
@@ -226,6 +288,14 @@ pub mod pallet {
     #[pallet::unbounded]
     #[pallet::getter(fn tx_revert_error)]
     pub(super) type TxRevertError<T: Config> = StorageMap<_, Identity, TransactionHash, String, OptionQuery>;
+
+    /// Mapping from block number to the hashes of its transactions, for the blocks whose
+    /// transaction outputs have not been pruned yet.
+    /// Only written when `TxOutputsRetention` is set.
+    #[pallet::storage]
+    #[pallet::unbounded]
+    #[pallet::getter(fn block_transaction_hashes)]
+    pub(super) type BlockTransactionHashes<T: Config> = StorageMap<_, Identity, u64, Vec<TransactionHash>, ValueQuery>;
     /// The Starknet pallet storage items.
     /// STORAGE
     /// Mapping of contract address to state root.
@@ -305,6 +375,12 @@ pub mod pallet {
     #[pallet::getter(fn fee_token_address)]
     pub(super) type FeeTokenAddress<T: Config> = StorageValue<_, ContractAddress, ValueQuery>;
 
+    /// The address of the fee token ERC20 contract of V3 transactions, priced in fri.
+    #[pallet::storage]
+    #[pallet::unbounded]
+    #[pallet::getter(fn strk_fee_token_address)]
+    pub(super) type StrkFeeTokenAddress<T: Config> = StorageValue<_, ContractAddress, OptionQuery>;
+
     /// Current sequencer address.
     #[pallet::storage]
     #[pallet::unbounded]
@@ -317,12 +393,70 @@ pub mod pallet {
     #[pallet::getter(fn seq_addr_update)]
     pub type SeqAddrUpdate<T: Config> = StorageValue<_, bool, ValueQuery>;
 
+    /// The account allowed to update the chain parameters through [`EnsureAdmin`], if any.
+    #[pallet::storage]
+    #[pallet::getter(fn admin)]
+    pub type Admin<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
+
+    /// Sequencer registry, mapping the block authors to the address receiving their fees.
+    ///
+    /// Authors missing from the registry use the address of their sequencer address inherent.
+    #[pallet::storage]
+    #[pallet::unbounded]
+    #[pallet::getter(fn registered_sequencer)]
+    pub(super) type Sequencers<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AuthorityId, ContractAddress, OptionQuery>;
+
+    /// Starknet resources consumed by the transactions of the current block.
+    #[pallet::storage]
+    #[pallet::getter(fn block_resources_used)]
+    pub(super) type BlockResourcesUsed<T: Config> = StorageValue<_, BlockResources, ValueQuery>;
+
+    /// The parameters set by the node sealing the current block.
+    #[pallet::storage]
+    #[pallet::getter(fn sealing_parameters)]
+    pub(super) type BlockSealingParameters<T: Config> = StorageValue<_, SealingParameters, ValueQuery>;
+
     /// Information about processed L1 Messages
     /// Based on Nonce value.
+    /// `Twox64Concat` is fine here as the nonces are assigned by the L1 core contract.
     #[pallet::storage]
     #[pallet::unbounded]
-    #[pallet::getter(fn l1_messages)]
-    pub(super) type L1Messages<T: Config> = StorageValue<_, BTreeSet<Nonce>, ValueQuery>;
+    pub(super) type L1Messages<T: Config> = StorageMap<_, Twox64Concat, Nonce, (), OptionQuery>;
+
+    /// The maximum number of steps of an invoke transaction.
+    #[pallet::storage]
+    #[pallet::getter(fn invoke_tx_max_n_steps)]
+    pub type InvokeTxMaxNSteps<T: Config> = StorageValue<_, u32, ValueQuery, T::InvokeTxMaxNSteps>;
+
+    /// The maximum number of steps of a transaction validation.
+    #[pallet::storage]
+    #[pallet::getter(fn validate_max_n_steps)]
+    pub type ValidateMaxNSteps<T: Config> = StorageValue<_, u32, ValueQuery, T::ValidateMaxNSteps>;
+
+    /// The L1 gas price used to compute the transaction fees.
+    #[pallet::storage]
+    #[pallet::unbounded]
+    #[pallet::getter(fn l1_gas_price)]
+    pub type L1GasPrice<T: Config> = StorageValue<_, ResourcePrice, ValueQuery, T::L1GasPrice>;
+
+    /// The protocol version written in the block headers.
+    #[pallet::storage]
+    #[pallet::getter(fn protocol_version)]
+    pub type ProtocolVersion<T: Config> = StorageValue<_, u8, ValueQuery, T::ProtocolVersion>;
+
+    /// The global state root written in the block headers.
+    ///
+    /// The state root is not computed: it is the last one known, the one announced along with the
+    /// last state diff imported from another sequencer, zero otherwise.
+    #[pallet::storage]
+    #[pallet::getter(fn global_state_root)]
+    pub type GlobalStateRoot<T: Config> = StorageValue<_, StarkHash, ValueQuery>;
+
+    /// Whether transactions are free.
+    #[pallet::storage]
+    #[pallet::getter(fn is_transaction_fee_disabled)]
+    pub type DisableTransactionFee<T: Config> = StorageValue<_, bool, ValueQuery, T::DisableTransactionFee>;
 
     /// ChainID for the palle'a, 'a, t startknet
     #[pallet::storage]
@@ -355,12 +489,29 @@ pub mod pallet {
         /// contracts classes.
         pub contract_classes: Vec<(SierraClassHash, ContractClass)>,
         pub storage: Vec<(ContractStorageKey, StarkFelt)>,
+        /// The nonces of the contracts, for a genesis restoring the state of another chain.
+        pub nonces: Vec<(ContractAddress, Nonce)>,
         /// The address of the fee token.
         /// Must be set to the address of the fee token ERC20 contract.
         pub fee_token_address: ContractAddress,
         /// Chain Id, this must be set in the genesis file
         /// The default value will be MADARA custom chain id
         pub chain_id: Felt252Wrapper,
+        /// The account allowed to update the chain parameters, see [`EnsureAdmin`].
+        pub admin: Option<T::AccountId>,
+        /// The address of the fee token ERC20 contract of V3 transactions, if they are accepted.
+        pub strk_fee_token_address: Option<ContractAddress>,
+        /// The chain parameters, the ones left unset use the value of the runtime config.
+        pub invoke_tx_max_n_steps: Option<u32>,
+        pub validate_max_n_steps: Option<u32>,
+        pub l1_gas_price: Option<ResourcePrice>,
+        pub protocol_version: Option<u8>,
+        pub disable_transaction_fee: Option<bool>,
+        /// The sequencer registry, see [`Sequencers`].
+        pub sequencers: Vec<(T::AuthorityId, ContractAddress)>,
+        /// The nonces of the L1 messages already handled, for a genesis restoring the state of
+        /// another chain.
+        pub l1_messages: Vec<Nonce>,
         pub _phantom: PhantomData<T>,
     }
 
@@ -372,8 +523,18 @@ pub mod pallet {
                 sierra_to_casm_class_hash: vec![],
                 contract_classes: vec![],
                 storage: vec![],
+                nonces: vec![],
                 fee_token_address: ContractAddress::default(),
                 chain_id: DefaultChainId::get(),
+                admin: None,
+                strk_fee_token_address: None,
+                invoke_tx_max_n_steps: None,
+                validate_max_n_steps: None,
+                l1_gas_price: None,
+                protocol_version: None,
+                disable_transaction_fee: None,
+                sequencers: vec![],
+                l1_messages: vec![],
                 _phantom: PhantomData,
             }
         }
@@ -415,15 +576,105 @@ pub mod pallet {
                 StorageView::<T>::insert(key, value);
             }
 
+            for (address, nonce) in self.nonces.iter() {
+                Nonces::<T>::insert(address, nonce);
+            }
+
             LastKnownEthBlock::<T>::set(None);
             // Set the fee token address from the genesis config.
             FeeTokenAddress::<T>::set(self.fee_token_address);
             SeqAddrUpdate::<T>::put(true);
 
-            ChainIdStorage::<T>::put(self.chain_id)
+            ChainIdStorage::<T>::put(self.chain_id);
+
+            if let Some(admin) = &self.admin {
+                // The admin signs its calls without owning any balance
+                frame_system::Pallet::<T>::inc_providers(admin);
+                Admin::<T>::put(admin);
+            }
+            StrkFeeTokenAddress::<T>::set(self.strk_fee_token_address);
+            if let Some(max_n_steps) = self.invoke_tx_max_n_steps {
+                InvokeTxMaxNSteps::<T>::put(max_n_steps);
+            }
+            if let Some(max_n_steps) = self.validate_max_n_steps {
+                ValidateMaxNSteps::<T>::put(max_n_steps);
+            }
+            if let Some(gas_price) = self.l1_gas_price {
+                L1GasPrice::<T>::put(gas_price);
+            }
+            if let Some(protocol_version) = self.protocol_version {
+                ProtocolVersion::<T>::put(protocol_version);
+            }
+            if let Some(disabled) = self.disable_transaction_fee {
+                DisableTransactionFee::<T>::put(disabled);
+            }
+            <Pallet<T>>::store_default_chain_parameters();
+
+            for (authority, sequencer_address) in self.sequencers.iter() {
+                assert!(
+                    !Sequencers::<T>::contains_key(authority),
+                    "Authority {:?} is registered more than once",
+                    authority,
+                );
+                Sequencers::<T>::insert(authority, sequencer_address);
+            }
+
+            for nonce in self.l1_messages.iter() {
+                L1Messages::<T>::insert(nonce, ());
+            }
         }
     }
 
+    /// The Starknet pallet events.
+    /// EVENTS
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        FeeTokenAddressChanged {
+            old_fee_token_address: ContractAddress,
+            new_fee_token_address: ContractAddress,
+        },
+        StrkFeeTokenAddressChanged {
+            old_fee_token_address: Option<ContractAddress>,
+            new_fee_token_address: Option<ContractAddress>,
+        },
+        ChainIdChanged {
+            old_chain_id: Felt252Wrapper,
+            new_chain_id: Felt252Wrapper,
+        },
+        InvokeTxMaxNStepsChanged {
+            old_max_n_steps: u32,
+            new_max_n_steps: u32,
+        },
+        ValidateMaxNStepsChanged {
+            old_max_n_steps: u32,
+            new_max_n_steps: u32,
+        },
+        L1GasPriceChanged {
+            old_gas_price: ResourcePrice,
+            new_gas_price: ResourcePrice,
+        },
+        ProtocolVersionChanged {
+            old_protocol_version: u8,
+            new_protocol_version: u8,
+        },
+        TransactionFeeDisabledChanged {
+            disabled: bool,
+        },
+        AdminChanged {
+            old_admin: Option<T::AccountId>,
+            new_admin: Option<T::AccountId>,
+        },
+        SequencerAdded {
+            authority: T::AuthorityId,
+            sequencer_address: ContractAddress,
+        },
+        SequencerRemoved {
+            authority: T::AuthorityId,
+            sequencer_address: ContractAddress,
+        },
+    }
+
     /// The Starknet pallet custom errors.
     /// ERRORS
     #[pallet::error]
@@ -450,7 +701,14 @@ pub mod pallet {
         MissingCallInfo,
         FailedToCreateATransactionalStorageExecution,
         L1MessageAlreadyExecuted,
+        FeeTokenNotConfigured,
         MissingL1GasUsage,
+        InvalidStateOverride,
+        SequencerAlreadyRegistered,
+        SequencerNotRegistered,
+        UnregisteredSequencerAddress,
+        StateEditsNotAllowed,
+        StateImportNotAllowed,
     }
 
     /// The Starknet pallet external functions.
@@ -479,11 +737,33 @@ pub mod pallet {
 
             let addr = StarkFelt::new(addr).map_err(|_| Error::<T>::SequencerAddressNotValid)?;
             let addr = ContractAddress(addr.try_into().map_err(|_| Error::<T>::SequencerAddressNotValid)?);
+            if let Some(registered_addr) = Self::block_author_sequencer_address() {
+                ensure!(addr == registered_addr, Error::<T>::UnregisteredSequencerAddress);
+            }
             SequencerAddress::<T>::put(addr);
             SeqAddrUpdate::<T>::put(true);
             Ok(())
         }
 
+        /// Set the current block author's sequencer address, along with the parameters of the node
+        /// sealing the block.
+        ///
+        /// Either this call or `set_sequencer_address`, which keeps the default parameters, should
+        /// be invoked exactly once per block.
+        ///
+        /// The dispatch origin for this call must be `Inherent`.
+        #[pallet::call_index(19)]
+        #[pallet::weight((0, DispatchClass::Mandatory))]
+        pub fn set_block_parameters(
+            origin: OriginFor<T>,
+            addr: [u8; 32],
+            sealing_parameters: SealingParameters,
+        ) -> DispatchResult {
+            Self::set_sequencer_address(origin, addr)?;
+            BlockSealingParameters::<T>::put(sealing_parameters);
+            Ok(())
+        }
+
         /// The invoke transaction is the main transaction type used to invoke contract functions in
         /// Starknet.
         /// See `https://docs.starknet.io/documentation/architecture_and_concepts/Blocks/transactions/#invoke_transaction`.
@@ -514,16 +794,22 @@ pub mod pallet {
             ensure!(ContractClassHashes::<T>::contains_key(sender_address), Error::<T>::AccountNotDeployed);
 
             // Execute
-            let tx_execution_infos = transaction
-                .execute(
-                    &mut BlockifierStateAdapter::<T>::default(),
-                    &Self::get_block_context(),
-                    &RuntimeExecutionConfigBuilder::new::<T>().build(),
-                )
+            let block_context = Self::get_block_context_in(PriceUnit::for_tx_version(input_transaction.version()))?;
+            let mut state = BlockifierStateAdapter::<T>::default();
+            let execution_config = RuntimeExecutionConfigBuilder::new::<T>().build();
+            // Sponsored transactions are validated and paid for by their paymaster
+            let tx_execution_infos =
+                match input_transaction.into_sponsored_executable::<T::SystemHash>(chain_id, false) {
+                    Some(sponsored_transaction) => {
+                        sponsored_transaction.execute(&mut state, &block_context, &execution_config)
+                    }
+                    None => transaction.execute(&mut state, &block_context, &execution_config),
+                }
                 .map_err(|e| {
                     log::error!("failed to execute invoke tx: {:?}", e);
                     Error::<T>::TransactionExecutionFailed
                 })?;
+            Self::record_block_resources(&tx_execution_infos, state.count_state_changes());
 
             let tx_hash = transaction.tx_hash;
             Self::emit_and_store_tx_and_fees_events(
@@ -575,13 +861,12 @@ pub mod pallet {
             );
 
             // Execute
+            let block_context = Self::get_block_context_in(PriceUnit::for_tx_version(input_transaction.version()))?;
+            let mut state = BlockifierStateAdapter::<T>::default();
             let tx_execution_infos = transaction
-                .execute(
-                    &mut BlockifierStateAdapter::<T>::default(),
-                    &Self::get_block_context(),
-                    &RuntimeExecutionConfigBuilder::new::<T>().build(),
-                )
+                .execute(&mut state, &block_context, &RuntimeExecutionConfigBuilder::new::<T>().build())
                 .map_err(|_| Error::<T>::TransactionExecutionFailed)?;
+            Self::record_block_resources(&tx_execution_infos, state.count_state_changes());
 
             let tx_hash = transaction.tx_hash();
             Self::emit_and_store_tx_and_fees_events(
@@ -627,16 +912,15 @@ pub mod pallet {
             );
 
             // Execute
+            let block_context = Self::get_block_context_in(PriceUnit::for_tx_version(input_transaction.version()))?;
+            let mut state = BlockifierStateAdapter::<T>::default();
             let tx_execution_infos = transaction
-                .execute(
-                    &mut BlockifierStateAdapter::<T>::default(),
-                    &Self::get_block_context(),
-                    &RuntimeExecutionConfigBuilder::new::<T>().build(),
-                )
+                .execute(&mut state, &block_context, &RuntimeExecutionConfigBuilder::new::<T>().build())
                 .map_err(|e| {
                     log::error!("failed to deploy account: {:?}", e);
                     Error::<T>::TransactionExecutionFailed
                 })?;
+            Self::record_block_resources(&tx_execution_infos, state.count_state_changes());
 
             let tx_hash = transaction.tx_hash;
             Self::emit_and_store_tx_and_fees_events(
@@ -688,19 +972,17 @@ pub mod pallet {
             // Store information about message being processed
             // The next instruction executes the message
             // Either successfully  or not
-            L1Messages::<T>::mutate(|nonces| nonces.insert(nonce));
+            L1Messages::<T>::insert(nonce, ());
 
             // Execute
+            let mut state = BlockifierStateAdapter::<T>::default();
             let tx_execution_infos = transaction
-                .execute(
-                    &mut BlockifierStateAdapter::<T>::default(),
-                    &Self::get_block_context(),
-                    &RuntimeExecutionConfigBuilder::new::<T>().build(),
-                )
+                .execute(&mut state, &Self::get_block_context(), &RuntimeExecutionConfigBuilder::new::<T>().build())
                 .map_err(|e| {
                     log::error!("Failed to consume l1 message: {}", e);
                     Error::<T>::TransactionExecutionFailed
                 })?;
+            Self::record_block_resources(&tx_execution_infos, state.count_state_changes());
 
             let tx_hash = transaction.tx_hash;
             Self::emit_and_store_tx_and_fees_events(
@@ -716,6 +998,252 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Set the address of the fee token ERC20 contract.
+        ///
+        /// This changes the config hash of the chain, which has to be updated on the settlement
+        /// layer accordingly.
+        ///
+        /// The dispatch origin for this call must be `AdminOrigin`.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_fee_token_address(origin: OriginFor<T>, fee_token_address: ContractAddress) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let old_fee_token_address = FeeTokenAddress::<T>::get();
+            FeeTokenAddress::<T>::put(fee_token_address);
+            Self::deposit_event(Event::FeeTokenAddressChanged {
+                old_fee_token_address,
+                new_fee_token_address: fee_token_address,
+            });
+
+            Ok(())
+        }
+
+        /// Set the address of the fee token ERC20 contract of V3 transactions, or unset it to
+        /// reject them.
+        ///
+        /// The dispatch origin for this call must be `AdminOrigin`.
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_strk_fee_token_address(
+            origin: OriginFor<T>,
+            fee_token_address: Option<ContractAddress>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let old_fee_token_address = StrkFeeTokenAddress::<T>::get();
+            StrkFeeTokenAddress::<T>::set(fee_token_address);
+            Self::deposit_event(Event::StrkFeeTokenAddressChanged {
+                old_fee_token_address,
+                new_fee_token_address: fee_token_address,
+            });
+
+            Ok(())
+        }
+
+        /// Set the chain id.
+        ///
+        /// This changes the hash of every transaction signed from now on, as well as the config
+        /// hash of the chain, which has to be updated on the settlement layer accordingly.
+        ///
+        /// The dispatch origin for this call must be `AdminOrigin`.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_chain_id(origin: OriginFor<T>, chain_id: Felt252Wrapper) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let old_chain_id = ChainIdStorage::<T>::get();
+            ChainIdStorage::<T>::put(chain_id);
+            Self::deposit_event(Event::ChainIdChanged { old_chain_id, new_chain_id: chain_id });
+
+            Ok(())
+        }
+
+        /// Set the maximum number of steps of an invoke transaction.
+        ///
+        /// The dispatch origin for this call must be `AdminOrigin`.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_invoke_tx_max_n_steps(origin: OriginFor<T>, max_n_steps: u32) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let old_max_n_steps = InvokeTxMaxNSteps::<T>::get();
+            InvokeTxMaxNSteps::<T>::put(max_n_steps);
+            Self::deposit_event(Event::InvokeTxMaxNStepsChanged { old_max_n_steps, new_max_n_steps: max_n_steps });
+
+            Ok(())
+        }
+
+        /// Set the maximum number of steps of a transaction validation.
+        ///
+        /// The dispatch origin for this call must be `AdminOrigin`.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_validate_max_n_steps(origin: OriginFor<T>, max_n_steps: u32) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let old_max_n_steps = ValidateMaxNSteps::<T>::get();
+            ValidateMaxNSteps::<T>::put(max_n_steps);
+            Self::deposit_event(Event::ValidateMaxNStepsChanged { old_max_n_steps, new_max_n_steps: max_n_steps });
+
+            Ok(())
+        }
+
+        /// Set the L1 gas price used to compute the transaction fees.
+        ///
+        /// The dispatch origin for this call must be `AdminOrigin`.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_l1_gas_price(origin: OriginFor<T>, gas_price: ResourcePrice) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let old_gas_price = L1GasPrice::<T>::get();
+            L1GasPrice::<T>::put(gas_price);
+            Self::deposit_event(Event::L1GasPriceChanged { old_gas_price, new_gas_price: gas_price });
+
+            Ok(())
+        }
+
+        /// Set the protocol version written in the block headers.
+        ///
+        /// The dispatch origin for this call must be `AdminOrigin`.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_protocol_version(origin: OriginFor<T>, protocol_version: u8) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let old_protocol_version = ProtocolVersion::<T>::get();
+            ProtocolVersion::<T>::put(protocol_version);
+            Self::deposit_event(Event::ProtocolVersionChanged {
+                old_protocol_version,
+                new_protocol_version: protocol_version,
+            });
+
+            Ok(())
+        }
+
+        /// Enable or disable the transaction fees.
+        ///
+        /// The dispatch origin for this call must be `AdminOrigin`.
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::DbWeight::get().writes(1))]
+        pub fn set_disable_transaction_fee(origin: OriginFor<T>, disabled: bool) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            DisableTransactionFee::<T>::put(disabled);
+            Self::deposit_event(Event::TransactionFeeDisabledChanged { disabled });
+
+            Ok(())
+        }
+
+        /// Set the account allowed to update the chain parameters, or unset it to only allow root.
+        ///
+        /// The dispatch origin for this call must be `AdminOrigin`.
+        #[pallet::call_index(17)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_admin(origin: OriginFor<T>, new_admin: Option<T::AccountId>) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let old_admin = Admin::<T>::get();
+            Admin::<T>::set(new_admin.clone());
+            Self::deposit_event(Event::AdminChanged { old_admin, new_admin });
+
+            Ok(())
+        }
+
+        /// Register the address receiving the fees of the blocks authored by `authority`.
+        ///
+        /// The dispatch origin for this call must be `AdminOrigin`.
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn add_sequencer(
+            origin: OriginFor<T>,
+            authority: T::AuthorityId,
+            sequencer_address: ContractAddress,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            ensure!(!Sequencers::<T>::contains_key(&authority), Error::<T>::SequencerAlreadyRegistered);
+            Sequencers::<T>::insert(&authority, sequencer_address);
+            Self::deposit_event(Event::SequencerAdded { authority, sequencer_address });
+
+            Ok(())
+        }
+
+        /// Remove `authority` from the sequencer registry.
+        ///
+        /// Its blocks use the address of the sequencer address inherent again.
+        ///
+        /// The dispatch origin for this call must be `AdminOrigin`.
+        #[pallet::call_index(14)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn remove_sequencer(origin: OriginFor<T>, authority: T::AuthorityId) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let sequencer_address = Sequencers::<T>::take(&authority).ok_or(Error::<T>::SequencerNotRegistered)?;
+            Self::deposit_event(Event::SequencerRemoved { authority, sequencer_address });
+
+            Ok(())
+        }
+
+        /// Write the given nonces, class hashes, fee token balances and storage values to the
+        /// state, e.g. to set up the state of a development chain.
+        ///
+        /// The dispatch origin for this call must be `None`, and it is only accepted in the pool
+        /// from the local node. It fails unless the node sealing the block allows state edits.
+        #[pallet::call_index(15)]
+        #[pallet::weight((0, DispatchClass::Operational))]
+        pub fn apply_state_edits(origin: OriginFor<T>, state_edits: StateOverrides) -> DispatchResult {
+            ensure_none(origin)?;
+            ensure!(Self::sealing_parameters().allow_state_edits, Error::<T>::StateEditsNotAllowed);
+
+            Self::write_state_overrides(state_edits)
+        }
+
+        /// Add `amount` to the fee token balance of `address` and to the fee token total supply,
+        /// e.g. to fund the accounts of a development chain.
+        ///
+        /// The dispatch origin for this call must be `None`, and it is only accepted in the pool
+        /// from the local node. It fails unless the node sealing the block allows state edits.
+        #[pallet::call_index(18)]
+        #[pallet::weight((0, DispatchClass::Operational))]
+        pub fn mint(origin: OriginFor<T>, address: ContractAddress, amount: StarkFelt) -> DispatchResult {
+            ensure_none(origin)?;
+            ensure!(Self::sealing_parameters().allow_state_edits, Error::<T>::StateEditsNotAllowed);
+
+            Self::mint_fee_token(address, amount)
+        }
+
+        /// Write the state diff of a block imported from another sequencer, instead of executing
+        /// its transactions.
+        ///
+        /// The transactions of the block are stored along with the outputs of their execution by
+        /// the sequencer, they are part of the Starknet block as if they had been executed. The
+        /// state root announced by the sequencer is kept as the one of the block.
+        ///
+        /// The dispatch origin for this call must be `None`, and it is only accepted when the node
+        /// sealing the block allows state imports. It is never accepted in the pool, only pushed by
+        /// the node to the blocks it imports.
+        #[pallet::call_index(16)]
+        #[pallet::weight((0, DispatchClass::Operational))]
+        pub fn import_state_diff(
+            origin: OriginFor<T>,
+            state_diff: BlockStateDiff,
+            transactions: Vec<ImportedTransaction>,
+            state_root: StarkHash,
+        ) -> DispatchResult {
+            ensure_none(origin)?;
+            ensure!(Self::sealing_parameters().allow_state_import, Error::<T>::StateImportNotAllowed);
+
+            Self::write_state_diff(state_diff);
+            GlobalStateRoot::<T>::put(state_root);
+            for transaction in transactions {
+                Self::store_imported_transaction(transaction);
+            }
+
+            Ok(())
+        }
     }
 
     #[pallet::inherent]
@@ -725,15 +1253,58 @@ pub mod pallet {
         const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
 
         fn create_inherent(data: &InherentData) -> Option<Self::Call> {
-            let inherent_data = data
-                .get_data::<InherentType>(&INHERENT_IDENTIFIER)
-                .expect("Sequencer address inherent data not correctly encoded")
-                .unwrap_or(DEFAULT_SEQUENCER_ADDRESS);
-            Some(Call::set_sequencer_address { addr: inherent_data })
+            // The registered address of the block author takes precedence over the local one
+            let addr = match Self::block_author_sequencer_address() {
+                Some(registered_addr) => Felt252Wrapper::from(registered_addr).0.to_bytes_be(),
+                None => data
+                    .get_data::<InherentType>(&INHERENT_IDENTIFIER)
+                    .expect("Sequencer address inherent data not correctly encoded")
+                    .unwrap_or(DEFAULT_SEQUENCER_ADDRESS),
+            };
+            let sealing_parameters = data
+                .get_data::<SealingParameters>(&sealing::INHERENT_IDENTIFIER)
+                .expect("Sealing parameters inherent data not correctly encoded")
+                .unwrap_or_default();
+
+            Some(Call::set_block_parameters { addr, sealing_parameters })
+        }
+
+        fn check_inherent(call: &Self::Call, data: &InherentData) -> Result<(), Self::Error> {
+            let addr = match call {
+                Call::set_sequencer_address { addr } => addr,
+                Call::set_block_parameters { addr, sealing_parameters } => {
+                    // The budgets only matter to the author, but the state changes have to be
+                    // allowed by the checking node as well
+                    let allowed = data
+                        .get_data::<SealingParameters>(&sealing::INHERENT_IDENTIFIER)
+                        .ok()
+                        .flatten()
+                        .unwrap_or_default();
+                    if sealing_parameters.allows_more_than(&allowed) {
+                        return Err(InherentError::UnallowedStateChanges);
+                    }
+                    addr
+                }
+                _ => return Ok(()),
+            };
+
+            // The digest of the checked block is given by the runtime, the one of the block being
+            // built otherwise
+            let digest = data
+                .get_data::<Digest>(&BLOCK_DIGEST_INHERENT_IDENTIFIER)
+                .map_err(|_| InherentError::WrongAddressFormat)?
+                .unwrap_or_else(frame_system::Pallet::<T>::digest);
+
+            match Self::registered_sequencer_address(&digest) {
+                Some(registered_addr) if Felt252Wrapper::from(registered_addr).0.to_bytes_be() != *addr => {
+                    Err(InherentError::UnregisteredSequencerAddress)
+                }
+                _ => Ok(()),
+            }
         }
 
         fn is_inherent(call: &Self::Call) -> bool {
-            matches!(call, Call::set_sequencer_address { .. })
+            matches!(call, Call::set_sequencer_address { .. } | Call::set_block_parameters { .. })
         }
     }
 
@@ -746,12 +1317,28 @@ pub mod pallet {
         /// By default unsigned transactions are disallowed, but implementing the validator
         /// here we make sure that some particular calls (in this case all calls)
         /// are being whitelisted and marked as valid.
-        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
-            // The priority right now is the max u64 - nonce because for unsigned transactions we need to
-            // determine an absolute priority. For now we use that for the benchmark (lowest nonce goes first)
-            // otherwise we have a nonce error and everything fails.
-            // Once we have a real fee market this is where we'll chose the most profitable transaction.
+        fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            let state_edit_tag = match call {
+                Call::apply_state_edits { state_edits } => Some(state_edits.encode()),
+                Call::mint { address, amount } => Some((address, amount).encode()),
+                _ => None,
+            };
+            if let Some(state_edit_tag) = state_edit_tag {
+                // Never propagated, state edits can only come from the node itself. Whether they are
+                // allowed depends on the node sealing the block they are included in.
+                if source == TransactionSource::External {
+                    return Err(InvalidTransaction::Call.into());
+                }
+                return ValidTransaction::with_tag_prefix("starknet_state_edits")
+                    .priority(TransactionPriority::MAX)
+                    .and_provides(state_edit_tag)
+                    .longevity(T::TransactionLongevity::get())
+                    .propagate(false)
+                    .build();
+            }
 
+            // The most profitable transactions go first, the `requires` tags making sure the
+            // transactions of an account are still executed in nonce order.
             let transaction = Self::get_call_transaction(call.clone()).map_err(|_| InvalidTransaction::Call)?;
 
             let tx_priority_info = Self::validate_unsigned_tx_nonce(&transaction)?;
@@ -759,7 +1346,7 @@ pub mod pallet {
             Self::validate_unsigned_tx(&transaction)?;
 
             let mut valid_transaction_builder = ValidTransaction::with_tag_prefix("starknet")
-                .priority(u64::MAX)
+                .priority(Self::transaction_priority(&transaction))
                 .longevity(T::TransactionLongevity::get())
                 .propagate(true);
 
@@ -777,7 +1364,10 @@ pub mod pallet {
                     valid_transaction_builder =
                         valid_transaction_builder.and_provides((Felt252Wrapper::ZERO, Felt252Wrapper(nonce.0)));
                 }
-                _ => {}
+                // Nonce-less transactions are identified by their hash
+                TxPriorityInfo::InvokeV0 { transaction_hash } => {
+                    valid_transaction_builder = valid_transaction_builder.and_provides(transaction_hash);
+                }
             }
 
             valid_transaction_builder.build()
@@ -792,8 +1382,40 @@ pub mod pallet {
         /// In the default implementation of pre_dispatch for the ValidateUnsigned trait,
         /// this function calls the validate_unsigned function in order to verify validity
         /// before dispatch. In our case, since transaction was already validated in
-        /// `validate_unsigned` we can just return Ok.
-        fn pre_dispatch(_call: &Self::Call) -> Result<(), TransactionValidityError> {
+        /// `validate_unsigned` we only check that the block can still hold the transaction.
+        fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+            if matches!(
+                call,
+                Call::set_sequencer_address { .. }
+                    | Call::set_block_parameters { .. }
+                    | Call::apply_state_edits { .. }
+                    | Call::mint { .. }
+                    | Call::import_state_diff { .. }
+            ) {
+                return Ok(());
+            }
+
+            let limits = Self::max_block_resources();
+            // Without any limit, there is no need to execute the transaction beforehand
+            if limits == BlockResources::MAX {
+                return Ok(());
+            }
+            let Ok(transaction) = Self::get_call_transaction(call.clone()) else {
+                return Ok(());
+            };
+            // A transaction failing to execute is refused by its dispatch
+            let Some(tx_resources) = Self::transaction_resources(&transaction) else {
+                return Ok(());
+            };
+
+            if tx_resources.exceeds(&limits) {
+                return Err(InvalidTransaction::Custom(block_limits::TRANSACTION_TOO_LARGE).into());
+            }
+            // The block builder keeps the transaction in the pool for a next block
+            if Self::block_resources_used().saturating_add(&tx_resources).exceeds(&limits) {
+                return Err(InvalidTransaction::ExhaustsResources.into());
+            }
+
             Ok(())
         }
     }
@@ -801,6 +1423,59 @@ pub mod pallet {
 
 /// The Starknet pallet internal functions.
 impl<T: Config> Pallet<T> {
+    /// Writes the state diff of an imported block to the state.
+    fn write_state_diff(state_diff: BlockStateDiff) {
+        for (contract_address, key, value) in state_diff.storage {
+            StorageView::<T>::insert((contract_address, key), value);
+        }
+        for (contract_address, nonce) in state_diff.nonces {
+            Nonces::<T>::insert(contract_address, nonce);
+        }
+        for (contract_address, class_hash) in state_diff.class_hashes {
+            ContractClassHashes::<T>::insert(contract_address, class_hash);
+        }
+        for (class_hash, contract_class, compiled_class_hash) in state_diff.declared_classes {
+            ContractClasses::<T>::insert(class_hash, contract_class);
+            if let Some(compiled_class_hash) = compiled_class_hash {
+                CompiledClassHashes::<T>::insert(class_hash, compiled_class_hash);
+            }
+        }
+    }
+
+    /// Stores a transaction of an imported block along with its outputs, as its execution would.
+    fn store_imported_transaction(imported: ImportedTransaction) {
+        let ImportedTransaction { transaction_hash, transaction, events, messages, revert_error } = imported;
+        if let Transaction::L1Handler(transaction) = &transaction {
+            L1Messages::<T>::insert(Nonce(StarkFelt::from(transaction.nonce)), ());
+        }
+        for event in events {
+            TxEvents::<T>::append(transaction_hash, event);
+        }
+        for message in messages {
+            TxMessages::<T>::append(transaction_hash, message);
+        }
+        Self::store_transaction(transaction_hash, transaction, revert_error);
+    }
+
+    /// The resources the current block can hold: the limits of the runtime, lowered by the
+    /// budgets of the node sealing the block.
+    pub fn max_block_resources() -> BlockResources {
+        T::MaxBlockResources::get().min(&Self::sealing_parameters().max_block_resources)
+    }
+
+    /// Returns the address registered for the author of the current block, if any.
+    pub fn block_author_sequencer_address() -> Option<ContractAddress> {
+        Self::registered_sequencer_address(&frame_system::Pallet::<T>::digest())
+    }
+
+    /// Returns the address registered for the author of the block with the given digest, if any.
+    pub fn registered_sequencer_address(digest: &Digest) -> Option<ContractAddress> {
+        let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
+        let author = T::FindAuthor::find_author(pre_runtime_digests)?;
+
+        Self::registered_sequencer(author)
+    }
+
     /// Returns the transaction for the Call
     ///
     /// # Arguments
@@ -846,13 +1521,42 @@ impl<T: Config> Pallet<T> {
             sequencer_address,
             fee_token_address,
             vm_resource_fee_cost,
-            invoke_tx_max_n_steps: T::InvokeTxMaxNSteps::get(),
-            validate_max_n_steps: T::ValidateMaxNSteps::get(),
-            gas_price: T::L1GasPrice::get().price_in_wei,
+            invoke_tx_max_n_steps: Self::invoke_tx_max_n_steps(),
+            validate_max_n_steps: Self::validate_max_n_steps(),
+            gas_price: Self::l1_gas_price().price_in_wei,
             max_recursion_depth: T::MaxRecursionDepth::get(),
         }
     }
 
+    /// Creates a [BlockContext] object charging the fees in `unit`.
+    ///
+    /// Fails if no fee token or no gas price is configured for `unit`.
+    pub fn get_block_context_in(unit: PriceUnit) -> Result<BlockContext, Error<T>> {
+        Self::block_context_in(Self::get_block_context(), unit)
+    }
+
+    /// Switches `block_context` to charge the fees in `unit`.
+    ///
+    /// The contexts charge the fees in wei, so they are left untouched for this unit, e.g. along
+    /// with the gas price of a simulated block.
+    pub fn block_context_in(mut block_context: BlockContext, unit: PriceUnit) -> Result<BlockContext, Error<T>> {
+        if unit != PriceUnit::Wei {
+            block_context.fee_token_address =
+                Self::fee_token_address_in(unit).ok_or(Error::<T>::FeeTokenNotConfigured)?;
+            block_context.gas_price = Self::l1_gas_price().price_in(unit).ok_or(Error::<T>::FeeTokenNotConfigured)?;
+        }
+
+        Ok(block_context)
+    }
+
+    /// Returns the address of the fee token of `unit`, if any.
+    pub fn fee_token_address_in(unit: PriceUnit) -> Option<ContractAddress> {
+        match unit {
+            PriceUnit::Wei => Some(Self::fee_token_address()),
+            PriceUnit::Fri => Self::strk_fee_token_address(),
+        }
+    }
+
     /// convert chain_id
     #[inline(always)]
     pub fn chain_id_str() -> String {
@@ -901,11 +1605,29 @@ impl<T: Config> Pallet<T> {
         address: ContractAddress,
         function_selector: EntryPointSelector,
         calldata: Calldata,
+    ) -> Result<Vec<Felt252Wrapper>, DispatchError> {
+        Self::call_contract_with_state(
+            &mut BlockifierStateAdapter::<T>::default(),
+            address,
+            function_selector,
+            calldata,
+        )
+    }
+
+    /// Call a smart contract function, reading and writing through the given state.
+    pub(crate) fn call_contract_with_state<S: State>(
+        state: &mut S,
+        address: ContractAddress,
+        function_selector: EntryPointSelector,
+        calldata: Calldata,
     ) -> Result<Vec<Felt252Wrapper>, DispatchError> {
         // Get current block context
         let block_context = Self::get_block_context();
         // Get class hash
-        let class_hash = ContractClassHashes::<T>::try_get(address).map_err(|_| Error::<T>::ContractNotFound)?;
+        let class_hash = state.get_class_hash_at(address).map_err(|_| Error::<T>::StateReaderError)?;
+        if class_hash == ClassHash::default() {
+            return Err(Error::<T>::ContractNotFound.into());
+        }
 
         let entrypoint = CallEntryPoint {
             class_hash: Some(class_hash),
@@ -924,11 +1646,7 @@ impl<T: Config> Pallet<T> {
         let mut entry_point_execution_context =
             EntryPointExecutionContext::new(block_context, Default::default(), max_n_steps);
 
-        match entrypoint.execute(
-            &mut BlockifierStateAdapter::<T>::default(),
-            &mut resources,
-            &mut entry_point_execution_context,
-        ) {
+        match entrypoint.execute(state, &mut resources, &mut entry_point_execution_context) {
             Ok(v) => {
                 log!(debug, "Successfully called a smart contract function: {:?}", v);
                 let result = v.execution.retdata.0.iter().map(|x| (*x).into()).collect();
@@ -964,15 +1682,35 @@ impl<T: Config> Pallet<T> {
         let transaction_count = transactions.len();
 
         let parent_block_hash = Self::parent_block_hash(&block_number);
-        let events_count = transaction_hashes.iter().map(|tx_hash| TxEvents::<T>::get(tx_hash).len() as u128).sum();
+
+        let transaction_commitment = mp_block::calculate_transaction_commitment(
+            &transactions
+                .iter()
+                .zip(transaction_hashes.iter())
+                .map(|(tx, tx_hash)| (Felt252Wrapper::from(tx_hash.0), tx.signature()))
+                .collect::<Vec<_>>(),
+        );
+        let event_hashes: Vec<Felt252Wrapper> = transaction_hashes
+            .iter()
+            .flat_map(TxEvents::<T>::get)
+            .map(|event| {
+                let keys: Vec<Felt252Wrapper> = event.content.keys.iter().map(|key| key.0.into()).collect();
+                let data: Vec<Felt252Wrapper> = event.content.data.0.iter().map(|felt| (*felt).into()).collect();
+                mp_block::event_hash(event.from_address.0.0.into(), &keys, &data)
+            })
+            .collect();
+        let events_count = event_hashes.len() as u128;
+        let event_commitment = mp_block::calculate_event_commitment(&event_hashes);
+        // The global state root is not computed, the last known one is kept
+        let global_state_root = Self::global_state_root();
 
         let sequencer_address = Self::sequencer_address();
         let block_timestamp = Self::block_timestamp();
 
-        let protocol_version = T::ProtocolVersion::get();
+        let protocol_version = Self::protocol_version();
         let extra_data = None;
 
-        let l1_gas_price = T::L1GasPrice::get();
+        let l1_gas_price = Self::l1_gas_price();
 
         let block = StarknetBlock::new(
             StarknetHeader::new(
@@ -985,6 +1723,9 @@ impl<T: Config> Pallet<T> {
                 protocol_version,
                 l1_gas_price,
                 extra_data,
+                global_state_root,
+                transaction_commitment.into(),
+                event_commitment.into(),
             ),
             transactions,
         );
@@ -992,6 +1733,10 @@ impl<T: Config> Pallet<T> {
         let blockhash = block.header().hash::<T::SystemHash>();
         BlockHash::<T>::insert(block_number, blockhash);
 
+        if T::TxOutputsRetention::get().is_some() && !transaction_hashes.is_empty() {
+            BlockTransactionHashes::<T>::insert(block_number, transaction_hashes);
+        }
+
         // Kill pending storage.
         Pending::<T>::kill();
         PendingHashes::<T>::kill();
@@ -1000,6 +1745,74 @@ impl<T: Config> Pallet<T> {
         frame_system::Pallet::<T>::deposit_log(digest);
     }
 
+    /// Remove the events, messages to L1 and revert errors of the transactions of a block from the
+    /// state. Returns the weight consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_number` - The number of the block to prune.
+    fn prune_transaction_outputs(block_number: u64) -> Weight {
+        let transaction_hashes = BlockTransactionHashes::<T>::take(block_number);
+        for tx_hash in transaction_hashes.iter() {
+            TxEvents::<T>::remove(tx_hash);
+            TxMessages::<T>::remove(tx_hash);
+            TxRevertError::<T>::remove(tx_hash);
+        }
+
+        T::DbWeight::get().reads_writes(1, 1 + 3 * transaction_hashes.len() as u64)
+    }
+
+    /// Returns the resources the transaction would consume if executed now, without keeping any of
+    /// its changes, or `None` if it fails to execute.
+    ///
+    /// As with the Starknet bouncer, the resources of a transaction are only known once executed,
+    /// so the transactions are executed once more before being dispatched.
+    fn transaction_resources(transaction: &UserOrL1HandlerTransaction) -> Option<BlockResources> {
+        frame_support::storage::transactional::with_transaction(|| {
+            let chain_id = Self::chain_id();
+            let execution_config = RuntimeExecutionConfigBuilder::new::<T>().build();
+            let mut state = CachedBlockifierStateAdapter(BlockifierStateAdapter::<T>::default());
+            let tx_resources = match transaction {
+                UserOrL1HandlerTransaction::User(transaction) => {
+                    let block_context = Self::get_block_context_in(PriceUnit::for_tx_version(transaction.version()));
+                    block_context.ok().and_then(|block_context| {
+                        Self::execute_user_transaction(
+                            transaction.clone(),
+                            chain_id,
+                            &block_context,
+                            &execution_config,
+                            &mut state,
+                        )
+                        .ok()
+                    })
+                }
+                UserOrL1HandlerTransaction::L1Handler(transaction, paid_fee_on_l1) => transaction
+                    .clone()
+                    .into_executable::<T::SystemHash>(chain_id, *paid_fee_on_l1, false)
+                    .execute(&mut state, &Self::get_block_context(), &execution_config)
+                    .ok(),
+            }
+            .map(|tx_execution_infos| {
+                BlockResources::from_execution_info(&tx_execution_infos, state.count_state_changes())
+            });
+
+            frame_support::storage::TransactionOutcome::Rollback(Result::<_, DispatchError>::Ok(tx_resources))
+        })
+        .ok()
+        .flatten()
+    }
+
+    /// Add the resources consumed by a transaction to the ones of the current block.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_execution_infos` - The execution info of the transaction.
+    /// * `state_changes` - The changes the transaction made to the state.
+    fn record_block_resources(tx_execution_infos: &TransactionExecutionInfo, state_changes: StateChangesCount) {
+        let tx_resources = BlockResources::from_execution_info(tx_execution_infos, state_changes);
+        BlockResourcesUsed::<T>::mutate(|used| *used = used.saturating_add(&tx_resources));
+    }
+
     /// Aggregate L2 > L1 messages from the call info.
     ///
     /// # Arguments
@@ -1129,7 +1942,55 @@ impl<T: Config> Pallet<T> {
         .into()
     }
 
-    pub fn is_transaction_fee_disabled() -> bool {
-        T::DisableTransactionFee::get()
+    /// Persist the chain parameters still relying on their `Config` defaults.
+    ///
+    /// Once stored, they only change through their setter calls and not anymore when the runtime
+    /// defaults change. Done at genesis, and by [`migrations::v2`] for the existing chains. Returns
+    /// the weight consumed.
+    pub fn store_default_chain_parameters() -> Weight {
+        let mut writes = 0;
+        if !InvokeTxMaxNSteps::<T>::exists() {
+            InvokeTxMaxNSteps::<T>::put(T::InvokeTxMaxNSteps::get());
+            writes += 1;
+        }
+        if !ValidateMaxNSteps::<T>::exists() {
+            ValidateMaxNSteps::<T>::put(T::ValidateMaxNSteps::get());
+            writes += 1;
+        }
+        if !L1GasPrice::<T>::exists() {
+            L1GasPrice::<T>::put(T::L1GasPrice::get());
+            writes += 1;
+        }
+        if !ProtocolVersion::<T>::exists() {
+            ProtocolVersion::<T>::put(T::ProtocolVersion::get());
+            writes += 1;
+        }
+        if !DisableTransactionFee::<T>::exists() {
+            DisableTransactionFee::<T>::put(T::DisableTransactionFee::get());
+            writes += 1;
+        }
+
+        T::DbWeight::get().reads_writes(5, writes)
+    }
+}
+
+/// Ensure the origin is signed by the [`Admin`] account.
+///
+/// Meant to be used, alone or next to root, as the `AdminOrigin` of the runtime.
+pub struct EnsureAdmin<T>(PhantomData<T>);
+
+impl<T: Config> EnsureOrigin<T::RuntimeOrigin> for EnsureAdmin<T> {
+    type Success = T::AccountId;
+
+    fn try_origin(origin: T::RuntimeOrigin) -> Result<Self::Success, T::RuntimeOrigin> {
+        origin.into().and_then(|origin| match (origin, Admin::<T>::get()) {
+            (frame_system::RawOrigin::Signed(who), Some(admin)) if who == admin => Ok(who),
+            (origin, _) => Err(T::RuntimeOrigin::from(origin)),
+        })
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn try_successful_origin() -> Result<T::RuntimeOrigin, ()> {
+        Admin::<T>::get().map(|admin| frame_system::RawOrigin::Signed(admin).into()).ok_or(())
     }
 }
@@ -0,0 +1,174 @@
+//! Versioned migrations of the pallet storage.
+//!
+//! Every bump of the pallet [`StorageVersion`] comes with a `vN` module holding the migration from
+//! the previous version, wrapped in a [`VersionedMigration`] so it only runs once, and appended to
+//! [`Migrations`]. A bump changing the layout of the storage items read by the client also bumps
+//! the [`StarknetStorageSchemaVersion`], which needs a matching `StorageOverride` in `mc-storage`
+//! for the RPC to keep reading the blocks of both schemas.
+use alloc::collections::BTreeSet;
+#[cfg(feature = "try-runtime")]
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use frame_support::migrations::VersionedMigration;
+use frame_support::pallet_prelude::*;
+use frame_support::storage_alias;
+use frame_support::traits::OnRuntimeUpgrade;
+#[cfg(feature = "try-runtime")]
+use mp_fee::ResourcePrice;
+use mp_storage::{StarknetStorageSchemaVersion, PALLET_STARKNET_SCHEMA};
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+use starknet_api::api_core::Nonce;
+
+use crate::{
+    Config, DisableTransactionFee, InvokeTxMaxNSteps, L1GasPrice, L1Messages, Pallet, ProtocolVersion,
+    ValidateMaxNSteps,
+};
+
+/// The migrations of the pallet storage, in order.
+///
+/// To be run by the `Executive` of the runtime on runtime upgrades.
+pub type Migrations<T> = (v1::MigrateV0ToV1<T>, v2::MigrateV1ToV2<T>);
+
+/// Write the schema of the storage, used by the client to pick its storage reader.
+fn put_storage_schema(schema: StarknetStorageSchemaVersion) {
+    frame_support::storage::unhashed::put::<StarknetStorageSchemaVersion>(PALLET_STARKNET_SCHEMA, &schema);
+}
+
+pub(crate) mod v0 {
+    use super::*;
+
+    /// The set of consumed L1 messages nonces, read and rewritten in full for every message.
+    #[storage_alias]
+    pub(crate) type L1Messages<T: Config> = StorageValue<Pallet<T>, BTreeSet<Nonce>, ValueQuery>;
+}
+
+pub mod v1 {
+    use super::*;
+
+    /// Move the consumed L1 messages nonces from the `v0` set to the [`L1Messages`] map.
+    ///
+    /// The client does not read the L1 messages, the storage schema stays
+    /// [`StarknetStorageSchemaVersion::V1`].
+    pub struct InnerMigrateV0ToV1<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for InnerMigrateV0ToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let nonces = v0::L1Messages::<T>::take();
+            let migrated = nonces.len() as u64;
+            for nonce in nonces {
+                L1Messages::<T>::insert(nonce, ());
+            }
+            // Chains started before the schema was written at genesis
+            put_storage_schema(StarknetStorageSchemaVersion::V1);
+
+            log::info!(target: crate::LOG_TARGET, "Migrated {migrated} L1 messages nonces to the L1Messages map");
+
+            T::DbWeight::get().reads_writes(1, migrated + 2)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            let migrated = v0::L1Messages::<T>::get().len() as u64;
+            ensure!(L1Messages::<T>::iter_keys().next().is_none(), "L1Messages map is not empty before the migration");
+
+            Ok(migrated.encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let migrated = u64::decode(&mut &state[..]).map_err(|_| "Invalid pre-upgrade state")?;
+            ensure!(!v0::L1Messages::<T>::exists(), "L1 messages set still exists after the migration");
+            ensure!(
+                L1Messages::<T>::iter_keys().count() as u64 == migrated,
+                "Every L1 message nonce should be in the L1Messages map"
+            );
+
+            Ok(())
+        }
+    }
+
+    /// [`InnerMigrateV0ToV1`] run when the on-chain storage version is 0, then set to 1.
+    pub type MigrateV0ToV1<T> =
+        VersionedMigration<0, 1, InnerMigrateV0ToV1<T>, Pallet<T>, <T as frame_system::Config>::DbWeight>;
+}
+
+pub mod v2 {
+    use super::*;
+
+    /// The chain parameters stored before a migration, `None` for the ones relying on their
+    /// `Config` defaults.
+    #[cfg(feature = "try-runtime")]
+    type StoredChainParameters = (Option<u32>, Option<u32>, Option<ResourcePrice>, Option<u8>, Option<bool>);
+
+    #[cfg(feature = "try-runtime")]
+    fn stored_chain_parameters<T: Config>() -> StoredChainParameters {
+        (
+            InvokeTxMaxNSteps::<T>::try_get().ok(),
+            ValidateMaxNSteps::<T>::try_get().ok(),
+            L1GasPrice::<T>::try_get().ok(),
+            ProtocolVersion::<T>::try_get().ok(),
+            DisableTransactionFee::<T>::try_get().ok(),
+        )
+    }
+
+    /// Persist the chain parameters still relying on their `Config` defaults, see
+    /// [`Pallet::store_default_chain_parameters`].
+    ///
+    /// The client does not read the chain parameters, the storage schema stays
+    /// [`StarknetStorageSchemaVersion::V1`].
+    pub struct InnerMigrateV1ToV2<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for InnerMigrateV1ToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            Pallet::<T>::store_default_chain_parameters()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            Ok(stored_chain_parameters::<T>().encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let (invoke_tx_max_n_steps, validate_max_n_steps, l1_gas_price, protocol_version, disable_transaction_fee) =
+                StoredChainParameters::decode(&mut &state[..]).map_err(|_| "Invalid pre-upgrade state")?;
+            let (
+                Some(stored_invoke_tx_max_n_steps),
+                Some(stored_validate_max_n_steps),
+                Some(stored_l1_gas_price),
+                Some(stored_protocol_version),
+                Some(stored_disable_transaction_fee),
+            ) = stored_chain_parameters::<T>()
+            else {
+                return Err("Every chain parameter should be stored after the migration".into());
+            };
+
+            // The values stored beforehand are kept, the missing ones are the `Config` defaults
+            ensure!(
+                stored_invoke_tx_max_n_steps == invoke_tx_max_n_steps.unwrap_or_else(T::InvokeTxMaxNSteps::get),
+                "Unexpected invoke transaction max steps"
+            );
+            ensure!(
+                stored_validate_max_n_steps == validate_max_n_steps.unwrap_or_else(T::ValidateMaxNSteps::get),
+                "Unexpected validation max steps"
+            );
+            ensure!(stored_l1_gas_price == l1_gas_price.unwrap_or_else(T::L1GasPrice::get), "Unexpected L1 gas price");
+            ensure!(
+                stored_protocol_version == protocol_version.unwrap_or_else(T::ProtocolVersion::get),
+                "Unexpected protocol version"
+            );
+            ensure!(
+                stored_disable_transaction_fee == disable_transaction_fee.unwrap_or_else(T::DisableTransactionFee::get),
+                "Unexpected transaction fee setting"
+            );
+
+            Ok(())
+        }
+    }
+
+    /// [`InnerMigrateV1ToV2`] run when the on-chain storage version is 1, then set to 2.
+    pub type MigrateV1ToV2<T> =
+        VersionedMigration<1, 2, InnerMigrateV1ToV2<T>, Pallet<T>, <T as frame_system::Config>::DbWeight>;
+}
@@ -0,0 +1,697 @@
+//! Optimistic parallel execution of a sequence of transactions, in the style of Block-STM.
+//!
+//! The transactions are executed concurrently against a multi-version memory holding the values
+//! written by each of them, the values it does not hold being read from the state the sequence
+//! starts from. An execution records the version of each value it read, the transaction and
+//! incarnation which wrote it. Once executed, a transaction is validated by checking that it
+//! would read the same versions again. The transactions failing validation, or reading the
+//! writes of an aborted incarnation, are executed again until all of them are validated: their
+//! results and writes are then the ones of a sequential execution.
+//!
+//! The transactions paying a fee all update the balance of the sequencer, so they mostly end up
+//! executed one after the other.
+//!
+//! The state of the chain can only be read from the thread running the runtime, which serves
+//! the reads of the workers. Without threads, as in Wasm, a single worker runs on that thread.
+//!
+//! Only the sequences executed within a single runtime call go through it: the transactions
+//! re-executed to trace or simulate them, by the native runtime of the node serving the RPC.
+//! Block production does not use it yet. A produced block dispatches each of its transactions as
+//! an extrinsic of its own, one after the other, and the blocks are executed by the Wasm runtime,
+//! where a single worker would run anyway.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::marker::PhantomData;
+use core::mem;
+use core::num::NonZeroUsize;
+use std::sync::mpsc::{self, Sender, SyncSender};
+use std::sync::{Condvar, Mutex};
+
+use blockifier::block_context::BlockContext;
+use blockifier::execution::contract_class::ContractClass;
+use blockifier::state::cached_state::{CommitmentStateDiff, ContractStorageKey, StateChangesCount};
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::{State, StateReader, StateResult};
+use blockifier::transaction::objects::TransactionExecutionInfo;
+use mp_felt::Felt252Wrapper;
+use mp_state::StateChanges;
+use mp_transactions::execution::ExecutionConfig;
+use mp_transactions::UserOrL1HandlerTransaction;
+use starknet_api::api_core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+use starknet_crypto::FieldElement;
+
+use crate::blockifier_state_adapter::{BlockifierStateAdapter, StateCache};
+use crate::{Config, Pallet};
+
+/// Maximum number of threads executing transactions.
+const MAX_EXECUTION_WORKERS: usize = 16;
+
+/// Returns the number of threads to execute transactions on, a single one when threads are not
+/// available.
+pub(crate) fn execution_workers() -> usize {
+    std::thread::available_parallelism().map_or(1, NonZeroUsize::get).min(MAX_EXECUTION_WORKERS)
+}
+
+type TxIndex = usize;
+type Incarnation = u32;
+/// The transaction, and its incarnation, which wrote a value.
+type Version = (TxIndex, Incarnation);
+
+/// A location of the state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum StateKey {
+    Storage(ContractStorageKey),
+    Nonce(ContractAddress),
+    ClassHash(ContractAddress),
+    CompiledClassHash(ClassHash),
+    ContractClass(ClassHash),
+}
+
+/// The value of a location, of the kind of its [StateKey].
+#[derive(Clone)]
+enum StateValue {
+    Storage(StarkFelt),
+    Nonce(Nonce),
+    ClassHash(ClassHash),
+    CompiledClassHash(CompiledClassHash),
+    ContractClass(ContractClass),
+}
+
+/// Reads a location of the state of the chain, `None` for a class which is not declared.
+fn read_chain_state<T: Config>(key: StateKey) -> StateResult<Option<StateValue>> {
+    let mut state = BlockifierStateAdapter::<T>::default();
+    let value = match key {
+        StateKey::Storage((contract_address, key)) => {
+            state.get_storage_at(contract_address, key).map(StateValue::Storage)
+        }
+        StateKey::Nonce(contract_address) => state.get_nonce_at(contract_address).map(StateValue::Nonce),
+        StateKey::ClassHash(contract_address) => state.get_class_hash_at(contract_address).map(StateValue::ClassHash),
+        StateKey::CompiledClassHash(class_hash) => {
+            state.get_compiled_class_hash(class_hash).map(StateValue::CompiledClassHash)
+        }
+        StateKey::ContractClass(class_hash) => {
+            state.get_compiled_contract_class(&class_hash).map(StateValue::ContractClass)
+        }
+    };
+    match value {
+        Ok(value) => Ok(Some(value)),
+        Err(StateError::UndeclaredClassHash(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Writes the values to the state of the chain.
+fn write_chain_state<T: Config>(writes: impl IntoIterator<Item = (StateKey, StateValue)>) {
+    for write in writes {
+        match write {
+            (StateKey::Storage(contract_storage_key), StateValue::Storage(value)) => {
+                crate::StorageView::<T>::insert(contract_storage_key, value)
+            }
+            (StateKey::Nonce(contract_address), StateValue::Nonce(nonce)) => {
+                crate::Nonces::<T>::insert(contract_address, nonce)
+            }
+            (StateKey::ClassHash(contract_address), StateValue::ClassHash(class_hash)) => {
+                crate::ContractClassHashes::<T>::insert(contract_address, class_hash)
+            }
+            (StateKey::CompiledClassHash(class_hash), StateValue::CompiledClassHash(compiled_class_hash)) => {
+                crate::CompiledClassHashes::<T>::insert(class_hash, compiled_class_hash)
+            }
+            (StateKey::ContractClass(class_hash), StateValue::ContractClass(contract_class)) => {
+                crate::ContractClasses::<T>::insert(class_hash, contract_class)
+            }
+            (key, _) => unreachable!("the value written to {key:?} is of the kind of its location"),
+        }
+    }
+}
+
+/// Reads a location of the state of the chain, from the thread running the runtime or through
+/// it.
+type ChainStateReader<'a> = &'a dyn Fn(StateKey) -> StateResult<Option<StateValue>>;
+
+/// A read of the state of the chain, sent to the thread running the runtime along with the
+/// channel to answer on.
+type ChainStateRequest = (StateKey, SyncSender<StateResult<Option<StateValue>>>);
+
+fn forward_chain_state_read(requests: &Sender<ChainStateRequest>, key: StateKey) -> StateResult<Option<StateValue>> {
+    let (reply, response) = mpsc::sync_channel(1);
+    requests.send((key, reply)).expect("the runtime thread serves the reads until the workers are done");
+    response.recv().expect("the runtime thread answers every read")
+}
+
+/// The values of the state of the chain read so far, which the execution does not modify.
+#[derive(Default)]
+struct BaseState {
+    values: Mutex<BTreeMap<StateKey, Option<StateValue>>>,
+}
+
+impl BaseState {
+    /// A failed read is not kept, the location is read again by the next execution reading it.
+    fn read(&self, key: StateKey, read_chain_state: ChainStateReader<'_>) -> StateResult<Option<StateValue>> {
+        if let Some(value) = self.values.lock().expect("poisoned lock").get(&key) {
+            return Ok(value.clone());
+        }
+
+        let value = read_chain_state(key)?;
+        self.values.lock().expect("poisoned lock").insert(key, value.clone());
+        Ok(value)
+    }
+}
+
+enum Entry {
+    Written(Incarnation, StateValue),
+    /// Written by an aborted incarnation, likely to be written again by the next one.
+    Estimate,
+}
+
+enum MemoryRead {
+    Written(Version, StateValue),
+    /// The transaction which last wrote the location has to be executed again.
+    Estimate(TxIndex),
+    /// No transaction wrote the location, its value is the one of the base state.
+    Missing,
+}
+
+struct Memory {
+    entries: BTreeMap<StateKey, BTreeMap<TxIndex, Entry>>,
+    /// The locations written by the last incarnation of each transaction.
+    written_keys: Vec<BTreeSet<StateKey>>,
+}
+
+impl Memory {
+    fn latest_entry(&self, key: &StateKey, tx_index: TxIndex) -> Option<(TxIndex, &Entry)> {
+        self.entries.get(key)?.range(..tx_index).next_back().map(|(writer, entry)| (*writer, entry))
+    }
+}
+
+/// The values written by each transaction.
+struct MultiVersionMemory(Mutex<Memory>);
+
+impl MultiVersionMemory {
+    fn new(len: usize) -> Self {
+        Self(Mutex::new(Memory { entries: BTreeMap::new(), written_keys: vec![BTreeSet::new(); len] }))
+    }
+
+    /// Reads a location as seen by `tx_index`, the last write of the transactions before it.
+    fn read(&self, key: &StateKey, tx_index: TxIndex) -> MemoryRead {
+        let memory = self.0.lock().expect("poisoned lock");
+        match memory.latest_entry(key, tx_index) {
+            Some((writer, Entry::Written(incarnation, value))) => {
+                MemoryRead::Written((writer, *incarnation), value.clone())
+            }
+            Some((writer, Entry::Estimate)) => MemoryRead::Estimate(writer),
+            None => MemoryRead::Missing,
+        }
+    }
+
+    /// Records the writes of an incarnation, replacing the ones of the previous incarnation.
+    fn record(&self, tx_index: TxIndex, incarnation: Incarnation, writes: BTreeMap<StateKey, StateValue>) {
+        let mut memory = self.0.lock().expect("poisoned lock");
+        let written_keys: BTreeSet<StateKey> = writes.keys().copied().collect();
+        let previous_keys = mem::replace(&mut memory.written_keys[tx_index], written_keys);
+        for key in previous_keys.iter().filter(|key| !writes.contains_key(*key)) {
+            if let Some(versions) = memory.entries.get_mut(key) {
+                versions.remove(&tx_index);
+            }
+        }
+        for (key, value) in writes {
+            memory.entries.entry(key).or_default().insert(tx_index, Entry::Written(incarnation, value));
+        }
+    }
+
+    /// Marks the writes of the last incarnation of a transaction as estimates of the next one.
+    fn mark_estimates(&self, tx_index: TxIndex) {
+        let mut memory = self.0.lock().expect("poisoned lock");
+        let memory = &mut *memory;
+        for key in &memory.written_keys[tx_index] {
+            if let Some(versions) = memory.entries.get_mut(key) {
+                versions.insert(tx_index, Entry::Estimate);
+            }
+        }
+    }
+
+    /// Returns whether reading the locations again gives the same versions.
+    fn validate(&self, tx_index: TxIndex, reads: &[(StateKey, Option<Version>)]) -> bool {
+        let memory = self.0.lock().expect("poisoned lock");
+        reads.iter().all(|(key, version)| match memory.latest_entry(key, tx_index) {
+            Some((writer, Entry::Written(incarnation, _))) => *version == Some((writer, *incarnation)),
+            Some((_, Entry::Estimate)) => false,
+            None => version.is_none(),
+        })
+    }
+
+    /// The last value written to each location.
+    fn into_latest_writes(self) -> impl Iterator<Item = (StateKey, StateValue)> {
+        let memory = self.0.into_inner().expect("poisoned lock");
+        memory.entries.into_iter().filter_map(|(key, versions)| match versions.into_iter().next_back() {
+            Some((_, Entry::Written(_, value))) => Some((key, value)),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    ReadyToExecute,
+    Executing,
+    Executed,
+    /// Waiting for the execution of a transaction whose writes it read.
+    Aborting,
+}
+
+enum Task {
+    Execution(TxIndex, Incarnation),
+    Validation(TxIndex, Incarnation),
+}
+
+struct SchedulerState {
+    statuses: Vec<(Incarnation, Status)>,
+    /// The transactions waiting for the execution of each transaction.
+    dependents: Vec<Vec<TxIndex>>,
+    /// The next transaction to try executing.
+    execution_index: TxIndex,
+    /// The next transaction to try validating.
+    validation_index: TxIndex,
+    /// Number of tasks being worked on.
+    active_tasks: usize,
+}
+
+/// Hands out the executions and validations, the ones of the first transactions first.
+struct Scheduler {
+    state: Mutex<SchedulerState>,
+    changed: Condvar,
+}
+
+impl Scheduler {
+    fn new(len: usize) -> Self {
+        Self {
+            state: Mutex::new(SchedulerState {
+                statuses: vec![(0, Status::ReadyToExecute); len],
+                dependents: vec![Vec::new(); len],
+                execution_index: 0,
+                validation_index: 0,
+                active_tasks: 0,
+            }),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// Returns the next task, waiting for one while others are worked on. `None` once all the
+    /// transactions are executed and validated.
+    fn next_task(&self) -> Option<Task> {
+        let mut state = self.state.lock().expect("poisoned lock");
+        loop {
+            if state.validation_index < state.execution_index {
+                let tx_index = state.validation_index;
+                state.validation_index += 1;
+                if let (incarnation, Status::Executed) = state.statuses[tx_index] {
+                    state.active_tasks += 1;
+                    return Some(Task::Validation(tx_index, incarnation));
+                }
+            } else if state.execution_index < state.statuses.len() {
+                let tx_index = state.execution_index;
+                state.execution_index += 1;
+                if let (incarnation, Status::ReadyToExecute) = state.statuses[tx_index] {
+                    state.statuses[tx_index].1 = Status::Executing;
+                    state.active_tasks += 1;
+                    return Some(Task::Execution(tx_index, incarnation));
+                }
+            } else if state.active_tasks == 0 {
+                self.changed.notify_all();
+                return None;
+            } else {
+                state = self.changed.wait(state).expect("poisoned lock");
+            }
+        }
+    }
+
+    /// Makes the transaction wait for the execution of `blocking_tx_index`. Returns false when
+    /// it is already executed, the transaction can then be executed again right away.
+    fn add_dependency(&self, tx_index: TxIndex, blocking_tx_index: TxIndex) -> bool {
+        let mut state = self.state.lock().expect("poisoned lock");
+        if state.statuses[blocking_tx_index].1 == Status::Executed {
+            return false;
+        }
+
+        state.statuses[tx_index].1 = Status::Aborting;
+        state.dependents[blocking_tx_index].push(tx_index);
+        state.active_tasks -= 1;
+        self.changed.notify_all();
+        true
+    }
+
+    fn finish_execution(&self, tx_index: TxIndex, incarnation: Incarnation) {
+        let mut state = self.state.lock().expect("poisoned lock");
+        state.statuses[tx_index] = (incarnation, Status::Executed);
+        for dependent in mem::take(&mut state.dependents[tx_index]) {
+            let (incarnation, _) = state.statuses[dependent];
+            state.statuses[dependent] = (incarnation + 1, Status::ReadyToExecute);
+            state.execution_index = state.execution_index.min(dependent);
+        }
+        // The transaction, and the ones after it which may have read its previous writes, are
+        // validated again
+        state.validation_index = state.validation_index.min(tx_index);
+        state.active_tasks -= 1;
+        self.changed.notify_all();
+    }
+
+    fn finish_validation(&self, memory: &MultiVersionMemory, tx_index: TxIndex, incarnation: Incarnation, valid: bool) {
+        let mut state = self.state.lock().expect("poisoned lock");
+        // A validation of an incarnation already aborted is ignored
+        if !valid && state.statuses[tx_index] == (incarnation, Status::Executed) {
+            memory.mark_estimates(tx_index);
+            state.statuses[tx_index] = (incarnation + 1, Status::ReadyToExecute);
+            state.execution_index = state.execution_index.min(tx_index);
+            state.validation_index = state.validation_index.min(tx_index + 1);
+        }
+        state.active_tasks -= 1;
+        self.changed.notify_all();
+    }
+}
+
+/// The state an incarnation of a transaction is executed against.
+///
+/// It keeps the changes of the transaction the way [CachedBlockifierStateAdapter] does, so that
+/// its state diff and fee are the ones of a sequential execution.
+///
+/// [CachedBlockifierStateAdapter]: crate::blockifier_state_adapter::CachedBlockifierStateAdapter
+struct VersionedState<'a> {
+    tx_index: TxIndex,
+    memory: &'a MultiVersionMemory,
+    base: &'a BaseState,
+    read_chain_state: ChainStateReader<'a>,
+    /// The locations read from the other transactions or the base state, with the version read.
+    reads: Vec<(StateKey, Option<Version>)>,
+    read_values: BTreeMap<StateKey, Option<StateValue>>,
+    writes: BTreeMap<StateKey, StateValue>,
+    /// The transaction whose writes the execution has to wait for, when it read an estimate.
+    dependency: Option<TxIndex>,
+    state_cache: StateCache,
+    storage_updates: BTreeSet<ContractStorageKey>,
+    class_hash_updates: usize,
+    compiled_class_hash_updates: usize,
+}
+
+impl<'a> VersionedState<'a> {
+    fn new(
+        tx_index: TxIndex,
+        memory: &'a MultiVersionMemory,
+        base: &'a BaseState,
+        read_chain_state: ChainStateReader<'a>,
+    ) -> Self {
+        Self {
+            tx_index,
+            memory,
+            base,
+            read_chain_state,
+            reads: Vec::new(),
+            read_values: BTreeMap::new(),
+            writes: BTreeMap::new(),
+            dependency: None,
+            state_cache: StateCache::default(),
+            storage_updates: BTreeSet::new(),
+            class_hash_updates: 0,
+            compiled_class_hash_updates: 0,
+        }
+    }
+
+    /// Reads a location, from the writes of the transaction, then the ones of the transactions
+    /// before it, then the base state. A location is always read with the same value.
+    fn read(&mut self, key: StateKey) -> StateResult<Option<StateValue>> {
+        if let Some(blocking_tx_index) = self.dependency {
+            return Err(dependency_error(blocking_tx_index));
+        }
+        if let Some(value) = self.writes.get(&key) {
+            return Ok(Some(value.clone()));
+        }
+        if let Some(value) = self.read_values.get(&key) {
+            return Ok(value.clone());
+        }
+
+        let (version, value) = match self.memory.read(&key, self.tx_index) {
+            MemoryRead::Written(version, value) => (Some(version), Some(value)),
+            MemoryRead::Estimate(blocking_tx_index) => {
+                self.dependency = Some(blocking_tx_index);
+                return Err(dependency_error(blocking_tx_index));
+            }
+            MemoryRead::Missing => (None, self.base.read(key, self.read_chain_state)?),
+        };
+        self.reads.push((key, version));
+        self.read_values.insert(key, value.clone());
+        Ok(value)
+    }
+}
+
+fn dependency_error(blocking_tx_index: TxIndex) -> StateError {
+    StateError::StateReadError(format!("transaction {blocking_tx_index} has to be executed again first"))
+}
+
+impl<'a> StateChanges for VersionedState<'a> {
+    fn count_state_changes(&self) -> StateChangesCount {
+        let n_modified_contracts =
+            BTreeSet::from_iter(self.storage_updates.iter().map(|&(contract_address, _)| contract_address)).len();
+        StateChangesCount {
+            n_modified_contracts,
+            n_storage_updates: self.storage_updates.len(),
+            n_class_hash_updates: self.class_hash_updates,
+            n_compiled_class_hash_updates: self.compiled_class_hash_updates,
+        }
+    }
+}
+
+impl<'a> StateReader for VersionedState<'a> {
+    fn get_storage_at(&mut self, contract_address: ContractAddress, key: StorageKey) -> StateResult<StarkFelt> {
+        let value = match self.read(StateKey::Storage((contract_address, key)))? {
+            Some(StateValue::Storage(value)) => value,
+            _ => StarkFelt::default(),
+        };
+        if self.state_cache.get_storage_at(contract_address, key).is_none() {
+            self.state_cache.set_storage_initial_value(contract_address, key, value);
+        }
+        Ok(value)
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        let nonce = match self.read(StateKey::Nonce(contract_address))? {
+            Some(StateValue::Nonce(nonce)) => nonce,
+            _ => Nonce::default(),
+        };
+        if self.state_cache.get_nonce_at(contract_address).is_none() {
+            self.state_cache.set_nonce_initial_value(contract_address, nonce);
+        }
+        Ok(nonce)
+    }
+
+    fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        let class_hash = match self.read(StateKey::ClassHash(contract_address))? {
+            Some(StateValue::ClassHash(class_hash)) => class_hash,
+            _ => ClassHash::default(),
+        };
+        if self.state_cache.get_class_hash_at(contract_address).is_none() {
+            self.state_cache.set_class_hash_initial_value(contract_address, class_hash);
+        }
+        Ok(class_hash)
+    }
+
+    fn get_compiled_contract_class(&mut self, class_hash: &ClassHash) -> StateResult<ContractClass> {
+        match self.read(StateKey::ContractClass(*class_hash))? {
+            Some(StateValue::ContractClass(contract_class)) => Ok(contract_class),
+            _ => Err(StateError::UndeclaredClassHash(*class_hash)),
+        }
+    }
+
+    fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        let compiled_class_hash = match self.read(StateKey::CompiledClassHash(class_hash))? {
+            Some(StateValue::CompiledClassHash(compiled_class_hash)) => compiled_class_hash,
+            _ => return Err(StateError::UndeclaredClassHash(class_hash)),
+        };
+        if self.state_cache.get_compiled_class_hash(class_hash).is_none() {
+            self.state_cache.set_compiled_class_hash_initial_value(class_hash, compiled_class_hash);
+        }
+        Ok(compiled_class_hash)
+    }
+}
+
+impl<'a> State for VersionedState<'a> {
+    fn set_storage_at(&mut self, contract_address: ContractAddress, key: StorageKey, value: StarkFelt) {
+        self.state_cache.set_storage_value(contract_address, key, value);
+        self.storage_updates.insert((contract_address, key));
+        self.writes.insert(StateKey::Storage((contract_address, key)), StateValue::Storage(value));
+    }
+
+    fn increment_nonce(&mut self, contract_address: ContractAddress) -> StateResult<()> {
+        let current_nonce = match self.read(StateKey::Nonce(contract_address))? {
+            Some(StateValue::Nonce(nonce)) => nonce,
+            _ => Nonce::default(),
+        };
+        let current_nonce: FieldElement = Felt252Wrapper::from(current_nonce.0).into();
+        let new_nonce: Nonce = Felt252Wrapper(current_nonce + FieldElement::ONE).into();
+
+        self.state_cache.set_nonce_value(contract_address, new_nonce);
+        self.writes.insert(StateKey::Nonce(contract_address), StateValue::Nonce(new_nonce));
+
+        Ok(())
+    }
+
+    fn set_class_hash_at(&mut self, contract_address: ContractAddress, class_hash: ClassHash) -> StateResult<()> {
+        self.state_cache.set_class_hash_write(contract_address, class_hash);
+        self.class_hash_updates += 1;
+        self.writes.insert(StateKey::ClassHash(contract_address), StateValue::ClassHash(class_hash));
+
+        Ok(())
+    }
+
+    fn set_contract_class(&mut self, class_hash: &ClassHash, contract_class: ContractClass) -> StateResult<()> {
+        self.writes.insert(StateKey::ContractClass(*class_hash), StateValue::ContractClass(contract_class));
+
+        Ok(())
+    }
+
+    fn set_compiled_class_hash(
+        &mut self,
+        class_hash: ClassHash,
+        compiled_class_hash: CompiledClassHash,
+    ) -> StateResult<()> {
+        self.state_cache.set_compiled_class_hash_write(class_hash, compiled_class_hash);
+        self.compiled_class_hash_updates += 1;
+        self.writes.insert(StateKey::CompiledClassHash(class_hash), StateValue::CompiledClassHash(compiled_class_hash));
+
+        Ok(())
+    }
+
+    fn to_state_diff(&self) -> CommitmentStateDiff {
+        self.state_cache.to_state_diff()
+    }
+}
+
+/// The output of the last incarnation of a transaction.
+struct TransactionOutput {
+    incarnation: Incarnation,
+    reads: Vec<(StateKey, Option<Version>)>,
+    result: Result<TransactionExecutionInfo, String>,
+    state_diff: CommitmentStateDiff,
+}
+
+struct ParallelExecutor<'a, T: Config> {
+    transactions: &'a [UserOrL1HandlerTransaction],
+    chain_id: Felt252Wrapper,
+    block_context: &'a BlockContext,
+    execution_config: &'a ExecutionConfig,
+    scheduler: Scheduler,
+    memory: MultiVersionMemory,
+    base: BaseState,
+    outputs: Vec<Mutex<Option<TransactionOutput>>>,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: Config> ParallelExecutor<'a, T> {
+    fn run_worker(&self, read_chain_state: ChainStateReader<'_>) {
+        while let Some(task) = self.scheduler.next_task() {
+            match task {
+                Task::Execution(tx_index, incarnation) => self.execute(tx_index, incarnation, read_chain_state),
+                Task::Validation(tx_index, incarnation) => {
+                    let valid = match &*self.outputs[tx_index].lock().expect("poisoned lock") {
+                        Some(output) if output.incarnation == incarnation => {
+                            self.memory.validate(tx_index, &output.reads)
+                        }
+                        _ => false,
+                    };
+                    self.scheduler.finish_validation(&self.memory, tx_index, incarnation, valid);
+                }
+            }
+        }
+    }
+
+    fn execute(&self, tx_index: TxIndex, incarnation: Incarnation, read_chain_state: ChainStateReader<'_>) {
+        loop {
+            let mut state = VersionedState::new(tx_index, &self.memory, &self.base, read_chain_state);
+            let result = Pallet::<T>::execute_user_or_l1_handler_transaction(
+                &self.transactions[tx_index],
+                self.chain_id,
+                self.block_context,
+                self.execution_config,
+                &mut state,
+            );
+
+            if let Some(blocking_tx_index) = state.dependency {
+                if self.scheduler.add_dependency(tx_index, blocking_tx_index) {
+                    return;
+                }
+                // The transaction it read from was executed meanwhile
+                continue;
+            }
+
+            let state_diff = state.to_state_diff();
+            let VersionedState { reads, writes, .. } = state;
+            self.memory.record(tx_index, incarnation, writes);
+            *self.outputs[tx_index].lock().expect("poisoned lock") =
+                Some(TransactionOutput { incarnation, reads, result: result.map_err(|e| e.to_string()), state_diff });
+            self.scheduler.finish_execution(tx_index, incarnation);
+            return;
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Executes the transactions on up to `workers` threads and writes their changes to the
+    /// state. The results, and the state, are the ones of their execution one after the other,
+    /// whatever the number of workers.
+    ///
+    /// The errors of the transactions are returned as their message.
+    pub(crate) fn execute_transactions_in_parallel(
+        chain_id: Felt252Wrapper,
+        block_context: &BlockContext,
+        execution_config: &ExecutionConfig,
+        transactions: &[UserOrL1HandlerTransaction],
+        workers: usize,
+    ) -> Vec<(Result<TransactionExecutionInfo, String>, CommitmentStateDiff)> {
+        let executor = ParallelExecutor::<T> {
+            transactions,
+            chain_id,
+            block_context,
+            execution_config,
+            scheduler: Scheduler::new(transactions.len()),
+            memory: MultiVersionMemory::new(transactions.len()),
+            base: BaseState::default(),
+            outputs: transactions.iter().map(|_| Mutex::new(None)).collect(),
+            _phantom: PhantomData,
+        };
+
+        let workers = workers.min(transactions.len());
+        if workers <= 1 {
+            executor.run_worker(&read_chain_state::<T>);
+        } else {
+            std::thread::scope(|scope| {
+                let (requests, served_requests) = mpsc::channel::<ChainStateRequest>();
+                for _ in 0..workers {
+                    let requests = requests.clone();
+                    let executor = &executor;
+                    scope.spawn(move || executor.run_worker(&|key| forward_chain_state_read(&requests, key)));
+                }
+                // The requests stop once all the workers are done, dropping their senders
+                drop(requests);
+                for (key, reply) in served_requests {
+                    // The worker no longer waits for the reply only if it panicked
+                    let _ = reply.send(read_chain_state::<T>(key));
+                }
+            });
+        }
+
+        let ParallelExecutor { memory, outputs, .. } = executor;
+        // Every transaction is executed and validated, the last value written to a location is
+        // the one a sequential execution ends with
+        write_chain_state::<T>(memory.into_latest_writes());
+
+        outputs
+            .into_iter()
+            .map(|output| {
+                let output = output.into_inner().expect("poisoned lock").expect("every transaction is executed");
+                (output.result, output.state_diff)
+            })
+            .collect()
+    }
+}
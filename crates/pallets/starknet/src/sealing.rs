@@ -0,0 +1,75 @@
+//! Block parameters depending on how the node seals its blocks, passed to the runtime by the
+//! sequencer address inherent.
+use sp_inherents::InherentIdentifier;
+
+use crate::block_limits::BlockResources;
+
+/// The identifier of the inherent data holding the [`SealingParameters`] of the block.
+pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"sealprm0";
+
+/// Parameters set by the node sealing a block.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    parity_scale_codec::Encode,
+    parity_scale_codec::Decode,
+    scale_info::TypeInfo,
+    parity_scale_codec::MaxEncodedLen,
+)]
+pub struct SealingParameters {
+    /// The budgets of the block, capped by the `MaxBlockResources` of the runtime.
+    pub max_block_resources: BlockResources,
+    /// Whether `apply_state_edits` and `mint` can be dispatched, which should only be the case on
+    /// development chains.
+    pub allow_state_edits: bool,
+    /// Whether `import_state_diff` can be dispatched, which should only be the case on nodes
+    /// importing their blocks from another sequencer.
+    pub allow_state_import: bool,
+}
+
+impl Default for SealingParameters {
+    /// No budget and no state change outside of the transactions.
+    fn default() -> Self {
+        Self { max_block_resources: BlockResources::MAX, allow_state_edits: false, allow_state_import: false }
+    }
+}
+
+impl SealingParameters {
+    /// Whether the block allows state changes that `allowed` does not.
+    pub fn allows_more_than(&self, allowed: &Self) -> bool {
+        (self.allow_state_edits && !allowed.allow_state_edits)
+            || (self.allow_state_import && !allowed.allow_state_import)
+    }
+}
+
+#[cfg(feature = "std")]
+mod inherent_data_provider {
+    use sp_inherents::InherentData;
+
+    use super::*;
+
+    /// The inherent data provider for the sealing parameters.
+    pub struct InherentDataProvider(pub SealingParameters);
+
+    #[async_trait::async_trait]
+    impl sp_inherents::InherentDataProvider for InherentDataProvider {
+        async fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), sp_inherents::Error> {
+            inherent_data.put_data(INHERENT_IDENTIFIER, &self.0)
+        }
+
+        async fn try_handle_error(
+            &self,
+            _identifier: &InherentIdentifier,
+            _error: &[u8],
+        ) -> Option<Result<(), sp_inherents::Error>> {
+            // Errors are reported by the sequencer address inherent data provider
+            None
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub use inherent_data_provider::InherentDataProvider;
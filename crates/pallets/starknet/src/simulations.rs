@@ -1,45 +1,81 @@
+use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
 
+use blockifier::abi::abi_utils::{get_erc20_balance_var_addresses, get_storage_var_address};
 use blockifier::block_context::BlockContext;
-use blockifier::state::cached_state::CommitmentStateDiff;
-use blockifier::state::state_api::State;
+use blockifier::execution::contract_class::ContractClass;
+use blockifier::state::cached_state::{CommitmentStateDiff, ContractStorageKey, StateChangesCount};
+use blockifier::state::state_api::{State, StateReader, StateResult};
 use blockifier::transaction::errors::TransactionExecutionError;
 use blockifier::transaction::objects::TransactionExecutionInfo;
 use frame_support::storage;
+use mp_fee::PriceUnit;
 use mp_felt::Felt252Wrapper;
-use mp_simulations::{PlaceHolderErrorTypeForFailedStarknetExecution, SimulationFlags, TransactionSimulationResult};
+use mp_simulations::{
+    PlaceHolderErrorTypeForFailedStarknetExecution, SimulatedBlock, SimulationFlags, StateOverrides,
+    TransactionSimulationResult,
+};
+use mp_state::StateChanges;
 use mp_transactions::execution::{Execute, ExecutionConfig};
+use mp_transactions::getters::TransactionVersion;
 use mp_transactions::{HandleL1MessageTransaction, UserOrL1HandlerTransaction, UserTransaction};
-use sp_core::Get;
 use sp_runtime::DispatchError;
-use starknet_api::transaction::Fee;
-
-use crate::blockifier_state_adapter::{BlockifierStateAdapter, CachedBlockifierStateAdapter};
+use starknet_api::api_core::{ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, Nonce};
+use starknet_api::block::BlockNumber;
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{Calldata, Fee};
+use starknet_crypto::FieldElement;
+
+use crate::blockifier_state_adapter::{BlockifierStateAdapter, CachedBlockifierStateAdapter, ContractClassCache};
 use crate::execution_config::RuntimeExecutionConfigBuilder;
-use crate::{Config, Error, Pallet};
+use crate::parallel_execution::execution_workers;
+use crate::{Config, ContractClassHashes, Error, Nonces, Pallet, StorageView};
 
 impl<T: Config> Pallet<T> {
     pub fn estimate_fee(transactions: Vec<UserTransaction>) -> Result<Vec<(u64, u64)>, DispatchError> {
+        Self::estimate_fee_with_overrides(transactions, StateOverrides::default())
+    }
+
+    /// Estimates the fee of the transactions, executing them against the on-chain state
+    /// modified by the given overrides.
+    pub fn estimate_fee_with_overrides(
+        transactions: Vec<UserTransaction>,
+        state_overrides: StateOverrides,
+    ) -> Result<Vec<(u64, u64)>, DispatchError> {
         storage::transactional::with_transaction(|| {
             storage::TransactionOutcome::Rollback(Result::<_, DispatchError>::Ok(Self::estimate_fee_inner(
                 transactions,
+                state_overrides,
             )))
         })
         .map_err(|_| Error::<T>::FailedToCreateATransactionalStorageExecution)?
     }
 
-    fn estimate_fee_inner(transactions: Vec<UserTransaction>) -> Result<Vec<(u64, u64)>, DispatchError> {
+    fn estimate_fee_inner(
+        transactions: Vec<UserTransaction>,
+        state_overrides: StateOverrides,
+    ) -> Result<Vec<(u64, u64)>, DispatchError> {
         let transactions_len = transactions.len();
         let chain_id = Self::chain_id();
-        let block_context = Self::get_block_context();
+        let mut overlay = StateOverlay::new::<T>(state_overrides, Self::fee_token_address())?;
+        let contract_classes = ContractClassCache::default();
         let mut execution_config = RuntimeExecutionConfigBuilder::new::<T>().with_query_mode().build();
 
         let fee_res_iterator = transactions
             .into_iter()
             .map(|tx| {
                 execution_config.set_offset_version(tx.offset_version());
-
-                match Self::execute_transaction_with_state_diff(tx, chain_id, &block_context, &execution_config) {
+                let block_context = Self::get_block_context_in(PriceUnit::for_tx_version(tx.version()))?;
+
+                match Self::execute_transaction_with_overrides(
+                    tx,
+                    chain_id,
+                    &block_context,
+                    &execution_config,
+                    &mut overlay,
+                    &contract_classes,
+                ) {
                     (Ok(execution_info), _) if !execution_info.is_reverted() => Ok(execution_info),
                     (Err(e), _) => {
                         log::error!("Transaction execution failed during fee estimation: {e}");
@@ -75,11 +111,24 @@ impl<T: Config> Pallet<T> {
     pub fn simulate_transactions(
         transactions: Vec<UserTransaction>,
         simulation_flags: &SimulationFlags,
+    ) -> Result<Vec<(CommitmentStateDiff, TransactionSimulationResult)>, DispatchError> {
+        Self::simulate_transactions_with_overrides(transactions, simulation_flags, StateOverrides::default())
+    }
+
+    /// Simulates the transactions, executing them against the on-chain state modified by the
+    /// given overrides.
+    ///
+    /// The returned state diffs are relative to the overridden state.
+    pub fn simulate_transactions_with_overrides(
+        transactions: Vec<UserTransaction>,
+        simulation_flags: &SimulationFlags,
+        state_overrides: StateOverrides,
     ) -> Result<Vec<(CommitmentStateDiff, TransactionSimulationResult)>, DispatchError> {
         storage::transactional::with_transaction(|| {
             storage::TransactionOutcome::Rollback(Result::<_, DispatchError>::Ok(Self::simulate_transactions_inner(
                 transactions,
                 simulation_flags,
+                state_overrides,
             )))
         })
         .map_err(|_| Error::<T>::FailedToCreateATransactionalStorageExecution)?
@@ -88,27 +137,185 @@ impl<T: Config> Pallet<T> {
     fn simulate_transactions_inner(
         transactions: Vec<UserTransaction>,
         simulation_flags: &SimulationFlags,
+        state_overrides: StateOverrides,
     ) -> Result<Vec<(CommitmentStateDiff, TransactionSimulationResult)>, DispatchError> {
         let chain_id = Self::chain_id();
         let block_context = Self::get_block_context();
+        let mut overlay = StateOverlay::new::<T>(state_overrides, block_context.fee_token_address)?;
+        let contract_classes = ContractClassCache::default();
         let mut execution_config =
             RuntimeExecutionConfigBuilder::new::<T>().with_simulation_mode(simulation_flags).build();
 
-        let tx_execution_results: Vec<(CommitmentStateDiff, TransactionSimulationResult)> = transactions
-            .into_iter()
-            .map(|tx| {
-                execution_config.set_offset_version(tx.offset_version());
+        let mut tx_execution_results = Vec::with_capacity(transactions.len());
+        for tx in transactions {
+            execution_config.set_offset_version(tx.offset_version());
+            let tx_block_context =
+                Self::block_context_in(block_context.clone(), PriceUnit::for_tx_version(tx.version()))?;
+
+            let res = Self::execute_transaction_with_overrides(
+                tx,
+                chain_id,
+                &tx_block_context,
+                &execution_config,
+                &mut overlay,
+                &contract_classes,
+            );
+            let result = res.0.map_err(|e| {
+                log::error!("Transaction execution failed during simulation: {e}");
+                PlaceHolderErrorTypeForFailedStarknetExecution
+            });
+            tx_execution_results.push((res.1, result));
+        }
+
+        Ok(tx_execution_results)
+    }
 
-                let res = Self::execute_transaction_with_state_diff(tx, chain_id, &block_context, &execution_config);
+    /// Simulates a sequence of blocks, each one being executed on top of the state left by the
+    /// previous one.
+    ///
+    /// Unless overridden, a block inherits the context of the previous one with its number
+    /// incremented. The first block inherits the context of the pending block.
+    pub fn simulate_blocks(
+        blocks: Vec<SimulatedBlock>,
+        simulation_flags: &SimulationFlags,
+    ) -> Result<Vec<Vec<(CommitmentStateDiff, TransactionSimulationResult)>>, DispatchError> {
+        storage::transactional::with_transaction(|| {
+            storage::TransactionOutcome::Rollback(Result::<_, DispatchError>::Ok(Self::simulate_blocks_inner(
+                blocks,
+                simulation_flags,
+            )))
+        })
+        .map_err(|_| Error::<T>::FailedToCreateATransactionalStorageExecution)?
+    }
+
+    fn simulate_blocks_inner(
+        blocks: Vec<SimulatedBlock>,
+        simulation_flags: &SimulationFlags,
+    ) -> Result<Vec<Vec<(CommitmentStateDiff, TransactionSimulationResult)>>, DispatchError> {
+        let chain_id = Self::chain_id();
+        let mut block_context = Self::get_block_context();
+        let contract_classes = ContractClassCache::default();
+        let mut execution_config =
+            RuntimeExecutionConfigBuilder::new::<T>().with_simulation_mode(simulation_flags).build();
+
+        let mut blocks_execution_results = Vec::with_capacity(blocks.len());
+        for (index, block) in blocks.into_iter().enumerate() {
+            if index > 0 {
+                block_context.block_number = BlockNumber(block_context.block_number.0 + 1);
+            }
+            block.block_context_overrides.apply_to(&mut block_context);
+
+            // The transactions write straight into the storage, so the next block sees their effects
+            let mut tx_execution_results = Vec::with_capacity(block.transactions.len());
+            for tx in block.transactions {
+                execution_config.set_offset_version(tx.offset_version());
+                let tx_block_context =
+                    Self::block_context_in(block_context.clone(), PriceUnit::for_tx_version(tx.version()))?;
+
+                let res = Self::execute_transaction_with_state_diff(
+                    tx,
+                    chain_id,
+                    &tx_block_context,
+                    &execution_config,
+                    &contract_classes,
+                );
                 let result = res.0.map_err(|e| {
                     log::error!("Transaction execution failed during simulation: {e}");
                     PlaceHolderErrorTypeForFailedStarknetExecution
                 });
-                (res.1, result)
-            })
-            .collect();
+                tx_execution_results.push((res.1, result));
+            }
 
-        Ok(tx_execution_results)
+            blocks_execution_results.push(tx_execution_results);
+        }
+
+        Ok(blocks_execution_results)
+    }
+
+    /// Calls a smart contract function against the on-chain state modified by the given overrides.
+    pub fn call_contract_with_overrides(
+        address: ContractAddress,
+        function_selector: EntryPointSelector,
+        calldata: Calldata,
+        state_overrides: StateOverrides,
+    ) -> Result<Vec<Felt252Wrapper>, DispatchError> {
+        storage::transactional::with_transaction(|| {
+            storage::TransactionOutcome::Rollback(Result::<_, DispatchError>::Ok(
+                Self::call_contract_with_overrides_inner(address, function_selector, calldata, state_overrides),
+            ))
+        })
+        .map_err(|_| Error::<T>::FailedToCreateATransactionalStorageExecution)?
+    }
+
+    /// Writes the values of the overrides to the on-chain state.
+    pub(crate) fn write_state_overrides(state_overrides: StateOverrides) -> Result<(), DispatchError> {
+        let overlay = StateOverlay::new::<T>(state_overrides, Self::fee_token_address())?;
+
+        for (contract_storage_key, value) in overlay.storage {
+            StorageView::<T>::insert(contract_storage_key, value);
+        }
+        for (contract_address, nonce) in overlay.nonces {
+            Nonces::<T>::insert(contract_address, nonce);
+        }
+        for (contract_address, class_hash) in overlay.class_hashes {
+            ContractClassHashes::<T>::insert(contract_address, class_hash);
+        }
+
+        Ok(())
+    }
+
+    /// Adds `amount` to the fee token balance of `address` and to the fee token total supply.
+    pub(crate) fn mint_fee_token(address: ContractAddress, amount: StarkFelt) -> Result<(), DispatchError> {
+        let fee_token_address = Self::fee_token_address();
+        let balance_keys = get_erc20_balance_var_addresses(&address).map_err(|_| Error::<T>::InvalidStateOverride)?;
+        let total_supply_low_key =
+            get_storage_var_address("ERC20_total_supply", &[]).map_err(|_| Error::<T>::InvalidStateOverride)?;
+        let total_supply_high_key: StorageKey =
+            Felt252Wrapper(Felt252Wrapper::from(total_supply_low_key).0 + FieldElement::ONE).into();
+
+        Self::add_to_fee_token_u256(fee_token_address, balance_keys, amount)?;
+        Self::add_to_fee_token_u256(fee_token_address, (total_supply_low_key, total_supply_high_key), amount)
+    }
+
+    /// Adds `amount` to the u256 the fee token stores at the `low_key` and `high_key` felts.
+    fn add_to_fee_token_u256(
+        fee_token_address: ContractAddress,
+        (low_key, high_key): (StorageKey, StorageKey),
+        amount: StarkFelt,
+    ) -> Result<(), DispatchError> {
+        let read = |key| {
+            u128::try_from(Felt252Wrapper::from(StorageView::<T>::get((fee_token_address, key))))
+                .map_err(|_| Error::<T>::InvalidStateOverride)
+        };
+        // The fee token stores its u256 values split in two felts
+        let amount = Felt252Wrapper::from(amount).0.to_bytes_be();
+        let (amount_high, amount_low) = amount.split_at(16);
+        let amount_high = u128::from_be_bytes(amount_high.try_into().map_err(|_| Error::<T>::InvalidStateOverride)?);
+        let amount_low = u128::from_be_bytes(amount_low.try_into().map_err(|_| Error::<T>::InvalidStateOverride)?);
+
+        let (low, carry) = read(low_key)?.overflowing_add(amount_low);
+        let high = read(high_key)?
+            .checked_add(amount_high)
+            .and_then(|high| high.checked_add(carry.into()))
+            .ok_or(Error::<T>::InvalidStateOverride)?;
+
+        StorageView::<T>::insert((fee_token_address, low_key), StarkFelt::from(low));
+        StorageView::<T>::insert((fee_token_address, high_key), StarkFelt::from(high));
+
+        Ok(())
+    }
+
+    fn call_contract_with_overrides_inner(
+        address: ContractAddress,
+        function_selector: EntryPointSelector,
+        calldata: Calldata,
+        state_overrides: StateOverrides,
+    ) -> Result<Vec<Felt252Wrapper>, DispatchError> {
+        let fee_token_address = Self::fee_token_address();
+        let mut overlay = StateOverlay::new::<T>(state_overrides, fee_token_address)?;
+        let mut state = OverriddenStateAdapter::new(BlockifierStateAdapter::<T>::default(), &mut overlay);
+
+        Self::call_contract_with_state(&mut state, address, function_selector, calldata)
     }
 
     pub fn simulate_message(
@@ -183,7 +390,7 @@ impl<T: Config> Pallet<T> {
             }?;
 
         if let Some(l1_gas_usage) = tx_execution_infos.actual_resources.0.get("l1_gas_usage") {
-            Ok((T::L1GasPrice::get().price_in_wei, tx_execution_infos.actual_fee.0 as u64, *l1_gas_usage))
+            Ok((Self::l1_gas_price().price_in_wei, tx_execution_infos.actual_fee.0 as u64, *l1_gas_usage))
         } else {
             Err(Error::<T>::MissingL1GasUsage.into())
         }
@@ -234,23 +441,57 @@ impl<T: Config> Pallet<T> {
         chain_id: Felt252Wrapper,
         block_context: &BlockContext,
         execution_config: &ExecutionConfig,
+        contract_classes: &ContractClassCache,
     ) -> (Result<TransactionExecutionInfo, TransactionExecutionError>, CommitmentStateDiff) {
-        let mut cached_state = CachedBlockifierStateAdapter(BlockifierStateAdapter::<T>::default());
-        let result = match transaction {
+        let mut cached_state = CachedBlockifierStateAdapter(BlockifierStateAdapter::<T>::with_contract_class_cache(
+            contract_classes.clone(),
+        ));
+        let result =
+            Self::execute_user_transaction(transaction, chain_id, block_context, execution_config, &mut cached_state);
+
+        (result, cached_state.to_state_diff())
+    }
+
+    fn execute_transaction_with_overrides(
+        transaction: UserTransaction,
+        chain_id: Felt252Wrapper,
+        block_context: &BlockContext,
+        execution_config: &ExecutionConfig,
+        overlay: &mut StateOverlay,
+        contract_classes: &ContractClassCache,
+    ) -> (Result<TransactionExecutionInfo, TransactionExecutionError>, CommitmentStateDiff) {
+        let state = BlockifierStateAdapter::<T>::with_contract_class_cache(contract_classes.clone());
+        let mut state = OverriddenStateAdapter::new(CachedBlockifierStateAdapter(state), overlay);
+        let result = Self::execute_user_transaction(transaction, chain_id, block_context, execution_config, &mut state);
+
+        (result, state.to_state_diff())
+    }
+
+    pub(crate) fn execute_user_transaction<S: State + StateChanges>(
+        transaction: UserTransaction,
+        chain_id: Felt252Wrapper,
+        block_context: &BlockContext,
+        execution_config: &ExecutionConfig,
+        state: &mut S,
+    ) -> Result<TransactionExecutionInfo, TransactionExecutionError> {
+        match transaction {
             UserTransaction::Declare(tx, contract_class) => tx
                 .try_into_executable::<T::SystemHash>(chain_id, contract_class.clone(), tx.offset_version())
-                .and_then(|exec| exec.execute(&mut cached_state, block_context, execution_config)),
+                .and_then(|exec| exec.execute(state, block_context, execution_config)),
             UserTransaction::DeployAccount(tx) => {
                 let executable = tx.into_executable::<T::SystemHash>(chain_id, tx.offset_version());
-                executable.execute(&mut cached_state, block_context, execution_config)
+                executable.execute(state, block_context, execution_config)
             }
             UserTransaction::Invoke(tx) => {
-                let executable = tx.into_executable::<T::SystemHash>(chain_id, tx.offset_version());
-                executable.execute(&mut cached_state, block_context, execution_config)
+                match tx.into_sponsored_executable::<T::SystemHash>(chain_id, tx.offset_version()) {
+                    Some(executable) => executable.execute(state, block_context, execution_config),
+                    None => {
+                        let executable = tx.into_executable::<T::SystemHash>(chain_id, tx.offset_version());
+                        executable.execute(state, block_context, execution_config)
+                    }
+                }
             }
-        };
-
-        (result, cached_state.to_state_diff())
+        }
     }
 
     fn execute_message(
@@ -259,15 +500,46 @@ impl<T: Config> Pallet<T> {
         block_context: &BlockContext,
         execution_config: &ExecutionConfig,
     ) -> (Result<TransactionExecutionInfo, TransactionExecutionError>, CommitmentStateDiff) {
-        // Follow `offset` from Pallet Starknet where it is set to false
         let mut cached_state = CachedBlockifierStateAdapter(BlockifierStateAdapter::<T>::default());
+        let result =
+            Self::execute_l1_handler_transaction(message, chain_id, block_context, execution_config, &mut cached_state);
+
+        (result, cached_state.to_state_diff())
+    }
+
+    fn execute_l1_handler_transaction<S: State + StateChanges>(
+        message: HandleL1MessageTransaction,
+        chain_id: Felt252Wrapper,
+        block_context: &BlockContext,
+        execution_config: &ExecutionConfig,
+        state: &mut S,
+    ) -> Result<TransactionExecutionInfo, TransactionExecutionError> {
+        // Follow `offset` from Pallet Starknet where it is set to false
         let fee = Fee(u128::MAX);
         let executable = message.into_executable::<T::SystemHash>(chain_id, fee, false);
-        let result = executable.execute(&mut cached_state, block_context, execution_config);
+        executable.execute(state, block_context, execution_config)
+    }
 
-        (result, cached_state.to_state_diff())
+    /// Executes a transaction of a sequence against the given state.
+    pub(crate) fn execute_user_or_l1_handler_transaction<S: State + StateChanges>(
+        transaction: &UserOrL1HandlerTransaction,
+        chain_id: Felt252Wrapper,
+        block_context: &BlockContext,
+        execution_config: &ExecutionConfig,
+        state: &mut S,
+    ) -> Result<TransactionExecutionInfo, TransactionExecutionError> {
+        match transaction {
+            UserOrL1HandlerTransaction::User(tx) => {
+                Self::execute_user_transaction(tx.clone(), chain_id, block_context, execution_config, state)
+            }
+            UserOrL1HandlerTransaction::L1Handler(tx, _fee) => {
+                Self::execute_l1_handler_transaction(tx.clone(), chain_id, block_context, execution_config, state)
+            }
+        }
     }
 
+    /// Executes the transactions, in parallel when possible, with the results of their execution
+    /// one after the other, and writes their changes to the state.
     fn execute_user_or_l1_handler_transactions(
         chain_id: Felt252Wrapper,
         block_context: &BlockContext,
@@ -275,17 +547,13 @@ impl<T: Config> Pallet<T> {
         transactions: Vec<UserOrL1HandlerTransaction>,
     ) -> Result<Vec<(TransactionExecutionInfo, CommitmentStateDiff)>, PlaceHolderErrorTypeForFailedStarknetExecution>
     {
-        let exec_transactions: Vec<_> = transactions
-            .iter()
-            .map(|user_or_l1_tx| match user_or_l1_tx {
-                UserOrL1HandlerTransaction::User(tx) => {
-                    Self::execute_transaction_with_state_diff(tx.clone(), chain_id, block_context, execution_config)
-                }
-                UserOrL1HandlerTransaction::L1Handler(tx, _fee) => {
-                    Self::execute_message(tx.clone(), chain_id, block_context, execution_config)
-                }
-            })
-            .collect();
+        let exec_transactions = Self::execute_transactions_in_parallel(
+            chain_id,
+            block_context,
+            execution_config,
+            &transactions,
+            execution_workers(),
+        );
 
         let mut execution_infos = Vec::with_capacity(exec_transactions.len());
         for (exec_result, state_diff) in exec_transactions {
@@ -301,3 +569,148 @@ impl<T: Config> Pallet<T> {
         Ok(execution_infos)
     }
 }
+
+/// The on-chain values substituted by a set of [StateOverrides].
+#[derive(Default)]
+struct StateOverlay {
+    storage: BTreeMap<ContractStorageKey, StarkFelt>,
+    nonces: BTreeMap<ContractAddress, Nonce>,
+    class_hashes: BTreeMap<ContractAddress, ClassHash>,
+}
+
+impl StateOverlay {
+    fn new<T: Config>(
+        state_overrides: StateOverrides,
+        fee_token_address: ContractAddress,
+    ) -> Result<Self, DispatchError> {
+        let mut overlay = Self::default();
+
+        for contract_override in state_overrides {
+            let contract_address: ContractAddress = contract_override.contract_address.into();
+
+            if let Some(nonce) = contract_override.nonce {
+                overlay.nonces.insert(contract_address, nonce.into());
+            }
+            if let Some(class_hash) = contract_override.class_hash {
+                overlay.class_hashes.insert(contract_address, class_hash.into());
+            }
+            if let Some(balance) = contract_override.balance {
+                // The fee token stores balances as an u256, split in two felts
+                let (low_key, high_key) =
+                    get_erc20_balance_var_addresses(&contract_address).map_err(|_| Error::<T>::InvalidStateOverride)?;
+                let balance = balance.0.to_bytes_be();
+                let (high, low) = balance.split_at(16);
+                let high = u128::from_be_bytes(high.try_into().map_err(|_| Error::<T>::InvalidStateOverride)?);
+                let low = u128::from_be_bytes(low.try_into().map_err(|_| Error::<T>::InvalidStateOverride)?);
+                overlay.storage.insert((fee_token_address, low_key), StarkFelt::from(low));
+                overlay.storage.insert((fee_token_address, high_key), StarkFelt::from(high));
+            }
+            for (key, value) in contract_override.storage {
+                overlay.storage.insert((contract_address, key.into()), value.into());
+            }
+        }
+
+        Ok(overlay)
+    }
+}
+
+/// Wrapper around a state adapter serving the overridden values instead of the on-chain ones.
+///
+/// Writes are forwarded to the wrapped adapter and applied to the overlay as well, so the same
+/// overlay can be shared by the successive transactions of a simulation.
+struct OverriddenStateAdapter<'a, S> {
+    state: S,
+    overlay: &'a mut StateOverlay,
+    // Nonces of the overridden addresses never reach the wrapped adapter, so they are tracked
+    // here to be part of the state diff.
+    nonce_updates: BTreeMap<ContractAddress, Nonce>,
+}
+
+impl<'a, S> OverriddenStateAdapter<'a, S> {
+    fn new(state: S, overlay: &'a mut StateOverlay) -> Self {
+        Self { state, overlay, nonce_updates: BTreeMap::new() }
+    }
+}
+
+impl<'a, S: StateChanges> StateChanges for OverriddenStateAdapter<'a, S> {
+    fn count_state_changes(&self) -> StateChangesCount {
+        self.state.count_state_changes()
+    }
+}
+
+impl<'a, S: StateReader> StateReader for OverriddenStateAdapter<'a, S> {
+    fn get_storage_at(&mut self, contract_address: ContractAddress, key: StorageKey) -> StateResult<StarkFelt> {
+        match self.overlay.storage.get(&(contract_address, key)) {
+            Some(value) => Ok(*value),
+            None => self.state.get_storage_at(contract_address, key),
+        }
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        match self.overlay.nonces.get(&contract_address) {
+            Some(nonce) => Ok(*nonce),
+            None => self.state.get_nonce_at(contract_address),
+        }
+    }
+
+    fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        match self.overlay.class_hashes.get(&contract_address) {
+            Some(class_hash) => Ok(*class_hash),
+            None => self.state.get_class_hash_at(contract_address),
+        }
+    }
+
+    fn get_compiled_contract_class(&mut self, class_hash: &ClassHash) -> StateResult<ContractClass> {
+        self.state.get_compiled_contract_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.state.get_compiled_class_hash(class_hash)
+    }
+}
+
+impl<'a, S: State> State for OverriddenStateAdapter<'a, S> {
+    fn set_storage_at(&mut self, contract_address: ContractAddress, key: StorageKey, value: StarkFelt) {
+        if let Some(overridden) = self.overlay.storage.get_mut(&(contract_address, key)) {
+            *overridden = value;
+        }
+        self.state.set_storage_at(contract_address, key, value);
+    }
+
+    fn increment_nonce(&mut self, contract_address: ContractAddress) -> StateResult<()> {
+        match self.overlay.nonces.get_mut(&contract_address) {
+            Some(nonce) => {
+                let current_nonce: FieldElement = Felt252Wrapper::from(nonce.0).into();
+                *nonce = Felt252Wrapper(current_nonce + FieldElement::ONE).into();
+                self.nonce_updates.insert(contract_address, *nonce);
+                Ok(())
+            }
+            None => self.state.increment_nonce(contract_address),
+        }
+    }
+
+    fn set_class_hash_at(&mut self, contract_address: ContractAddress, class_hash: ClassHash) -> StateResult<()> {
+        if let Some(overridden) = self.overlay.class_hashes.get_mut(&contract_address) {
+            *overridden = class_hash;
+        }
+        self.state.set_class_hash_at(contract_address, class_hash)
+    }
+
+    fn set_contract_class(&mut self, class_hash: &ClassHash, contract_class: ContractClass) -> StateResult<()> {
+        self.state.set_contract_class(class_hash, contract_class)
+    }
+
+    fn set_compiled_class_hash(
+        &mut self,
+        class_hash: ClassHash,
+        compiled_class_hash: CompiledClassHash,
+    ) -> StateResult<()> {
+        self.state.set_compiled_class_hash(class_hash, compiled_class_hash)
+    }
+
+    fn to_state_diff(&self) -> CommitmentStateDiff {
+        let mut state_diff = self.state.to_state_diff();
+        state_diff.address_to_nonce.extend(self.nonce_updates.iter().map(|(address, nonce)| (*address, *nonce)));
+        state_diff
+    }
+}
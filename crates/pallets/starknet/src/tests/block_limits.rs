@@ -0,0 +1,124 @@
+use frame_support::assert_ok;
+use mp_felt::Felt252Wrapper;
+use mp_transactions::InvokeTransaction;
+use sp_runtime::traits::ValidateUnsigned;
+use sp_runtime::transaction_validity::{InvalidTransaction, TransactionValidityError};
+
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::block_limits::{BlockResources, TRANSACTION_TOO_LARGE};
+use crate::sealing::SealingParameters;
+use crate::tests::get_invoke_dummy;
+use crate::{BlockSealingParameters, Call};
+
+#[test]
+fn executed_transactions_consume_block_resources() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let transaction: InvokeTransaction = get_invoke_dummy(Felt252Wrapper::ZERO).into();
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction));
+
+        let used = Starknet::block_resources_used();
+        assert!(used.n_steps > 0);
+        // The fee transfer emits an event and updates the balances of the sender and the sequencer
+        assert!(used.n_events > 0);
+        assert!(used.state_diff_size > 0);
+        assert_eq!(used.n_transactions, 1);
+
+        // Accounting starts over with every block
+        run_to_block(3);
+        assert_eq!(Starknet::block_resources_used(), BlockResources::default());
+    });
+}
+
+#[test]
+fn given_transaction_overshooting_block_resources_then_it_is_not_dispatched() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let transaction: InvokeTransaction = get_invoke_dummy(Felt252Wrapper::ZERO).into();
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction));
+        let used = Starknet::block_resources_used();
+        // Room is left for half a transaction: the limit is not reached yet, but the next
+        // transaction would overshoot it
+        MaxBlockResources::set(&BlockResources { n_steps: used.n_steps + used.n_steps / 2, ..BlockResources::MAX });
+
+        let next_transaction: InvokeTransaction = get_invoke_dummy(Felt252Wrapper::ONE).into();
+        assert_eq!(
+            Starknet::pre_dispatch(&Call::invoke { transaction: next_transaction.clone() }),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::ExhaustsResources))
+        );
+        // Checking the transaction does not consume any resources
+        assert_eq!(Starknet::block_resources_used(), used);
+
+        // The sequencer address inherent is always dispatched
+        assert_ok!(Starknet::pre_dispatch(&Call::set_sequencer_address { addr: [0; 32] }));
+
+        // The transaction left over goes into the next block
+        run_to_block(3);
+        assert_ok!(Starknet::pre_dispatch(&Call::invoke { transaction: next_transaction }));
+    });
+}
+
+#[test]
+fn given_transaction_larger_than_a_block_then_it_is_invalid() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        MaxBlockResources::set(&BlockResources { n_steps: 1, ..BlockResources::MAX });
+
+        let transaction: InvokeTransaction = get_invoke_dummy(Felt252Wrapper::ZERO).into();
+        assert_eq!(
+            Starknet::pre_dispatch(&Call::invoke { transaction }),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::Custom(TRANSACTION_TOO_LARGE)))
+        );
+    });
+}
+
+#[test]
+fn given_block_transactions_limit_reached_then_transactions_are_not_dispatched() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        MaxBlockResources::set(&BlockResources { n_transactions: 2, ..BlockResources::MAX });
+
+        for nonce in [Felt252Wrapper::ZERO, Felt252Wrapper::ONE] {
+            let transaction: InvokeTransaction = get_invoke_dummy(nonce).into();
+            assert_ok!(Starknet::pre_dispatch(&Call::invoke { transaction: transaction.clone() }));
+            assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction));
+        }
+
+        let next_transaction: InvokeTransaction = get_invoke_dummy(Felt252Wrapper::TWO).into();
+        assert_eq!(
+            Starknet::pre_dispatch(&Call::invoke { transaction: next_transaction.clone() }),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::ExhaustsResources))
+        );
+
+        // The transaction left over goes into the next block
+        run_to_block(3);
+        assert_ok!(Starknet::pre_dispatch(&Call::invoke { transaction: next_transaction }));
+    });
+}
+
+#[test]
+fn given_sealing_budgets_then_they_lower_the_block_limits() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        BlockSealingParameters::<MockRuntime>::put(SealingParameters {
+            max_block_resources: BlockResources { n_transactions: 1, ..BlockResources::MAX },
+            ..Default::default()
+        });
+
+        let transaction: InvokeTransaction = get_invoke_dummy(Felt252Wrapper::ZERO).into();
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction));
+
+        let next_transaction: InvokeTransaction = get_invoke_dummy(Felt252Wrapper::ONE).into();
+        assert_eq!(
+            Starknet::pre_dispatch(&Call::invoke { transaction: next_transaction.clone() }),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::ExhaustsResources))
+        );
+
+        // The budgets are set for every block by the node sealing it
+        run_to_block(3);
+        assert_ok!(Starknet::pre_dispatch(&Call::invoke { transaction: next_transaction }));
+    });
+}
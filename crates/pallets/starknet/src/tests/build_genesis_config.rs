@@ -1,10 +1,13 @@
+use frame_support::traits::Get;
+use mp_fee::ResourcePrice;
 use mp_genesis_config::{GenesisData, GenesisLoader};
 use sp_runtime::{BuildStorage, Storage};
-use starknet_api::api_core::{ClassHash, ContractAddress};
+use starknet_api::api_core::{ClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::StarkFelt;
 
 use super::mock::default_mock;
 use super::utils::get_contract_class;
-use crate::GenesisConfig;
+use crate::{GenesisConfig, L1Messages, Pallet};
 
 #[test]
 fn works_when_sierra_clash_hash_in_mapping_is_known() {
@@ -54,6 +57,76 @@ fn fail_with_unknown_class_hash_in_contracts() {
     genesis.assimilate_storage(&mut t).unwrap();
 }
 
+#[test]
+fn nonces_are_stored_at_genesis() {
+    let genesis: GenesisConfig<default_mock::MockRuntime> =
+        GenesisConfig { nonces: vec![(ContractAddress(1u8.into()), Nonce(7u8.into()))], ..Default::default() };
+    let t = genesis.build_storage().unwrap();
+
+    sp_io::TestExternalities::new(t).execute_with(|| {
+        assert_eq!(Pallet::<default_mock::MockRuntime>::nonce(ContractAddress(1u8.into())), Nonce(7u8.into()));
+    });
+}
+
+#[test]
+fn admin_and_chain_parameters_are_stored_at_genesis() {
+    let gas_price = ResourcePrice { price_in_strk: Some(3), price_in_wei: 42 };
+    let genesis: GenesisConfig<default_mock::MockRuntime> = GenesisConfig {
+        admin: Some(1),
+        invoke_tx_max_n_steps: Some(10),
+        l1_gas_price: Some(gas_price),
+        disable_transaction_fee: Some(true),
+        ..Default::default()
+    };
+    let t = genesis.build_storage().unwrap();
+
+    sp_io::TestExternalities::new(t).execute_with(|| {
+        assert_eq!(Pallet::<default_mock::MockRuntime>::admin(), Some(1));
+        assert_eq!(frame_system::Pallet::<default_mock::MockRuntime>::providers(&1), 1);
+        assert_eq!(Pallet::<default_mock::MockRuntime>::invoke_tx_max_n_steps(), 10);
+        assert_eq!(Pallet::<default_mock::MockRuntime>::l1_gas_price(), gas_price);
+        assert!(Pallet::<default_mock::MockRuntime>::is_transaction_fee_disabled());
+        // Unset parameters use the runtime config
+        assert_eq!(
+            Pallet::<default_mock::MockRuntime>::validate_max_n_steps(),
+            <default_mock::MockRuntime as crate::Config>::ValidateMaxNSteps::get()
+        );
+    });
+}
+
+#[test]
+fn sequencers_are_registered_at_genesis() {
+    let sequencer_address = ContractAddress(PatriciaKey(StarkFelt::from(7u64)));
+    let genesis: GenesisConfig<default_mock::MockRuntime> =
+        GenesisConfig { sequencers: vec![(3, sequencer_address)], ..Default::default() };
+    let t = genesis.build_storage().unwrap();
+
+    sp_io::TestExternalities::new(t).execute_with(|| {
+        assert_eq!(Pallet::<default_mock::MockRuntime>::registered_sequencer(3), Some(sequencer_address));
+        assert_eq!(Pallet::<default_mock::MockRuntime>::registered_sequencer(4), None);
+    });
+}
+
+#[test]
+fn restored_chain_state_is_stored_at_genesis() {
+    let genesis_data: GenesisData = serde_json::from_str(
+        r#"{"contract_classes":[],"sierra_class_hash_to_casm_class_hash":[],"contracts":[],"predeployed_accounts":[],"storage":[],"fee_token_address":"0x5","chain_id":"MADARA","admin":"0x0100000000000000","sequencers":[["0x0300000000000000","0x7"]],"l1_messages":["0x2"]}"#,
+    )
+    .unwrap();
+    let genesis: GenesisConfig<default_mock::MockRuntime> = GenesisLoader::new(Default::default(), genesis_data).into();
+    let t = genesis.build_storage().unwrap();
+
+    sp_io::TestExternalities::new(t).execute_with(|| {
+        assert_eq!(Pallet::<default_mock::MockRuntime>::admin(), Some(1));
+        assert_eq!(
+            Pallet::<default_mock::MockRuntime>::registered_sequencer(3),
+            Some(ContractAddress(PatriciaKey(StarkFelt::from(7u64))))
+        );
+        assert!(L1Messages::<default_mock::MockRuntime>::contains_key(Nonce(StarkFelt::from(2u64))));
+        assert!(!L1Messages::<default_mock::MockRuntime>::contains_key(Nonce(StarkFelt::from(3u64))));
+    });
+}
+
 #[test]
 fn check_genesis_storage() {
     // setup
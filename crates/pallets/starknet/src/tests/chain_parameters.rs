@@ -0,0 +1,104 @@
+use frame_support::{assert_noop, assert_ok};
+use mp_fee::ResourcePrice;
+use mp_felt::Felt252Wrapper;
+use sp_runtime::DispatchError;
+use starknet_api::api_core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::StarkFelt;
+
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::{Admin, Event, InvokeTxMaxNSteps, L1GasPrice};
+
+#[test]
+fn chain_parameters_can_only_be_set_by_admin_origin() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        assert_noop!(Starknet::set_invoke_tx_max_n_steps(RuntimeOrigin::signed(1), 10), DispatchError::BadOrigin);
+        assert_noop!(Starknet::set_protocol_version(RuntimeOrigin::none(), 1), DispatchError::BadOrigin);
+    });
+}
+
+#[test]
+fn chain_parameters_can_be_set_by_the_admin_account() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        assert_ok!(Starknet::set_admin(RuntimeOrigin::root(), Some(1)));
+        assert_eq!(Admin::<MockRuntime>::get(), Some(1));
+
+        assert_ok!(Starknet::set_invoke_tx_max_n_steps(RuntimeOrigin::signed(1), 10));
+        assert_eq!(InvokeTxMaxNSteps::<MockRuntime>::get(), 10);
+        assert_noop!(Starknet::set_invoke_tx_max_n_steps(RuntimeOrigin::signed(2), 20), DispatchError::BadOrigin);
+
+        // The admin can hand over its role
+        assert_ok!(Starknet::set_admin(RuntimeOrigin::signed(1), Some(2)));
+        assert_noop!(Starknet::set_protocol_version(RuntimeOrigin::signed(1), 1), DispatchError::BadOrigin);
+        assert_ok!(Starknet::set_protocol_version(RuntimeOrigin::signed(2), 1));
+        System::assert_has_event(Event::<MockRuntime>::AdminChanged { old_admin: Some(1), new_admin: Some(2) }.into());
+    });
+}
+
+#[test]
+fn chain_parameters_updates_are_used_by_the_block_context() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let gas_price = ResourcePrice { price_in_strk: Some(3), price_in_wei: 42 };
+        assert_ok!(Starknet::set_l1_gas_price(RuntimeOrigin::root(), gas_price));
+        assert_ok!(Starknet::set_invoke_tx_max_n_steps(RuntimeOrigin::root(), 10));
+        assert_ok!(Starknet::set_validate_max_n_steps(RuntimeOrigin::root(), 20));
+
+        let block_context = Starknet::get_block_context();
+        assert_eq!(block_context.gas_price, 42);
+        assert_eq!(block_context.invoke_tx_max_n_steps, 10);
+        assert_eq!(block_context.validate_max_n_steps, 20);
+
+        System::assert_has_event(
+            Event::<MockRuntime>::L1GasPriceChanged {
+                old_gas_price: ResourcePrice { price_in_strk: None, price_in_wei: 10 },
+                new_gas_price: gas_price,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn config_hash_follows_chain_id_and_fee_token_address() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let initial_config_hash = Starknet::config_hash();
+
+        let fee_token_address = ContractAddress(PatriciaKey(StarkFelt::from(0xfeeu64)));
+        assert_ok!(Starknet::set_fee_token_address(RuntimeOrigin::root(), fee_token_address));
+        assert_eq!(Starknet::fee_token_address(), fee_token_address);
+        assert_eq!(Starknet::get_block_context().fee_token_address, fee_token_address);
+        let fee_token_config_hash = Starknet::config_hash();
+        assert_ne!(fee_token_config_hash, initial_config_hash);
+
+        let chain_id = Felt252Wrapper::from(0x5345504f4c4941u64);
+        assert_ok!(Starknet::set_chain_id(RuntimeOrigin::root(), chain_id));
+        assert_eq!(Starknet::chain_id(), chain_id);
+        assert_ne!(Starknet::config_hash(), fee_token_config_hash);
+    });
+}
+
+#[test]
+fn store_default_chain_parameters_only_fills_missing_values() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        assert_ok!(Starknet::set_invoke_tx_max_n_steps(RuntimeOrigin::root(), 10));
+        // Storage from before the parameters were moved out of the runtime constants
+        L1GasPrice::<MockRuntime>::kill();
+        assert!(!L1GasPrice::<MockRuntime>::exists());
+
+        Starknet::store_default_chain_parameters();
+
+        assert_eq!(L1GasPrice::<MockRuntime>::get(), ResourcePrice { price_in_strk: None, price_in_wei: 10 });
+        assert!(L1GasPrice::<MockRuntime>::exists());
+        assert_eq!(InvokeTxMaxNSteps::<MockRuntime>::get(), 10);
+    });
+}
@@ -0,0 +1,29 @@
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::StateReader;
+
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::blockifier_state_adapter::{BlockifierStateAdapter, ContractClassCache};
+use crate::ContractClasses;
+
+#[test]
+fn classes_are_decoded_once_for_the_adapters_sharing_a_cache() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        let account_address = get_account_address(None, AccountType::V0(AccountTypeV0Inner::NoValidate));
+        let class_hash = Starknet::contract_class_hash_by_address(account_address);
+        let contract_classes = ContractClassCache::default();
+
+        let contract_class = BlockifierStateAdapter::<MockRuntime>::with_contract_class_cache(contract_classes.clone())
+            .get_compiled_contract_class(&class_hash)
+            .unwrap();
+
+        // The storage is not read again by the adapters sharing the cache
+        ContractClasses::<MockRuntime>::remove(class_hash);
+        let mut state = BlockifierStateAdapter::<MockRuntime>::with_contract_class_cache(contract_classes);
+        assert_eq!(state.get_compiled_contract_class(&class_hash).unwrap(), contract_class);
+
+        let mut state = BlockifierStateAdapter::<MockRuntime>::default();
+        assert!(matches!(state.get_compiled_contract_class(&class_hash), Err(StateError::UndeclaredClassHash(_))));
+    });
+}
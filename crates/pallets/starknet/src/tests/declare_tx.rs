@@ -333,8 +333,10 @@ fn test_verify_require_tag() {
         )
         .unwrap();
 
+        let expected_priority =
+            UnsignedPriority::get() + (transaction.max_fee() / L1GasPrice::get().price_in_wei) as u64;
         let valid_transaction_expected = ValidTransaction::with_tag_prefix("starknet")
-            .priority(u64::MAX)
+            .priority(expected_priority)
             .and_provides((*transaction.sender_address(), *transaction.nonce()))
             .longevity(TransactionLongevity::get())
             .propagate(true)
@@ -0,0 +1,76 @@
+use frame_support::assert_ok;
+use mp_fee::{PriceUnit, ResourcePrice};
+use starknet_api::api_core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::StarkFelt;
+
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::Error;
+
+#[test]
+fn legacy_transactions_pay_in_the_legacy_fee_token() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let Ok(block_context) = Starknet::get_block_context_in(PriceUnit::for_tx_version(1)) else {
+            panic!("the legacy fee token is always configured")
+        };
+        assert_eq!(block_context.fee_token_address, Starknet::fee_token_address());
+        assert_eq!(block_context.gas_price, Starknet::l1_gas_price().price_in_wei);
+    });
+}
+
+#[test]
+fn strk_block_context_requires_token_and_price() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        assert!(matches!(
+            Starknet::get_block_context_in(PriceUnit::Fri),
+            Err(Error::<MockRuntime>::FeeTokenNotConfigured)
+        ));
+
+        let strk_fee_token_address = ContractAddress(PatriciaKey(StarkFelt::from(0x5752u64)));
+        assert_ok!(Starknet::set_strk_fee_token_address(RuntimeOrigin::root(), Some(strk_fee_token_address)));
+        // No price in fri yet
+        assert!(matches!(
+            Starknet::get_block_context_in(PriceUnit::Fri),
+            Err(Error::<MockRuntime>::FeeTokenNotConfigured)
+        ));
+
+        assert_ok!(Starknet::set_l1_gas_price(
+            RuntimeOrigin::root(),
+            ResourcePrice { price_in_strk: Some(7), price_in_wei: 10 }
+        ));
+        let Ok(block_context) = Starknet::get_block_context_in(PriceUnit::for_tx_version(3)) else {
+            panic!("the fri fee token is configured")
+        };
+        assert_eq!(block_context.fee_token_address, strk_fee_token_address);
+        assert_eq!(block_context.gas_price, 7);
+        // Legacy transactions are not affected
+        assert!(
+            matches!(Starknet::get_block_context_in(PriceUnit::Wei), Ok(block_context) if block_context.gas_price == 10)
+        );
+    });
+}
+
+#[test]
+fn wei_block_context_is_left_untouched() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let mut block_context = Starknet::get_block_context();
+        // e.g. the gas price override of a simulated block
+        block_context.gas_price = 42;
+
+        let Ok(wei_block_context) = Starknet::block_context_in(block_context.clone(), PriceUnit::Wei) else {
+            panic!("the legacy fee token is always configured")
+        };
+        assert_eq!(wei_block_context.gas_price, 42);
+        assert_eq!(wei_block_context.fee_token_address, block_context.fee_token_address);
+        assert!(matches!(
+            Starknet::block_context_in(block_context, PriceUnit::Fri),
+            Err(Error::<MockRuntime>::FeeTokenNotConfigured)
+        ));
+    });
+}
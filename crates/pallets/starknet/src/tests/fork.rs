@@ -0,0 +1,195 @@
+use std::sync::Arc;
+
+use blockifier::abi::abi_utils::get_erc20_balance_var_addresses;
+use blockifier::execution::contract_class::ContractClass;
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::StateReader;
+use mp_felt::Felt252Wrapper;
+use mp_fork::{ForkedStateExt, ForkedStateReader};
+use mp_transactions::{UserOrL1HandlerTransaction, UserTransaction};
+use starknet_api::api_core::{ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use super::constants::BLOCKIFIER_ACCOUNT_ADDRESS;
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::blockifier_state_adapter::BlockifierStateAdapter;
+use crate::execution_config::RuntimeExecutionConfigBuilder;
+use crate::tests::get_invoke_dummy;
+use crate::{CompiledClassHashes, ContractClassHashes, StorageView};
+
+/// A forked chain where every contract is deployed with the same class, nonce and storage.
+struct ForkedChain;
+
+impl ForkedStateReader for ForkedChain {
+    fn storage_at(&self, _contract_address: ContractAddress, _key: StorageKey) -> Result<StarkFelt, String> {
+        Ok(StarkFelt::from(7u64))
+    }
+
+    fn nonce_at(&self, _contract_address: ContractAddress) -> Result<Nonce, String> {
+        Ok(Nonce(StarkFelt::from(3u64)))
+    }
+
+    fn class_hash_at(&self, _contract_address: ContractAddress) -> Result<ClassHash, String> {
+        Ok(forked_class_hash())
+    }
+
+    fn contract_class(&self, _class_hash: ClassHash) -> Result<Option<ContractClass>, String> {
+        Ok(None)
+    }
+
+    fn compiled_class_hash(&self, _class_hash: ClassHash) -> Result<Option<CompiledClassHash>, String> {
+        Ok(Some(CompiledClassHash(StarkFelt::from(0x43u64))))
+    }
+}
+
+/// A forked chain which cannot be reached.
+struct UnreachableForkedChain;
+
+impl ForkedStateReader for UnreachableForkedChain {
+    fn storage_at(&self, _contract_address: ContractAddress, _key: StorageKey) -> Result<StarkFelt, String> {
+        Err("unreachable".to_string())
+    }
+
+    fn nonce_at(&self, _contract_address: ContractAddress) -> Result<Nonce, String> {
+        Err("unreachable".to_string())
+    }
+
+    fn class_hash_at(&self, _contract_address: ContractAddress) -> Result<ClassHash, String> {
+        Err("unreachable".to_string())
+    }
+
+    fn contract_class(&self, _class_hash: ClassHash) -> Result<Option<ContractClass>, String> {
+        Err("unreachable".to_string())
+    }
+
+    fn compiled_class_hash(&self, _class_hash: ClassHash) -> Result<Option<CompiledClassHash>, String> {
+        Err("unreachable".to_string())
+    }
+}
+
+fn forked_class_hash() -> ClassHash {
+    ClassHash(StarkFelt::from(0x42u64))
+}
+
+fn forked_contract_address() -> ContractAddress {
+    ContractAddress(PatriciaKey(StarkFelt::from(0xf0f0u64)))
+}
+
+fn storage_key() -> StorageKey {
+    StorageKey(PatriciaKey(StarkFelt::from(1u64)))
+}
+
+fn new_forked_test_ext() -> sp_io::TestExternalities {
+    let mut ext = new_test_ext::<MockRuntime>();
+    ext.register_extension(ForkedStateExt::new(Arc::new(ForkedChain)));
+    ext
+}
+
+#[test]
+fn reads_missing_locally_fall_back_to_the_forked_state() {
+    new_forked_test_ext().execute_with(|| {
+        basic_test_setup(2);
+        let mut state = BlockifierStateAdapter::<MockRuntime>::default();
+        let contract_address = forked_contract_address();
+
+        assert_eq!(state.get_storage_at(contract_address, storage_key()).unwrap(), StarkFelt::from(7u64));
+        assert_eq!(state.get_nonce_at(contract_address).unwrap(), Nonce(StarkFelt::from(3u64)));
+        assert_eq!(state.get_class_hash_at(contract_address).unwrap(), forked_class_hash());
+        assert!(state.get_compiled_contract_class(&forked_class_hash()).is_err());
+        assert_eq!(
+            state.get_compiled_class_hash(forked_class_hash()).unwrap(),
+            CompiledClassHash(StarkFelt::from(0x43u64))
+        );
+
+        // The forked values are cached in the local state
+        assert_eq!(StorageView::<MockRuntime>::get((contract_address, storage_key())), StarkFelt::from(7u64));
+        assert_eq!(Starknet::nonce(contract_address), Nonce(StarkFelt::from(3u64)));
+        assert_eq!(Starknet::contract_class_hash_by_address(contract_address), forked_class_hash());
+        assert_eq!(
+            CompiledClassHashes::<MockRuntime>::get(forked_class_hash()),
+            Some(CompiledClassHash(StarkFelt::from(0x43u64)))
+        );
+    });
+}
+
+#[test]
+fn failing_forked_reads_are_errors_and_not_cached() {
+    let mut ext = new_test_ext::<MockRuntime>();
+    ext.register_extension(ForkedStateExt::new(Arc::new(UnreachableForkedChain)));
+    ext.execute_with(|| {
+        basic_test_setup(2);
+        let mut state = BlockifierStateAdapter::<MockRuntime>::default();
+        let contract_address = forked_contract_address();
+
+        assert!(matches!(state.get_storage_at(contract_address, storage_key()), Err(StateError::StateReadError(_))));
+        assert!(matches!(state.get_nonce_at(contract_address), Err(StateError::StateReadError(_))));
+        assert!(matches!(state.get_class_hash_at(contract_address), Err(StateError::StateReadError(_))));
+        assert!(matches!(state.get_compiled_contract_class(&forked_class_hash()), Err(StateError::StateReadError(_))));
+        assert!(matches!(state.get_compiled_class_hash(forked_class_hash()), Err(StateError::StateReadError(_))));
+
+        assert!(!StorageView::<MockRuntime>::contains_key((contract_address, storage_key())));
+        assert!(!ContractClassHashes::<MockRuntime>::contains_key(contract_address));
+    });
+}
+
+#[test]
+fn local_state_takes_precedence_over_the_forked_state() {
+    new_forked_test_ext().execute_with(|| {
+        basic_test_setup(2);
+        let mut state = BlockifierStateAdapter::<MockRuntime>::default();
+        let contract_address: ContractAddress = Felt252Wrapper::from_hex_be(BLOCKIFIER_ACCOUNT_ADDRESS).unwrap().into();
+        StorageView::<MockRuntime>::insert((contract_address, storage_key()), StarkFelt::from(2u64));
+
+        assert_eq!(state.get_storage_at(contract_address, storage_key()).unwrap(), StarkFelt::from(2u64));
+        assert_eq!(
+            state.get_class_hash_at(contract_address).unwrap(),
+            ContractClassHashes::<MockRuntime>::get(contract_address)
+        );
+        assert_ne!(state.get_class_hash_at(contract_address).unwrap(), forked_class_hash());
+    });
+}
+
+#[test]
+fn reads_stay_local_without_a_forked_chain() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        let mut state = BlockifierStateAdapter::<MockRuntime>::default();
+        let contract_address = forked_contract_address();
+
+        assert_eq!(state.get_storage_at(contract_address, storage_key()).unwrap(), StarkFelt::default());
+        assert_eq!(state.get_class_hash_at(contract_address).unwrap(), ClassHash::default());
+        assert!(!StorageView::<MockRuntime>::contains_key((contract_address, storage_key())));
+        assert!(!ContractClassHashes::<MockRuntime>::contains_key(contract_address));
+    });
+}
+
+#[test]
+fn failing_forked_reads_fail_the_transactions_executed_in_parallel() {
+    let mut ext = new_test_ext::<MockRuntime>();
+    ext.register_extension(ForkedStateExt::new(Arc::new(UnreachableForkedChain)));
+    ext.execute_with(|| {
+        basic_test_setup(2);
+        // The fee transfer reads the balance of the sequencer, now missing locally
+        let (low_key, high_key) = get_erc20_balance_var_addresses(&Starknet::sequencer_address()).unwrap();
+        StorageView::<MockRuntime>::remove((Starknet::fee_token_address(), low_key));
+        StorageView::<MockRuntime>::remove((Starknet::fee_token_address(), high_key));
+        let transactions =
+            [UserOrL1HandlerTransaction::User(UserTransaction::Invoke(get_invoke_dummy(Felt252Wrapper::ZERO).into()))];
+        let execution_config = RuntimeExecutionConfigBuilder::new::<MockRuntime>().build();
+
+        for workers in [1, 2] {
+            let outputs = Starknet::execute_transactions_in_parallel(
+                Starknet::chain_id(),
+                &Starknet::get_block_context(),
+                &execution_config,
+                &transactions,
+                workers,
+            );
+            assert!(outputs[0].0.is_err());
+        }
+        let sender_address: ContractAddress = Felt252Wrapper::from_hex_be(BLOCKIFIER_ACCOUNT_ADDRESS).unwrap().into();
+        assert_eq!(Starknet::nonce(sender_address), Nonce::default());
+    });
+}
@@ -20,7 +20,7 @@ fn should_ensure_l1_message_not_executed_work_properly() {
 
         assert!(Starknet::ensure_l1_message_not_executed(&nonce).is_ok());
 
-        L1Messages::<MockRuntime>::mutate(|nonces| nonces.insert(nonce));
+        L1Messages::<MockRuntime>::insert(nonce, ());
 
         assert_eq!(Starknet::ensure_l1_message_not_executed(&nonce), Err(InvalidTransaction::Stale));
     });
@@ -63,7 +63,7 @@ fn should_reject_used_nonce() {
 
         let tx = UserOrL1HandlerTransaction::L1Handler(transaction, Fee(100));
 
-        L1Messages::<MockRuntime>::mutate(|nonces| nonces.insert(Nonce(nonce.into())));
+        L1Messages::<MockRuntime>::insert(Nonce(nonce.into()), ());
 
         assert_matches!(Starknet::validate_unsigned_tx_nonce(&tx), Err(InvalidTransaction::Stale));
     });
@@ -61,7 +61,7 @@ fn should_reject_used_nonce() {
 
         assert!(Starknet::validate_unsigned(tx_source, &call).is_ok());
 
-        L1Messages::<MockRuntime>::mutate(|nonces| nonces.insert(Nonce(StarkFelt::from(nonce))));
+        L1Messages::<MockRuntime>::insert(Nonce(StarkFelt::from(nonce)), ());
 
         assert_eq!(
             Starknet::validate_unsigned(tx_source, &call),
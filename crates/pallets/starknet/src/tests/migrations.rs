@@ -0,0 +1,81 @@
+use std::collections::BTreeSet;
+
+use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+use mp_fee::ResourcePrice;
+use mp_storage::{StarknetStorageSchemaVersion, PALLET_STARKNET_SCHEMA};
+use starknet_api::api_core::Nonce;
+use starknet_api::hash::StarkFelt;
+
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::migrations::{v0, v1, v2, Migrations};
+use crate::{InvokeTxMaxNSteps, L1GasPrice, L1Messages, Pallet};
+
+fn consumed_nonces() -> BTreeSet<Nonce> {
+    (0..3u64).map(|nonce| Nonce(StarkFelt::from(nonce))).collect()
+}
+
+#[test]
+fn l1_messages_are_moved_to_the_map() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        StorageVersion::new(0).put::<Pallet<MockRuntime>>();
+        frame_support::storage::unhashed::kill(PALLET_STARKNET_SCHEMA);
+        v0::L1Messages::<MockRuntime>::put(consumed_nonces());
+
+        v1::MigrateV0ToV1::<MockRuntime>::on_runtime_upgrade();
+
+        assert!(!v0::L1Messages::<MockRuntime>::exists());
+        assert_eq!(L1Messages::<MockRuntime>::iter_keys().count(), 3);
+        assert!(Starknet::ensure_l1_message_not_executed(&Nonce(StarkFelt::from(2u64))).is_err());
+        assert!(Starknet::ensure_l1_message_not_executed(&Nonce(StarkFelt::from(3u64))).is_ok());
+        assert_eq!(Pallet::<MockRuntime>::on_chain_storage_version(), 1);
+        assert_eq!(
+            frame_support::storage::unhashed::get::<StarknetStorageSchemaVersion>(PALLET_STARKNET_SCHEMA),
+            Some(StarknetStorageSchemaVersion::V1)
+        );
+    });
+}
+
+#[test]
+fn default_chain_parameters_are_stored() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        StorageVersion::new(1).put::<Pallet<MockRuntime>>();
+        // Storage from before the parameters were moved out of the runtime constants
+        L1GasPrice::<MockRuntime>::kill();
+        InvokeTxMaxNSteps::<MockRuntime>::put(10);
+
+        v2::MigrateV1ToV2::<MockRuntime>::on_runtime_upgrade();
+
+        assert!(L1GasPrice::<MockRuntime>::exists());
+        assert_eq!(L1GasPrice::<MockRuntime>::get(), ResourcePrice { price_in_strk: None, price_in_wei: 10 });
+        assert_eq!(InvokeTxMaxNSteps::<MockRuntime>::get(), 10);
+        assert_eq!(Pallet::<MockRuntime>::on_chain_storage_version(), 2);
+    });
+}
+
+#[test]
+fn migrations_are_skipped_once_applied() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        Pallet::<MockRuntime>::current_storage_version().put::<Pallet<MockRuntime>>();
+        v0::L1Messages::<MockRuntime>::put(consumed_nonces());
+
+        Migrations::<MockRuntime>::on_runtime_upgrade();
+
+        assert!(v0::L1Messages::<MockRuntime>::exists());
+        assert_eq!(L1Messages::<MockRuntime>::iter_keys().count(), 0);
+    });
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn migrations_pass_the_try_runtime_checks() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        StorageVersion::new(0).put::<Pallet<MockRuntime>>();
+        v0::L1Messages::<MockRuntime>::put(consumed_nonces());
+        L1GasPrice::<MockRuntime>::kill();
+
+        Migrations::<MockRuntime>::try_on_runtime_upgrade(true).unwrap();
+
+        assert_eq!(Pallet::<MockRuntime>::on_chain_storage_version(), Pallet::<MockRuntime>::current_storage_version());
+    });
+}
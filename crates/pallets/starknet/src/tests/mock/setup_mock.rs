@@ -19,6 +19,7 @@ macro_rules! mock_runtime {
 			use starknet_api::api_core::{PatriciaKey, ContractAddress};
 			use starknet_api::hash::StarkFelt;
 			use mp_fee::ResourcePrice;
+			use crate::block_limits::BlockResources;
 
 
 			type Block = frame_system::mocking::MockBlock<MockRuntime>;
@@ -67,6 +68,7 @@ macro_rules! mock_runtime {
 			parameter_types! {
 				pub const UnsignedPriority: u64 = 1 << 20;
 				pub const TransactionLongevity: u64 = u64::MAX;
+				pub const MaxPendingTransactionsPerAccount: u64 = 16;
 				pub const InvokeTxMaxNSteps: u32 = 1_000_000;
 				pub const ValidateMaxNSteps: u32 = 1_000_000;
 				pub const DisableTransactionFee: bool = $disable_transaction_fee;
@@ -75,13 +77,36 @@ macro_rules! mock_runtime {
                 pub const MaxRecursionDepth: u32 = 50;
 				pub const ProgramHash: Felt252Wrapper = mp_program_hash::SN_OS_PROGRAM_HASH;
 				pub const L1GasPrice: ResourcePrice = ResourcePrice { price_in_strk: None, price_in_wei: 10 };
+				pub storage MaxBlockResources: BlockResources = BlockResources::MAX;
+				pub storage TxOutputsRetention: Option<u64> = None;
+				pub storage BlockAuthor: Option<u64> = None;
             }
 
+			/// Returns the `BlockAuthor` instead of decoding the digests.
+			pub struct MockFindAuthor;
+			impl frame_support::traits::FindAuthor<u64> for MockFindAuthor {
+				fn find_author<'a, I>(digests: I) -> Option<u64>
+				where
+					I: 'a + IntoIterator<Item = (frame_support::ConsensusEngineId, &'a [u8])>,
+				{
+					// The author is read from the `mock` pre-runtime digest, if any
+					digests
+						.into_iter()
+						.find_map(|(id, mut data)| {
+							(id == *b"mock").then(|| parity_scale_codec::Decode::decode(&mut data).ok()).flatten()
+						})
+						.or_else(BlockAuthor::get)
+				}
+			}
+
 			impl pallet_starknet::Config for MockRuntime {
+				type RuntimeEvent = RuntimeEvent;
+				type AdminOrigin = frame_support::traits::EitherOfDiverse<system::EnsureRoot<u64>, pallet_starknet::EnsureAdmin<Self>>;
 				type SystemHash = mp_hashers::pedersen::PedersenHasher;
 				type TimestampProvider = Timestamp;
 				type UnsignedPriority = UnsignedPriority;
 				type TransactionLongevity = TransactionLongevity;
+				type MaxPendingTransactionsPerAccount = MaxPendingTransactionsPerAccount;
 				type InvokeTxMaxNSteps = InvokeTxMaxNSteps;
 				type ValidateMaxNSteps = ValidateMaxNSteps;
 				type DisableTransactionFee = DisableTransactionFee;
@@ -90,6 +115,10 @@ macro_rules! mock_runtime {
                 type MaxRecursionDepth = MaxRecursionDepth;
 				type ProgramHash = ProgramHash;
 				type L1GasPrice = L1GasPrice;
+				type MaxBlockResources = MaxBlockResources;
+				type TxOutputsRetention = TxOutputsRetention;
+				type AuthorityId = u64;
+				type FindAuthor = MockFindAuthor;
 			}
 
 			/// Run to block n.
@@ -14,22 +14,37 @@ use crate::tests::utils::sign_message_hash;
 use crate::{Config, Nonces};
 
 mod account_helper;
+mod block_limits;
 mod build_genesis_config;
 mod call_contract;
+mod chain_parameters;
+mod contract_class_cache;
 mod declare_tx;
 mod deploy_account_tx;
 mod erc20;
 mod events;
+mod fee_tokens;
 mod fees_disabled;
+mod fork;
 mod genesis_block;
 mod invoke_tx;
 mod l1_handler_validation;
 mod l1_message;
+mod migrations;
 mod no_nonce_validation;
+mod parallel_execution;
+mod paymaster;
 mod query_tx;
 mod re_execute_transactions;
 mod send_message;
 mod sequencer_address;
+mod sequencer_registry;
+mod simulate_blocks;
+mod state_edits;
+mod state_import;
+mod state_overrides;
+mod transaction_pool;
+mod tx_outputs_pruning;
 
 mod block;
 mod constants;
@@ -0,0 +1,158 @@
+use blockifier::state::cached_state::{CommitmentStateDiff, ContractStorageKey};
+use blockifier::transaction::objects::TransactionExecutionInfo;
+use mp_felt::Felt252Wrapper;
+use mp_transactions::{UserOrL1HandlerTransaction, UserTransaction};
+use starknet_api::api_core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+
+use super::mock::default_mock::*;
+use super::mock::*;
+use super::re_execute_transactions::{execute_transasction, get_test_txs};
+use crate::execution_config::RuntimeExecutionConfigBuilder;
+use crate::tests::get_invoke_dummy;
+use crate::{CompiledClassHashes, ContractClassHashes, ContractClasses, Nonces, StorageView};
+
+type Outputs = Vec<(Result<TransactionExecutionInfo, String>, CommitmentStateDiff)>;
+
+/// The Starknet state, to compare the ones the executions end with.
+#[derive(Debug, PartialEq)]
+struct ChainState {
+    storage: Vec<(ContractStorageKey, StarkFelt)>,
+    nonces: Vec<(ContractAddress, Nonce)>,
+    class_hashes: Vec<(ContractAddress, ClassHash)>,
+    declared_classes: Vec<ClassHash>,
+    compiled_class_hashes: Vec<(ClassHash, CompiledClassHash)>,
+}
+
+fn chain_state() -> ChainState {
+    let mut state = ChainState {
+        storage: StorageView::<MockRuntime>::iter().collect(),
+        nonces: Nonces::<MockRuntime>::iter().collect(),
+        class_hashes: ContractClassHashes::<MockRuntime>::iter().collect(),
+        declared_classes: ContractClasses::<MockRuntime>::iter_keys().collect(),
+        compiled_class_hashes: CompiledClassHashes::<MockRuntime>::iter().collect(),
+    };
+    state.storage.sort();
+    state.nonces.sort();
+    state.class_hashes.sort();
+    state.declared_classes.sort();
+    state.compiled_class_hashes.sort();
+    state
+}
+
+/// Executes the transactions one after the other.
+fn execute_sequentially(transactions: &[UserOrL1HandlerTransaction]) -> Outputs {
+    transactions
+        .iter()
+        .map(|transaction| {
+            let (execution_info, state_diff) = execute_transasction(transaction.clone()).unwrap();
+            (Ok(execution_info), state_diff)
+        })
+        .collect()
+}
+
+fn execute_in_parallel(transactions: &[UserOrL1HandlerTransaction], workers: usize) -> Outputs {
+    let chain_id = Starknet::chain_id();
+    let block_context = Starknet::get_block_context();
+    let execution_config = RuntimeExecutionConfigBuilder::new::<MockRuntime>().build();
+
+    Starknet::execute_transactions_in_parallel(chain_id, &block_context, &execution_config, transactions, workers)
+}
+
+/// Executes the transactions built on a fresh chain, returning their outputs and the state they
+/// lead to.
+fn execute_on_new_chain(
+    transactions: fn() -> Vec<UserOrL1HandlerTransaction>,
+    execute: impl FnOnce(&[UserOrL1HandlerTransaction]) -> Outputs,
+) -> (Outputs, ChainState) {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        let transactions = transactions();
+        let outputs = execute(&transactions);
+        (outputs, chain_state())
+    })
+}
+
+fn invokes_from_the_same_account() -> Vec<UserOrL1HandlerTransaction> {
+    (0u64..8)
+        .map(|nonce| UserOrL1HandlerTransaction::User(UserTransaction::Invoke(get_invoke_dummy(nonce.into()).into())))
+        .collect()
+}
+
+fn assert_parallel_execution_is_sequential(transactions: fn() -> Vec<UserOrL1HandlerTransaction>) {
+    let (expected_outputs, expected_state) = execute_on_new_chain(transactions, execute_sequentially);
+
+    for workers in [1, 2, 4, 8] {
+        let (outputs, state) =
+            execute_on_new_chain(transactions, |transactions| execute_in_parallel(transactions, workers));
+        pretty_assertions::assert_eq!(outputs, expected_outputs, "outputs with {workers} workers");
+        pretty_assertions::assert_eq!(state, expected_state, "state with {workers} workers");
+    }
+}
+
+#[test]
+fn parallel_execution_of_mixed_transactions_is_sequential() {
+    assert_parallel_execution_is_sequential(get_test_txs);
+}
+
+#[test]
+fn parallel_execution_of_conflicting_transactions_is_sequential() {
+    assert_parallel_execution_is_sequential(invokes_from_the_same_account);
+}
+
+#[test]
+fn parallel_execution_is_deterministic() {
+    let (expected_outputs, expected_state) =
+        execute_on_new_chain(get_test_txs, |transactions| execute_in_parallel(transactions, 4));
+
+    for _ in 0..10 {
+        let (outputs, state) = execute_on_new_chain(get_test_txs, |transactions| execute_in_parallel(transactions, 4));
+        assert_eq!(outputs, expected_outputs);
+        assert_eq!(state, expected_state);
+    }
+}
+
+#[test]
+fn parallel_execution_reports_failing_transactions() {
+    // The second transaction reuses the nonce of the first one
+    let transactions = || {
+        let mut transactions = invokes_from_the_same_account();
+        transactions[1] = transactions[0].clone();
+        transactions
+    };
+
+    for workers in [1, 4] {
+        let (outputs, _) =
+            execute_on_new_chain(transactions, |transactions| execute_in_parallel(transactions, workers));
+        assert!(outputs[0].0.is_ok());
+        assert!(outputs[1].0.is_err());
+        // The nonce of the account is not incremented by the failing transaction
+        assert!(outputs[2].0.is_err());
+    }
+}
+
+#[test]
+fn parallel_execution_of_no_transactions() {
+    let (outputs, state) = execute_on_new_chain(Vec::new, |transactions| execute_in_parallel(transactions, 4));
+    let (_, expected_state) = execute_on_new_chain(Vec::new, execute_sequentially);
+
+    assert!(outputs.is_empty());
+    assert_eq!(state, expected_state);
+}
+
+#[test]
+fn re_executed_transactions_see_the_ones_before() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        let sender_address: ContractAddress =
+            Felt252Wrapper::from_hex_be(super::constants::BLOCKIFIER_ACCOUNT_ADDRESS).unwrap().into();
+        let transactions = invokes_from_the_same_account();
+
+        let res =
+            Starknet::re_execute_transactions(transactions[..4].to_vec(), transactions[4..].to_vec()).unwrap().unwrap();
+
+        assert_eq!(res.len(), 4);
+        // Storage changes have been reverted
+        assert_eq!(Starknet::nonce(sender_address), Nonce(Felt252Wrapper::ZERO.into()));
+    });
+}
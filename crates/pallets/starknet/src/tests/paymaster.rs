@@ -0,0 +1,152 @@
+use blockifier::abi::abi_utils::get_erc20_balance_var_addresses;
+use blockifier::state::state_api::State;
+use frame_support::{assert_err, assert_ok};
+use mp_felt::Felt252Wrapper;
+use mp_simulations::SimulationFlags;
+use mp_transactions::compute_hash::ComputeTransactionHash;
+use mp_transactions::{InvokeTransaction, InvokeTransactionV1, SponsoredInvokeTransactionV1, UserTransaction};
+use starknet_api::api_core::ContractAddress;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::TransactionHash;
+
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::blockifier_state_adapter::BlockifierStateAdapter;
+use crate::tests::get_invoke_dummy;
+use crate::{Config, Error};
+
+fn paymaster_address() -> ContractAddress {
+    get_account_address(None, AccountType::V0(AccountTypeV0Inner::NoValidate))
+}
+
+fn get_sponsored_invoke_dummy(nonce: Felt252Wrapper) -> SponsoredInvokeTransactionV1 {
+    SponsoredInvokeTransactionV1 {
+        tx: get_invoke_dummy(nonce),
+        paymaster: paymaster_address().into(),
+        paymaster_signature: vec![],
+    }
+}
+
+fn set_fee_token_balance(address: &ContractAddress, balance: u128) {
+    let (low_key, high_key) = get_erc20_balance_var_addresses(address).unwrap();
+    let mut state_adapter = BlockifierStateAdapter::<MockRuntime>::default();
+
+    state_adapter.set_storage_at(Starknet::fee_token_address(), low_key, StarkFelt::from(balance));
+    state_adapter.set_storage_at(Starknet::fee_token_address(), high_key, StarkFelt::from(0u128));
+}
+
+fn get_fee_token_balance(address: &ContractAddress) -> StarkFelt {
+    let mut state_adapter = BlockifierStateAdapter::<MockRuntime>::default();
+
+    state_adapter.get_fee_token_balance(&Starknet::get_block_context(), address).unwrap().0
+}
+
+#[test]
+fn sponsored_transaction_fee_is_paid_by_the_paymaster() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let transaction = get_sponsored_invoke_dummy(Felt252Wrapper::ZERO);
+        let sender_address: ContractAddress = transaction.tx.sender_address.into();
+        set_fee_token_balance(&sender_address, 0);
+        set_fee_token_balance(&paymaster_address(), u64::MAX as u128);
+
+        let chain_id = Starknet::chain_id();
+        let tx_hash = transaction.compute_hash::<<MockRuntime as Config>::SystemHash>(chain_id, false);
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction.into()));
+
+        assert_eq!(get_fee_token_balance(&sender_address), StarkFelt::from(0u128));
+        assert!(get_fee_token_balance(&paymaster_address()) < StarkFelt::from(u64::MAX as u128));
+
+        // The fee transfer, last event of the receipt, is sent from the paymaster
+        let events = Starknet::tx_events(TransactionHash::from(tx_hash));
+        assert_eq!(events.last().unwrap().content.data.0[0], *paymaster_address().0.key());
+    });
+}
+
+#[test]
+fn sponsored_transaction_simulation_charges_the_paymaster() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let transaction = get_sponsored_invoke_dummy(Felt252Wrapper::ZERO);
+        set_fee_token_balance(&transaction.tx.sender_address.into(), 0);
+        set_fee_token_balance(&paymaster_address(), u64::MAX as u128);
+
+        let fees = Starknet::estimate_fee(vec![UserTransaction::Invoke(transaction.clone().into())])
+            .expect("estimate should not fail");
+        assert!(fees[0].0 > 0);
+
+        let simulation_flags = SimulationFlags { skip_validate: false, skip_fee_charge: false };
+        let simulations =
+            Starknet::simulate_transactions(vec![UserTransaction::Invoke(transaction.into())], &simulation_flags)
+                .unwrap();
+        let Ok(execution_info) = &simulations[0].1 else { panic!("the sponsored transaction should succeed") };
+
+        // The paymaster validation shows up in the validation trace
+        let validate_call_info = execution_info.validate_call_info.as_ref().unwrap();
+        assert_eq!(validate_call_info.inner_calls.last().unwrap().call.storage_address, paymaster_address());
+        let fee_transfer_call_info = execution_info.fee_transfer_call_info.as_ref().unwrap();
+        assert_eq!(fee_transfer_call_info.call.caller_address, paymaster_address());
+    });
+}
+
+#[test]
+fn sponsor_running_out_of_balance_mid_block_fails_the_next_transactions() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let transaction = get_sponsored_invoke_dummy(Felt252Wrapper::ZERO);
+        set_fee_token_balance(&paymaster_address(), u64::MAX as u128);
+        let (fee, _) = Starknet::estimate_fee(vec![UserTransaction::Invoke(transaction.clone().into())]).unwrap()[0];
+        let fee = fee as u128;
+        let max_fee = 2 * fee;
+
+        // Enough for the first transaction only, while the sender could still pay for itself
+        set_fee_token_balance(&paymaster_address(), max_fee + fee / 2);
+        let first_transaction = SponsoredInvokeTransactionV1 {
+            tx: InvokeTransactionV1 { max_fee, ..transaction.tx.clone() },
+            ..transaction
+        };
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), first_transaction.into()));
+        assert!(get_fee_token_balance(&paymaster_address()) < StarkFelt::from(max_fee));
+
+        let second_transaction = get_sponsored_invoke_dummy(Felt252Wrapper::ONE);
+        let second_transaction = SponsoredInvokeTransactionV1 {
+            tx: InvokeTransactionV1 { max_fee, ..second_transaction.tx.clone() },
+            ..second_transaction
+        };
+        assert_err!(
+            Starknet::invoke(RuntimeOrigin::none(), second_transaction.clone().into()),
+            Error::<MockRuntime>::TransactionExecutionFailed
+        );
+
+        // The same transaction goes through once it is not sponsored anymore
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), second_transaction.tx.into()));
+    });
+}
+
+#[test]
+fn sponsored_transaction_commits_to_its_paymaster_and_carries_the_paymaster_signature() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        let transaction = SponsoredInvokeTransactionV1 {
+            paymaster_signature: vec![Felt252Wrapper::ONE, Felt252Wrapper::TWO],
+            ..get_sponsored_invoke_dummy(Felt252Wrapper::ZERO)
+        };
+        let chain_id = Starknet::chain_id();
+        let tx_hash = transaction.compute_hash::<<MockRuntime as Config>::SystemHash>(chain_id, false);
+
+        let sponsored_transaction = InvokeTransaction::Sponsored(transaction.clone())
+            .into_sponsored_executable::<<MockRuntime as Config>::SystemHash>(chain_id, false)
+            .unwrap();
+
+        assert_eq!(sponsored_transaction.paymaster, paymaster_address());
+        assert_eq!(sponsored_transaction.paymaster_signature.0, vec![StarkFelt::from(1u128), StarkFelt::from(2u128)]);
+        assert_eq!(sponsored_transaction.tx.tx_hash, TransactionHash::from(tx_hash));
+        assert!(
+            InvokeTransaction::V1(transaction.tx)
+                .into_sponsored_executable::<<MockRuntime as Config>::SystemHash>(chain_id, false)
+                .is_none()
+        );
+    });
+}
@@ -86,7 +86,7 @@ fn re_execute_tx_with_a_transfer_ok() {
     });
 }
 
-fn get_test_txs() -> Vec<UserOrL1HandlerTransaction> {
+pub(super) fn get_test_txs() -> Vec<UserOrL1HandlerTransaction> {
     let chain_id = Starknet::chain_id();
 
     // Deploy
@@ -144,7 +144,7 @@ fn get_test_txs() -> Vec<UserOrL1HandlerTransaction> {
     ]
 }
 
-fn execute_transasction(
+pub(super) fn execute_transasction(
     user_or_l1_tx: UserOrL1HandlerTransaction,
 ) -> Result<(TransactionExecutionInfo, CommitmentStateDiff), PlaceHolderErrorTypeForFailedStarknetExecution> {
     let mut cached_state = CachedBlockifierStateAdapter(BlockifierStateAdapter::<MockRuntime>::default());
@@ -0,0 +1,155 @@
+use frame_support::inherent::{InherentData, ProvideInherent};
+use frame_support::{assert_noop, assert_ok};
+use mp_felt::Felt252Wrapper;
+use mp_sequencer_address::{
+    InherentError, BLOCK_DIGEST_INHERENT_IDENTIFIER, DEFAULT_SEQUENCER_ADDRESS, INHERENT_IDENTIFIER,
+};
+use parity_scale_codec::Encode;
+use sp_runtime::{Digest, DigestItem, DispatchError};
+use starknet_api::api_core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::StarkFelt;
+
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::{Call, Error, Event};
+
+const AUTHOR: u64 = 7;
+
+fn registered_address() -> ContractAddress {
+    ContractAddress(PatriciaKey(StarkFelt::from(0x5e9u64)))
+}
+
+fn registered_address_bytes() -> [u8; 32] {
+    Felt252Wrapper::from(registered_address()).0.to_bytes_be()
+}
+
+#[test]
+fn sequencers_can_only_be_registered_by_admin_origin() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        assert_noop!(
+            Starknet::add_sequencer(RuntimeOrigin::signed(1), AUTHOR, registered_address()),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(Starknet::remove_sequencer(RuntimeOrigin::none(), AUTHOR), DispatchError::BadOrigin);
+    });
+}
+
+#[test]
+fn sequencers_are_added_and_removed() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        assert_ok!(Starknet::add_sequencer(RuntimeOrigin::root(), AUTHOR, registered_address()));
+        assert_eq!(Starknet::registered_sequencer(AUTHOR), Some(registered_address()));
+        System::assert_last_event(
+            Event::<MockRuntime>::SequencerAdded { authority: AUTHOR, sequencer_address: registered_address() }.into(),
+        );
+        assert_noop!(
+            Starknet::add_sequencer(RuntimeOrigin::root(), AUTHOR, registered_address()),
+            Error::<MockRuntime>::SequencerAlreadyRegistered
+        );
+
+        assert_ok!(Starknet::remove_sequencer(RuntimeOrigin::root(), AUTHOR));
+        assert_eq!(Starknet::registered_sequencer(AUTHOR), None);
+        System::assert_last_event(
+            Event::<MockRuntime>::SequencerRemoved { authority: AUTHOR, sequencer_address: registered_address() }
+                .into(),
+        );
+        assert_noop!(
+            Starknet::remove_sequencer(RuntimeOrigin::root(), AUTHOR),
+            Error::<MockRuntime>::SequencerNotRegistered
+        );
+    });
+}
+
+#[test]
+fn inherent_uses_the_address_registered_for_the_block_author() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        assert_ok!(Starknet::add_sequencer(RuntimeOrigin::root(), AUTHOR, registered_address()));
+
+        let mut inherent_data = InherentData::new();
+        inherent_data.put_data(INHERENT_IDENTIFIER, &DEFAULT_SEQUENCER_ADDRESS).unwrap();
+
+        // Unregistered authors keep the address of their inherent data
+        BlockAuthor::set(&Some(AUTHOR + 1));
+        let Some(Call::set_block_parameters { addr, .. }) = Starknet::create_inherent(&inherent_data) else {
+            panic!("the block parameters inherent should be created")
+        };
+        assert_eq!(addr, DEFAULT_SEQUENCER_ADDRESS);
+
+        BlockAuthor::set(&Some(AUTHOR));
+        let Some(Call::set_block_parameters { addr, .. }) = Starknet::create_inherent(&inherent_data) else {
+            panic!("the block parameters inherent should be created")
+        };
+        assert_eq!(addr, registered_address_bytes());
+    });
+}
+
+#[test]
+fn inherent_check_rejects_addresses_not_registered_for_the_block_author() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        assert_ok!(Starknet::add_sequencer(RuntimeOrigin::root(), AUTHOR, registered_address()));
+        let inherent_data = InherentData::new();
+
+        BlockAuthor::set(&Some(AUTHOR));
+        assert!(matches!(
+            Starknet::check_inherent(&Call::set_sequencer_address { addr: DEFAULT_SEQUENCER_ADDRESS }, &inherent_data),
+            Err(InherentError::UnregisteredSequencerAddress)
+        ));
+        assert!(
+            Starknet::check_inherent(&Call::set_sequencer_address { addr: registered_address_bytes() }, &inherent_data)
+                .is_ok()
+        );
+
+        BlockAuthor::set(&Some(AUTHOR + 1));
+        assert!(
+            Starknet::check_inherent(&Call::set_sequencer_address { addr: DEFAULT_SEQUENCER_ADDRESS }, &inherent_data)
+                .is_ok()
+        );
+    });
+}
+
+#[test]
+fn inherent_check_finds_the_author_in_the_digest_of_the_checked_block() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        assert_ok!(Starknet::add_sequencer(RuntimeOrigin::root(), AUTHOR, registered_address()));
+        BlockAuthor::set(&None);
+
+        let mut inherent_data = InherentData::new();
+        let digest = Digest { logs: vec![DigestItem::PreRuntime(*b"mock", AUTHOR.encode())] };
+        inherent_data.put_data(BLOCK_DIGEST_INHERENT_IDENTIFIER, &digest).unwrap();
+
+        assert!(matches!(
+            Starknet::check_inherent(&Call::set_sequencer_address { addr: DEFAULT_SEQUENCER_ADDRESS }, &inherent_data),
+            Err(InherentError::UnregisteredSequencerAddress)
+        ));
+        assert!(
+            Starknet::check_inherent(&Call::set_sequencer_address { addr: registered_address_bytes() }, &inherent_data)
+                .is_ok()
+        );
+    });
+}
+
+#[test]
+fn block_author_cannot_set_another_sequencer_address() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        assert_ok!(Starknet::add_sequencer(RuntimeOrigin::root(), AUTHOR, registered_address()));
+        BlockAuthor::set(&Some(AUTHOR));
+        System::set_block_number(3);
+
+        assert_noop!(
+            Starknet::set_sequencer_address(RuntimeOrigin::none(), DEFAULT_SEQUENCER_ADDRESS),
+            Error::<MockRuntime>::UnregisteredSequencerAddress
+        );
+        assert_ok!(Starknet::set_sequencer_address(RuntimeOrigin::none(), registered_address_bytes()));
+        assert_eq!(Starknet::sequencer_address(), registered_address());
+        // The fees of the block go to the registered address
+        assert_eq!(Starknet::get_block_context().sequencer_address, registered_address());
+    });
+}
@@ -0,0 +1,66 @@
+use blockifier::abi::abi_utils::get_storage_var_address;
+use mp_felt::Felt252Wrapper;
+use mp_simulations::{BlockContextOverrides, SimulatedBlock, SimulationFlags};
+use mp_transactions::UserTransaction;
+use starknet_api::api_core::{ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::StarkFelt;
+
+use super::constants::{BLOCKIFIER_ACCOUNT_ADDRESS, FEE_TOKEN_ADDRESS};
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::tests::get_invoke_dummy;
+use crate::tests::utils::build_get_balance_contract_call;
+
+#[test]
+fn given_sequence_of_blocks_state_is_carried_over() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let sender_address: ContractAddress = Felt252Wrapper::from_hex_be(BLOCKIFIER_ACCOUNT_ADDRESS).unwrap().into();
+        let fee_token_address = ContractAddress(PatriciaKey(StarkFelt::try_from(FEE_TOKEN_ADDRESS).unwrap()));
+        let overridden_sequencer_address = Felt252Wrapper::from(0xbeefu64);
+        let (selector, calldata) = build_get_balance_contract_call(*sender_address.0.key());
+        let sender_balance = Starknet::call_contract(fee_token_address, selector, calldata.clone()).unwrap();
+
+        let simulation_flags = SimulationFlags { skip_validate: false, skip_fee_charge: false };
+        let blocks = vec![
+            SimulatedBlock {
+                block_context_overrides: BlockContextOverrides::default(),
+                transactions: vec![UserTransaction::Invoke(get_invoke_dummy(Felt252Wrapper::ZERO).into())],
+            },
+            SimulatedBlock {
+                block_context_overrides: BlockContextOverrides {
+                    block_timestamp: Some(u32::MAX as u64),
+                    sequencer_address: Some(overridden_sequencer_address),
+                    ..Default::default()
+                },
+                // Only valid if the nonce has been incremented by the previous block
+                transactions: vec![UserTransaction::Invoke(get_invoke_dummy(Felt252Wrapper::ONE).into())],
+            },
+        ];
+
+        let results = Starknet::simulate_blocks(blocks, &simulation_flags).unwrap();
+
+        assert_eq!(results.len(), 2);
+        for (expected_nonce, block_results) in [1u64, 2].into_iter().zip(&results) {
+            assert_eq!(block_results.len(), 1);
+            assert!(block_results[0].1.is_ok());
+            assert_eq!(
+                block_results[0].0.address_to_nonce.get(&sender_address),
+                Some(&Nonce(StarkFelt::from(expected_nonce)))
+            );
+        }
+        // The fee of the second block is paid to the overridden sequencer address
+        let sequencer_balance_key =
+            get_storage_var_address("ERC20_balances", &[overridden_sequencer_address.into()]).unwrap();
+        let sequencer_balance = results[1][0].0.storage_updates[&fee_token_address].get(&sequencer_balance_key);
+        assert!(sequencer_balance.is_some_and(|balance| *balance != StarkFelt::default()));
+        assert!(!results[0][0].0.storage_updates[&fee_token_address].contains_key(&sequencer_balance_key));
+
+        // Nothing is persisted once the simulation is over
+        assert_eq!(Starknet::nonce(sender_address), Nonce(StarkFelt::default()));
+        assert_eq!(Starknet::call_contract(fee_token_address, selector, calldata).unwrap(), sender_balance);
+        assert_eq!(Starknet::storage((fee_token_address, sequencer_balance_key)), StarkFelt::default());
+        assert_eq!(Starknet::pending().len(), 0);
+    });
+}
@@ -0,0 +1,211 @@
+use blockifier::abi::abi_utils::{get_erc20_balance_var_addresses, get_storage_var_address};
+use frame_support::inherent::{InherentData, ProvideInherent};
+use frame_support::{assert_noop, assert_ok};
+use mp_felt::Felt252Wrapper;
+use mp_sequencer_address::{InherentError, DEFAULT_SEQUENCER_ADDRESS};
+use mp_simulations::ContractStateOverride;
+use sp_runtime::traits::ValidateUnsigned;
+use sp_runtime::transaction_validity::{InvalidTransaction, TransactionSource, TransactionValidityError};
+use starknet_api::api_core::{ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use super::constants::{BLOCKIFIER_ACCOUNT_ADDRESS, FEE_TOKEN_ADDRESS};
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::sealing::{SealingParameters, INHERENT_IDENTIFIER as SEALING_INHERENT_IDENTIFIER};
+use crate::tests::utils::build_get_balance_contract_call;
+use crate::{BlockSealingParameters, Call, Error};
+
+fn allow_state_edits() -> SealingParameters {
+    SealingParameters { allow_state_edits: true, ..Default::default() }
+}
+
+fn state_edits() -> Vec<ContractStateOverride> {
+    vec![ContractStateOverride {
+        contract_address: Felt252Wrapper::from_hex_be(BLOCKIFIER_ACCOUNT_ADDRESS).unwrap(),
+        nonce: Some(Felt252Wrapper::from(5u64)),
+        class_hash: Some(Felt252Wrapper::from(0x1234u64)),
+        balance: Some(Felt252Wrapper::from(42u128)),
+        storage: vec![(Felt252Wrapper::ONE, Felt252Wrapper::TWO)],
+    }]
+}
+
+#[test]
+fn state_edits_are_rejected_unless_allowed() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        assert_noop!(
+            Starknet::apply_state_edits(RuntimeOrigin::none(), state_edits()),
+            Error::<MockRuntime>::StateEditsNotAllowed
+        );
+
+        // Allowed for the current block only
+        BlockSealingParameters::<MockRuntime>::put(allow_state_edits());
+        assert_ok!(Starknet::apply_state_edits(RuntimeOrigin::none(), state_edits()));
+        run_to_block(3);
+        assert_noop!(
+            Starknet::apply_state_edits(RuntimeOrigin::none(), state_edits()),
+            Error::<MockRuntime>::StateEditsNotAllowed
+        );
+    });
+}
+
+#[test]
+fn state_edits_are_allowed_by_the_sealing_parameters_inherent() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let mut inherent_data = InherentData::new();
+        let Some(Call::set_block_parameters { sealing_parameters, .. }) = Starknet::create_inherent(&inherent_data)
+        else {
+            panic!("the block parameters inherent should be created")
+        };
+        assert_eq!(sealing_parameters, SealingParameters::default());
+
+        inherent_data.put_data(SEALING_INHERENT_IDENTIFIER, &allow_state_edits()).unwrap();
+        let Some(call) = Starknet::create_inherent(&inherent_data) else {
+            panic!("the block parameters inherent should be created")
+        };
+        assert_eq!(
+            call,
+            Call::set_block_parameters { addr: DEFAULT_SEQUENCER_ADDRESS, sealing_parameters: allow_state_edits() }
+        );
+        assert!(Starknet::check_inherent(&call, &inherent_data).is_ok());
+
+        // A node which does not allow state edits rejects the blocks allowing them
+        assert!(matches!(
+            Starknet::check_inherent(&call, &InherentData::new()),
+            Err(InherentError::UnallowedStateChanges)
+        ));
+    });
+}
+
+#[test]
+fn state_edits_are_only_accepted_from_the_local_node() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let call = Call::apply_state_edits { state_edits: state_edits() };
+        assert_eq!(
+            Starknet::validate_unsigned(TransactionSource::External, &call),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::Call))
+        );
+
+        let valid_transaction = Starknet::validate_unsigned(TransactionSource::Local, &call).unwrap();
+        assert_eq!(valid_transaction.priority, u64::MAX);
+        assert!(!valid_transaction.propagate);
+    });
+}
+
+#[test]
+fn state_edits_are_written_to_the_state() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        BlockSealingParameters::<MockRuntime>::put(allow_state_edits());
+
+        let account_address = Felt252Wrapper::from_hex_be(BLOCKIFIER_ACCOUNT_ADDRESS).unwrap();
+        let contract_address: ContractAddress = account_address.into();
+        assert_ok!(Starknet::apply_state_edits(RuntimeOrigin::none(), state_edits()));
+
+        assert_eq!(Starknet::nonce(contract_address), Nonce(StarkFelt::from(5u64)));
+        assert_eq!(Starknet::contract_class_hash_by_address(contract_address), Felt252Wrapper::from(0x1234u64).into());
+        assert_eq!(
+            Starknet::get_storage_at(contract_address, StorageKey(PatriciaKey(StarkFelt::from(1u64)))).unwrap(),
+            StarkFelt::from(2u64)
+        );
+
+        let fee_token_address = ContractAddress(PatriciaKey(StarkFelt::try_from(FEE_TOKEN_ADDRESS).unwrap()));
+        let (selector, calldata) = build_get_balance_contract_call(account_address.into());
+        assert_eq!(
+            Starknet::call_contract(fee_token_address, selector, calldata).unwrap(),
+            vec![Felt252Wrapper::from(42u128), Felt252Wrapper::ZERO]
+        );
+    });
+}
+
+fn fee_token_u256(low_key: StorageKey) -> (StarkFelt, StarkFelt) {
+    let fee_token_address = ContractAddress(PatriciaKey(StarkFelt::try_from(FEE_TOKEN_ADDRESS).unwrap()));
+    let high_key: StorageKey = Felt252Wrapper(Felt252Wrapper::from(low_key).0 + Felt252Wrapper::ONE.0).into();
+    (
+        Starknet::get_storage_at(fee_token_address, low_key).unwrap(),
+        Starknet::get_storage_at(fee_token_address, high_key).unwrap(),
+    )
+}
+
+#[test]
+fn mint_is_rejected_unless_allowed() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let address: ContractAddress = Felt252Wrapper::from_hex_be(BLOCKIFIER_ACCOUNT_ADDRESS).unwrap().into();
+        assert_noop!(
+            Starknet::mint(RuntimeOrigin::none(), address, StarkFelt::from(1u64)),
+            Error::<MockRuntime>::StateEditsNotAllowed
+        );
+
+        BlockSealingParameters::<MockRuntime>::put(allow_state_edits());
+        assert_eq!(
+            Starknet::validate_unsigned(
+                TransactionSource::External,
+                &Call::mint { address, amount: StarkFelt::from(1u64) }
+            ),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::Call))
+        );
+        assert!(
+            Starknet::validate_unsigned(
+                TransactionSource::Local,
+                &Call::mint { address, amount: StarkFelt::from(1u64) }
+            )
+            .is_ok()
+        );
+    });
+}
+
+#[test]
+fn mint_adds_to_the_balance_and_the_total_supply() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        BlockSealingParameters::<MockRuntime>::put(allow_state_edits());
+
+        let address: ContractAddress = Felt252Wrapper::from_hex_be(BLOCKIFIER_ACCOUNT_ADDRESS).unwrap().into();
+        let (balance_key, _) = get_erc20_balance_var_addresses(&address).unwrap();
+        let total_supply_key = get_storage_var_address("ERC20_total_supply", &[]).unwrap();
+        let (balance_low, balance_high) = fee_token_u256(balance_key);
+        let (total_supply_low, total_supply_high) = fee_token_u256(total_supply_key);
+
+        assert_ok!(Starknet::mint(RuntimeOrigin::none(), address, StarkFelt::from(1000u64)));
+        assert_ok!(Starknet::mint(RuntimeOrigin::none(), address, StarkFelt::from(234u64)));
+
+        let increased = |value: StarkFelt| StarkFelt::from(u128::try_from(Felt252Wrapper::from(value)).unwrap() + 1234);
+        assert_eq!(fee_token_u256(balance_key), (increased(balance_low), balance_high));
+        assert_eq!(fee_token_u256(total_supply_key), (increased(total_supply_low), total_supply_high));
+    });
+}
+
+#[test]
+fn mint_carries_to_the_high_felt() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        BlockSealingParameters::<MockRuntime>::put(allow_state_edits());
+
+        let address: ContractAddress = Felt252Wrapper::from_hex_be(BLOCKIFIER_ACCOUNT_ADDRESS).unwrap().into();
+        let (balance_key, _) = get_erc20_balance_var_addresses(&address).unwrap();
+        assert_ok!(Starknet::apply_state_edits(
+            RuntimeOrigin::none(),
+            vec![ContractStateOverride {
+                contract_address: Felt252Wrapper::from_hex_be(FEE_TOKEN_ADDRESS).unwrap(),
+                storage: vec![(balance_key.into(), Felt252Wrapper::from(u128::MAX))],
+                ..Default::default()
+            }]
+        ));
+
+        let (_, balance_high) = fee_token_u256(balance_key);
+
+        assert_ok!(Starknet::mint(RuntimeOrigin::none(), address, StarkFelt::from(2u64)));
+
+        let balance_high = StarkFelt::from(u128::try_from(Felt252Wrapper::from(balance_high)).unwrap() + 1);
+        assert_eq!(fee_token_u256(balance_key), (StarkFelt::from(1u64), balance_high));
+    });
+}
@@ -0,0 +1,140 @@
+use frame_support::{assert_noop, assert_ok};
+use mp_state::BlockStateDiff;
+use mp_transactions::{HandleL1MessageTransaction, ImportedTransaction, Transaction};
+use sp_runtime::traits::ValidateUnsigned;
+use sp_runtime::transaction_validity::{InvalidTransaction, TransactionSource, TransactionValidityError};
+use starknet_api::api_core::{ClassHash, CompiledClassHash, ContractAddress, EthAddress, Nonce, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{
+    Event as StarknetEvent, EventContent, EventData, EventKey, L2ToL1Payload, MessageToL1, TransactionHash,
+};
+
+use super::constants::{BLOCKIFIER_ACCOUNT_ADDRESS, TOKEN_CONTRACT_CLASS_HASH};
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::sealing::SealingParameters;
+use crate::{BlockSealingParameters, Call, Error, L1Messages};
+
+fn contract_address() -> ContractAddress {
+    ContractAddress(PatriciaKey(StarkFelt::try_from(BLOCKIFIER_ACCOUNT_ADDRESS).unwrap()))
+}
+
+fn state_diff() -> BlockStateDiff {
+    let token_class_hash = ClassHash(StarkFelt::try_from(TOKEN_CONTRACT_CLASS_HASH).unwrap());
+    let contract_class = Starknet::contract_class_by_class_hash(token_class_hash).unwrap();
+
+    BlockStateDiff {
+        storage: vec![(contract_address(), StorageKey(PatriciaKey(StarkFelt::from(1u64))), StarkFelt::from(2u64))],
+        nonces: vec![(contract_address(), Nonce(StarkFelt::from(5u64)))],
+        class_hashes: vec![(contract_address(), ClassHash(StarkFelt::from(0x1234u64)))],
+        declared_classes: vec![(
+            ClassHash(StarkFelt::from(0x1234u64)),
+            contract_class,
+            Some(CompiledClassHash(StarkFelt::from(0x5678u64))),
+        )],
+    }
+}
+
+fn allow_state_import() {
+    BlockSealingParameters::<MockRuntime>::put(SealingParameters { allow_state_import: true, ..Default::default() });
+}
+
+fn state_root() -> StarkHash {
+    StarkHash::from(0x5747u64)
+}
+
+#[test]
+fn state_imports_are_rejected_unless_allowed() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        assert_noop!(
+            Starknet::import_state_diff(RuntimeOrigin::none(), state_diff(), Vec::new(), state_root()),
+            Error::<MockRuntime>::StateImportNotAllowed
+        );
+    });
+}
+
+#[test]
+fn state_imports_are_never_accepted_in_the_pool() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        allow_state_import();
+
+        let call =
+            Call::import_state_diff { state_diff: state_diff(), transactions: Vec::new(), state_root: state_root() };
+        assert_eq!(
+            Starknet::validate_unsigned(TransactionSource::Local, &call),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::Call))
+        );
+    });
+}
+
+#[test]
+fn state_imports_are_written_to_the_state() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        allow_state_import();
+
+        assert_ok!(Starknet::import_state_diff(RuntimeOrigin::none(), state_diff(), Vec::new(), state_root()));
+
+        let class_hash = ClassHash(StarkFelt::from(0x1234u64));
+        assert_eq!(Starknet::nonce(contract_address()), Nonce(StarkFelt::from(5u64)));
+        assert_eq!(Starknet::contract_class_hash_by_address(contract_address()), class_hash);
+        assert_eq!(
+            Starknet::get_storage_at(contract_address(), StorageKey(PatriciaKey(StarkFelt::from(1u64)))).unwrap(),
+            StarkFelt::from(2u64)
+        );
+        assert!(Starknet::contract_class_by_class_hash(class_hash).is_some());
+        assert_eq!(
+            Starknet::compiled_class_hash_by_class_hash(class_hash),
+            Some(CompiledClassHash(StarkFelt::from(0x5678u64)))
+        );
+        assert_eq!(Starknet::global_state_root(), state_root());
+    });
+}
+
+#[test]
+fn imported_transactions_are_stored_with_their_outputs() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+        allow_state_import();
+
+        let transaction_hash = TransactionHash(StarkFelt::from(0xabcu64));
+        let transaction = Transaction::L1Handler(HandleL1MessageTransaction {
+            nonce: 7,
+            contract_address: Default::default(),
+            entry_point_selector: Default::default(),
+            calldata: Default::default(),
+        });
+        let event = StarknetEvent {
+            from_address: contract_address(),
+            content: EventContent {
+                keys: vec![EventKey(StarkFelt::from(1u64))],
+                data: EventData(vec![StarkFelt::from(2u64)]),
+            },
+        };
+        let message = MessageToL1 {
+            from_address: contract_address(),
+            to_address: EthAddress([1u8; 20].into()),
+            payload: L2ToL1Payload(vec![StarkFelt::from(3u64)]),
+        };
+        let imported = ImportedTransaction {
+            transaction_hash,
+            transaction: transaction.clone(),
+            events: vec![event.clone()],
+            messages: vec![message.clone()],
+            revert_error: Some("reverted".to_string()),
+        };
+
+        assert_ok!(Starknet::import_state_diff(RuntimeOrigin::none(), state_diff(), vec![imported], state_root()));
+
+        assert_eq!(Starknet::pending(), vec![transaction]);
+        assert_eq!(Starknet::pending_hashes(), vec![transaction_hash]);
+        assert_eq!(Starknet::tx_events(transaction_hash), vec![event]);
+        assert_eq!(Starknet::tx_messages(transaction_hash), vec![message]);
+        assert_eq!(Starknet::tx_revert_error(transaction_hash), Some("reverted".to_string()));
+        assert!(L1Messages::<MockRuntime>::contains_key(Nonce(StarkFelt::from(7u64))));
+    });
+}
@@ -0,0 +1,58 @@
+use frame_support::assert_ok;
+use mp_felt::Felt252Wrapper;
+use mp_simulations::ContractStateOverride;
+use mp_transactions::UserTransaction;
+use starknet_api::api_core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::StarkFelt;
+
+use super::constants::{BLOCKIFIER_ACCOUNT_ADDRESS, FEE_TOKEN_ADDRESS};
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::tests::get_invoke_dummy;
+use crate::tests::utils::build_get_balance_contract_call;
+
+#[test]
+fn given_balance_override_call_returns_overridden_balance() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let fee_token_address = ContractAddress(PatriciaKey(StarkFelt::try_from(FEE_TOKEN_ADDRESS).unwrap()));
+        let account_address = Felt252Wrapper::from_hex_be(BLOCKIFIER_ACCOUNT_ADDRESS).unwrap();
+        let (selector, calldata) = build_get_balance_contract_call(account_address.into());
+
+        let on_chain_balance = Starknet::call_contract(fee_token_address, selector, calldata.clone()).unwrap();
+
+        let state_overrides = vec![ContractStateOverride {
+            contract_address: account_address,
+            balance: Some(Felt252Wrapper::from(42u128)),
+            ..Default::default()
+        }];
+        let overridden_balance =
+            Starknet::call_contract_with_overrides(fee_token_address, selector, calldata.clone(), state_overrides)
+                .unwrap();
+        pretty_assertions::assert_eq!(overridden_balance, vec![Felt252Wrapper::from(42u128), Felt252Wrapper::ZERO]);
+
+        // The overrides must not outlive the call
+        pretty_assertions::assert_eq!(
+            Starknet::call_contract(fee_token_address, selector, calldata).unwrap(),
+            on_chain_balance
+        );
+    });
+}
+
+#[test]
+fn given_nonce_override_estimate_fee_works() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let tx = UserTransaction::Invoke(get_invoke_dummy(Felt252Wrapper::from(5u64)).into());
+        assert!(Starknet::estimate_fee(vec![tx.clone()]).is_err());
+
+        let state_overrides = vec![ContractStateOverride {
+            contract_address: Felt252Wrapper::from_hex_be(BLOCKIFIER_ACCOUNT_ADDRESS).unwrap(),
+            nonce: Some(Felt252Wrapper::from(5u64)),
+            ..Default::default()
+        }];
+        assert_ok!(Starknet::estimate_fee_with_overrides(vec![tx], state_overrides));
+    });
+}
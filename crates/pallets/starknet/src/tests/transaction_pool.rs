@@ -0,0 +1,90 @@
+use mp_felt::Felt252Wrapper;
+use mp_transactions::{InvokeTransactionV0, InvokeTransactionV1};
+use sp_runtime::traits::ValidateUnsigned;
+use sp_runtime::transaction_validity::{InvalidTransaction, TransactionSource, TransactionValidityError};
+
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::tests::get_invoke_dummy;
+use crate::Call;
+
+#[test]
+fn higher_max_fee_gets_higher_priority() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let cheap_transaction = InvokeTransactionV1 { max_fee: 1_000, ..get_invoke_dummy(Felt252Wrapper::ZERO) };
+        let expensive_transaction =
+            InvokeTransactionV1 { max_fee: 1_000_000, ..get_invoke_dummy(Felt252Wrapper::ZERO) };
+
+        let cheap = Starknet::validate_unsigned(
+            TransactionSource::External,
+            &Call::invoke { transaction: cheap_transaction.into() },
+        )
+        .unwrap();
+        let expensive = Starknet::validate_unsigned(
+            TransactionSource::External,
+            &Call::invoke { transaction: expensive_transaction.into() },
+        )
+        .unwrap();
+
+        assert_eq!(cheap.priority, UnsignedPriority::get() + 100);
+        assert!(expensive.priority > cheap.priority);
+        // Both compete for the same nonce of the account
+        assert_eq!(cheap.provides, expensive.provides);
+    });
+}
+
+#[test]
+fn gapped_nonces_are_queued_until_the_pending_limit() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let last_queued_nonce = Felt252Wrapper::from(MaxPendingTransactionsPerAccount::get() - 1);
+        let queued = Starknet::validate_unsigned(
+            TransactionSource::External,
+            &Call::invoke { transaction: get_invoke_dummy(last_queued_nonce).into() },
+        )
+        .unwrap();
+        // Held in the future queue of the pool until the previous nonce is provided
+        assert_eq!(queued.requires.len(), 1);
+
+        let too_far_nonce = Felt252Wrapper::from(MaxPendingTransactionsPerAccount::get());
+        assert_eq!(
+            Starknet::validate_unsigned(
+                TransactionSource::External,
+                &Call::invoke { transaction: get_invoke_dummy(too_far_nonce).into() },
+            ),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::Future))
+        );
+    });
+}
+
+#[test]
+fn nonceless_transactions_provide_their_hash() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let transaction = InvokeTransactionV0 {
+            max_fee: 1_000,
+            signature: vec![],
+            contract_address: Felt252Wrapper::from(0x1234_u64),
+            entry_point_selector: Felt252Wrapper::ONE,
+            calldata: vec![],
+        };
+        let other_transaction = InvokeTransactionV0 { calldata: vec![Felt252Wrapper::ONE], ..transaction.clone() };
+
+        let valid_transaction =
+            Starknet::validate_unsigned(TransactionSource::External, &Call::invoke { transaction: transaction.into() })
+                .unwrap();
+        let other_valid_transaction = Starknet::validate_unsigned(
+            TransactionSource::External,
+            &Call::invoke { transaction: other_transaction.into() },
+        )
+        .unwrap();
+
+        assert_eq!(valid_transaction.provides.len(), 1);
+        assert_ne!(valid_transaction.provides, other_valid_transaction.provides);
+        assert!(valid_transaction.requires.is_empty());
+    });
+}
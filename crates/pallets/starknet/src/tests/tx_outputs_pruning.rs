@@ -0,0 +1,46 @@
+use frame_support::assert_ok;
+use frame_support::traits::Hooks;
+use mp_felt::Felt252Wrapper;
+use mp_transactions::InvokeTransaction;
+
+use super::mock::default_mock::*;
+use super::mock::*;
+use crate::tests::get_invoke_dummy;
+
+#[test]
+fn transaction_outputs_are_pruned_once_out_of_the_retention_window() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        TxOutputsRetention::set(&Some(1));
+        basic_test_setup(2);
+
+        let transaction: InvokeTransaction = get_invoke_dummy(Felt252Wrapper::ZERO).into();
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction));
+        let tx_hash = *Starknet::pending_hashes().last().unwrap();
+        run_to_block(3);
+        assert_eq!(Starknet::block_transaction_hashes(2), vec![tx_hash]);
+
+        // Block 2 is still in the retention window of block 3
+        Starknet::on_initialize(3);
+        assert!(!Starknet::tx_events(tx_hash).is_empty());
+
+        Starknet::on_initialize(4);
+        assert!(Starknet::tx_events(tx_hash).is_empty());
+        assert!(Starknet::block_transaction_hashes(2).is_empty());
+    });
+}
+
+#[test]
+fn transaction_outputs_are_kept_without_retention() {
+    new_test_ext::<MockRuntime>().execute_with(|| {
+        basic_test_setup(2);
+
+        let transaction: InvokeTransaction = get_invoke_dummy(Felt252Wrapper::ZERO).into();
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction));
+        let tx_hash = *Starknet::pending_hashes().last().unwrap();
+        run_to_block(3);
+
+        Starknet::on_initialize(100);
+        assert!(!Starknet::tx_events(tx_hash).is_empty());
+        assert!(Starknet::block_transaction_hashes(2).is_empty());
+    });
+}
@@ -1,6 +1,7 @@
 //! Transaction validation logic.
 use blockifier::transaction::errors::TransactionExecutionError;
 use frame_support::traits::EnsureOrigin;
+use mp_transactions::compute_hash::ComputeTransactionHash;
 use mp_transactions::execution::Validate;
 
 use super::*;
@@ -57,7 +58,7 @@ impl<OuterOrigin: Into<Result<RawOrigin, OuterOrigin>> + From<RawOrigin>> Ensure
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum TxPriorityInfo {
-    InvokeV0,
+    InvokeV0 { transaction_hash: Felt252Wrapper },
     L1Handler { nonce: Felt252Wrapper },
     RegularTxs { sender_address: Felt252Wrapper, transaction_nonce: Felt252Wrapper, sender_nonce: Felt252Wrapper },
 }
@@ -72,7 +73,11 @@ impl<T: Config> Pallet<T> {
                 let sender_nonce: Felt252Wrapper = Pallet::<T>::nonce(sender_address).into();
                 let transaction_nonce = match tx.nonce() {
                     Some(n) => *n,
-                    None => return Ok(TxPriorityInfo::InvokeV0),
+                    None => {
+                        return Ok(TxPriorityInfo::InvokeV0 {
+                            transaction_hash: tx.compute_hash::<T::SystemHash>(Self::chain_id(), false),
+                        });
+                    }
                 };
 
                 // Reject transaction with an already used Nonce
@@ -81,8 +86,15 @@ impl<T: Config> Pallet<T> {
                 }
 
                 // A transaction with a nonce higher than the expected nonce is placed in
-                // the future queue of the transaction pool.
+                // the future queue of the transaction pool, until the nonces in between are filled.
                 if sender_nonce < transaction_nonce {
+                    // Bounds the number of transactions an account can keep in the pool
+                    if Felt252Wrapper(transaction_nonce.0 - sender_nonce.0)
+                        >= Felt252Wrapper::from(T::MaxPendingTransactionsPerAccount::get())
+                    {
+                        Err(InvalidTransaction::Future)?;
+                    }
+
                     log::debug!(
                         "Nonce is too high. Expected: {:?}, got: {:?}. This transaction will be placed in the \
                          transaction pool and executed in the future when the nonce is reached.",
@@ -101,27 +113,64 @@ impl<T: Config> Pallet<T> {
         }
     }
 
+    /// Returns the priority of a transaction in the pool.
+    ///
+    /// The supported transaction versions have no tip, so their bid is their max fee expressed in
+    /// units of L1 gas at the current price. L1 handlers are paid on L1 and always go first.
+    pub fn transaction_priority(transaction: &UserOrL1HandlerTransaction) -> TransactionPriority {
+        match transaction {
+            UserOrL1HandlerTransaction::User(tx) => {
+                let gas_price = Self::l1_gas_price().price_in(PriceUnit::for_tx_version(tx.version())).unwrap_or(1);
+                let max_gas_amount = tx.max_fee() / gas_price.max(1);
+
+                T::UnsignedPriority::get().saturating_add(max_gas_amount.try_into().unwrap_or(TransactionPriority::MAX))
+            }
+            UserOrL1HandlerTransaction::L1Handler(..) => TransactionPriority::MAX,
+        }
+    }
+
     pub fn validate_unsigned_tx(transaction: &UserOrL1HandlerTransaction) -> Result<(), InvalidTransaction> {
         let chain_id = Self::chain_id();
-        let block_context = Self::get_block_context();
+        let block_context = match transaction {
+            // Reject the transactions whose fees cannot be charged
+            UserOrL1HandlerTransaction::User(transaction) => {
+                Self::get_block_context_in(PriceUnit::for_tx_version(transaction.version()))
+                    .map_err(|_| InvalidTransaction::Payment)?
+            }
+            UserOrL1HandlerTransaction::L1Handler(..) => Self::get_block_context(),
+        };
         let mut state: BlockifierStateAdapter<T> = BlockifierStateAdapter::<T>::default();
         let mut execution_resources = ExecutionResources::default();
         let mut initial_gas = blockifier::abi::constants::INITIAL_GAS_COST;
 
         match transaction {
             UserOrL1HandlerTransaction::User(transaction) => {
-                let validation_result =
-                    match transaction {
-                        // There is no way to validate it before the account is actuallly deployed
-                        UserTransaction::DeployAccount(_) => Ok(None),
-                        UserTransaction::Declare(tx, contract_class) => tx
-                            .try_into_executable::<T::SystemHash>(chain_id, contract_class.clone(), false)
-                            .map_err(|_| InvalidTransaction::BadProof)?
-                            .validate_tx(&mut state, &block_context, &mut execution_resources, &mut initial_gas, false),
-                        UserTransaction::Invoke(tx) => tx
-                            .into_executable::<T::SystemHash>(chain_id, false)
-                            .validate_tx(&mut state, &block_context, &mut execution_resources, &mut initial_gas, false),
-                    };
+                let validation_result = match transaction {
+                    // There is no way to validate it before the account is actuallly deployed
+                    UserTransaction::DeployAccount(_) => Ok(None),
+                    UserTransaction::Declare(tx, contract_class) => tx
+                        .try_into_executable::<T::SystemHash>(chain_id, contract_class.clone(), false)
+                        .map_err(|_| InvalidTransaction::BadProof)?
+                        .validate_tx(&mut state, &block_context, &mut execution_resources, &mut initial_gas, false),
+                    UserTransaction::Invoke(tx) => {
+                        match tx.into_sponsored_executable::<T::SystemHash>(chain_id, false) {
+                            Some(executable) => executable.validate_tx(
+                                &mut state,
+                                &block_context,
+                                &mut execution_resources,
+                                &mut initial_gas,
+                                false,
+                            ),
+                            None => tx.into_executable::<T::SystemHash>(chain_id, false).validate_tx(
+                                &mut state,
+                                &block_context,
+                                &mut execution_resources,
+                                &mut initial_gas,
+                                false,
+                            ),
+                        }
+                    }
+                };
 
                 if let Err(TransactionExecutionError::ValidateTransactionError(
                     EntryPointExecutionError::PreExecutionError(PreExecutionError::UninitializedStorageAddress(
@@ -156,6 +205,6 @@ impl<T: Config> Pallet<T> {
     }
 
     pub fn ensure_l1_message_not_executed(nonce: &Nonce) -> Result<(), InvalidTransaction> {
-        if L1Messages::<T>::get().contains(nonce) { Err(InvalidTransaction::Stale) } else { Ok(()) }
+        if L1Messages::<T>::contains_key(nonce) { Err(InvalidTransaction::Stale) } else { Ok(()) }
     }
 }
@@ -0,0 +1,113 @@
+//! Transaction and event commitments of a block.
+//!
+//! Both are roots of Starknet binary Merkle-Patricia trees of height 64, whose leaves are keyed
+//! by the position of the transaction or event in the block. Starknet defines them with the
+//! Pedersen hash, whatever the hasher of the chain.
+
+use alloc::vec::Vec;
+
+use mp_felt::Felt252Wrapper;
+use mp_hashers::pedersen::PedersenHasher;
+use mp_hashers::HasherT;
+
+/// Height of the commitment trees.
+const COMMITMENT_TREE_HEIGHT: u8 = 64;
+
+/// Node of a commitment tree, as seen by its parent.
+enum Node {
+    /// A leaf or a binary node, which hashes to the given value.
+    Hashed(Felt252Wrapper),
+    /// An edge node, leading to `child` through the `length` lower bits of `path`.
+    Edge { child: Felt252Wrapper, path: u64, length: u8 },
+}
+
+impl Node {
+    fn hash(&self) -> Felt252Wrapper {
+        match self {
+            Node::Hashed(hash) => *hash,
+            Node::Edge { child, path, length } => {
+                let path = Felt252Wrapper::from(*path);
+                Felt252Wrapper(PedersenHasher::hash_elements(child.0, path.0) + Felt252Wrapper::from(*length).0)
+            }
+        }
+    }
+}
+
+/// Computes the root of the commitment tree of `leaves`, the first leaf at key 0.
+///
+/// The leaves of value zero are not part of the tree, the root of an empty tree is zero.
+pub fn calculate_commitment(leaves: &[Felt252Wrapper]) -> Felt252Wrapper {
+    let leaves: Vec<(u64, Felt252Wrapper)> = leaves
+        .iter()
+        .enumerate()
+        .filter(|(_, leaf)| **leaf != Felt252Wrapper::ZERO)
+        .map(|(index, leaf)| (index as u64, *leaf))
+        .collect();
+    if leaves.is_empty() {
+        return Felt252Wrapper::ZERO;
+    }
+
+    subtree(&leaves, COMMITMENT_TREE_HEIGHT).hash()
+}
+
+/// Builds the subtree of `height` holding `leaves`, which are sorted by key, not empty, and
+/// share all the key bits above that height.
+fn subtree(leaves: &[(u64, Felt252Wrapper)], height: u8) -> Node {
+    if height == 0 {
+        return Node::Hashed(leaves[0].1);
+    }
+
+    let bit = 1u64 << (height - 1);
+    let (left, right) = leaves.split_at(leaves.partition_point(|(key, _)| key & bit == 0));
+    if left.is_empty() || right.is_empty() {
+        // A single branch is taken, the edge to it grows by one bit
+        let (leaves, direction) = if left.is_empty() { (right, 1) } else { (left, 0) };
+        return match subtree(leaves, height - 1) {
+            Node::Edge { child, path, length } => {
+                Node::Edge { child, path: path | direction << length, length: length + 1 }
+            }
+            Node::Hashed(child) => Node::Edge { child, path: direction, length: 1 },
+        };
+    }
+
+    let left = subtree(left, height - 1).hash();
+    let right = subtree(right, height - 1).hash();
+    Node::Hashed(Felt252Wrapper(PedersenHasher::hash_elements(left.0, right.0)))
+}
+
+/// Computes the leaf of a transaction in the transaction commitment tree:
+/// h(transaction_hash, h(signature)).
+///
+/// L1 handler transactions have an empty signature.
+pub fn transaction_commitment_leaf(transaction_hash: Felt252Wrapper, signature: &[Felt252Wrapper]) -> Felt252Wrapper {
+    let signature_hash = PedersenHasher::compute_hash_on_wrappers(signature);
+
+    Felt252Wrapper(PedersenHasher::hash_elements(transaction_hash.0, signature_hash.0))
+}
+
+/// Computes the transaction commitment of a block from the hashes and signatures of its
+/// transactions, in order.
+pub fn calculate_transaction_commitment(transactions: &[(Felt252Wrapper, Vec<Felt252Wrapper>)]) -> Felt252Wrapper {
+    let leaves: Vec<Felt252Wrapper> = transactions
+        .iter()
+        .map(|(transaction_hash, signature)| transaction_commitment_leaf(*transaction_hash, signature))
+        .collect();
+
+    calculate_commitment(&leaves)
+}
+
+/// Computes the hash of an event, the leaf of the event commitment tree:
+/// h(from_address, h(keys), h(data)).
+pub fn event_hash(from_address: Felt252Wrapper, keys: &[Felt252Wrapper], data: &[Felt252Wrapper]) -> Felt252Wrapper {
+    PedersenHasher::compute_hash_on_wrappers(&[
+        from_address,
+        PedersenHasher::compute_hash_on_wrappers(keys),
+        PedersenHasher::compute_hash_on_wrappers(data),
+    ])
+}
+
+/// Computes the event commitment of a block from the hashes of its events, in the order of the
+/// transactions emitting them.
+pub fn calculate_event_commitment(event_hashes: &[Felt252Wrapper]) -> Felt252Wrapper {
+    calculate_commitment(event_hashes)
+}
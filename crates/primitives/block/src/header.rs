@@ -34,6 +34,22 @@ pub struct Header {
     pub l1_gas_price: ResourcePrice,
     /// Extraneous data that might be useful for running transactions
     pub extra_data: Option<U256>,
+    // Added after the fields above, the blocks encoded without them are decoded by
+    // `Block::decode_legacy`.
+    /// The state commitment after this block, as announced by the sequencer of an imported block.
+    ///
+    /// Madara does not compute the state commitment: the blocks it executes keep the last known
+    /// root, zero on a chain which never imported a block.
+    pub global_state_root: StarkHash,
+    /// A commitment to the transactions included in the block
+    pub transaction_commitment: StarkHash,
+    /// A commitment to the events produced in this block
+    pub event_commitment: StarkHash,
+    /// Whether the header was decoded from the layout of the blocks logged before it carried the
+    /// state root and the commitments, see [`Header::hash`].
+    #[cfg_attr(feature = "parity-scale-codec", codec(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub legacy: bool,
 }
 
 impl Header {
@@ -50,6 +66,9 @@ impl Header {
         protocol_version: u8,
         l1_gas_price: ResourcePrice,
         extra_data: Option<U256>,
+        global_state_root: StarkHash,
+        transaction_commitment: StarkHash,
+        event_commitment: StarkHash,
     ) -> Self {
         Self {
             parent_block_hash,
@@ -61,6 +80,10 @@ impl Header {
             protocol_version,
             l1_gas_price,
             extra_data,
+            global_state_root,
+            transaction_commitment,
+            event_commitment,
+            legacy: false,
         }
     }
 
@@ -81,8 +104,37 @@ impl Header {
         }
     }
 
-    /// Compute the hash of the header.
+    /// Compute the hash of the header with the formula of Starknet:
+    /// h(block_number, global_state_root, sequencer_address, block_timestamp, transaction_count,
+    /// transaction_commitment, event_count, event_commitment, 0, 0, parent_block_hash).
+    ///
+    /// The state root not being computed, it is only the Starknet hash of the block if the header
+    /// carries the root announced by a Starknet sequencer. The legacy headers keep the hash they
+    /// were logged with:
+    /// h(block_number, sequencer_address, block_timestamp, transaction_count, event_count,
+    /// protocol_version, 0, parent_block_hash).
     pub fn hash<H: HasherT>(&self) -> Felt252Wrapper {
+        if self.legacy {
+            return self.legacy_hash::<H>();
+        }
+
+        let data: &[Felt252Wrapper] = &[
+            self.block_number.into(),
+            self.global_state_root.into(),
+            self.sequencer_address.0.0.into(),
+            self.block_timestamp.into(),
+            self.transaction_count.into(),
+            self.transaction_commitment.into(),
+            self.event_count.into(),
+            self.event_commitment.into(),
+            Felt252Wrapper::ZERO,
+            Felt252Wrapper::ZERO,
+            self.parent_block_hash.into(),
+        ];
+
+        H::compute_hash_on_wrappers(data)
+    }
+    fn legacy_hash<H: HasherT>(&self) -> Felt252Wrapper {
         let data: &[Felt252Wrapper] = &[
             self.block_number.into(),
             self.sequencer_address.0.0.into(),
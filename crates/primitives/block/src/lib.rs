@@ -4,10 +4,12 @@
 #[doc(hidden)]
 pub extern crate alloc;
 
+mod commitments;
 mod header;
 
 use alloc::vec::Vec;
 
+pub use commitments::*;
 pub use header::*;
 use mp_felt::Felt252Wrapper;
 use mp_hashers::HasherT;
@@ -81,6 +83,48 @@ impl Block {
     ) -> impl '_ + Iterator<Item = Felt252Wrapper> {
         self.transactions.iter().map(move |tx| tx.compute_hash::<H>(chain_id, false))
     }
+
+    /// Decodes a block encoded before its header carried the state root and the commitments, which
+    /// are left to zero. The header is marked as [`Header::legacy`].
+    #[cfg(feature = "parity-scale-codec")]
+    pub fn decode_legacy<I: parity_scale_codec::Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
+        use parity_scale_codec::Decode;
+
+        // The fields are decoded in the order they are written
+        let header = Header {
+            parent_block_hash: Decode::decode(input)?,
+            block_number: Decode::decode(input)?,
+            sequencer_address: Decode::decode(input)?,
+            block_timestamp: Decode::decode(input)?,
+            transaction_count: Decode::decode(input)?,
+            event_count: Decode::decode(input)?,
+            protocol_version: Decode::decode(input)?,
+            l1_gas_price: Decode::decode(input)?,
+            extra_data: Decode::decode(input)?,
+            legacy: true,
+            ..Default::default()
+        };
+
+        Ok(Self { header, transactions: Decode::decode(input)? })
+    }
+
+    /// Encodes a block with the layout read by [`Block::decode_legacy`].
+    #[cfg(feature = "parity-scale-codec")]
+    pub fn encode_legacy_to<O: parity_scale_codec::Output + ?Sized>(&self, dest: &mut O) {
+        use parity_scale_codec::Encode;
+
+        let header = &self.header;
+        header.parent_block_hash.encode_to(dest);
+        header.block_number.encode_to(dest);
+        header.sequencer_address.encode_to(dest);
+        header.block_timestamp.encode_to(dest);
+        header.transaction_count.encode_to(dest);
+        header.event_count.encode_to(dest);
+        header.protocol_version.encode_to(dest);
+        header.l1_gas_price.encode_to(dest);
+        header.extra_data.encode_to(dest);
+        self.transactions.encode_to(dest);
+    }
 }
 
 #[cfg(test)]
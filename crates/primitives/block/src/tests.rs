@@ -7,7 +7,7 @@ use starknet_api::api_core::{ChainId, ContractAddress, PatriciaKey};
 use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::hash::{StarkFelt, StarkHash};
 
-use crate::Header;
+use crate::{calculate_commitment, event_hash, transaction_commitment_leaf, Header};
 
 fn generate_dummy_header() -> Vec<Felt252Wrapper> {
     vec![
@@ -76,6 +76,100 @@ fn test_real_header_hash() {
     assert_eq!(hash, expected_hash);
 }
 
+#[test]
+fn test_header_hash_is_starknet_block_hash() {
+    // Block 86000 of alpha-mainnet
+    let header = Header {
+        parent_block_hash: StarkHash::try_from("0x045543088ce763aba7db8f6bfb33e33cc50af5c2ed5a26d38d5071c352a49c1d")
+            .unwrap(),
+        block_number: 86000,
+        global_state_root: StarkHash::try_from("0x006727a7aae8c38618a179aeebccd6302c67ad5f8528894d1dde794e9ae0bbfa")
+            .unwrap(),
+        sequencer_address: ContractAddress(PatriciaKey(
+            StarkFelt::try_from("0x1176a1bd84444c89232ec27754698e5d2e7e1a7f1539f12027f28b23ec9f3d8").unwrap(),
+        )),
+        block_timestamp: 1687235884,
+        transaction_count: 197,
+        transaction_commitment: StarkFelt::try_from(
+            "0x70369cef825889dc005916dba67332b71f270b7af563d0433cee3342dda527d",
+        )
+        .unwrap(),
+        event_count: 1430,
+        event_commitment: StarkFelt::try_from("0x2043ba1ef46882ce1dbb17b501fffa4b71f87f618e8f394e9605959d92efdf6")
+            .unwrap(),
+        ..Default::default()
+    };
+
+    let expected_hash =
+        Felt252Wrapper::from_hex_be("0x001d126ca058c7e546d59cf4e10728e4b023ca0fb368e8abcabf0b5335f4487a").unwrap();
+
+    assert_eq!(header.hash::<PedersenHasher>(), expected_hash);
+}
+
+fn edge_hash(child: Felt252Wrapper, path: u64, length: u8) -> Felt252Wrapper {
+    let hash = PedersenHasher::hash_elements(child.0, Felt252Wrapper::from(path).0);
+    Felt252Wrapper(hash + Felt252Wrapper::from(length).0)
+}
+
+fn binary_hash(left: Felt252Wrapper, right: Felt252Wrapper) -> Felt252Wrapper {
+    Felt252Wrapper(PedersenHasher::hash_elements(left.0, right.0))
+}
+
+#[test]
+fn test_commitment_of_no_leaves_is_zero() {
+    assert_eq!(calculate_commitment(&[]), Felt252Wrapper::ZERO);
+    assert_eq!(calculate_commitment(&[Felt252Wrapper::ZERO]), Felt252Wrapper::ZERO);
+}
+
+#[test]
+fn test_commitment_of_a_leaf_is_an_edge_to_it() {
+    let leaf = Felt252Wrapper::from(7u64);
+
+    assert_eq!(calculate_commitment(&[leaf]), edge_hash(leaf, 0, 64));
+}
+
+#[test]
+fn test_commitment_tree_shape() {
+    let leaves = [Felt252Wrapper::from(1u64), Felt252Wrapper::from(2u64), Felt252Wrapper::from(3u64)];
+
+    // Keys 0 and 1 share a binary node, key 2 is one step away from its own
+    let low = binary_hash(leaves[0], leaves[1]);
+    let high = edge_hash(leaves[2], 0, 1);
+    let expected = edge_hash(binary_hash(low, high), 0, 62);
+
+    assert_eq!(calculate_commitment(&leaves), expected);
+}
+
+#[test]
+fn test_commitment_ignores_zero_leaves() {
+    let leaf = Felt252Wrapper::from(5u64);
+
+    // The leaf at key 1 is reached through the last bit of the path
+    assert_eq!(calculate_commitment(&[Felt252Wrapper::ZERO, leaf]), edge_hash(leaf, 1, 64));
+}
+
+#[test]
+fn test_commitment_leaves() {
+    let transaction_hash = Felt252Wrapper::from(0xabcu64);
+    let empty_hash = PedersenHasher::compute_hash_on_elements(&[]);
+
+    assert_eq!(
+        transaction_commitment_leaf(transaction_hash, &[]),
+        Felt252Wrapper(PedersenHasher::hash_elements(transaction_hash.0, empty_hash))
+    );
+
+    let from_address = Felt252Wrapper::from(0x1u64);
+    let keys = [Felt252Wrapper::from(0x2u64)];
+    let data = [Felt252Wrapper::from(0x3u64), Felt252Wrapper::from(0x4u64)];
+    let expected = PedersenHasher::compute_hash_on_wrappers(&[
+        from_address,
+        PedersenHasher::compute_hash_on_wrappers(&keys),
+        PedersenHasher::compute_hash_on_wrappers(&data),
+    ]);
+
+    assert_eq!(event_hash(from_address, &keys, &data), expected);
+}
+
 #[test]
 fn test_to_block_context() {
     let sequencer_address = ContractAddress(PatriciaKey(StarkFelt::try_from("0xFF").unwrap()));
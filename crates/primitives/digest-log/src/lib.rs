@@ -21,22 +21,60 @@ mod tests;
 
 pub use error::FindLogError;
 use mp_block::Block as StarknetBlock;
-use parity_scale_codec::{Decode, Encode};
+use parity_scale_codec::{Decode, Encode, Input, Output};
 use sp_runtime::generic::{Digest, OpaqueDigestItemId};
 use sp_runtime::ConsensusEngineId;
 
 pub const MADARA_ENGINE_ID: ConsensusEngineId = [b'm', b'a', b'd', b'a'];
 
+/// Index of the blocks logged before their header carried the state root and the commitments.
+const LEGACY_BLOCK_INDEX: u8 = 0;
+const BLOCK_INDEX: u8 = 1;
+
 /// A Madara log
 ///
 /// Right now we only expect Madara to log the Starknet block,
 /// but other usecases may appears later on.
-#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+///
+/// The blocks are versioned by the index of the log: the ones logged with an earlier layout are
+/// still decoded, see [`StarknetBlock::decode_legacy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Log {
-    #[codec(index = 0)]
     Block(StarknetBlock),
 }
 
+impl Encode for Log {
+    fn size_hint(&self) -> usize {
+        match self {
+            Log::Block(block) => 1 + block.size_hint(),
+        }
+    }
+
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        match self {
+            // Legacy blocks keep their layout, which their hash depends on
+            Log::Block(block) if block.header().legacy => {
+                dest.push_byte(LEGACY_BLOCK_INDEX);
+                block.encode_legacy_to(dest);
+            }
+            Log::Block(block) => {
+                dest.push_byte(BLOCK_INDEX);
+                block.encode_to(dest);
+            }
+        }
+    }
+}
+
+impl Decode for Log {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
+        match input.read_byte()? {
+            LEGACY_BLOCK_INDEX => Ok(Log::Block(StarknetBlock::decode_legacy(input)?)),
+            BLOCK_INDEX => Ok(Log::Block(StarknetBlock::decode(input)?)),
+            _ => Err("Invalid Madara log index".into()),
+        }
+    }
+}
+
 /// Return the wrapped [StarknetBlock] contained in a given [Digest]
 pub fn find_starknet_block(digest: &Digest) -> Result<StarknetBlock, FindLogError> {
     find_log(digest).map(|log| match log {
@@ -1,4 +1,8 @@
 use assert_matches::assert_matches;
+use mp_block::Header;
+use mp_felt::Felt252Wrapper;
+use mp_hashers::pedersen::PedersenHasher;
+use mp_hashers::HasherT;
 use sp_runtime::{Digest, DigestItem};
 
 use super::*;
@@ -48,3 +52,67 @@ fn other_consensus_engine_id() {
     assert_matches!(find_log(&digest), Err(FindLogError::NotLog));
     assert_matches!(find_starknet_block(&digest), Err(FindLogError::NotLog));
 }
+
+#[test]
+fn legacy_blocks_are_decoded() {
+    let mut header = StarknetBlock::default().header().clone();
+    header.block_number = 3;
+    header.transaction_count = 2;
+    header.protocol_version = 1;
+
+    // Layout of the blocks logged before the header carried the state root and the commitments
+    let mut encoded = vec![0u8];
+    header.parent_block_hash.encode_to(&mut encoded);
+    header.block_number.encode_to(&mut encoded);
+    header.sequencer_address.encode_to(&mut encoded);
+    header.block_timestamp.encode_to(&mut encoded);
+    header.transaction_count.encode_to(&mut encoded);
+    header.event_count.encode_to(&mut encoded);
+    header.protocol_version.encode_to(&mut encoded);
+    header.l1_gas_price.encode_to(&mut encoded);
+    header.extra_data.encode_to(&mut encoded);
+    mp_block::BlockTransactions::new().encode_to(&mut encoded);
+
+    let mut digest = Digest::default();
+    digest.push(DigestItem::Consensus(MADARA_ENGINE_ID, encoded));
+
+    assert_eq!(
+        find_starknet_block(&digest).unwrap(),
+        StarknetBlock::new(Header { legacy: true, ..header }, Vec::new())
+    );
+}
+
+#[test]
+fn legacy_blocks_keep_their_hash() {
+    let header = Header { block_number: 3, transaction_count: 2, protocol_version: 1, ..Default::default() };
+    let mut encoded = vec![LEGACY_BLOCK_INDEX];
+    StarknetBlock::new(header.clone(), Vec::new()).encode_legacy_to(&mut encoded);
+
+    let Log::Block(block) = Log::decode(&mut &encoded[..]).unwrap();
+    // h(block_number, sequencer_address, block_timestamp, transaction_count, event_count,
+    // protocol_version, 0, parent_block_hash)
+    let legacy_hash = PedersenHasher::compute_hash_on_wrappers(&[
+        Felt252Wrapper::from(3u64),
+        Felt252Wrapper::ZERO,
+        Felt252Wrapper::ZERO,
+        Felt252Wrapper::from(2u128),
+        Felt252Wrapper::ZERO,
+        Felt252Wrapper::from(1u8),
+        Felt252Wrapper::ZERO,
+        Felt252Wrapper::ZERO,
+    ]);
+    assert_eq!(block.header().hash::<PedersenHasher>(), legacy_hash);
+    assert_ne!(header.hash::<PedersenHasher>(), legacy_hash);
+
+    // They are logged again with their layout
+    assert_eq!(Log::Block(block).encode(), encoded);
+}
+
+#[test]
+fn blocks_are_logged_with_their_version() {
+    let block = StarknetBlock::default();
+    let encoded = Log::Block(block.clone()).encode();
+
+    assert_eq!(encoded[0], 1);
+    assert_eq!(Log::decode(&mut &encoded[..]).unwrap(), Log::Block(block));
+}
@@ -67,6 +67,39 @@ pub struct ResourcePrice {
     pub price_in_wei: u128,
 }
 
+impl ResourcePrice {
+    /// The price of one unit of the given resource in `unit`, if any
+    pub fn price_in(&self, unit: PriceUnit) -> Option<u128> {
+        match unit {
+            PriceUnit::Wei => Some(self.price_in_wei),
+            PriceUnit::Fri => self.price_in_strk.map(u128::from),
+        }
+    }
+}
+
+/// The unit fees are paid in, each one having its own fee token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "parity-scale-codec", derive(parity_scale_codec::Encode, parity_scale_codec::Decode))]
+#[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "UPPERCASE"))]
+pub enum PriceUnit {
+    /// 10^-18 ETH, or of the chain legacy fee token
+    Wei,
+    /// 10^-18 STRK, or of the chain V3 fee token
+    Fri,
+}
+
+impl PriceUnit {
+    /// The unit the fees of a transaction of the given version are paid in
+    ///
+    /// V3 transactions pay in STRK, older ones in ETH. The V3 transactions are not supported yet,
+    /// so every fee is paid in wei until they are.
+    pub fn for_tx_version(version: u8) -> Self {
+        if version >= 3 { PriceUnit::Fri } else { PriceUnit::Wei }
+    }
+}
+
 impl From<ResourcePrice> for CoreResourcePrice {
     fn from(item: ResourcePrice) -> Self {
         // TODO: when we rebase starknet-rs those field type will be FieldElements
@@ -0,0 +1,130 @@
+//! Host functions reading the state of the chain a node is forked from.
+//!
+//! When running in fork mode, the node registers a [`ForkedStateExt`] on its runtime calls. The
+//! runtime then answers the state reads missing locally with the state of the remote chain at the
+//! fork block. Without the extension, every read returns `Ok(None)`. A read failing on the remote
+//! chain returns the error message, so that it is not mistaken for a missing value.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[doc(hidden)]
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+use blockifier::execution::contract_class::ContractClass;
+use parity_scale_codec::Decode;
+#[cfg(feature = "std")]
+use parity_scale_codec::Encode;
+#[cfg(feature = "std")]
+use sp_externalities::ExternalitiesExt;
+use sp_runtime_interface::runtime_interface;
+use starknet_api::api_core::{ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+/// Reader of the state of the forked chain, at the fork block.
+///
+/// The values of the contracts missing in the forked chain are the default ones.
+#[cfg(feature = "std")]
+pub trait ForkedStateReader: Send + Sync {
+    fn storage_at(&self, contract_address: ContractAddress, key: StorageKey) -> Result<StarkFelt, String>;
+    fn nonce_at(&self, contract_address: ContractAddress) -> Result<Nonce, String>;
+    fn class_hash_at(&self, contract_address: ContractAddress) -> Result<ClassHash, String>;
+    fn contract_class(&self, class_hash: ClassHash) -> Result<Option<ContractClass>, String>;
+    fn compiled_class_hash(&self, class_hash: ClassHash) -> Result<Option<CompiledClassHash>, String>;
+}
+
+#[cfg(feature = "std")]
+sp_externalities::decl_extension! {
+    /// The state of the forked chain, registered on the runtime calls of a node in fork mode.
+    pub struct ForkedStateExt(Arc<dyn ForkedStateReader>);
+}
+
+#[cfg(feature = "std")]
+impl ForkedStateExt {
+    pub fn new(reader: Arc<dyn ForkedStateReader>) -> Self {
+        Self(reader)
+    }
+}
+
+/// Raw host functions, see the typed wrappers of the crate.
+#[runtime_interface]
+pub trait ForkedState {
+    fn storage_at(&mut self, contract_address: [u8; 32], key: [u8; 32]) -> Result<Option<[u8; 32]>, String> {
+        let Some(reader) = self.extension::<ForkedStateExt>() else {
+            return Ok(None);
+        };
+        let contract_address = ContractAddress(PatriciaKey(StarkFelt(contract_address)));
+        reader.storage_at(contract_address, StorageKey(PatriciaKey(StarkFelt(key)))).map(|value| Some(value.0))
+    }
+
+    fn nonce_at(&mut self, contract_address: [u8; 32]) -> Result<Option<[u8; 32]>, String> {
+        let Some(reader) = self.extension::<ForkedStateExt>() else {
+            return Ok(None);
+        };
+        reader.nonce_at(ContractAddress(PatriciaKey(StarkFelt(contract_address)))).map(|nonce| Some(nonce.0.0))
+    }
+
+    fn class_hash_at(&mut self, contract_address: [u8; 32]) -> Result<Option<[u8; 32]>, String> {
+        let Some(reader) = self.extension::<ForkedStateExt>() else {
+            return Ok(None);
+        };
+        reader
+            .class_hash_at(ContractAddress(PatriciaKey(StarkFelt(contract_address))))
+            .map(|class_hash| Some(class_hash.0.0))
+    }
+
+    /// Returns the SCALE encoded class.
+    fn contract_class(&mut self, class_hash: [u8; 32]) -> Result<Option<Vec<u8>>, String> {
+        let Some(reader) = self.extension::<ForkedStateExt>() else {
+            return Ok(None);
+        };
+        reader
+            .contract_class(ClassHash(StarkFelt(class_hash)))
+            .map(|contract_class| contract_class.map(|contract_class| contract_class.encode()))
+    }
+
+    fn compiled_class_hash(&mut self, class_hash: [u8; 32]) -> Result<Option<[u8; 32]>, String> {
+        let Some(reader) = self.extension::<ForkedStateExt>() else {
+            return Ok(None);
+        };
+        reader
+            .compiled_class_hash(ClassHash(StarkFelt(class_hash)))
+            .map(|compiled_class_hash| compiled_class_hash.map(|compiled_class_hash| compiled_class_hash.0.0))
+    }
+}
+
+/// The value of a storage slot in the forked chain.
+pub fn storage_at(contract_address: ContractAddress, key: StorageKey) -> Result<Option<StarkFelt>, String> {
+    forked_state::storage_at(contract_address.0.0.0, key.0.0.0).map(|value| value.map(StarkFelt))
+}
+
+/// The nonce of a contract in the forked chain.
+pub fn nonce_at(contract_address: ContractAddress) -> Result<Option<Nonce>, String> {
+    forked_state::nonce_at(contract_address.0.0.0).map(|nonce| nonce.map(|nonce| Nonce(StarkFelt(nonce))))
+}
+
+/// The class hash of a contract in the forked chain, the default hash if it is not deployed.
+pub fn class_hash_at(contract_address: ContractAddress) -> Result<Option<ClassHash>, String> {
+    forked_state::class_hash_at(contract_address.0.0.0)
+        .map(|class_hash| class_hash.map(|class_hash| ClassHash(StarkFelt(class_hash))))
+}
+
+/// A class declared in the forked chain.
+pub fn contract_class(class_hash: ClassHash) -> Result<Option<ContractClass>, String> {
+    let Some(contract_class) = forked_state::contract_class(class_hash.0.0)? else {
+        return Ok(None);
+    };
+    ContractClass::decode(&mut &contract_class[..])
+        .map(Some)
+        .map_err(|_| String::from("Failed to decode the class of the forked chain"))
+}
+
+/// The compiled class hash of a Sierra class declared in the forked chain.
+pub fn compiled_class_hash(class_hash: ClassHash) -> Result<Option<CompiledClassHash>, String> {
+    forked_state::compiled_class_hash(class_hash.0.0)
+        .map(|compiled_class_hash| compiled_class_hash.map(|hash| CompiledClassHash(StarkFelt(hash))))
+}
@@ -4,10 +4,12 @@ use std::string::String;
 use std::vec::Vec;
 
 use blockifier::execution::contract_class::ContractClass as StarknetContractClass;
+use mp_fee::ResourcePrice;
 use mp_felt::Felt252Wrapper;
 use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_with::serde_as;
+use starknet_api::state::ContractClass as SierraContractClass;
 use starknet_core::serde::unsigned_field_element::UfeHex;
 use starknet_crypto::FieldElement;
 
@@ -47,6 +49,25 @@ pub type ContractAddress = HexFelt;
 pub type StorageKey = HexFelt;
 pub type ContractStorageKey = (ContractAddress, StorageKey);
 pub type StorageValue = HexFelt;
+pub type Nonce = HexFelt;
+
+/// Bytes serialized as a hex string, e.g. a SCALE encoded account id of the runtime.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HexBytes(pub Vec<u8>);
+
+impl Serialize for HexBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(&self.0)))
+    }
+}
+
+impl<'de> Deserialize<'de> for HexBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex_string = String::deserialize(deserializer)?;
+        let hex_string = hex_string.strip_prefix("0x").unwrap_or(&hex_string);
+        hex::decode(hex_string).map(Self).map_err(|err| Error::custom(err.to_string()))
+    }
+}
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct GenesisData {
@@ -55,8 +76,49 @@ pub struct GenesisData {
     pub contracts: Vec<(ContractAddress, ClassHash)>,
     pub predeployed_accounts: Vec<PredeployedAccount>,
     pub storage: Vec<(ContractStorageKey, StorageValue)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nonces: Vec<(ContractAddress, Nonce)>,
+    /// The Sierra definitions of the Cairo 1 classes, served by the RPC.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sierra_classes: Vec<(ClassHash, SierraContractClass)>,
     pub fee_token_address: ContractAddress,
     pub chain_id: String,
+    /// The chain parameters, the ones left unset use the value of the runtime config.
+    #[serde(default, skip_serializing_if = "ChainParameters::is_empty")]
+    pub chain_parameters: ChainParameters,
+    /// The SCALE encoded account allowed to update the chain parameters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin: Option<HexBytes>,
+    /// The sequencer registry, from the SCALE encoded authority ids to the sequencer addresses.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sequencers: Vec<(HexBytes, ContractAddress)>,
+    /// The nonces of the L1 messages already handled, which can't be handled again.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub l1_messages: Vec<Nonce>,
+}
+
+/// Chain parameters updatable by the admin of the chain, set at genesis.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChainParameters {
+    /// The address of the fee token ERC20 contract of V3 transactions, if they are accepted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strk_fee_token_address: Option<ContractAddress>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invoke_tx_max_n_steps: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validate_max_n_steps: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_gas_price: Option<ResourcePrice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disable_transaction_fee: Option<bool>,
+}
+
+impl ChainParameters {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -91,7 +153,10 @@ pub struct PredeployedAccount {
     pub contract_address: ContractAddress,
     pub class_hash: ClassHash,
     pub name: String,
-    #[serde(serialize_with = "buffer_to_hex")]
+    /// What the account is meant for, e.g. `deployer` or `faucet`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(default, serialize_with = "buffer_to_hex", deserialize_with = "hex_to_buffer")]
     pub private_key: Option<Vec<u8>>,
     pub public_key: HexFelt,
 }
@@ -108,14 +173,25 @@ where
     }
 }
 
+/// Reads a buffer written by [`buffer_to_hex`], or as an array of bytes.
 pub fn hex_to_buffer<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let hex_string = String::deserialize(deserializer)?;
-    if hex_string.is_empty() {
-        Ok(None)
-    } else {
-        hex::decode(&hex_string).map(Some).map_err(|err| Error::custom(err.to_string()))
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Buffer {
+        Hex(String),
+        Bytes(Vec<u8>),
+    }
+
+    match Option::<Buffer>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Buffer::Bytes(bytes)) => Ok(Some(bytes)),
+        Some(Buffer::Hex(hex_string)) if hex_string.is_empty() => Ok(None),
+        Some(Buffer::Hex(hex_string)) => {
+            let hex_string = hex_string.strip_prefix("0x").unwrap_or(&hex_string);
+            hex::decode(hex_string).map(Some).map_err(|err| Error::custom(err.to_string()))
+        }
     }
 }
@@ -7,6 +7,10 @@ use thiserror_no_std::Error;
 /// The identifier for the `sequencer_address` inherent.
 pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"seqaddr0";
 
+/// The identifier of the inherent data holding the digest of the block whose inherents are
+/// checked, used to find its author.
+pub const BLOCK_DIGEST_INHERENT_IDENTIFIER: InherentIdentifier = *b"blkdgst0";
+
 /// Default value in case the sequencer address is not set.
 pub const DEFAULT_SEQUENCER_ADDRESS: [u8; 32] =
     [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 222, 173];
@@ -24,12 +28,20 @@ pub enum InherentError {
     /// Submitted address must be `[u8; 32]`.
     #[error("Inherent decoding error")]
     WrongAddressFormat,
+    /// Submitted address is not the one registered for the author of the block.
+    #[error("Sequencer address not registered for the block author")]
+    UnregisteredSequencerAddress,
+    /// The block allows state edits or imports which the node does not allow.
+    #[error("Sealing parameters allowing state changes not allowed by the node")]
+    UnallowedStateChanges,
 }
 
 impl IsFatalError for InherentError {
     fn is_fatal_error(&self) -> bool {
         match self {
             InherentError::WrongAddressFormat => true,
+            InherentError::UnregisteredSequencerAddress => true,
+            InherentError::UnallowedStateChanges => true,
         }
     }
 }
@@ -69,6 +81,9 @@ mod reexport_for_client_only {
 
     #[derive(Copy, Clone, Decode, Encode, sp_core::RuntimeDebug)]
     /// The inherent data provider for sequencer address.
+    ///
+    /// The provided address is only used by block authors which are not in the sequencer registry
+    /// of the runtime, the address registered for the author taking precedence otherwise.
     pub struct InherentDataProvider {
         /// The sequencer address field.
         pub sequencer_address: InherentType,
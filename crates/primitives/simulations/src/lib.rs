@@ -5,7 +5,11 @@ pub extern crate alloc;
 
 use alloc::vec::Vec;
 
+use blockifier::block_context::BlockContext;
 use blockifier::transaction::objects::TransactionExecutionInfo;
+use mp_felt::Felt252Wrapper;
+use mp_transactions::UserTransaction;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_core::types::SimulationFlag;
 
 // TODO: This is a placeholder
@@ -45,3 +49,62 @@ impl From<Vec<SimulationFlag>> for SimulationFlags {
         Self { skip_validate, skip_fee_charge }
     }
 }
+
+/// Values to temporarily substitute to the on-chain state of a contract before executing
+/// a call, a fee estimation or a simulation.
+///
+/// Every field left to `None` (or empty) is read from the chain, as usual.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "parity-scale-codec", derive(parity_scale_codec::Encode, parity_scale_codec::Decode))]
+#[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
+pub struct ContractStateOverride {
+    pub contract_address: Felt252Wrapper,
+    pub nonce: Option<Felt252Wrapper>,
+    pub class_hash: Option<Felt252Wrapper>,
+    /// Balance of the contract in the fee token.
+    pub balance: Option<Felt252Wrapper>,
+    /// Storage slots, as `(key, value)` pairs.
+    pub storage: Vec<(Felt252Wrapper, Felt252Wrapper)>,
+}
+
+pub type StateOverrides = Vec<ContractStateOverride>;
+
+/// Values replacing the ones of the block context a simulated block is executed in.
+///
+/// Every field left to `None` keeps the value inherited from the previous block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "parity-scale-codec", derive(parity_scale_codec::Encode, parity_scale_codec::Decode))]
+#[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
+pub struct BlockContextOverrides {
+    pub block_number: Option<u64>,
+    pub block_timestamp: Option<u64>,
+    pub sequencer_address: Option<Felt252Wrapper>,
+    /// Gas price, in wei.
+    pub gas_price: Option<u128>,
+}
+
+impl BlockContextOverrides {
+    pub fn apply_to(&self, block_context: &mut BlockContext) {
+        if let Some(block_number) = self.block_number {
+            block_context.block_number = BlockNumber(block_number);
+        }
+        if let Some(block_timestamp) = self.block_timestamp {
+            block_context.block_timestamp = BlockTimestamp(block_timestamp);
+        }
+        if let Some(sequencer_address) = self.sequencer_address {
+            block_context.sequencer_address = sequencer_address.into();
+        }
+        if let Some(gas_price) = self.gas_price {
+            block_context.gas_price = gas_price;
+        }
+    }
+}
+
+/// A block to simulate, as part of a sequence of blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "parity-scale-codec", derive(parity_scale_codec::Encode, parity_scale_codec::Decode))]
+#[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
+pub struct SimulatedBlock {
+    pub block_context_overrides: BlockContextOverrides,
+    pub transactions: Vec<UserTransaction>,
+}
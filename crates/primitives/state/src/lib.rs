@@ -4,6 +4,8 @@
 #[doc(hidden)]
 extern crate alloc;
 
+use alloc::vec::Vec;
+
 use blockifier::execution::contract_class::ContractClass;
 use blockifier::state::cached_state::{ContractStorageKey, StateChangesCount};
 use blockifier::state::errors::StateError;
@@ -15,6 +17,23 @@ use starknet_api::stdlib::collections::HashMap;
 
 type ContractClassMapping = HashMap<ClassHash, ContractClass>;
 
+/// The changes a block made to the state, as published by the sequencer which built it.
+///
+/// Writing it to the state brings it to the one of the end of the block, without executing the
+/// transactions of the block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "parity-scale-codec", derive(parity_scale_codec::Encode, parity_scale_codec::Decode))]
+#[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
+pub struct BlockStateDiff {
+    /// The storage values written, as `(contract address, key, value)`.
+    pub storage: Vec<(ContractAddress, StorageKey, StarkFelt)>,
+    pub nonces: Vec<(ContractAddress, Nonce)>,
+    /// The class hash of the contracts deployed or whose class was replaced.
+    pub class_hashes: Vec<(ContractAddress, ClassHash)>,
+    /// The classes declared, along with the compiled class hash of the Sierra ones.
+    pub declared_classes: Vec<(ClassHash, ContractClass, Option<CompiledClassHash>)>,
+}
+
 /// This trait allows to get the state changes of a starknet tx and therefore enables computing the
 /// fees.
 pub trait StateChanges {
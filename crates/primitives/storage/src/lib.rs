@@ -38,12 +38,17 @@ lazy_static! {
     pub static ref SN_NONCE_PREFIX: Vec<u8> = [twox_128(PALLET_STARKNET), twox_128(STARKNET_NONCE)].concat();
     pub static ref SN_CONTRACT_CLASS_HASH_PREFIX: Vec<u8> =
         [twox_128(PALLET_STARKNET), twox_128(STARKNET_CONTRACT_CLASS_HASH)].concat();
+    pub static ref SN_CONTRACT_CLASS_PREFIX: Vec<u8> =
+        [twox_128(PALLET_STARKNET), twox_128(STARKNET_CONTRACT_CLASS)].concat();
     pub static ref SN_STORAGE_PREFIX: Vec<u8> = [twox_128(PALLET_STARKNET), twox_128(STARKNET_STORAGE)].concat();
     pub static ref SN_COMPILED_CLASS_HASH_PREFIX: Vec<u8> =
         [twox_128(PALLET_STARKNET), twox_128(STARKNET_COMPILED_CLASS_HASH)].concat();
 }
 
 /// The schema version for Pallet Starknet's storage.
+///
+/// Bumped by the migrations of `pallet_starknet` changing the layout of the storage items read by
+/// the client.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "parity-scale-codec", derive(parity_scale_codec::Decode, parity_scale_codec::Encode))]
@@ -7,8 +7,8 @@ use starknet_crypto::FieldElement;
 
 use super::{
     DeclareTransaction, DeclareTransactionV0, DeclareTransactionV1, DeclareTransactionV2, DeployAccountTransaction,
-    HandleL1MessageTransaction, InvokeTransaction, InvokeTransactionV0, InvokeTransactionV1, Transaction,
-    UserTransaction, SIMULATE_TX_VERSION_OFFSET,
+    HandleL1MessageTransaction, InvokeTransaction, InvokeTransactionV0, InvokeTransactionV1,
+    SponsoredInvokeTransactionV1, Transaction, UserTransaction, SIMULATE_TX_VERSION_OFFSET,
 };
 use crate::UserOrL1HandlerTransaction;
 
@@ -75,11 +75,45 @@ impl ComputeTransactionHash for InvokeTransactionV1 {
     }
 }
 
+/// The hash of a sponsored transaction is the one of its V1 transaction with the paymaster
+/// address appended to the hashed elements:
+/// `h("invoke", version, sender_address, 0, h(calldata), max_fee, chain_id, nonce, paymaster)`.
+///
+/// The paymaster can't be swapped without invalidating the sender signature, and the hash never
+/// collides with the one of the unsponsored transaction.
+impl ComputeTransactionHash for SponsoredInvokeTransactionV1 {
+    fn compute_hash<H: HasherT>(&self, chain_id: Felt252Wrapper, offset_version: bool) -> Felt252Wrapper {
+        let prefix = FieldElement::from_byte_slice_be(INVOKE_PREFIX).unwrap();
+        let version = if offset_version { SIMULATE_TX_VERSION_OFFSET + FieldElement::ONE } else { FieldElement::ONE };
+        let sender_address = self.tx.sender_address.into();
+        let entrypoint_selector = FieldElement::ZERO;
+        let calldata_hash = compute_hash_on_elements(convert_calldata(&self.tx.calldata));
+        let max_fee = FieldElement::from(self.tx.max_fee);
+        let chain_id = chain_id.into();
+        let nonce = FieldElement::from(self.tx.nonce);
+        let paymaster = self.paymaster.into();
+
+        H::compute_hash_on_elements(&[
+            prefix,
+            version,
+            sender_address,
+            entrypoint_selector,
+            calldata_hash,
+            max_fee,
+            chain_id,
+            nonce,
+            paymaster,
+        ])
+        .into()
+    }
+}
+
 impl ComputeTransactionHash for InvokeTransaction {
     fn compute_hash<H: HasherT>(&self, chain_id: Felt252Wrapper, offset_version: bool) -> Felt252Wrapper {
         match self {
             InvokeTransaction::V0(tx) => tx.compute_hash::<H>(chain_id, offset_version),
             InvokeTransaction::V1(tx) => tx.compute_hash::<H>(chain_id, offset_version),
+            InvokeTransaction::Sponsored(tx) => tx.compute_hash::<H>(chain_id, offset_version),
         }
     }
 }
@@ -11,8 +11,8 @@ use starknet_crypto::FieldElement;
 use crate::compute_hash::ComputeTransactionHash;
 use crate::{
     DeclareTransaction, DeclareTransactionV0, DeclareTransactionV1, DeclareTransactionV2, DeployAccountTransaction,
-    HandleL1MessageTransaction, InvokeTransaction, InvokeTransactionV0, InvokeTransactionV1, Transaction,
-    UserTransaction,
+    HandleL1MessageTransaction, InvokeTransaction, InvokeTransactionV0, InvokeTransactionV1,
+    SponsoredInvokeTransactionV1, Transaction, UserTransaction,
 };
 
 #[test]
@@ -244,6 +244,38 @@ fn test_invoke_tx_v1_hash() {
     assert_eq!(tx_hash, expected_tx_hash);
 }
 
+#[test]
+fn test_sponsored_invoke_tx_v1_hash_commits_to_paymaster() {
+    let chain_id = Felt252Wrapper(FieldElement::from_byte_slice_be(b"SN_GOERLI").unwrap());
+
+    let transaction = SponsoredInvokeTransactionV1 {
+        tx: InvokeTransactionV1 {
+            max_fee: 1,
+            signature: vec![],
+            nonce: Felt252Wrapper::ZERO,
+            sender_address: Felt252Wrapper::from(19911991_u128),
+            calldata: vec![Felt252Wrapper::ONE, Felt252Wrapper::TWO, Felt252Wrapper::THREE],
+            offset_version: false,
+        },
+        paymaster: Felt252Wrapper::from(0x1234_u128),
+        paymaster_signature: vec![],
+    };
+    let other_paymaster =
+        SponsoredInvokeTransactionV1 { paymaster: Felt252Wrapper::from(0x5678_u128), ..transaction.clone() };
+    let paymaster_signed =
+        SponsoredInvokeTransactionV1 { paymaster_signature: vec![Felt252Wrapper::ONE], ..transaction.clone() };
+
+    let tx_hash = transaction.compute_hash::<PedersenHasher>(chain_id, false);
+
+    assert_ne!(tx_hash, other_paymaster.compute_hash::<PedersenHasher>(chain_id, false));
+    assert_ne!(tx_hash, transaction.tx.compute_hash::<PedersenHasher>(chain_id, false));
+    // The paymaster signs the hash, so its signature isn't part of it
+    assert_eq!(tx_hash, paymaster_signed.compute_hash::<PedersenHasher>(chain_id, false));
+
+    let wrapped_transaction = InvokeTransaction::Sponsored(transaction);
+    assert_eq!(tx_hash, wrapped_transaction.compute_hash::<PedersenHasher>(chain_id, false));
+}
+
 #[test]
 fn test_handle_l1_message_tx_hash() {
     // Computed with `calculate_transaction_hash_common` from the cairo lang package
@@ -11,9 +11,11 @@ use starknet_api::transaction as sttx;
 use starknet_api::transaction::{Fee, TransactionVersion};
 
 use super::compute_hash::ComputeTransactionHash;
+use super::execution::SponsoredInvokeTransaction;
 use super::{
     DeclareTransaction, DeclareTransactionV0, DeclareTransactionV1, DeclareTransactionV2, DeployAccountTransaction,
     HandleL1MessageTransaction, InvokeTransaction, InvokeTransactionV0, InvokeTransactionV1,
+    SponsoredInvokeTransactionV1,
 };
 
 impl DeclareTransactionV0 {
@@ -130,6 +132,10 @@ impl InvokeTransactionV1 {
     ) -> btx::InvokeTransaction {
         let transaction_hash = self.compute_hash::<H>(chain_id, offset_version);
 
+        self.into_executable_with_hash(transaction_hash)
+    }
+
+    fn into_executable_with_hash(&self, transaction_hash: Felt252Wrapper) -> btx::InvokeTransaction {
         btx::InvokeTransaction {
             tx: sttx::InvokeTransaction::V1(sttx::InvokeTransactionV1 {
                 max_fee: sttx::Fee(self.max_fee),
@@ -143,7 +149,24 @@ impl InvokeTransactionV1 {
     }
 }
 
+impl SponsoredInvokeTransactionV1 {
+    pub fn into_executable<H: HasherT>(
+        &self,
+        chain_id: Felt252Wrapper,
+        offset_version: bool,
+    ) -> SponsoredInvokeTransaction {
+        let transaction_hash = self.compute_hash::<H>(chain_id, offset_version);
+
+        SponsoredInvokeTransaction {
+            tx: self.tx.into_executable_with_hash(transaction_hash),
+            paymaster: self.paymaster.into(),
+            paymaster_signature: vec_of_felt_to_signature(&self.paymaster_signature),
+        }
+    }
+}
+
 impl InvokeTransaction {
+    /// Returns the executable of the transaction, without its paymaster if it is sponsored.
     pub fn into_executable<H: HasherT>(
         &self,
         chain_id: Felt252Wrapper,
@@ -152,6 +175,19 @@ impl InvokeTransaction {
         match self {
             InvokeTransaction::V0(tx) => tx.into_executable::<H>(chain_id, offset_version),
             InvokeTransaction::V1(tx) => tx.into_executable::<H>(chain_id, offset_version),
+            InvokeTransaction::Sponsored(tx) => tx.into_executable::<H>(chain_id, offset_version).tx,
+        }
+    }
+
+    /// Returns the executable of the transaction if it is sponsored by a paymaster.
+    pub fn into_sponsored_executable<H: HasherT>(
+        &self,
+        chain_id: Felt252Wrapper,
+        offset_version: bool,
+    ) -> Option<SponsoredInvokeTransaction> {
+        match self {
+            InvokeTransaction::Sponsored(tx) => Some(tx.into_executable::<H>(chain_id, offset_version)),
+            InvokeTransaction::V0(_) | InvokeTransaction::V1(_) => None,
         }
     }
 }
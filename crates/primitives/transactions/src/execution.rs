@@ -304,6 +304,11 @@ pub trait Execute: Sized + GetAccountTransactionContext + GetTransactionCalldata
         disable_validation: bool,
     ) -> TransactionExecutionResult<ValidateExecuteCallInfo>;
 
+    /// Returns the address whose balance covers the fee of the transaction.
+    fn fee_payer(&self, account_tx_context: &AccountTransactionContext) -> ContractAddress {
+        account_tx_context.sender_address
+    }
+
     fn handle_nonce(
         account_tx_context: &AccountTransactionContext,
         state: &mut dyn State,
@@ -328,11 +333,12 @@ pub trait Execute: Sized + GetAccountTransactionContext + GetTransactionCalldata
         Ok(())
     }
 
-    /// Handles nonce and checks that the account's balance covers max fee.
+    /// Handles nonce and checks that the fee payer's balance covers max fee.
     fn handle_nonce_and_check_fee_balance<S: State + StateChanges>(
         state: &mut S,
         block_context: &BlockContext,
         account_tx_context: &AccountTransactionContext,
+        fee_payer: &ContractAddress,
         execution_config: &ExecutionConfig,
     ) -> TransactionExecutionResult<()> {
         // Handle nonce.
@@ -352,8 +358,7 @@ pub trait Execute: Sized + GetAccountTransactionContext + GetTransactionCalldata
             && !execution_config.is_query
         {
             log::debug!("Inside checking balance");
-            let (balance_low, balance_high) =
-                state.get_fee_token_balance(block_context, &account_tx_context.sender_address)?;
+            let (balance_low, balance_high) = state.get_fee_token_balance(block_context, fee_payer)?;
 
             if balance_high <= StarkFelt::from(0_u8) && balance_low < StarkFelt::from(account_tx_context.max_fee.0) {
                 return Err(TransactionExecutionError::MaxFeeExceedsBalance {
@@ -379,7 +384,14 @@ pub trait Execute: Sized + GetAccountTransactionContext + GetTransactionCalldata
         let account_tx_context = self.get_account_transaction_context(execution_config.offset_version);
 
         // Nonce and fee check should be done before running user code.
-        Self::handle_nonce_and_check_fee_balance(state, block_context, &account_tx_context, execution_config)?;
+        let fee_payer = self.fee_payer(&account_tx_context);
+        Self::handle_nonce_and_check_fee_balance(
+            state,
+            block_context,
+            &account_tx_context,
+            &fee_payer,
+            execution_config,
+        )?;
 
         // execute
         let ValidateExecuteCallInfo { validate_call_info, execute_call_info, revert_error } = self.execute_inner(
@@ -433,10 +445,12 @@ pub trait Execute: Sized + GetAccountTransactionContext + GetTransactionCalldata
             None,
         )?;
 
+        // The fee transfer is made on behalf of the fee payer
+        let fee_payer = self.fee_payer(&account_tx_context);
         let (actual_fee, fee_transfer_call_info) = charge_fee(
             state,
             block_context,
-            account_tx_context,
+            AccountTransactionContext { sender_address: fee_payer, ..account_tx_context },
             &actual_resources,
             execution_config.disable_transaction_fee,
             execution_config.disable_fee_charge,
@@ -478,26 +492,168 @@ impl Execute for InvokeTransaction {
         } else {
             None
         };
-        let validate_execute_call_info = match self.tx {
-            // V0 tx cannot revert, we cannot charge the failling ones
-            starknet_api::transaction::InvokeTransaction::V0(_) => {
-                let execute_call_info = self.run_execute(state, resources, &mut context, remaining_gas)?;
-                ValidateExecuteCallInfo::new_accepted(validate_call_info, execute_call_info)
-            }
-            starknet_api::transaction::InvokeTransaction::V1(_) => {
-                match self.run_execute(state, resources, &mut context, remaining_gas) {
-                    Ok(execute_call_info) => {
-                        ValidateExecuteCallInfo::new_accepted(validate_call_info, execute_call_info)
-                    }
-                    Err(e) => {
-                        log::debug!("Invoke transaction reverted with error: {:?}", e);
-                        ValidateExecuteCallInfo::new_reverted(validate_call_info, context.error_trace())
-                    }
+
+        run_validated_invoke(self, state, resources, remaining_gas, &mut context, validate_call_info)
+    }
+}
+
+/// Runs the `__execute__` entrypoint of an invoke transaction whose validation already happened.
+fn run_validated_invoke<S: State>(
+    tx: &InvokeTransaction,
+    state: &mut S,
+    resources: &mut ExecutionResources,
+    remaining_gas: &mut u64,
+    context: &mut EntryPointExecutionContext,
+    validate_call_info: Option<CallInfo>,
+) -> TransactionExecutionResult<ValidateExecuteCallInfo> {
+    let validate_execute_call_info = match tx.tx {
+        // V0 tx cannot revert, we cannot charge the failling ones
+        starknet_api::transaction::InvokeTransaction::V0(_) => {
+            let execute_call_info = tx.run_execute(state, resources, context, remaining_gas)?;
+            ValidateExecuteCallInfo::new_accepted(validate_call_info, execute_call_info)
+        }
+        starknet_api::transaction::InvokeTransaction::V1(_) => {
+            match tx.run_execute(state, resources, context, remaining_gas) {
+                Ok(execute_call_info) => ValidateExecuteCallInfo::new_accepted(validate_call_info, execute_call_info),
+                Err(e) => {
+                    log::debug!("Invoke transaction reverted with error: {:?}", e);
+                    ValidateExecuteCallInfo::new_reverted(validate_call_info, context.error_trace())
                 }
             }
+        }
+    };
+
+    Ok(validate_execute_call_info)
+}
+
+/// An invoke transaction whose validation is extended to, and whose fee is paid by, a paymaster
+/// contract instead of its sender.
+///
+/// The paymaster is an account contract: its `__validate__` entrypoint is called right after the
+/// sender's one, with the same calldata, the sender as caller address and the paymaster signature
+/// as transaction signature, and decides whether to sponsor the transaction. Its call info is
+/// nested in the validation call info so it shows up in the traces.
+pub struct SponsoredInvokeTransaction {
+    pub tx: InvokeTransaction,
+    pub paymaster: ContractAddress,
+    pub paymaster_signature: TransactionSignature,
+}
+
+impl SponsoredInvokeTransaction {
+    fn validate_sponsored_tx(
+        &self,
+        state: &mut dyn State,
+        resources: &mut ExecutionResources,
+        remaining_gas: &mut u64,
+        context: &mut EntryPointExecutionContext,
+    ) -> TransactionExecutionResult<Option<CallInfo>> {
+        let mut validate_call_info = self.tx.validate_tx_inner(
+            state,
+            resources,
+            remaining_gas,
+            context,
+            GetTransactionCalldata::calldata(self),
+        )?;
+
+        let validate_paymaster_call = CallEntryPoint {
+            entry_point_type: EntryPointType::External,
+            entry_point_selector: self.validate_entry_point_selector(),
+            calldata: GetTransactionCalldata::calldata(self),
+            class_hash: None,
+            code_address: None,
+            storage_address: self.paymaster,
+            // Lets the paymaster tell apart the sponsored transactions from its own ones
+            caller_address: context.account_tx_context.sender_address,
+            call_type: CallType::Call,
+            initial_gas: *remaining_gas,
         };
 
-        Ok(validate_execute_call_info)
+        // The paymaster checks its own signature, the sender one is restored right after the call
+        let sender_signature =
+            core::mem::replace(&mut context.account_tx_context.signature, self.paymaster_signature.clone());
+        let validate_paymaster_call_info = validate_paymaster_call.execute(state, resources, context);
+        context.account_tx_context.signature = sender_signature;
+        let validate_paymaster_call_info =
+            validate_paymaster_call_info.map_err(TransactionExecutionError::ValidateTransactionError)?;
+        verify_no_calls_to_other_contracts(&validate_paymaster_call_info, String::from(VALIDATE_ENTRY_POINT_NAME))?;
+        update_remaining_gas(remaining_gas, &validate_paymaster_call_info);
+
+        match validate_call_info.as_mut() {
+            Some(call_info) => call_info.inner_calls.push(validate_paymaster_call_info),
+            None => validate_call_info = Some(validate_paymaster_call_info),
+        }
+
+        Ok(validate_call_info)
+    }
+}
+
+impl GetAccountTransactionContext for SponsoredInvokeTransaction {
+    fn get_account_transaction_context(&self, offset_version: bool) -> AccountTransactionContext {
+        self.tx.get_account_transaction_context(offset_version)
+    }
+}
+
+impl GetTransactionCalldata for SponsoredInvokeTransaction {
+    fn calldata(&self) -> Calldata {
+        GetTransactionCalldata::calldata(&self.tx)
+    }
+}
+
+impl GetTxType for SponsoredInvokeTransaction {
+    fn tx_type() -> TransactionType {
+        TransactionType::InvokeFunction
+    }
+}
+
+impl Validate for SponsoredInvokeTransaction {
+    const VALIDATE_TX_ENTRY_POINT_NAME: &'static str = VALIDATE_ENTRY_POINT_NAME;
+
+    fn validate_tx(
+        &self,
+        state: &mut dyn State,
+        block_context: &BlockContext,
+        resources: &mut ExecutionResources,
+        remaining_gas: &mut u64,
+        validate_tx: bool,
+    ) -> TransactionExecutionResult<Option<CallInfo>> {
+        let account_tx_context = self.get_account_transaction_context(validate_tx);
+        let mut context = EntryPointExecutionContext::new(
+            block_context.clone(),
+            account_tx_context,
+            block_context.invoke_tx_max_n_steps,
+        );
+
+        self.validate_sponsored_tx(state, resources, remaining_gas, &mut context)
+    }
+}
+
+impl Execute for SponsoredInvokeTransaction {
+    fn execute_inner<S: State + StateChanges>(
+        &self,
+        state: &mut S,
+        block_context: &BlockContext,
+        resources: &mut ExecutionResources,
+        remaining_gas: &mut u64,
+        account_tx_context: &AccountTransactionContext,
+        disable_validation: bool,
+    ) -> TransactionExecutionResult<ValidateExecuteCallInfo> {
+        let mut context = EntryPointExecutionContext::new(
+            block_context.clone(),
+            account_tx_context.clone(),
+            block_context.invoke_tx_max_n_steps,
+        );
+
+        let validate_call_info = if !disable_validation {
+            self.validate_sponsored_tx(state, resources, remaining_gas, &mut context)?
+        } else {
+            None
+        };
+
+        run_validated_invoke(&self.tx, state, resources, remaining_gas, &mut context, validate_call_info)
+    }
+
+    fn fee_payer(&self, _account_tx_context: &AccountTransactionContext) -> ContractAddress {
+        self.paymaster
     }
 }
 
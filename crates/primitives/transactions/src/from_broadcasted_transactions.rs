@@ -22,8 +22,8 @@ use starknet_core::types::contract::{CompiledClass, CompiledClassEntrypoint, Com
 use starknet_core::types::{
     BroadcastedDeclareTransaction, BroadcastedDeclareTransactionV1, BroadcastedDeclareTransactionV2,
     BroadcastedDeployAccountTransaction, BroadcastedInvokeTransaction, BroadcastedTransaction,
-    CompressedLegacyContractClass, EntryPointsByType, FlattenedSierraClass, LegacyContractEntryPoint,
-    LegacyEntryPointsByType, SierraEntryPoint,
+    CompressedLegacyContractClass, ContractClass as CoreContractClass, EntryPointsByType, FlattenedSierraClass,
+    LegacyContractEntryPoint, LegacyEntryPointsByType, SierraEntryPoint,
 };
 use starknet_crypto::FieldElement;
 use thiserror::Error;
@@ -138,47 +138,102 @@ impl TryFrom<BroadcastedDeclareTransaction> for UserTransaction {
 
                 UserTransaction::Declare(tx, contract_class)
             }
-            BroadcastedDeclareTransaction::V2(BroadcastedDeclareTransactionV2 {
-                max_fee,
-                signature,
-                nonce,
-                contract_class,
-                sender_address,
-                compiled_class_hash,
-                is_query,
-                ..
-            }) => {
-                let tx = DeclareTransaction::V2(DeclareTransactionV2 {
-                    max_fee: max_fee.try_into().map_err(|_| BroadcastedTransactionConversionError::MaxFeeTooBig)?,
-                    signature: cast_vec_of_field_elements(signature),
-                    nonce: nonce.into(),
-                    class_hash: contract_class.class_hash().into(),
-                    sender_address: sender_address.into(),
-                    compiled_class_hash: compiled_class_hash.into(),
-                    offset_version: is_query,
-                });
+            BroadcastedDeclareTransaction::V2(tx) => declare_v2_into_user_transaction(tx, |_, _| None)?,
+        };
 
-                let casm_contract_class = flattened_sierra_to_casm_contract_class(contract_class)
-                    .map_err(|_| BroadcastedTransactionConversionError::SierraCompilationFailed)?;
+        Ok(user_tx)
+    }
+}
 
-                // ensure that the user has sign the correct class hash
-                if get_casm_cotract_class_hash(&casm_contract_class) != compiled_class_hash {
-                    return Err(BroadcastedTransactionConversionError::InvalidCompiledClassHash);
-                }
+/// Converts a declare v2 transaction, compiling its Sierra class unless `compiled_class` returns
+/// its compiled class.
+///
+/// `compiled_class` is given the class hash and the compiled class hash of the transaction, it
+/// must only return a class compiled from that Sierra class, to that compiled class hash.
+pub fn declare_v2_into_user_transaction(
+    tx: BroadcastedDeclareTransactionV2,
+    compiled_class: impl FnOnce(FieldElement, FieldElement) -> Option<ContractClass>,
+) -> Result<UserTransaction, BroadcastedTransactionConversionError> {
+    let BroadcastedDeclareTransactionV2 {
+        max_fee,
+        signature,
+        nonce,
+        contract_class,
+        sender_address,
+        compiled_class_hash,
+        is_query,
+        ..
+    } = tx;
+    let class_hash = contract_class.class_hash();
+
+    let tx = DeclareTransaction::V2(DeclareTransactionV2 {
+        max_fee: max_fee.try_into().map_err(|_| BroadcastedTransactionConversionError::MaxFeeTooBig)?,
+        signature: cast_vec_of_field_elements(signature),
+        nonce: nonce.into(),
+        class_hash: class_hash.into(),
+        sender_address: sender_address.into(),
+        compiled_class_hash: compiled_class_hash.into(),
+        offset_version: is_query,
+    });
+
+    if let Some(contract_class) = compiled_class(class_hash, compiled_class_hash) {
+        return Ok(UserTransaction::Declare(tx, contract_class));
+    }
 
-                let contract_class = ContractClass::V1(
-                    ContractClassV1::try_from(casm_contract_class)
-                        .map_err(|_| BroadcastedTransactionConversionError::CasmContractClassConversionFailed)?,
-                );
+    let casm_contract_class = flattened_sierra_to_casm_contract_class(contract_class)
+        .map_err(|_| BroadcastedTransactionConversionError::SierraCompilationFailed)?;
 
-                UserTransaction::Declare(tx, contract_class)
-            }
-        };
+    // ensure that the user has sign the correct class hash
+    if get_casm_cotract_class_hash(&casm_contract_class) != compiled_class_hash {
+        return Err(BroadcastedTransactionConversionError::InvalidCompiledClassHash);
+    }
 
-        Ok(user_tx)
+    let contract_class = ContractClass::V1(
+        ContractClassV1::try_from(casm_contract_class)
+            .map_err(|_| BroadcastedTransactionConversionError::CasmContractClassConversionFailed)?,
+    );
+
+    Ok(UserTransaction::Declare(tx, contract_class))
+}
+
+/// Converts a class served by a Starknet RPC to the class executed by the blockifier, compiling
+/// the Sierra classes.
+pub fn to_blockifier_contract_class(
+    contract_class: CoreContractClass,
+) -> Result<ContractClass, BroadcastedTransactionConversionError> {
+    match contract_class {
+        CoreContractClass::Legacy(contract_class) => {
+            let mut gz = GzDecoder::new(&contract_class.program[..]);
+            let mut decompressed_bytes = Vec::new();
+            std::io::Read::read_to_end(&mut gz, &mut decompressed_bytes)
+                .map_err(|_| BroadcastedTransactionConversionError::ProgramDecompressionFailed)?;
+
+            instantiate_blockifier_contract_class(Arc::new(contract_class), decompressed_bytes)
+        }
+        CoreContractClass::Sierra(contract_class) => {
+            compile_sierra_contract_class(contract_class).map(|(contract_class, _)| contract_class)
+        }
     }
 }
 
+/// Compiles a Sierra class served by a Starknet RPC, returning the class executed by the
+/// blockifier along with its compiled class hash.
+pub fn compile_sierra_contract_class(
+    contract_class: FlattenedSierraClass,
+) -> Result<(ContractClass, FieldElement), BroadcastedTransactionConversionError> {
+    let casm_contract_class = flattened_sierra_to_casm_contract_class(Arc::new(contract_class))
+        .map_err(|_| BroadcastedTransactionConversionError::SierraCompilationFailed)?;
+    let compiled_class_hash = get_casm_cotract_class_hash(&casm_contract_class);
+
+    Ok((
+        ContractClass::V1(
+            ContractClassV1::try_from(casm_contract_class)
+                .map_err(|_| BroadcastedTransactionConversionError::CasmContractClassConversionFailed)?,
+        ),
+        compiled_class_hash,
+    ))
+}
+
 fn instantiate_blockifier_contract_class(
     contract_class: Arc<CompressedLegacyContractClass>,
     program_decompressed_bytes: Vec<u8>,
@@ -337,17 +392,39 @@ impl TryFrom<BroadcastedInvokeTransaction> for UserTransaction {
     type Error = BroadcastedTransactionConversionError;
 
     fn try_from(value: BroadcastedInvokeTransaction) -> Result<Self, Self::Error> {
-        Ok(UserTransaction::Invoke(super::InvokeTransaction::V1(super::InvokeTransactionV1 {
+        Ok(UserTransaction::Invoke(super::InvokeTransaction::V1(value.try_into()?)))
+    }
+}
+
+impl TryFrom<BroadcastedInvokeTransaction> for super::InvokeTransactionV1 {
+    type Error = BroadcastedTransactionConversionError;
+
+    fn try_from(value: BroadcastedInvokeTransaction) -> Result<Self, Self::Error> {
+        Ok(super::InvokeTransactionV1 {
             max_fee: value.max_fee.try_into().map_err(|_| BroadcastedTransactionConversionError::MaxFeeTooBig)?,
             signature: cast_vec_of_field_elements(value.signature),
             nonce: value.nonce.into(),
             sender_address: value.sender_address.into(),
             calldata: cast_vec_of_field_elements(value.calldata),
             offset_version: value.is_query,
-        })))
+        })
     }
 }
 
+/// Converts an invoke transaction signed by its sender for the given paymaster to a sponsored
+/// transaction, `paymaster_signature` being the signature of the paymaster over the same hash.
+pub fn sponsored_invoke_into_user_transaction(
+    tx: BroadcastedInvokeTransaction,
+    paymaster: FieldElement,
+    paymaster_signature: Vec<FieldElement>,
+) -> Result<UserTransaction, BroadcastedTransactionConversionError> {
+    Ok(UserTransaction::Invoke(super::InvokeTransaction::Sponsored(super::SponsoredInvokeTransactionV1 {
+        tx: tx.try_into()?,
+        paymaster: paymaster.into(),
+        paymaster_signature: cast_vec_of_field_elements(paymaster_signature),
+    })))
+}
+
 impl TryFrom<BroadcastedDeployAccountTransaction> for UserTransaction {
     type Error = BroadcastedTransactionConversionError;
 
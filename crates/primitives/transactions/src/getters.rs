@@ -163,6 +163,7 @@ impl InvokeTransaction {
         match self {
             InvokeTransaction::V0(tx) => &tx.contract_address,
             InvokeTransaction::V1(tx) => &tx.sender_address,
+            InvokeTransaction::Sponsored(tx) => &tx.tx.sender_address,
         }
     }
 
@@ -170,6 +171,7 @@ impl InvokeTransaction {
         match self {
             InvokeTransaction::V0(tx) => &tx.signature,
             InvokeTransaction::V1(tx) => &tx.signature,
+            InvokeTransaction::Sponsored(tx) => &tx.tx.signature,
         }
     }
 
@@ -177,6 +179,7 @@ impl InvokeTransaction {
         match self {
             InvokeTransaction::V0(tx) => &tx.max_fee,
             InvokeTransaction::V1(tx) => &tx.max_fee,
+            InvokeTransaction::Sponsored(tx) => &tx.tx.max_fee,
         }
     }
 
@@ -184,6 +187,7 @@ impl InvokeTransaction {
         match self {
             InvokeTransaction::V0(tx) => &tx.calldata,
             InvokeTransaction::V1(tx) => &tx.calldata,
+            InvokeTransaction::Sponsored(tx) => &tx.tx.calldata,
         }
     }
 
@@ -191,6 +195,7 @@ impl InvokeTransaction {
         match self {
             InvokeTransaction::V0(_) => None,
             InvokeTransaction::V1(tx) => Some(&tx.nonce),
+            InvokeTransaction::Sponsored(tx) => Some(&tx.tx.nonce),
         }
     }
 
@@ -199,6 +204,14 @@ impl InvokeTransaction {
             // we don't accept V0 txs from the RPC
             InvokeTransaction::V0(_) => false,
             InvokeTransaction::V1(tx) => tx.offset_version,
+            InvokeTransaction::Sponsored(tx) => tx.tx.offset_version,
+        }
+    }
+
+    pub fn paymaster(&self) -> Option<&Felt252Wrapper> {
+        match self {
+            InvokeTransaction::V0(_) | InvokeTransaction::V1(_) => None,
+            InvokeTransaction::Sponsored(tx) => Some(&tx.paymaster),
         }
     }
 }
@@ -246,6 +259,7 @@ impl TransactionVersion for InvokeTransaction {
         match self {
             InvokeTransaction::V0(tx) => tx.version(),
             InvokeTransaction::V1(tx) => tx.version(),
+            InvokeTransaction::Sponsored(tx) => tx.tx.version(),
         }
     }
 }
@@ -20,7 +20,7 @@ use alloc::vec::Vec;
 use blockifier::execution::contract_class::ContractClass;
 use blockifier::transaction::transaction_types::TransactionType;
 use derive_more::From;
-use starknet_api::transaction::Fee;
+use starknet_api::transaction::{Event, Fee, MessageToL1, TransactionHash};
 use starknet_core::types::{MsgFromL1, TransactionExecutionStatus, TransactionFinalityStatus};
 use starknet_ff::FieldElement;
 
@@ -31,11 +31,53 @@ const SIMULATE_TX_VERSION_OFFSET: FieldElement =
 // pub mod utils;
 use mp_felt::Felt252Wrapper;
 
+/// The finality status of a transaction, including the mempool statuses.
+/// See `https://github.com/starkware-libs/starknet-specs/blob/v0.5.1/api/starknet_api_openrpc.json` `TXN_STATUS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
+pub enum TxnStatus {
+    /// The transaction is in the mempool, waiting to be included in a block.
+    Received,
+    /// The transaction has been refused by the mempool.
+    Rejected,
+    AcceptedOnL2,
+    AcceptedOnL1,
+}
+
+impl From<TransactionFinalityStatus> for TxnStatus {
+    fn from(value: TransactionFinalityStatus) -> Self {
+        match value {
+            TransactionFinalityStatus::AcceptedOnL2 => TxnStatus::AcceptedOnL2,
+            TransactionFinalityStatus::AcceptedOnL1 => TxnStatus::AcceptedOnL1,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransactionStatus {
-    pub finality_status: TransactionFinalityStatus,
-    pub execution_status: TransactionExecutionStatus,
+    pub finality_status: TxnStatus,
+    /// Only set once the transaction has been executed.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none", default))]
+    pub execution_status: Option<TransactionExecutionStatus>,
+    /// The reason why the transaction was rejected, only set along `TxnStatus::Rejected`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none", default))]
+    pub failure_reason: Option<alloc::string::String>,
+}
+
+impl TransactionStatus {
+    pub fn received() -> Self {
+        Self { finality_status: TxnStatus::Received, execution_status: None, failure_reason: None }
+    }
+
+    pub fn rejected(reason: alloc::string::String) -> Self {
+        Self { finality_status: TxnStatus::Rejected, execution_status: None, failure_reason: Some(reason) }
+    }
+
+    pub fn executed(finality_status: TransactionFinalityStatus, execution_status: TransactionExecutionStatus) -> Self {
+        Self { finality_status: finality_status.into(), execution_status: Some(execution_status), failure_reason: None }
+    }
 }
 
 /// Wrapper type for transaction execution error.
@@ -113,12 +155,26 @@ pub enum UserOrL1HandlerTransaction {
     L1Handler(HandleL1MessageTransaction, Fee),
 }
 
+/// A transaction of a block imported from another sequencer, along with the outputs of its
+/// execution there.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "parity-scale-codec", derive(parity_scale_codec::Encode, parity_scale_codec::Decode))]
+#[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
+pub struct ImportedTransaction {
+    pub transaction_hash: TransactionHash,
+    pub transaction: Transaction,
+    pub events: Vec<Event>,
+    pub messages: Vec<MessageToL1>,
+    pub revert_error: Option<alloc::string::String>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, From)]
 #[cfg_attr(feature = "parity-scale-codec", derive(parity_scale_codec::Encode, parity_scale_codec::Decode))]
 #[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
 pub enum InvokeTransaction {
     V0(InvokeTransactionV0),
     V1(InvokeTransactionV1),
+    Sponsored(SponsoredInvokeTransactionV1),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -144,6 +200,24 @@ pub struct InvokeTransactionV1 {
     pub offset_version: bool,
 }
 
+/// A V1 invoke transaction whose validation is extended to, and whose fee is paid by, a paymaster
+/// contract instead of its sender.
+///
+/// Its hash commits to the paymaster, see the [`ComputeTransactionHash`] implementation. Both the
+/// sender and the paymaster sign it, each one checking its own signature in its `__validate__`
+/// entrypoint.
+///
+/// [`ComputeTransactionHash`]: compute_hash::ComputeTransactionHash
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "parity-scale-codec", derive(parity_scale_codec::Encode, parity_scale_codec::Decode))]
+#[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
+pub struct SponsoredInvokeTransactionV1 {
+    /// The transaction of the sender, signed by the sender.
+    pub tx: InvokeTransactionV1,
+    pub paymaster: Felt252Wrapper,
+    pub paymaster_signature: Vec<Felt252Wrapper>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, From)]
 #[cfg_attr(feature = "parity-scale-codec", derive(parity_scale_codec::Encode, parity_scale_codec::Decode))]
 #[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
@@ -113,6 +113,7 @@ pub fn to_starknet_core_tx(
                     entry_point_selector: entry_point_selector.into(),
                     calldata: cast_vec_of_felt_252_wrappers(calldata),
                 }),
+                // The sponsored transactions are served as the V1 transaction of their sender
                 super::InvokeTransaction::V1(super::InvokeTransactionV1 {
                     max_fee,
                     signature,
@@ -120,6 +121,10 @@ pub fn to_starknet_core_tx(
                     sender_address,
                     calldata,
                     ..
+                })
+                | super::InvokeTransaction::Sponsored(super::SponsoredInvokeTransactionV1 {
+                    tx: super::InvokeTransactionV1 { max_fee, signature, nonce, sender_address, calldata, .. },
+                    ..
                 }) => starknet_core::types::InvokeTransaction::V1(starknet_core::types::InvokeTransactionV1 {
                     transaction_hash,
                     max_fee: max_fee.into(),
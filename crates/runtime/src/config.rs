@@ -6,6 +6,8 @@ pub use frame_support::weights::constants::{
 pub use frame_support::weights::{IdentityFee, Weight};
 pub use frame_support::{construct_runtime, parameter_types, StorageValue};
 pub use frame_system::Call as SystemCall;
+use pallet_starknet::block_limits::BlockResources;
+use pallet_starknet::sealing::SealingParameters;
 use parity_scale_codec::{Decode, Encode};
 use serde::{Deserialize, Serialize};
 use sp_core::RuntimeDebug;
@@ -44,10 +46,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     //   `spec_version`, and `authoring_version` are the same between Wasm and native.
     // This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
     //   the compatible custom types.
-    spec_version: 100,
+    spec_version: 101,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 2,
     state_version: 1,
 };
 
@@ -82,6 +84,9 @@ parameter_types! {
 
 /// The current sealing mode being used. This is needed for the runtime to adjust its behavior
 /// accordingly, e.g. suppress Aura validations in `OnTimestampSet` for manual or instant sealing.
+///
+/// The parameters of the blocks depending on the mode, e.g. their budgets, are passed to the
+/// Starknet pallet by the node sealing them, see [`SealingMode::sealing_parameters`].
 #[derive(Default, Clone, PartialEq, Decode, Encode, RuntimeDebug, Deserialize, Serialize)]
 pub enum SealingMode {
     #[default]
@@ -90,12 +95,51 @@ pub enum SealingMode {
     Instant {
         finalize: bool,
     },
+    /// Seal and finalize a block once `block_time` milliseconds have elapsed or `max_transactions`
+    /// are ready in the pool, whichever comes first. No block is sealed while the pool is empty.
+    ///
+    /// The budgets are enforced when building the block, along with the `MaxBlockResources` of the
+    /// Starknet pallet: the block builder leaves out the transactions which would exceed one of
+    /// them, and they are sealed in a new block right away.
+    Batch {
+        block_time: u64,
+        max_transactions: u32,
+        max_steps: Option<u64>,
+        max_state_diff_size: Option<u64>,
+    },
+    /// No block is authored, they are imported from another sequencer, re-executing their
+    /// transactions or writing their state diffs.
+    Sync,
 }
 
 impl SealingMode {
     pub fn is_default(&self) -> bool {
         matches!(self, SealingMode::Default)
     }
+
+    /// The parameters of the blocks sealed in this mode, passed to the Starknet pallet by the block
+    /// parameters inherent.
+    ///
+    /// State edits are only allowed on development chains, sealed manually or instantly, and state
+    /// diffs are only imported by the nodes syncing from another sequencer.
+    pub fn sealing_parameters(&self) -> SealingParameters {
+        match self {
+            SealingMode::Default => SealingParameters::default(),
+            SealingMode::Manual | SealingMode::Instant { .. } => {
+                SealingParameters { allow_state_edits: true, ..Default::default() }
+            }
+            SealingMode::Batch { max_transactions, max_steps, max_state_diff_size, .. } => SealingParameters {
+                max_block_resources: BlockResources {
+                    n_transactions: (*max_transactions).into(),
+                    n_steps: max_steps.unwrap_or(u64::MAX),
+                    state_diff_size: max_state_diff_size.unwrap_or(u64::MAX),
+                    ..BlockResources::MAX
+                },
+                ..Default::default()
+            },
+            SealingMode::Sync => SealingParameters { allow_state_import: true, ..Default::default() },
+        }
+    }
 }
 
 #[cfg(feature = "std")]
@@ -105,6 +149,17 @@ impl std::fmt::Display for SealingMode {
             SealingMode::Default => write!(f, "Default"),
             SealingMode::Manual => write!(f, "Manual"),
             SealingMode::Instant { finalize } => write!(f, "Instant (finalize: {})", finalize),
+            SealingMode::Batch { block_time, max_transactions, max_steps, max_state_diff_size } => {
+                write!(f, "Batch (block time: {}ms, max transactions: {}", block_time, max_transactions)?;
+                if let Some(max_steps) = max_steps {
+                    write!(f, ", max steps: {}", max_steps)?;
+                }
+                if let Some(max_state_diff_size) = max_state_diff_size {
+                    write!(f, ", max state diff size: {}", max_state_diff_size)?;
+                }
+                write!(f, ")")
+            }
+            SealingMode::Sync => write!(f, "Sync"),
         }
     }
 }
@@ -29,9 +29,15 @@ pub use frame_support::weights::{IdentityFee, Weight};
 pub use frame_support::{construct_runtime, parameter_types, StorageValue};
 pub use frame_system::Call as SystemCall;
 use mp_felt::Felt252Wrapper;
-use mp_simulations::{PlaceHolderErrorTypeForFailedStarknetExecution, SimulationFlags, TransactionSimulationResult};
+use mp_simulations::{
+    PlaceHolderErrorTypeForFailedStarknetExecution, SimulatedBlock, SimulationFlags, StateOverrides,
+    TransactionSimulationResult,
+};
+use mp_state::BlockStateDiff;
 use mp_transactions::compute_hash::ComputeTransactionHash;
-use mp_transactions::{HandleL1MessageTransaction, Transaction, UserOrL1HandlerTransaction, UserTransaction};
+use mp_transactions::{
+    HandleL1MessageTransaction, ImportedTransaction, Transaction, UserOrL1HandlerTransaction, UserTransaction,
+};
 use pallet_grandpa::{fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList};
 /// Import the Starknet pallet.
 pub use pallet_starknet;
@@ -44,7 +50,7 @@ use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::crypto::KeyTypeId;
 use sp_core::OpaqueMetadata;
-use sp_runtime::traits::{BlakeTwo256, Block as BlockT, NumberFor};
+use sp_runtime::traits::{BlakeTwo256, Block as BlockT, Header as HeaderT, NumberFor};
 use sp_runtime::transaction_validity::{TransactionSource, TransactionValidity};
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
@@ -52,7 +58,7 @@ use sp_runtime::{generic, ApplyExtrinsicResult, DispatchError};
 pub use sp_runtime::{Perbill, Permill};
 use sp_std::prelude::*;
 use sp_version::RuntimeVersion;
-use starknet_api::api_core::{ClassHash, ContractAddress, EntryPointSelector, Nonce};
+use starknet_api::api_core::{ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, Nonce};
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{Calldata, Event as StarknetEvent, Fee, MessageToL1, TransactionHash};
@@ -92,9 +98,17 @@ pub type SignedExtra = (
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
+/// Storage migrations run on runtime upgrades, before the `on_runtime_upgrade` of the pallets.
+pub type Migrations = (pallet_starknet::migrations::Migrations<Runtime>,);
 /// Executive: handles dispatch to the various modules.
-pub type Executive =
-    frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllPalletsWithSystem>;
+pub type Executive = frame_executive::Executive<
+    Runtime,
+    Block,
+    frame_system::ChainContext<Runtime>,
+    Runtime,
+    AllPalletsWithSystem,
+    Migrations,
+>;
 
 #[cfg(feature = "runtime-benchmarks")]
 #[macro_use]
@@ -107,6 +121,7 @@ mod benches {
         [frame_system, SystemBench::<Runtime>]
         [pallet_balances, Balances]
         [pallet_timestamp, Timestamp]
+        [pallet_starknet, Starknet]
     );
 }
 
@@ -154,8 +169,10 @@ impl_runtime_apis! {
 
         fn check_inherents(
             block: Block,
-            data: sp_inherents::InherentData,
+            mut data: sp_inherents::InherentData,
         ) -> sp_inherents::CheckInherentsResult {
+            // Exposes the digest of the block, e.g. its author, to the inherent checks
+            data.replace_data(mp_sequencer_address::BLOCK_DIGEST_INHERENT_IDENTIFIER, block.header().digest());
             data.check_extrinsics(&block)
         }
     }
@@ -234,6 +251,7 @@ impl_runtime_apis! {
         }
     }
 
+    #[api_version(2)]
     impl pallet_starknet_runtime_api::StarknetRuntimeApi<Block> for Runtime {
 
         fn get_storage_at(address: ContractAddress, key: StorageKey) -> Result<StarkFelt, DispatchError> {
@@ -256,6 +274,10 @@ impl_runtime_apis! {
             Starknet::contract_class_by_class_hash(class_hash)
         }
 
+        fn compiled_class_hash_by_class_hash(class_hash: ClassHash) -> Option<CompiledClassHash> {
+            Starknet::compiled_class_hash_by_class_hash(class_hash)
+        }
+
         fn chain_id() -> Felt252Wrapper {
             Starknet::chain_id()
         }
@@ -292,6 +314,22 @@ impl_runtime_apis! {
             Starknet::simulate_transactions(transactions, &simulation_flags)
         }
 
+        fn simulate_blocks(blocks: Vec<SimulatedBlock>, simulation_flags: SimulationFlags) -> Result<Vec<Vec<(CommitmentStateDiff, TransactionSimulationResult)>>, DispatchError> {
+            Starknet::simulate_blocks(blocks, &simulation_flags)
+        }
+
+        fn call_with_overrides(address: ContractAddress, function_selector: EntryPointSelector, calldata: Calldata, state_overrides: StateOverrides) -> Result<Vec<Felt252Wrapper>, DispatchError> {
+            Starknet::call_contract_with_overrides(address, function_selector, calldata, state_overrides)
+        }
+
+        fn estimate_fee_with_overrides(transactions: Vec<UserTransaction>, state_overrides: StateOverrides) -> Result<Vec<(u64, u64)>, DispatchError> {
+            Starknet::estimate_fee_with_overrides(transactions, state_overrides)
+        }
+
+        fn simulate_transactions_with_overrides(transactions: Vec<UserTransaction>, simulation_flags: SimulationFlags, state_overrides: StateOverrides) -> Result<Vec<(CommitmentStateDiff, TransactionSimulationResult)>, DispatchError> {
+            Starknet::simulate_transactions_with_overrides(transactions, &simulation_flags, state_overrides)
+        }
+
         fn simulate_message(message: HandleL1MessageTransaction, simulation_flags: SimulationFlags) -> Result<Result<TransactionExecutionInfo, PlaceHolderErrorTypeForFailedStarknetExecution>, DispatchError> {
             Starknet::simulate_message(message, &simulation_flags)
         }
@@ -352,6 +390,7 @@ impl_runtime_apis! {
         }
     }
 
+    #[api_version(2)]
     impl pallet_starknet_runtime_api::ConvertTransactionRuntimeApi<Block> for Runtime {
         fn convert_transaction(transaction: UserTransaction) -> UncheckedExtrinsic {
             let call = match transaction {
@@ -375,6 +414,24 @@ impl_runtime_apis! {
             UncheckedExtrinsic::new_unsigned(call.into())
         }
 
+        fn convert_state_edits(state_edits: StateOverrides) -> UncheckedExtrinsic {
+            let call = pallet_starknet::Call::<Runtime>::apply_state_edits { state_edits };
+
+            UncheckedExtrinsic::new_unsigned(call.into())
+        }
+
+        fn convert_mint(address: ContractAddress, amount: StarkFelt) -> UncheckedExtrinsic {
+            let call = pallet_starknet::Call::<Runtime>::mint { address, amount };
+
+            UncheckedExtrinsic::new_unsigned(call.into())
+        }
+
+        fn convert_state_diff(state_diff: BlockStateDiff, transactions: Vec<ImportedTransaction>, state_root: StarkHash) -> UncheckedExtrinsic {
+            let call = pallet_starknet::Call::<Runtime>::import_state_diff { state_diff, transactions, state_root };
+
+            UncheckedExtrinsic::new_unsigned(call.into())
+        }
+
         fn convert_error(error: DispatchError) -> StarknetTransactionExecutionError {
             if error == PalletError::<Runtime>::ContractNotFound.into() {
                 return StarknetTransactionExecutionError::ContractNotFound;
@@ -1,6 +1,7 @@
 //! Configuration of the pallets used in the runtime.
 //! The pallets used in the runtime are configured here.
 //! This file is used to generate the `construct_runtime!` macro.
+use frame_support::traits::EitherOfDiverse;
 pub use frame_support::traits::{
     ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, OnTimestampSet, Randomness, StorageInfo,
 };
@@ -10,11 +11,13 @@ pub use frame_support::weights::constants::{
 pub use frame_support::weights::{IdentityFee, Weight};
 pub use frame_support::{construct_runtime, parameter_types, StorageValue};
 pub use frame_system::Call as SystemCall;
+use frame_system::EnsureRoot;
 pub use mp_chain_id::SN_GOERLI_CHAIN_ID;
 use mp_fee::ResourcePrice;
 pub use mp_program_hash::SN_OS_PROGRAM_HASH;
 /// Import the StarkNet pallet.
 pub use pallet_starknet;
+use pallet_starknet::block_limits::BlockResources;
 pub use pallet_timestamp::Call as TimestampCall;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_runtime::traits::{AccountIdLookup, BlakeTwo256};
@@ -33,10 +36,13 @@ use crate::*;
 
 /// Configure the Starknet pallet in pallets/starknet.
 impl pallet_starknet::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type AdminOrigin = EitherOfDiverse<EnsureRoot<AccountId>, pallet_starknet::EnsureAdmin<Runtime>>;
     type SystemHash = StarknetHasher;
     type TimestampProvider = Timestamp;
     type UnsignedPriority = UnsignedPriority;
     type TransactionLongevity = TransactionLongevity;
+    type MaxPendingTransactionsPerAccount = MaxPendingTransactionsPerAccount;
     #[cfg(not(feature = "disable-transaction-fee"))]
     type DisableTransactionFee = ConstBool<false>;
     #[cfg(feature = "disable-transaction-fee")]
@@ -48,6 +54,10 @@ impl pallet_starknet::Config for Runtime {
     type MaxRecursionDepth = MaxRecursionDepth;
     type ProgramHash = ProgramHash;
     type L1GasPrice = L1GasPrice;
+    type MaxBlockResources = MaxBlockResources;
+    type TxOutputsRetention = TxOutputsRetention;
+    type AuthorityId = AuraId;
+    type FindAuthor = pallet_aura::FindAccountFromAuthorIndex<Self, Aura>;
 }
 
 /// --------------------------------------
@@ -156,12 +166,31 @@ impl pallet_timestamp::Config for Runtime {
 parameter_types! {
     pub const UnsignedPriority: u64 = 1 << 20;
     pub const TransactionLongevity: u64 = u64::MAX;
+    pub const MaxPendingTransactionsPerAccount: u64 = 64;
     pub const InvokeTxMaxNSteps: u32 = 1_000_000;
     pub const ValidateMaxNSteps: u32 = 1_000_000;
     pub const ProtocolVersion: u8 = 0;
     pub const MaxRecursionDepth: u32 = 50;
     pub const ProgramHash: Felt252Wrapper = SN_OS_PROGRAM_HASH;
     pub const L1GasPrice: ResourcePrice = ResourcePrice { price_in_strk: None, price_in_wei: 10 };
+    /// Transaction outputs are served from the client database once out of this window
+    pub const TxOutputsRetention: Option<u64> = Some(1_000);
+    /// Builtin limits follow the ratios of the `all_cairo` layout for the steps limit, the node
+    /// sealing a block can lower them with its own budgets
+    pub const MaxBlockResources: BlockResources = BlockResources {
+        n_steps: 40_000_000,
+        pedersen_builtin: 1_250_000,
+        range_check_builtin: 2_500_000,
+        ecdsa_builtin: 19_531,
+        bitwise_builtin: 625_000,
+        ec_op_builtin: 39_062,
+        poseidon_builtin: 1_250_000,
+        keccak_builtin: 19_531,
+        state_diff_size: 20_000,
+        n_events: 10_000,
+        n_messages: 1_000,
+        n_transactions: u64::MAX,
+    };
 }
 
 /// Implement the OnTimestampSet trait to override the default Aura.
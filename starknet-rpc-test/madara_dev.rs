@@ -0,0 +1,121 @@
+#![feature(assert_matches)]
+
+use std::assert_matches::assert_matches;
+
+use anyhow::anyhow;
+use rstest::rstest;
+use serde_json::json;
+use starknet_core::types::{BlockId, BlockTag, BlockWithTxHashes, MaybePendingBlockWithTxHashes, StarknetError};
+use starknet_ff::FieldElement;
+use starknet_providers::jsonrpc::HttpTransport;
+use starknet_providers::{JsonRpcClient, MaybeUnknownErrorCode, Provider, ProviderError, StarknetErrorWithMessage};
+use starknet_test_utils::constants::{ARGENT_CONTRACT_ADDRESS, FEE_TOKEN_ADDRESS, SIGNER_PRIVATE};
+use starknet_test_utils::fixtures::{madara, ThreadSafeMadaraClient};
+use starknet_test_utils::utils::{build_single_owner_account, read_erc20_balance, AccountActions};
+use starknet_test_utils::{Transaction, TransactionResult};
+
+async fn latest_block(rpc: &JsonRpcClient<HttpTransport>) -> Result<BlockWithTxHashes, anyhow::Error> {
+    match rpc.get_block_with_tx_hashes(BlockId::Tag(BlockTag::Latest)).await? {
+        MaybePendingBlockWithTxHashes::Block(block) => Ok(block),
+        MaybePendingBlockWithTxHashes::PendingBlock(_) => Err(anyhow!("Expected block, got pending block")),
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn mint_adds_to_the_fee_token_balance(madara: &ThreadSafeMadaraClient) -> Result<(), anyhow::Error> {
+    let rpc = madara.get_starknet_client().await;
+    let fee_token_address = FieldElement::from_hex_be(FEE_TOKEN_ADDRESS).unwrap();
+    let account_address = FieldElement::from_hex_be(ARGENT_CONTRACT_ADDRESS).unwrap();
+
+    let mut madara_write_lock = madara.write().await;
+    let block_number = rpc.block_number().await?;
+    let balance = read_erc20_balance(&rpc, fee_token_address, account_address).await;
+
+    madara_write_lock.call_dev_rpc("mint", json!([ARGENT_CONTRACT_ADDRESS, "0x1234"])).await?;
+
+    // The mint is applied in a block of its own
+    assert_eq!(rpc.block_number().await?, block_number + 1);
+    let minted_balance = read_erc20_balance(&rpc, fee_token_address, account_address).await;
+    assert_eq!(minted_balance[0], balance[0] + FieldElement::from_hex_be("0x1234").unwrap());
+    assert_eq!(minted_balance[1], balance[1]);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn revert_drops_the_blocks_and_transactions_after_the_snapshot(
+    madara: &ThreadSafeMadaraClient,
+) -> Result<(), anyhow::Error> {
+    let rpc = madara.get_starknet_client().await;
+
+    let mut madara_write_lock = madara.write().await;
+    let snapshot_block = latest_block(&rpc).await?;
+    let snapshot_id = madara_write_lock.call_dev_rpc("snapshot", json!([])).await?;
+
+    let account = build_single_owner_account(&rpc, SIGNER_PRIVATE, ARGENT_CONTRACT_ADDRESS, true);
+    let results = madara_write_lock
+        .create_block_with_txs(vec![Transaction::Execution(account.transfer_tokens(
+            FieldElement::from_hex_be("0x1234").unwrap(),
+            FieldElement::ONE,
+            None,
+        ))])
+        .await?;
+    let transaction_hash = match results.into_iter().next() {
+        Some(Ok(TransactionResult::Execution(result))) => result.transaction_hash,
+        _ => return Err(anyhow!("Expected the transfer to be executed")),
+    };
+    madara_write_lock.create_empty_block().await?;
+    let reverted_block = latest_block(&rpc).await?;
+    assert_eq!(reverted_block.block_number, snapshot_block.block_number + 2);
+
+    madara_write_lock.call_dev_rpc("revert", json!([snapshot_id])).await?;
+
+    assert_eq!(latest_block(&rpc).await?.block_hash, snapshot_block.block_hash);
+    assert_matches!(
+        rpc.get_block_with_tx_hashes(BlockId::Hash(reverted_block.block_hash)).await.err(),
+        Some(ProviderError::StarknetError(StarknetErrorWithMessage {
+            message: _,
+            code: MaybeUnknownErrorCode::Known(StarknetError::BlockNotFound)
+        }))
+    );
+    assert_matches!(
+        rpc.get_transaction_receipt(transaction_hash).await.err(),
+        Some(ProviderError::StarknetError(StarknetErrorWithMessage {
+            message: _,
+            code: MaybeUnknownErrorCode::Known(StarknetError::TransactionHashNotFound)
+        }))
+    );
+
+    // The chain goes on from the snapshot
+    madara_write_lock.create_empty_block().await?;
+    assert_eq!(rpc.block_number().await?, snapshot_block.block_number + 1);
+
+    // The snapshot is consumed by the revert
+    assert!(madara_write_lock.call_dev_rpc("revert", json!([snapshot_id])).await.is_err());
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn next_block_timestamp_can_be_moved_forward(madara: &ThreadSafeMadaraClient) -> Result<(), anyhow::Error> {
+    let rpc = madara.get_starknet_client().await;
+
+    let mut madara_write_lock = madara.write().await;
+    let timestamp = latest_block(&rpc).await?.timestamp;
+
+    // Earlier than one block time after the latest block
+    assert!(madara_write_lock.call_dev_rpc("setNextBlockTimestamp", json!([timestamp])).await.is_err());
+
+    madara_write_lock.call_dev_rpc("setNextBlockTimestamp", json!([timestamp + 1000])).await?;
+    madara_write_lock.create_empty_block().await?;
+    assert_eq!(latest_block(&rpc).await?.timestamp, timestamp + 1000);
+
+    let next_timestamp = madara_write_lock.call_dev_rpc("increaseTime", json!([60])).await?;
+    madara_write_lock.create_empty_block().await?;
+    assert_eq!(json!(latest_block(&rpc).await?.timestamp), next_timestamp);
+
+    Ok(())
+}
@@ -203,6 +203,21 @@ impl MadaraClient {
         response.status().is_success().then_some(()).ok_or(anyhow!("failed to create a new block"))
     }
 
+    /// Calls the `madara_dev_{method}` RPC method, returning its result
+    pub async fn call_dev_rpc(&mut self, method: &str, params: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+        let body = json!({
+            "method": format!("madara_dev_{method}"),
+            "params": params,
+        });
+
+        let response = self.call_rpc(body).await?;
+        let mut response: serde_json::Value = serde_json::from_str(&response.text().await?)?;
+        match response.get("error") {
+            Some(error) => Err(anyhow!("madara_dev_{method} failed: {error}")),
+            None => Ok(response["result"].take()),
+        }
+    }
+
     pub async fn health(&self) -> anyhow::Result<bool> {
         let body = json!({
             "method": "system_health"